@@ -1,14 +1,58 @@
 use std::sync::Arc;
 use sea_orm::DatabaseConnection;
+use crate::mailer::Mailer;
+use crate::services::QueryRegistry;
 
 /// Application configuration loaded from environment variables
 #[derive(Clone, Debug)]
 pub struct Config {
     pub database_url: String,
     pub jwt_secret: String,
-    pub jwt_expiration_hours: usize,
+    pub access_token_expiration_minutes: usize,
+    pub refresh_token_expiration_days: usize,
+    /// Lifetime of the tokens admins get when impersonating a user
+    pub impersonation_expiration_minutes: usize,
     pub server_host: String,
     pub server_port: u16,
+    /// Base URL of the frontend, used for links in emails
+    pub app_url: String,
+    pub smtp_url: Option<String>,
+    pub mail_from: String,
+    pub mail_outbox_dir: String,
+    pub password_reset_expiration_minutes: usize,
+    /// Keep users out of the API until they verify their email address
+    pub require_email_verification: bool,
+    pub email_verification_expiration_hours: usize,
+    pub invitation_expiration_hours: usize,
+    /// OpenID Connect providers users can sign in with
+    pub oidc_providers: Vec<OidcProvider>,
+    /// Failed sign-ins allowed for one email address before it is locked out
+    pub login_max_failures_per_account: u32,
+    /// Failed sign-ins allowed from one IP address, across accounts, before it is locked out
+    pub login_max_failures_per_ip: u32,
+    /// Length of the first lockout; each further failure doubles it
+    pub login_lockout_base_seconds: u64,
+    pub login_lockout_max_seconds: u64,
+    /// Failures are forgotten after this long without another one
+    pub login_failure_window_minutes: u64,
+    /// Reverse proxies in front of the server that append to `X-Forwarded-For`; with none,
+    /// the client address is the connection's peer address
+    pub trusted_proxy_hops: usize,
+}
+
+/// An OpenID Connect provider, configured through `OIDC_<ID>_*` variables
+#[derive(Clone, Debug)]
+pub struct OidcProvider {
+    /// Identifier used in URLs and stored with linked identities, e.g. `google`
+    pub id: String,
+    /// Name shown on the sign-in button
+    pub name: String,
+    /// Issuer URL; its `/.well-known/openid-configuration` describes the endpoints
+    pub issuer: String,
+    pub client_id: String,
+    /// Left unset for public clients, which rely on PKCE alone
+    pub client_secret: Option<String>,
+    pub scopes: String,
 }
 
 impl Config {
@@ -19,32 +63,116 @@ impl Config {
                 .map_err(|_| "DATABASE_URL must be set".to_string())?,
             jwt_secret: std::env::var("JWT_SECRET")
                 .unwrap_or_else(|_| "your-secret-key-change-in-production".to_string()),
-            jwt_expiration_hours: std::env::var("JWT_EXPIRATION_HOURS")
-                .unwrap_or_else(|_| "24".to_string())
+            access_token_expiration_minutes: std::env::var("ACCESS_TOKEN_EXPIRATION_MINUTES")
+                .unwrap_or_else(|_| "15".to_string())
+                .parse()
+                .map_err(|_| "ACCESS_TOKEN_EXPIRATION_MINUTES must be a valid number".to_string())?,
+            refresh_token_expiration_days: std::env::var("REFRESH_TOKEN_EXPIRATION_DAYS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .map_err(|_| "REFRESH_TOKEN_EXPIRATION_DAYS must be a valid number".to_string())?,
+            impersonation_expiration_minutes: std::env::var("IMPERSONATION_EXPIRATION_MINUTES")
+                .unwrap_or_else(|_| "30".to_string())
                 .parse()
-                .map_err(|_| "JWT_EXPIRATION_HOURS must be a valid number".to_string())?,
+                .map_err(|_| "IMPERSONATION_EXPIRATION_MINUTES must be a valid number".to_string())?,
             server_host: std::env::var("SERVER_HOST")
                 .unwrap_or_else(|_| "127.0.0.1".to_string()),
             server_port: std::env::var("SERVER_PORT")
                 .unwrap_or_else(|_| "8080".to_string())
                 .parse()
                 .map_err(|_| "SERVER_PORT must be a valid number".to_string())?,
+            app_url: std::env::var("APP_URL")
+                .unwrap_or_else(|_| "http://127.0.0.1:8080".to_string()),
+            smtp_url: std::env::var("SMTP_URL").ok().filter(|url| !url.is_empty()),
+            mail_from: std::env::var("MAIL_FROM")
+                .unwrap_or_else(|_| "Ferrisbase <no-reply@ferrisbase.local>".to_string()),
+            mail_outbox_dir: std::env::var("MAIL_OUTBOX_DIR")
+                .unwrap_or_else(|_| "outbox".to_string()),
+            password_reset_expiration_minutes: std::env::var("PASSWORD_RESET_EXPIRATION_MINUTES")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .map_err(|_| "PASSWORD_RESET_EXPIRATION_MINUTES must be a valid number".to_string())?,
+            require_email_verification: std::env::var("REQUIRE_EMAIL_VERIFICATION")
+                .unwrap_or_else(|_| "false".to_string())
+                .parse()
+                .map_err(|_| "REQUIRE_EMAIL_VERIFICATION must be true or false".to_string())?,
+            email_verification_expiration_hours: std::env::var("EMAIL_VERIFICATION_EXPIRATION_HOURS")
+                .unwrap_or_else(|_| "24".to_string())
+                .parse()
+                .map_err(|_| "EMAIL_VERIFICATION_EXPIRATION_HOURS must be a valid number".to_string())?,
+            invitation_expiration_hours: std::env::var("INVITATION_EXPIRATION_HOURS")
+                .unwrap_or_else(|_| "168".to_string())
+                .parse()
+                .map_err(|_| "INVITATION_EXPIRATION_HOURS must be a valid number".to_string())?,
+            oidc_providers: oidc_providers_from_env()?,
+            login_max_failures_per_account: std::env::var("LOGIN_MAX_FAILURES_PER_ACCOUNT")
+                .unwrap_or_else(|_| "5".to_string())
+                .parse()
+                .map_err(|_| "LOGIN_MAX_FAILURES_PER_ACCOUNT must be a valid number".to_string())?,
+            login_max_failures_per_ip: std::env::var("LOGIN_MAX_FAILURES_PER_IP")
+                .unwrap_or_else(|_| "20".to_string())
+                .parse()
+                .map_err(|_| "LOGIN_MAX_FAILURES_PER_IP must be a valid number".to_string())?,
+            login_lockout_base_seconds: std::env::var("LOGIN_LOCKOUT_BASE_SECONDS")
+                .unwrap_or_else(|_| "30".to_string())
+                .parse()
+                .map_err(|_| "LOGIN_LOCKOUT_BASE_SECONDS must be a valid number".to_string())?,
+            login_lockout_max_seconds: std::env::var("LOGIN_LOCKOUT_MAX_SECONDS")
+                .unwrap_or_else(|_| "900".to_string())
+                .parse()
+                .map_err(|_| "LOGIN_LOCKOUT_MAX_SECONDS must be a valid number".to_string())?,
+            login_failure_window_minutes: std::env::var("LOGIN_FAILURE_WINDOW_MINUTES")
+                .unwrap_or_else(|_| "60".to_string())
+                .parse()
+                .map_err(|_| "LOGIN_FAILURE_WINDOW_MINUTES must be a valid number".to_string())?,
+            trusted_proxy_hops: std::env::var("TRUSTED_PROXY_HOPS")
+                .unwrap_or_else(|_| "0".to_string())
+                .parse()
+                .map_err(|_| "TRUSTED_PROXY_HOPS must be a valid number".to_string())?,
         })
     }
 }
 
+/// Read the providers listed in `OIDC_PROVIDERS` (comma separated ids)
+fn oidc_providers_from_env() -> Result<Vec<OidcProvider>, String> {
+    let ids = std::env::var("OIDC_PROVIDERS").unwrap_or_default();
+
+    ids.split(',')
+        .map(str::trim)
+        .filter(|id| !id.is_empty())
+        .map(|id| {
+            let prefix = format!("OIDC_{}", id.to_uppercase().replace('-', "_"));
+            let var = |name: &str| std::env::var(format!("{}_{}", prefix, name)).ok().filter(|v| !v.is_empty());
+            let required = |name: &str| var(name).ok_or_else(|| format!("{}_{} must be set", prefix, name));
+
+            Ok(OidcProvider {
+                id: id.to_lowercase(),
+                name: var("NAME").unwrap_or_else(|| id.to_string()),
+                issuer: required("ISSUER")?.trim_end_matches('/').to_string(),
+                client_id: required("CLIENT_ID")?,
+                client_secret: var("CLIENT_SECRET"),
+                scopes: var("SCOPES").unwrap_or_else(|| "openid email profile".to_string()),
+            })
+        })
+        .collect()
+}
+
 /// Shared application state
 #[derive(Clone)]
 pub struct AppState {
     pub db: Arc<DatabaseConnection>,
     pub config: Arc<Config>,
+    pub queries: Arc<QueryRegistry>,
+    pub mailer: Arc<dyn Mailer>,
 }
 
 impl AppState {
-    pub fn new(db: DatabaseConnection, config: Config) -> Self {
+    pub fn new(db: DatabaseConnection, config: Config, mailer: Arc<dyn Mailer>) -> Self {
         Self {
             db: Arc::new(db),
             config: Arc::new(config),
+            queries: Arc::new(QueryRegistry::default()),
+            mailer,
         }
     }
 }
@@ -1,5 +1,5 @@
 use axum::{
-    http::StatusCode,
+    http::{header, HeaderValue, StatusCode},
     response::{IntoResponse, Response},
     Json,
 };
@@ -18,6 +18,9 @@ pub enum AppError {
     #[error("Invalid input: {0}")]
     BadRequest(String),
 
+    #[error("Access denied: {0}")]
+    Forbidden(String),
+
     #[error("Resource not found: {0}")]
     NotFound(String),
 
@@ -32,6 +35,16 @@ pub enum AppError {
 
     #[error("Validation error: {0}")]
     ValidationError(String),
+
+    #[error("Too many requests: {0}")]
+    TooManyRequests(String),
+
+    /// Too many requests, with the seconds to wait before trying again sent as `Retry-After`
+    #[error("Too many requests: {0}")]
+    RetryLater(String, u64),
+
+    #[error("Upstream service error: {0}")]
+    Upstream(String),
 }
 
 impl IntoResponse for AppError {
@@ -43,6 +56,7 @@ impl IntoResponse for AppError {
             }
             AppError::Unauthorized(ref msg) => (StatusCode::UNAUTHORIZED, msg.as_str()),
             AppError::BadRequest(ref msg) => (StatusCode::BAD_REQUEST, msg.as_str()),
+            AppError::Forbidden(ref msg) => (StatusCode::FORBIDDEN, msg.as_str()),
             AppError::NotFound(ref msg) => (StatusCode::NOT_FOUND, msg.as_str()),
             AppError::InternalServerError(ref msg) => {
                 tracing::error!("Internal server error: {}", msg);
@@ -57,6 +71,12 @@ impl IntoResponse for AppError {
                 (StatusCode::INTERNAL_SERVER_ERROR, "Password hashing error")
             }
             AppError::ValidationError(ref msg) => (StatusCode::BAD_REQUEST, msg.as_str()),
+            AppError::TooManyRequests(ref msg) => (StatusCode::TOO_MANY_REQUESTS, msg.as_str()),
+            AppError::RetryLater(ref msg, _) => (StatusCode::TOO_MANY_REQUESTS, msg.as_str()),
+            AppError::Upstream(ref msg) => {
+                tracing::warn!("Upstream service error: {}", msg);
+                (StatusCode::BAD_GATEWAY, msg.as_str())
+            }
         };
 
         let body = Json(json!({
@@ -64,7 +84,12 @@ impl IntoResponse for AppError {
             "details": self.to_string(),
         }));
 
-        (status, body).into_response()
+        let mut response = (status, body).into_response();
+        if let AppError::RetryLater(_, retry_after) = self {
+            response.headers_mut().insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+        }
+
+        response
     }
 }
 
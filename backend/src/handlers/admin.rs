@@ -0,0 +1,91 @@
+use axum::{extract::{State, Path, Query}, http::StatusCode, Extension, Json};
+use serde::Deserialize;
+use crate::config::AppState;
+use crate::error::AppResult;
+use crate::services;
+use shared::models::{
+    AdminProjectResponse, AdminUserListResponse, AdminUserResponse, Claims, ImpersonationResponse,
+    UpdateUserRoleRequest,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct AdminUserParams {
+    pub search: Option<String>,
+    pub limit: Option<u64>,
+    pub offset: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AdminProjectParams {
+    pub search: Option<String>,
+}
+
+/// GET /api/admin/users - Search users by email or name
+pub async fn admin_list_users(
+    State(state): State<AppState>,
+    Query(params): Query<AdminUserParams>,
+) -> AppResult<Json<AdminUserListResponse>> {
+    let users = services::list_users(&state.db, params.search.as_deref(), params.limit, params.offset).await?;
+    Ok(Json(users))
+}
+
+/// POST /api/admin/users/:user_id/deactivate - Disable an account and end its sessions
+pub async fn admin_deactivate_user(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(user_id): Path<String>,
+) -> AppResult<Json<AdminUserResponse>> {
+    let user = services::deactivate_user(&state.db, &claims.sub, &user_id).await?;
+    Ok(Json(user))
+}
+
+/// POST /api/admin/users/:user_id/reactivate - Re-enable a deactivated account
+pub async fn admin_reactivate_user(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(user_id): Path<String>,
+) -> AppResult<Json<AdminUserResponse>> {
+    let user = services::reactivate_user(&state.db, &claims.sub, &user_id).await?;
+    Ok(Json(user))
+}
+
+/// PUT /api/admin/users/:user_id/role - Change a user's platform role
+pub async fn admin_update_user_role(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(user_id): Path<String>,
+    Json(req): Json<UpdateUserRoleRequest>,
+) -> AppResult<Json<AdminUserResponse>> {
+    let user = services::update_user_role(&state.db, &claims.sub, &user_id, req).await?;
+    Ok(Json(user))
+}
+
+/// POST /api/admin/users/:user_id/impersonate - Get a short-lived token to act as a user
+pub async fn admin_impersonate_user(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(user_id): Path<String>,
+) -> AppResult<Json<ImpersonationResponse>> {
+    let response = services::impersonate_user(&state.db, &claims.sub, &user_id, &state.config).await?;
+    Ok(Json(response))
+}
+
+/// GET /api/admin/projects - List every project with its owner and resource usage
+pub async fn admin_list_projects(
+    State(state): State<AppState>,
+    Query(params): Query<AdminProjectParams>,
+) -> AppResult<Json<Vec<AdminProjectResponse>>> {
+    let projects = services::list_all_projects(&state.db, params.search.as_deref()).await?;
+    Ok(Json(projects))
+}
+
+/// DELETE /api/admin/projects/:slug - Delete any project
+pub async fn admin_delete_project(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(slug): Path<String>,
+) -> AppResult<StatusCode> {
+    services::ensure_mfa_verified(&state.db, &claims).await?;
+    services::force_delete_project(&state.db, &claims.sub, &slug).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
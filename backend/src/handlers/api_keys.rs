@@ -0,0 +1,36 @@
+use axum::{extract::{State, Path}, http::StatusCode, Extension, Json};
+use crate::config::AppState;
+use crate::error::AppResult;
+use crate::services;
+use shared::models::{ApiKeyResponse, Claims, CreateApiKeyRequest, CreatedApiKeyResponse};
+
+/// GET /api/projects/:slug/api-keys - List a project's API keys
+pub async fn list_api_keys(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(slug): Path<String>,
+) -> AppResult<Json<Vec<ApiKeyResponse>>> {
+    let keys = services::list_api_keys(&state.db, &claims.sub, &slug).await?;
+    Ok(Json(keys))
+}
+
+/// POST /api/projects/:slug/api-keys - Create an API key, returning the full key once
+pub async fn create_api_key(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(slug): Path<String>,
+    Json(req): Json<CreateApiKeyRequest>,
+) -> AppResult<(StatusCode, Json<CreatedApiKeyResponse>)> {
+    let key = services::create_api_key(&state.db, &claims.sub, &slug, req).await?;
+    Ok((StatusCode::CREATED, Json(key)))
+}
+
+/// DELETE /api/projects/:slug/api-keys/:api_key_id - Revoke an API key
+pub async fn revoke_api_key(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((slug, api_key_id)): Path<(String, String)>,
+) -> AppResult<Json<ApiKeyResponse>> {
+    let key = services::revoke_api_key(&state.db, &claims.sub, &slug, &api_key_id).await?;
+    Ok(Json(key))
+}
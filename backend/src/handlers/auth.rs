@@ -1,9 +1,14 @@
-use axum::{extract::{State, Request}, Json};
+use std::net::SocketAddr;
+
+use axum::{extract::{ConnectInfo, State, Request}, http::HeaderMap, Extension, Json};
 
 use crate::config::AppState;
 use crate::error::AppResult;
 use crate::services;
-use shared::models::{AuthResponse, LoginRequest, RegisterRequest, UserResponse};
+use shared::models::{
+    AuthResponse, ChangePasswordRequest, Claims, ForgotPasswordRequest, LoginRequest, LoginResponse, LogoutRequest,
+    RefreshTokenRequest, RegisterRequest, ResetPasswordRequest, UserResponse, VerifyEmailRequest,
+};
 
 /// POST /auth/register - Register a new user
 pub async fn register(
@@ -12,31 +17,91 @@ pub async fn register(
 ) -> AppResult<Json<AuthResponse>> {
     let response = services::register_user(
         &state.db,
+        state.mailer.as_ref(),
         req,
-        &state.config.jwt_secret,
-        state.config.jwt_expiration_hours,
+        &state.config,
     )
     .await?;
 
     Ok(Json(response))
 }
 
-/// POST /auth/login - Login a user
+/// POST /auth/login - Login a user, or return an MFA challenge if they have MFA enabled
 pub async fn login(
     State(state): State<AppState>,
+    ConnectInfo(peer): ConnectInfo<SocketAddr>,
+    headers: HeaderMap,
     Json(req): Json<LoginRequest>,
-) -> AppResult<Json<AuthResponse>> {
+) -> AppResult<Json<LoginResponse>> {
+    let client_ip = crate::middleware::client_ip(&headers, peer, state.config.trusted_proxy_hops);
     let response = services::login_user(
         &state.db,
         req,
-        &state.config.jwt_secret,
-        state.config.jwt_expiration_hours,
+        client_ip,
+        &state.config,
     )
     .await?;
 
     Ok(Json(response))
 }
 
+/// POST /auth/refresh - Rotate a refresh token for a new access token
+pub async fn refresh(
+    State(state): State<AppState>,
+    Json(req): Json<RefreshTokenRequest>,
+) -> AppResult<Json<AuthResponse>> {
+    let response = services::refresh_session(&state.db, req, &state.config).await?;
+    Ok(Json(response))
+}
+
+/// POST /auth/password-reset - Email a password reset link, if the account exists
+pub async fn request_password_reset(
+    State(state): State<AppState>,
+    Json(req): Json<ForgotPasswordRequest>,
+) -> AppResult<Json<serde_json::Value>> {
+    services::request_password_reset(&state.db, state.mailer.as_ref(), req, &state.config).await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "If an account exists for that email, a password reset link has been sent"
+    })))
+}
+
+/// POST /auth/password-reset/confirm - Set a new password with a reset token
+pub async fn reset_password(
+    State(state): State<AppState>,
+    Json(req): Json<ResetPasswordRequest>,
+) -> AppResult<Json<serde_json::Value>> {
+    services::reset_password(&state.db, req).await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Password has been reset; please sign in with your new password"
+    })))
+}
+
+/// POST /auth/verify-email - Verify the user's email address with a verification token
+pub async fn verify_email(
+    State(state): State<AppState>,
+    Json(req): Json<VerifyEmailRequest>,
+) -> AppResult<Json<serde_json::Value>> {
+    services::verify_email(&state.db, req).await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Email address verified"
+    })))
+}
+
+/// POST /auth/verify-email/resend - Send the current user a new verification link
+pub async fn resend_verification_email(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<serde_json::Value>> {
+    services::resend_verification_email(&state.db, state.mailer.as_ref(), &claims.sub, &state.config).await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Verification email sent"
+    })))
+}
+
 /// GET /auth/me - Get current user info (requires auth middleware)
 pub async fn get_current_user(
     State(state): State<AppState>,
@@ -50,11 +115,40 @@ pub async fn get_current_user(
     Ok(Json(user))
 }
 
-/// POST /auth/logout - Logout (client-side token removal)
-/// This is mostly handled client-side by removing the JWT token
-/// Server can optionally implement token blacklisting here
-pub async fn logout() -> AppResult<Json<serde_json::Value>> {
+/// PUT /auth/password - Change the current user's password, signing out every other session
+pub async fn change_password(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<ChangePasswordRequest>,
+) -> AppResult<Json<AuthResponse>> {
+    services::ensure_not_impersonating(&claims)?;
+    let response = services::change_password(&state.db, &claims, req, &state.config).await?;
+    Ok(Json(response))
+}
+
+/// POST /auth/logout - Revoke the current access token, and its refresh token if one is sent
+pub async fn logout(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    req: Option<Json<LogoutRequest>>,
+) -> AppResult<Json<serde_json::Value>> {
+    let req = req.map(|Json(req)| req).unwrap_or_default();
+    services::logout_user(&state.db, &claims, req).await?;
+
     Ok(Json(serde_json::json!({
         "message": "Logged out successfully"
     })))
 }
+
+/// POST /auth/logout-all - Revoke every session of the current user
+pub async fn logout_all(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<serde_json::Value>> {
+    services::ensure_not_impersonating(&claims)?;
+    services::logout_all_sessions(&state.db, &claims.sub).await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Logged out of all sessions"
+    })))
+}
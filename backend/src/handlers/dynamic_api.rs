@@ -3,12 +3,11 @@ use axum::{
     Json,
     http::StatusCode,
 };
-use serde::{Deserialize, Serialize};
+use serde::Deserialize;
 use serde_json::Value as JsonValue;
 use crate::config::AppState;
 use crate::error::AppResult;
-use crate::services;
-use shared::models::Claims;
+use crate::services::{self, Principal};
 
 #[derive(Debug, Deserialize)]
 pub struct QueryParams {
@@ -20,13 +19,13 @@ pub struct QueryParams {
 /// List all rows from a user-defined table
 pub async fn list_table_rows(
     State(state): State<AppState>,
-    Extension(claims): Extension<Claims>,
+    Extension(principal): Extension<Principal>,
     Path((project_slug, table_name)): Path<(String, String)>,
     Query(params): Query<QueryParams>,
 ) -> AppResult<Json<Vec<JsonValue>>> {
     let rows = services::query_table(
         &state.db,
-        &claims.sub,
+        &principal,
         &project_slug,
         &table_name,
         None,
@@ -42,12 +41,12 @@ pub async fn list_table_rows(
 /// Get a single row by ID from a user-defined table
 pub async fn get_table_row(
     State(state): State<AppState>,
-    Extension(claims): Extension<Claims>,
+    Extension(principal): Extension<Principal>,
     Path((project_slug, table_name, row_id)): Path<(String, String, String)>,
 ) -> AppResult<Json<JsonValue>> {
     let row = services::get_table_row(
         &state.db,
-        &claims.sub,
+        &principal,
         &project_slug,
         &table_name,
         &row_id,
@@ -61,13 +60,13 @@ pub async fn get_table_row(
 /// Create a new row in a user-defined table
 pub async fn create_table_row(
     State(state): State<AppState>,
-    Extension(claims): Extension<Claims>,
+    Extension(principal): Extension<Principal>,
     Path((project_slug, table_name)): Path<(String, String)>,
     Json(data): Json<JsonValue>,
 ) -> AppResult<(StatusCode, Json<JsonValue>)> {
     let row = services::insert_table_row(
         &state.db,
-        &claims.sub,
+        &principal,
         &project_slug,
         &table_name,
         data,
@@ -81,13 +80,13 @@ pub async fn create_table_row(
 /// Update a row in a user-defined table
 pub async fn update_table_row(
     State(state): State<AppState>,
-    Extension(claims): Extension<Claims>,
+    Extension(principal): Extension<Principal>,
     Path((project_slug, table_name, row_id)): Path<(String, String, String)>,
     Json(data): Json<JsonValue>,
 ) -> AppResult<Json<JsonValue>> {
     let row = services::update_table_row(
         &state.db,
-        &claims.sub,
+        &principal,
         &project_slug,
         &table_name,
         &row_id,
@@ -102,14 +101,14 @@ pub async fn update_table_row(
 /// Partially update a row in a user-defined table
 pub async fn patch_table_row(
     State(state): State<AppState>,
-    Extension(claims): Extension<Claims>,
+    Extension(principal): Extension<Principal>,
     Path((project_slug, table_name, row_id)): Path<(String, String, String)>,
     Json(data): Json<JsonValue>,
 ) -> AppResult<Json<JsonValue>> {
     // PATCH works the same as PUT in this case since we only update provided fields
     let row = services::update_table_row(
         &state.db,
-        &claims.sub,
+        &principal,
         &project_slug,
         &table_name,
         &row_id,
@@ -124,12 +123,12 @@ pub async fn patch_table_row(
 /// Delete a row from a user-defined table
 pub async fn delete_table_row(
     State(state): State<AppState>,
-    Extension(claims): Extension<Claims>,
+    Extension(principal): Extension<Principal>,
     Path((project_slug, table_name, row_id)): Path<(String, String, String)>,
 ) -> AppResult<StatusCode> {
     services::delete_table_row(
         &state.db,
-        &claims.sub,
+        &principal,
         &project_slug,
         &table_name,
         &row_id,
@@ -138,42 +137,3 @@ pub async fn delete_table_row(
 
     Ok(StatusCode::NO_CONTENT)
 }
-
-#[derive(Debug, Deserialize)]
-pub struct ExecuteSqlRequest {
-    pub query: String,
-}
-
-#[derive(Debug, Serialize)]
-pub struct ExecuteSqlResponse {
-    pub rows: Vec<JsonValue>,
-    pub rows_affected: Option<u64>,
-    pub execution_time_ms: u128,
-}
-
-/// POST /api/sql/:project_slug
-/// Execute arbitrary SQL query within project context
-pub async fn execute_sql(
-    State(state): State<AppState>,
-    Extension(claims): Extension<Claims>,
-    Path(project_slug): Path<String>,
-    Json(request): Json<ExecuteSqlRequest>,
-) -> AppResult<Json<ExecuteSqlResponse>> {
-    let start_time = std::time::Instant::now();
-    
-    let (rows, rows_affected) = services::execute_sql(
-        &state.db,
-        &claims.sub,
-        &project_slug,
-        &request.query,
-    )
-    .await?;
-    
-    let execution_time_ms = start_time.elapsed().as_millis();
-    
-    Ok(Json(ExecuteSqlResponse {
-        rows,
-        rows_affected,
-        execution_time_ms,
-    }))
-}
@@ -0,0 +1,61 @@
+use axum::{extract::{State, Path}, Extension, Json};
+use crate::config::AppState;
+use crate::error::AppResult;
+use crate::services::{self, Principal};
+use serde_json::{Map, Value as JsonValue};
+use shared::models::{Claims, CreateFunctionRequest, FunctionResponse};
+
+/// GET /api/projects/:slug/functions - List all functions in a project
+pub async fn list_functions(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(slug): Path<String>,
+) -> AppResult<Json<Vec<FunctionResponse>>> {
+    let functions = services::list_project_functions(&state.db, &claims.sub, &slug).await?;
+    Ok(Json(functions))
+}
+
+/// POST /api/projects/:slug/functions - Create a SQL function
+pub async fn create_function(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(slug): Path<String>,
+    Json(req): Json<CreateFunctionRequest>,
+) -> AppResult<Json<FunctionResponse>> {
+    let function = services::create_project_function(&state.db, &claims.sub, &slug, req).await?;
+    Ok(Json(function))
+}
+
+/// GET /api/projects/:slug/functions/:function_name - Get a function definition
+pub async fn get_function(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((slug, function_name)): Path<(String, String)>,
+) -> AppResult<Json<FunctionResponse>> {
+    let function = services::get_project_function(&state.db, &claims.sub, &slug, &function_name).await?;
+    Ok(Json(function))
+}
+
+/// DELETE /api/projects/:slug/functions/:function_name - Drop a function
+pub async fn delete_function(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((slug, function_name)): Path<(String, String)>,
+) -> AppResult<Json<serde_json::Value>> {
+    services::delete_project_function(&state.db, &claims.sub, &slug, &function_name).await?;
+    
+    Ok(Json(serde_json::json!({
+        "message": "Function deleted successfully"
+    })))
+}
+
+/// POST /api/rpc/:project_slug/:function_name - Call a function with named arguments
+pub async fn call_function(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((project_slug, function_name)): Path<(String, String)>,
+    Json(args): Json<Map<String, JsonValue>>,
+) -> AppResult<Json<JsonValue>> {
+    let result = services::call_project_function(&state.db, &principal, &project_slug, &function_name, args).await?;
+    Ok(Json(result))
+}
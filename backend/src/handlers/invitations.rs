@@ -0,0 +1,75 @@
+use axum::{extract::{State, Path}, http::StatusCode, Extension, Json};
+use crate::config::AppState;
+use crate::error::AppResult;
+use crate::services;
+use shared::models::{
+    Claims, CreateInvitationRequest, InvitationDetailsResponse, InvitationResponse, InvitationTokenRequest,
+    ProjectResponse,
+};
+
+/// GET /api/projects/:slug/invitations - List a project's pending invitations
+pub async fn list_invitations(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(slug): Path<String>,
+) -> AppResult<Json<Vec<InvitationResponse>>> {
+    let invitations = services::list_project_invitations(&state.db, &claims.sub, &slug).await?;
+    Ok(Json(invitations))
+}
+
+/// POST /api/projects/:slug/invitations - Email an invitation to join a project
+pub async fn create_invitation(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(slug): Path<String>,
+    Json(req): Json<CreateInvitationRequest>,
+) -> AppResult<(StatusCode, Json<InvitationResponse>)> {
+    let invitation = services::create_project_invitation(
+        &state.db,
+        state.mailer.as_ref(),
+        &claims.sub,
+        &slug,
+        req,
+        &state.config,
+    )
+    .await?;
+    Ok((StatusCode::CREATED, Json(invitation)))
+}
+
+/// DELETE /api/projects/:slug/invitations/:invitation_id - Revoke a pending invitation
+pub async fn revoke_invitation(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((slug, invitation_id)): Path<(String, String)>,
+) -> AppResult<StatusCode> {
+    services::revoke_project_invitation(&state.db, &claims.sub, &slug, &invitation_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// POST /api/invitations/lookup - Describe the invitation behind a link
+pub async fn get_invitation(
+    State(state): State<AppState>,
+    Json(req): Json<InvitationTokenRequest>,
+) -> AppResult<Json<InvitationDetailsResponse>> {
+    let invitation = services::get_invitation_details(&state.db, req).await?;
+    Ok(Json(invitation))
+}
+
+/// POST /api/invitations/accept - Join the project an invitation was sent for
+pub async fn accept_invitation(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<InvitationTokenRequest>,
+) -> AppResult<Json<ProjectResponse>> {
+    let project = services::accept_invitation(&state.db, &claims.sub, req).await?;
+    Ok(Json(project))
+}
+
+/// POST /api/invitations/decline - Turn down an invitation
+pub async fn decline_invitation(
+    State(state): State<AppState>,
+    Json(req): Json<InvitationTokenRequest>,
+) -> AppResult<StatusCode> {
+    services::decline_invitation(&state.db, req).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
@@ -0,0 +1,47 @@
+use axum::{extract::{State, Path}, http::StatusCode, Extension, Json};
+use crate::config::AppState;
+use crate::error::AppResult;
+use crate::services;
+use shared::models::{AddMemberRequest, Claims, MemberResponse, UpdateMemberRequest};
+
+/// GET /api/projects/:slug/members - List a project's members and their roles
+pub async fn list_members(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(slug): Path<String>,
+) -> AppResult<Json<Vec<MemberResponse>>> {
+    let members = services::list_project_members(&state.db, &claims.sub, &slug).await?;
+    Ok(Json(members))
+}
+
+/// POST /api/projects/:slug/members - Add an existing user to a project
+pub async fn add_member(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(slug): Path<String>,
+    Json(req): Json<AddMemberRequest>,
+) -> AppResult<(StatusCode, Json<MemberResponse>)> {
+    let member = services::add_project_member(&state.db, &claims.sub, &slug, req).await?;
+    Ok((StatusCode::CREATED, Json(member)))
+}
+
+/// PUT /api/projects/:slug/members/:user_id - Change a member's role
+pub async fn update_member(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((slug, user_id)): Path<(String, String)>,
+    Json(req): Json<UpdateMemberRequest>,
+) -> AppResult<Json<MemberResponse>> {
+    let member = services::update_project_member(&state.db, &claims.sub, &slug, &user_id, req).await?;
+    Ok(Json(member))
+}
+
+/// DELETE /api/projects/:slug/members/:user_id - Remove a member, or leave a project
+pub async fn remove_member(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((slug, user_id)): Path<(String, String)>,
+) -> AppResult<StatusCode> {
+    services::remove_project_member(&state.db, &claims.sub, &slug, &user_id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
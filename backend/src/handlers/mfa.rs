@@ -0,0 +1,70 @@
+use axum::{extract::State, Extension, Json};
+use crate::config::AppState;
+use crate::error::AppResult;
+use crate::services;
+use shared::models::{
+    AuthResponse, Claims, MfaCodeRequest, MfaEnabledResponse, MfaStatusResponse, MfaVerifyRequest,
+    RecoveryCodesResponse, TotpEnrollmentResponse,
+};
+
+/// POST /auth/mfa/verify - Finish signing in with a code for an MFA challenge
+pub async fn verify_mfa(
+    State(state): State<AppState>,
+    Json(req): Json<MfaVerifyRequest>,
+) -> AppResult<Json<AuthResponse>> {
+    let response = services::verify_mfa_challenge(&state.db, req, &state.config).await?;
+    Ok(Json(response))
+}
+
+/// GET /auth/mfa - Get the current user's multi-factor authentication settings
+pub async fn get_mfa_status(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<MfaStatusResponse>> {
+    let status = services::get_mfa_status(&state.db, &claims.sub).await?;
+    Ok(Json(status))
+}
+
+/// POST /auth/mfa/totp/enroll - Start setting up an authenticator app
+pub async fn enroll_totp(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<TotpEnrollmentResponse>> {
+    services::ensure_not_impersonating(&claims)?;
+    let enrollment = services::enroll_totp(&state.db, &claims.sub).await?;
+    Ok(Json(enrollment))
+}
+
+/// POST /auth/mfa/totp/confirm - Enable MFA with a code from the new authenticator app
+pub async fn confirm_totp(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Json(req): Json<MfaCodeRequest>,
+) -> AppResult<Json<MfaEnabledResponse>> {
+    services::ensure_not_impersonating(&claims)?;
+    let response = services::confirm_totp(&state.db, &claims, req, &state.config).await?;
+    Ok(Json(response))
+}
+
+/// POST /auth/mfa/recovery-codes - Replace the current user's recovery codes
+pub async fn regenerate_recovery_codes(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<RecoveryCodesResponse>> {
+    services::ensure_not_impersonating(&claims)?;
+    let response = services::regenerate_recovery_codes(&state.db, &claims).await?;
+    Ok(Json(response))
+}
+
+/// DELETE /auth/mfa - Turn off multi-factor authentication
+pub async fn disable_mfa(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<serde_json::Value>> {
+    services::ensure_not_impersonating(&claims)?;
+    services::disable_mfa(&state.db, &claims).await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Multi-factor authentication disabled"
+    })))
+}
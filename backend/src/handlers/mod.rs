@@ -2,8 +2,30 @@ pub mod auth;
 pub mod projects;
 pub mod tables;
 pub mod dynamic_api;
+pub mod sql;
+pub mod schema;
+pub mod views;
+pub mod functions;
+pub mod policies;
+pub mod api_keys;
+pub mod members;
+pub mod invitations;
+pub mod admin;
+pub mod oidc;
+pub mod mfa;
 
 pub use auth::*;
 pub use projects::*;
 pub use tables::*;
 pub use dynamic_api::*;
+pub use sql::*;
+pub use schema::*;
+pub use views::*;
+pub use functions::*;
+pub use policies::*;
+pub use api_keys::*;
+pub use members::*;
+pub use invitations::*;
+pub use admin::*;
+pub use oidc::*;
+pub use mfa::*;
@@ -0,0 +1,39 @@
+use axum::{extract::{State, Path}, Extension, Json};
+use crate::config::AppState;
+use crate::error::AppResult;
+use crate::services;
+use shared::models::{
+    Claims, IdentityResponse, LoginResponse, OidcAuthorizeResponse, OidcCallbackRequest, OidcProviderResponse,
+};
+
+/// GET /auth/oidc/providers - List the providers users can sign in with
+pub async fn list_oidc_providers(State(state): State<AppState>) -> Json<Vec<OidcProviderResponse>> {
+    Json(services::list_oidc_providers(&state.config))
+}
+
+/// POST /auth/oidc/:provider/authorize - Start signing in with a provider
+pub async fn start_oidc_login(
+    State(state): State<AppState>,
+    Path(provider): Path<String>,
+) -> AppResult<Json<OidcAuthorizeResponse>> {
+    let response = services::start_oidc_login(&state.db, &provider, &state.config).await?;
+    Ok(Json(response))
+}
+
+/// POST /auth/oidc/callback - Finish signing in with the code the provider redirected back with
+pub async fn complete_oidc_login(
+    State(state): State<AppState>,
+    Json(req): Json<OidcCallbackRequest>,
+) -> AppResult<Json<LoginResponse>> {
+    let response = services::complete_oidc_login(&state.db, req, &state.config).await?;
+    Ok(Json(response))
+}
+
+/// GET /auth/identities - List the provider accounts linked to the current user
+pub async fn list_identities(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+) -> AppResult<Json<Vec<IdentityResponse>>> {
+    let identities = services::list_user_identities(&state.db, &claims.sub, &state.config).await?;
+    Ok(Json(identities))
+}
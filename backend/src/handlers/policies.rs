@@ -0,0 +1,39 @@
+use axum::{extract::{State, Path}, Extension, Json};
+use crate::config::AppState;
+use crate::error::AppResult;
+use crate::services;
+use shared::models::{Claims, CreatePolicyRequest, PolicyResponse};
+
+/// GET /api/projects/:slug/tables/:table_name/policies - List a table's row-level security policies
+pub async fn list_policies(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((slug, table_name)): Path<(String, String)>,
+) -> AppResult<Json<Vec<PolicyResponse>>> {
+    let policies = services::list_table_policies(&state.db, &claims.sub, &slug, &table_name).await?;
+    Ok(Json(policies))
+}
+
+/// POST /api/projects/:slug/tables/:table_name/policies - Add a row-level security policy
+pub async fn create_policy(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((slug, table_name)): Path<(String, String)>,
+    Json(req): Json<CreatePolicyRequest>,
+) -> AppResult<Json<PolicyResponse>> {
+    let policy = services::create_table_policy(&state.db, &claims.sub, &slug, &table_name, req).await?;
+    Ok(Json(policy))
+}
+
+/// DELETE /api/projects/:slug/tables/:table_name/policies/:policy_name - Remove a policy
+pub async fn delete_policy(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((slug, table_name, policy_name)): Path<(String, String, String)>,
+) -> AppResult<Json<serde_json::Value>> {
+    services::delete_table_policy(&state.db, &claims.sub, &slug, &table_name, &policy_name).await?;
+    
+    Ok(Json(serde_json::json!({
+        "message": "Policy deleted successfully"
+    })))
+}
@@ -50,6 +50,7 @@ pub async fn delete_project(
     Extension(claims): Extension<Claims>,
     Path(slug): Path<String>,
 ) -> AppResult<Json<serde_json::Value>> {
+    services::ensure_mfa_verified(&state.db, &claims).await?;
     services::delete_project(&state.db, &claims.sub, &slug).await?;
     
     Ok(Json(serde_json::json!({
@@ -0,0 +1,39 @@
+use axum::{extract::{State, Path}, Extension, Json};
+use crate::config::AppState;
+use crate::error::AppResult;
+use crate::services;
+use shared::models::{
+    ApplySchemaDiffRequest, Claims, ImportSchemaRequest, ImportSchemaResponse, SchemaDiffResponse,
+};
+
+/// POST /api/projects/:slug/schema/import - Create tables from a DDL script
+pub async fn import_schema(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(slug): Path<String>,
+    Json(req): Json<ImportSchemaRequest>,
+) -> AppResult<Json<ImportSchemaResponse>> {
+    let response = services::import_schema(&state.db, &claims.sub, &slug, &req.sql).await?;
+    Ok(Json(response))
+}
+
+/// GET /api/projects/:slug/schema/diff/:target_slug - Plan the changes that make the target match this project
+pub async fn diff_schema(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((slug, target_slug)): Path<(String, String)>,
+) -> AppResult<Json<SchemaDiffResponse>> {
+    let diff = services::diff_project_schemas(&state.db, &claims.sub, &slug, &target_slug).await?;
+    Ok(Json(diff))
+}
+
+/// POST /api/projects/:slug/schema/diff/:target_slug/apply - Apply the plan to the target project
+pub async fn apply_schema_diff(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((slug, target_slug)): Path<(String, String)>,
+    Json(req): Json<ApplySchemaDiffRequest>,
+) -> AppResult<Json<SchemaDiffResponse>> {
+    let diff = services::apply_schema_diff(&state.db, &claims.sub, &slug, &target_slug, req).await?;
+    Ok(Json(diff))
+}
@@ -0,0 +1,155 @@
+use axum::{extract::{State, Path, Query}, Extension, Json};
+use serde::Deserialize;
+use crate::config::AppState;
+use crate::error::AppResult;
+use crate::services::{self, Principal};
+use shared::models::{
+    CancelQueryResponse, Claims, CreateSavedQueryRequest, ExecuteSavedQueryRequest, ExecuteSqlRequest,
+    ExecuteSqlResponse, QueryHistoryEntry, SavedQueryResponse, SqlCompletions,
+};
+
+#[derive(Debug, Deserialize)]
+pub struct HistoryParams {
+    pub limit: Option<u64>,
+}
+
+/// POST /api/sql/:project_slug
+/// Execute a SQL script within project context
+pub async fn execute_sql(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path(project_slug): Path<String>,
+    Json(request): Json<ExecuteSqlRequest>,
+) -> AppResult<Json<ExecuteSqlResponse>> {
+    let response = services::execute_sql(&state.db, &state.queries, &principal, &project_slug, &request).await?;
+    Ok(Json(response))
+}
+
+/// POST /api/sql/:project_slug/cancel/:query_id
+/// Cancel a running SQL statement
+pub async fn cancel_sql(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((project_slug, query_id)): Path<(String, String)>,
+) -> AppResult<Json<CancelQueryResponse>> {
+    let response = services::cancel_sql_query(&state.db, &state.queries, &claims.sub, &project_slug, &query_id).await?;
+    Ok(Json(response))
+}
+
+/// GET /api/sql/:project_slug/history
+/// List the current user's recent executions in the project
+pub async fn list_sql_history(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(project_slug): Path<String>,
+    Query(params): Query<HistoryParams>,
+) -> AppResult<Json<Vec<QueryHistoryEntry>>> {
+    let history = services::list_query_history(&state.db, &claims.sub, &project_slug, params.limit).await?;
+    Ok(Json(history))
+}
+
+/// DELETE /api/sql/:project_slug/history
+/// Clear the current user's execution history in the project
+pub async fn clear_sql_history(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(project_slug): Path<String>,
+) -> AppResult<Json<serde_json::Value>> {
+    services::clear_query_history(&state.db, &claims.sub, &project_slug).await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Query history cleared"
+    })))
+}
+
+/// GET /api/sql/:project_slug/saved
+/// List the project's saved queries
+pub async fn list_saved_queries(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(project_slug): Path<String>,
+) -> AppResult<Json<Vec<SavedQueryResponse>>> {
+    let saved = services::list_saved_queries(&state.db, &claims.sub, &project_slug).await?;
+    Ok(Json(saved))
+}
+
+/// POST /api/sql/:project_slug/saved
+/// Save a named, optionally parameterized script
+pub async fn create_saved_query(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(project_slug): Path<String>,
+    Json(req): Json<CreateSavedQueryRequest>,
+) -> AppResult<Json<SavedQueryResponse>> {
+    let saved = services::create_saved_query(&state.db, &claims.sub, &project_slug, req).await?;
+    Ok(Json(saved))
+}
+
+/// GET /api/sql/:project_slug/saved/:saved_query_id
+/// Get a saved query
+pub async fn get_saved_query(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((project_slug, saved_query_id)): Path<(String, String)>,
+) -> AppResult<Json<SavedQueryResponse>> {
+    let saved = services::get_saved_query(&state.db, &claims.sub, &project_slug, &saved_query_id).await?;
+    Ok(Json(saved))
+}
+
+/// PUT /api/sql/:project_slug/saved/:saved_query_id
+/// Replace a saved query
+pub async fn update_saved_query(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((project_slug, saved_query_id)): Path<(String, String)>,
+    Json(req): Json<CreateSavedQueryRequest>,
+) -> AppResult<Json<SavedQueryResponse>> {
+    let saved =
+        services::update_saved_query(&state.db, &claims.sub, &project_slug, &saved_query_id, req).await?;
+    Ok(Json(saved))
+}
+
+/// DELETE /api/sql/:project_slug/saved/:saved_query_id
+/// Delete a saved query
+pub async fn delete_saved_query(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((project_slug, saved_query_id)): Path<(String, String)>,
+) -> AppResult<Json<serde_json::Value>> {
+    services::delete_saved_query(&state.db, &claims.sub, &project_slug, &saved_query_id).await?;
+
+    Ok(Json(serde_json::json!({
+        "message": "Saved query deleted successfully"
+    })))
+}
+
+/// POST /api/sql/:project_slug/saved/:saved_query_id/execute
+/// Run a saved query with JSON parameter values
+pub async fn execute_saved_query(
+    State(state): State<AppState>,
+    Extension(principal): Extension<Principal>,
+    Path((project_slug, saved_query_id)): Path<(String, String)>,
+    Json(req): Json<ExecuteSavedQueryRequest>,
+) -> AppResult<Json<ExecuteSqlResponse>> {
+    let response = services::execute_saved_query(
+        &state.db,
+        &state.queries,
+        &principal,
+        &project_slug,
+        &saved_query_id,
+        req,
+    )
+    .await?;
+    Ok(Json(response))
+}
+
+/// GET /api/sql/:project_slug/completions
+/// Schema, function, type and keyword completions for the SQL editor
+pub async fn get_sql_completions(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(project_slug): Path<String>,
+) -> AppResult<Json<SqlCompletions>> {
+    let completions = services::get_sql_completions(&state.db, &claims.sub, &project_slug).await?;
+    Ok(Json(completions))
+}
@@ -0,0 +1,70 @@
+use axum::{extract::{State, Path}, Extension, Json};
+use crate::config::AppState;
+use crate::error::AppResult;
+use crate::services;
+use shared::models::{Claims, CreateViewRequest, ViewRefreshScheduleRequest, ViewResponse};
+
+/// GET /api/projects/:slug/views - List all views in a project
+pub async fn list_views(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(slug): Path<String>,
+) -> AppResult<Json<Vec<ViewResponse>>> {
+    let views = services::list_project_views(&state.db, &claims.sub, &slug).await?;
+    Ok(Json(views))
+}
+
+/// POST /api/projects/:slug/views - Create a view or materialized view
+pub async fn create_view(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path(slug): Path<String>,
+    Json(req): Json<CreateViewRequest>,
+) -> AppResult<Json<ViewResponse>> {
+    let view = services::create_project_view(&state.db, &claims.sub, &slug, req).await?;
+    Ok(Json(view))
+}
+
+/// GET /api/projects/:slug/views/:view_name - Get a view with its columns
+pub async fn get_view(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((slug, view_name)): Path<(String, String)>,
+) -> AppResult<Json<ViewResponse>> {
+    let view = services::get_project_view(&state.db, &claims.sub, &slug, &view_name).await?;
+    Ok(Json(view))
+}
+
+/// DELETE /api/projects/:slug/views/:view_name - Drop a view
+pub async fn delete_view(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((slug, view_name)): Path<(String, String)>,
+) -> AppResult<Json<serde_json::Value>> {
+    services::delete_project_view(&state.db, &claims.sub, &slug, &view_name).await?;
+    
+    Ok(Json(serde_json::json!({
+        "message": "View deleted successfully"
+    })))
+}
+
+/// POST /api/projects/:slug/views/:view_name/refresh - Refresh a materialized view
+pub async fn refresh_view(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((slug, view_name)): Path<(String, String)>,
+) -> AppResult<Json<ViewResponse>> {
+    let view = services::refresh_project_view(&state.db, &claims.sub, &slug, &view_name).await?;
+    Ok(Json(view))
+}
+
+/// PUT /api/projects/:slug/views/:view_name/schedule - Set the automatic refresh interval
+pub async fn set_view_schedule(
+    State(state): State<AppState>,
+    Extension(claims): Extension<Claims>,
+    Path((slug, view_name)): Path<(String, String)>,
+    Json(req): Json<ViewRefreshScheduleRequest>,
+) -> AppResult<Json<ViewResponse>> {
+    let view = services::set_view_refresh_schedule(&state.db, &claims.sub, &slug, &view_name, req).await?;
+    Ok(Json(view))
+}
@@ -1,6 +1,7 @@
 pub mod config;
 pub mod error;
 pub mod handlers;
+pub mod mailer;
 pub mod middleware;
 pub mod routes;
 pub mod services;
@@ -1,4 +1,7 @@
-use backend::{routes, AppState, Config};
+use std::net::SocketAddr;
+
+use axum::http::header;
+use backend::{mailer, routes, AppState, Config};
 use database::{establish_connection, get_database_url};
 use tower_http::cors::{Any, CorsLayer};
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
@@ -29,15 +32,22 @@ async fn main() -> anyhow::Result<()> {
         .expect("Failed to connect to database");
     tracing::info!("Database connected and migrations applied");
 
+    // Send email over SMTP when configured, or to the local outbox
+    let mailer = mailer::mailer_from_config(&config).expect("Failed to configure mailer");
+
     // Create application state
-    let state = AppState::new(db, config.clone());
+    let state = AppState::new(db, config.clone(), mailer);
+
+    // Keep scheduled materialized views refreshed in the background
+    tokio::spawn(backend::services::run_view_refresh_scheduler(state.db.clone()));
 
     // Create router with CORS
     let app = routes::create_router(state).layer(
         CorsLayer::new()
             .allow_origin(Any)
             .allow_methods(Any)
-            .allow_headers(Any),
+            .allow_headers(Any)
+            .expose_headers([header::RETRY_AFTER]),
     );
 
     // Start server
@@ -45,7 +55,8 @@ async fn main() -> anyhow::Result<()> {
     tracing::info!("Starting server on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(&addr).await?;
-    axum::serve(listener, app).await?;
+    // Sign-in throttling needs the client's address
+    axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>()).await?;
 
     Ok(())
 }
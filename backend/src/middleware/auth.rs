@@ -1,12 +1,15 @@
 use axum::{
     extract::{Request, State},
-    http::StatusCode,
+    http::{HeaderMap, StatusCode},
     middleware::Next,
     response::Response,
 };
 
 use crate::config::AppState;
+use crate::error::AppError;
+use crate::services::api_key_service::{self, Principal};
 use crate::services::auth_service;
+use database::entities::users;
 use shared::models::Claims;
 
 /// Middleware to inject JWT secret and require authentication
@@ -15,21 +18,23 @@ pub async fn require_auth(
     mut req: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
-    // Get Authorization header
-    let auth_header = req
-        .headers()
-        .get("Authorization")
-        .and_then(|h| h.to_str().ok())
-        .ok_or(StatusCode::UNAUTHORIZED)?;
+    let (claims, user) = authenticate(&state, req.headers()).await?;
+    ensure_email_verified(&state, &user)?;
 
-    // Extract token
-    let token = auth_header
-        .strip_prefix("Bearer ")
-        .ok_or(StatusCode::UNAUTHORIZED)?;
+    // Add claims to request extensions
+    req.extensions_mut().insert(claims);
 
-    // Verify token
-    let claims = auth_service::verify_token(token, &state.config.jwt_secret)
-        .map_err(|_| StatusCode::UNAUTHORIZED)?;
+    Ok(next.run(req).await)
+}
+
+/// Middleware to require authentication, letting users whose email address is
+/// not verified yet through; used by the routes that get them verified
+pub async fn require_signed_in(
+    State(state): State<AppState>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let (claims, _) = authenticate(&state, req.headers()).await?;
 
     // Add claims to request extensions
     req.extensions_mut().insert(claims);
@@ -37,15 +42,64 @@ pub async fn require_auth(
     Ok(next.run(req).await)
 }
 
-/// Middleware to require admin role
+/// Middleware for the data and SQL APIs: accepts a project API key (in the `apikey`
+/// header or as the bearer token) as well as a user JWT, and stores the resulting
+/// `Principal` in the request extensions
+pub async fn require_project_auth(
+    State(state): State<AppState>,
+    mut req: Request,
+    next: Next,
+) -> Result<Response, StatusCode> {
+    let principal = match api_key(req.headers()) {
+        Some(key) => api_key_service::authenticate_api_key(&state.db, &key)
+            .await
+            .map_err(|e| match e {
+                AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+                e => {
+                    tracing::error!("Failed to check API key: {}", e);
+                    StatusCode::INTERNAL_SERVER_ERROR
+                }
+            })?,
+        None => {
+            let (claims, user) = authenticate(&state, req.headers()).await?;
+            ensure_email_verified(&state, &user)?;
+            req.extensions_mut().insert(claims.clone());
+            Principal::User(claims)
+        }
+    };
+
+    req.extensions_mut().insert(principal);
+
+    Ok(next.run(req).await)
+}
+
+/// Middleware to require the platform admin role.
+///
+/// The role is read from the database rather than the token, so a demoted admin
+/// loses access straight away.
 pub async fn require_admin(
     State(state): State<AppState>,
     mut req: Request,
     next: Next,
 ) -> Result<Response, StatusCode> {
+    let (claims, user) = authenticate(&state, req.headers()).await?;
+    ensure_email_verified(&state, &user)?;
+
+    // Check if user is admin
+    if user.role.as_deref() != Some("admin") {
+        return Err(StatusCode::FORBIDDEN);
+    }
+
+    // Add claims to request extensions
+    req.extensions_mut().insert(claims);
+
+    Ok(next.run(req).await)
+}
+
+/// Verify the bearer token of a request, returning its claims and the user it belongs to
+async fn authenticate(state: &AppState, headers: &HeaderMap) -> Result<(Claims, users::Model), StatusCode> {
     // Get Authorization header
-    let auth_header = req
-        .headers()
+    let auth_header = headers
         .get("Authorization")
         .and_then(|h| h.to_str().ok())
         .ok_or(StatusCode::UNAUTHORIZED)?;
@@ -59,15 +113,44 @@ pub async fn require_admin(
     let claims = auth_service::verify_token(token, &state.config.jwt_secret)
         .map_err(|_| StatusCode::UNAUTHORIZED)?;
 
-    // Check if user is admin
-    if claims.role != "admin" {
-        return Err(StatusCode::FORBIDDEN);
+    // Reject tokens revoked since they were issued
+    let user = auth_service::ensure_token_active(&state.db, &claims)
+        .await
+        .map_err(|e| match e {
+            AppError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            e => {
+                tracing::error!("Failed to check token revocation: {}", e);
+                StatusCode::INTERNAL_SERVER_ERROR
+            }
+        })?;
+
+    Ok((claims, user))
+}
+
+/// The API key a request carries; a user JWT in `Authorization` takes precedence over `apikey`
+fn api_key(headers: &HeaderMap) -> Option<String> {
+    let bearer = headers
+        .get("Authorization")
+        .and_then(|h| h.to_str().ok())
+        .and_then(|h| h.strip_prefix("Bearer "));
+
+    match bearer {
+        Some(token) if api_key_service::is_api_key(token) => Some(token.to_string()),
+        Some(_) => None,
+        None => headers
+            .get("apikey")
+            .and_then(|h| h.to_str().ok())
+            .map(str::to_string),
     }
+}
 
-    // Add claims to request extensions
-    req.extensions_mut().insert(claims);
+/// Hold back users with an unverified email address when the deployment requires verification
+fn ensure_email_verified(state: &AppState, user: &users::Model) -> Result<(), StatusCode> {
+    if state.config.require_email_verification && !user.email_verified.unwrap_or(false) {
+        return Err(StatusCode::FORBIDDEN);
+    }
 
-    Ok(next.run(req).await)
+    Ok(())
 }
 
 /// Helper function to extract claims from request extensions
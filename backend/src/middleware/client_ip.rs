@@ -0,0 +1,56 @@
+use std::net::{IpAddr, SocketAddr};
+
+use axum::http::HeaderMap;
+
+/// Address of the client that sent a request.
+///
+/// Behind reverse proxies every connection comes from the nearest proxy. Each proxy
+/// appends the address it received the request from to `X-Forwarded-For`, so with
+/// `trusted_proxy_hops` proxies in front of the server the client is that many entries
+/// from the right; anything further left was written by the client itself. Without
+/// trusted proxies, or when the header has fewer entries, the peer address is used.
+pub fn client_ip(headers: &HeaderMap, peer: SocketAddr, trusted_proxy_hops: usize) -> IpAddr {
+    if trusted_proxy_hops == 0 {
+        return peer.ip();
+    }
+
+    // Repeated headers form a single list, in order
+    let forwarded: Vec<&str> = headers
+        .get_all("x-forwarded-for")
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .map(str::trim)
+        .collect();
+
+    forwarded
+        .len()
+        .checked_sub(trusted_proxy_hops)
+        .and_then(|index| forwarded[index].parse().ok())
+        .unwrap_or_else(|| peer.ip())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn forwarded_for(values: &[&str]) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        for value in values {
+            headers.append("x-forwarded-for", value.parse().unwrap());
+        }
+        headers
+    }
+
+    #[test]
+    fn spoofed_entries_left_of_the_proxies_are_ignored() {
+        let peer: SocketAddr = "10.0.0.2:4000".parse().unwrap();
+        let headers = forwarded_for(&["1.1.1.1, 203.0.113.7", "10.0.0.1"]);
+
+        assert_eq!(client_ip(&headers, peer, 0), peer.ip());
+        assert_eq!(client_ip(&headers, peer, 1), "10.0.0.1".parse::<IpAddr>().unwrap());
+        assert_eq!(client_ip(&headers, peer, 2), "203.0.113.7".parse::<IpAddr>().unwrap());
+        assert_eq!(client_ip(&headers, peer, 4), peer.ip());
+        assert_eq!(client_ip(&HeaderMap::new(), peer, 1), peer.ip());
+    }
+}
@@ -1,5 +1,5 @@
 use axum::{
-    routing::{get, post},
+    routing::{delete, get, post, put},
     Router,
 };
 
@@ -24,12 +24,72 @@ pub fn create_router(state: AppState) -> Router {
             get(handlers::get_table)
                 .delete(handlers::delete_table)
         )
+        .route("/{slug}/tables/{table_name}/policies",
+            get(handlers::list_policies)
+                .post(handlers::create_policy)
+        )
+        .route("/{slug}/tables/{table_name}/policies/{policy_name}",
+            delete(handlers::delete_policy)
+        )
+        .route("/{slug}/schema/import",
+            post(handlers::import_schema)
+        )
+        .route("/{slug}/schema/diff/{target_slug}",
+            get(handlers::diff_schema)
+        )
+        .route("/{slug}/schema/diff/{target_slug}/apply",
+            post(handlers::apply_schema_diff)
+        )
+        .route("/{slug}/views",
+            get(handlers::list_views)
+                .post(handlers::create_view)
+        )
+        .route("/{slug}/views/{view_name}",
+            get(handlers::get_view)
+                .delete(handlers::delete_view)
+        )
+        .route("/{slug}/views/{view_name}/refresh",
+            post(handlers::refresh_view)
+        )
+        .route("/{slug}/views/{view_name}/schedule",
+            put(handlers::set_view_schedule)
+        )
+        .route("/{slug}/functions",
+            get(handlers::list_functions)
+                .post(handlers::create_function)
+        )
+        .route("/{slug}/functions/{function_name}",
+            get(handlers::get_function)
+                .delete(handlers::delete_function)
+        )
+        .route("/{slug}/api-keys",
+            get(handlers::list_api_keys)
+                .post(handlers::create_api_key)
+        )
+        .route("/{slug}/api-keys/{api_key_id}",
+            delete(handlers::revoke_api_key)
+        )
+        .route("/{slug}/members",
+            get(handlers::list_members)
+                .post(handlers::add_member)
+        )
+        .route("/{slug}/members/{user_id}",
+            put(handlers::update_member)
+                .delete(handlers::remove_member)
+        )
+        .route("/{slug}/invitations",
+            get(handlers::list_invitations)
+                .post(handlers::create_invitation)
+        )
+        .route("/{slug}/invitations/{invitation_id}",
+            delete(handlers::revoke_invitation)
+        )
         .layer(axum::middleware::from_fn_with_state(
             state.clone(),
             middleware::require_auth
         ));
 
-    // Dynamic REST API routes for user-defined tables
+    // Dynamic REST API routes for user-defined tables, open to project API keys
     let dynamic_api = Router::new()
         .route("/{project_slug}/{table_name}",
             get(handlers::list_table_rows)
@@ -43,17 +103,97 @@ pub fn create_router(state: AppState) -> Router {
         )
         .layer(axum::middleware::from_fn_with_state(
             state.clone(),
-            middleware::require_auth
+            middleware::require_project_auth
         ));
 
-    // SQL execution API
-    let sql_api = Router::new()
+    // SQL execution API; scripts and saved queries can also be run with a service key
+    let sql_execute = Router::new()
         .route("/{project_slug}",
             post(handlers::execute_sql)
         )
+        .route("/{project_slug}/saved/{saved_query_id}/execute",
+            post(handlers::execute_saved_query)
+        )
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::require_project_auth
+        ));
+
+    let sql_api = Router::new()
+        .route("/{project_slug}/cancel/{query_id}",
+            post(handlers::cancel_sql)
+        )
+        .route("/{project_slug}/completions",
+            get(handlers::get_sql_completions)
+        )
+        .route("/{project_slug}/history",
+            get(handlers::list_sql_history)
+                .delete(handlers::clear_sql_history)
+        )
+        .route("/{project_slug}/saved",
+            get(handlers::list_saved_queries)
+                .post(handlers::create_saved_query)
+        )
+        .route("/{project_slug}/saved/{saved_query_id}",
+            get(handlers::get_saved_query)
+                .put(handlers::update_saved_query)
+                .delete(handlers::delete_saved_query)
+        )
         .layer(axum::middleware::from_fn_with_state(
             state.clone(),
             middleware::require_auth
+        ))
+        .merge(sql_execute);
+
+    // RPC API for project functions
+    let rpc_api = Router::new()
+        .route("/{project_slug}/{function_name}",
+            post(handlers::call_function)
+        )
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::require_project_auth
+        ));
+
+    // Auth routes for signed-in users, including those who still have to verify their email
+    let protected_auth = Router::new()
+        .route("/me", get(handlers::get_current_user))
+        .route("/logout", post(handlers::logout))
+        .route("/logout-all", post(handlers::logout_all))
+        .route("/password", put(handlers::change_password))
+        .route("/verify-email/resend", post(handlers::resend_verification_email))
+        .route("/identities", get(handlers::list_identities))
+        .route("/mfa", get(handlers::get_mfa_status).delete(handlers::disable_mfa))
+        .route("/mfa/totp/enroll", post(handlers::enroll_totp))
+        .route("/mfa/totp/confirm", post(handlers::confirm_totp))
+        .route("/mfa/recovery-codes", post(handlers::regenerate_recovery_codes))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::require_signed_in
+        ));
+
+    // Invitation links can be looked up and declined without an account; accepting needs one
+    let invitations = Router::new()
+        .route("/accept", post(handlers::accept_invitation))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::require_signed_in
+        ))
+        .route("/lookup", post(handlers::get_invitation))
+        .route("/decline", post(handlers::decline_invitation));
+
+    // Platform administration
+    let admin = Router::new()
+        .route("/users", get(handlers::admin_list_users))
+        .route("/users/{user_id}/deactivate", post(handlers::admin_deactivate_user))
+        .route("/users/{user_id}/reactivate", post(handlers::admin_reactivate_user))
+        .route("/users/{user_id}/role", put(handlers::admin_update_user_role))
+        .route("/users/{user_id}/impersonate", post(handlers::admin_impersonate_user))
+        .route("/projects", get(handlers::admin_list_projects))
+        .route("/projects/{slug}", delete(handlers::admin_delete_project))
+        .layer(axum::middleware::from_fn_with_state(
+            state.clone(),
+            middleware::require_admin
         ));
 
     Router::new()
@@ -65,15 +205,28 @@ pub fn create_router(state: AppState) -> Router {
                 // Auth routes (public)
                 .route("/auth/register", post(handlers::register))
                 .route("/auth/login", post(handlers::login))
-                .route("/auth/logout", post(handlers::logout))
+                .route("/auth/refresh", post(handlers::refresh))
+                .route("/auth/password-reset", post(handlers::request_password_reset))
+                .route("/auth/password-reset/confirm", post(handlers::reset_password))
+                .route("/auth/verify-email", post(handlers::verify_email))
+                .route("/auth/mfa/verify", post(handlers::verify_mfa))
+                .route("/auth/oidc/providers", get(handlers::list_oidc_providers))
+                .route("/auth/oidc/{provider}/authorize", post(handlers::start_oidc_login))
+                .route("/auth/oidc/callback", post(handlers::complete_oidc_login))
                 // Protected auth routes
-                .route("/auth/me", get(handlers::get_current_user))
+                .nest("/auth", protected_auth)
                 // Project routes (protected)
                 .nest("/projects", protected_projects)
+                // Project invitation links
+                .nest("/invitations", invitations)
                 // Dynamic data API (protected)
                 .nest("/data", dynamic_api)
                 // SQL execution API (protected)
                 .nest("/sql", sql_api)
+                // Function RPC API (protected)
+                .nest("/rpc", rpc_api)
+                // Admin API (admins only)
+                .nest("/admin", admin)
         )
         .with_state(state)
 }
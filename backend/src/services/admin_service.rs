@@ -0,0 +1,327 @@
+use std::collections::HashMap;
+
+use chrono::Utc;
+use sea_orm::sea_query::{Expr, Func};
+use sea_orm::*;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use crate::services::audit_service::{record_audit_event, AuditAction};
+use crate::services::auth_service::{generate_impersonation_token, revoke_user_sessions, user_response};
+use crate::services::project_service::drop_project;
+use database::entities::prelude::*;
+use database::entities::{project_members, project_tables, projects, users};
+use shared::models::{
+    AdminProjectResponse, AdminUserListResponse, AdminUserResponse, ImpersonationResponse, UpdateUserRoleRequest,
+};
+
+const DEFAULT_PAGE_SIZE: u64 = 50;
+const MAX_PAGE_SIZE: u64 = 200;
+const PLATFORM_ROLES: [&str; 2] = ["user", "admin"];
+
+/// List users whose email or name contains `search`, newest first
+pub async fn list_users(
+    db: &DatabaseConnection,
+    search: Option<&str>,
+    limit: Option<u64>,
+    offset: Option<u64>,
+) -> AppResult<AdminUserListResponse> {
+    let mut query = Users::find();
+    if let Some(pattern) = search_pattern(search) {
+        query = query.filter(
+            Condition::any()
+                .add(Expr::expr(Func::lower(Expr::col(users::Column::Email))).like(&pattern))
+                .add(Expr::expr(Func::lower(Expr::col(users::Column::Name))).like(&pattern)),
+        );
+    }
+
+    let total = query.clone().count(db).await?;
+    let users = query
+        .order_by_desc(users::Column::CreatedAt)
+        .order_by_asc(users::Column::Email)
+        .limit(limit.unwrap_or(DEFAULT_PAGE_SIZE).clamp(1, MAX_PAGE_SIZE))
+        .offset(offset.unwrap_or(0))
+        .all(db)
+        .await?;
+
+    let user_ids: Vec<Uuid> = users.iter().map(|user| user.id).collect();
+    let project_counts: HashMap<Uuid, i64> = ProjectMembers::find()
+        .select_only()
+        .column(project_members::Column::UserId)
+        .column_as(project_members::Column::Id.count(), "count")
+        .filter(project_members::Column::UserId.is_in(user_ids))
+        .group_by(project_members::Column::UserId)
+        .into_tuple::<(Uuid, i64)>()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect();
+
+    let users = users
+        .into_iter()
+        .map(|user| {
+            let project_count = project_counts.get(&user.id).copied().unwrap_or(0) as u64;
+            admin_user_response(user, project_count)
+        })
+        .collect();
+
+    Ok(AdminUserListResponse { users, total })
+}
+
+/// Disable an account and sign it out everywhere
+pub async fn deactivate_user(db: &DatabaseConnection, admin_id: &str, user_id: &str) -> AppResult<AdminUserResponse> {
+    let admin_id = parse_user_id(admin_id)?;
+    let user = get_user(db, user_id).await?;
+
+    if user.id == admin_id {
+        return Err(AppError::BadRequest("You cannot deactivate your own account".to_string()));
+    }
+
+    let txn = db.begin().await?;
+    let user = set_user_active(&txn, user, false).await?;
+    revoke_user_sessions(&txn, user.id).await?;
+    record_audit_event(&txn, Some(admin_id), Some(user.id), AuditAction::UserDeactivated, None).await?;
+    txn.commit().await?;
+
+    with_project_count(db, user).await
+}
+
+/// Let a deactivated account sign in again
+pub async fn reactivate_user(db: &DatabaseConnection, admin_id: &str, user_id: &str) -> AppResult<AdminUserResponse> {
+    let admin_id = parse_user_id(admin_id)?;
+    let user = get_user(db, user_id).await?;
+
+    let txn = db.begin().await?;
+    let user = set_user_active(&txn, user, true).await?;
+    record_audit_event(&txn, Some(admin_id), Some(user.id), AuditAction::UserReactivated, None).await?;
+    txn.commit().await?;
+
+    with_project_count(db, user).await
+}
+
+/// Change a user's platform role; admins cannot change their own
+pub async fn update_user_role(
+    db: &DatabaseConnection,
+    admin_id: &str,
+    user_id: &str,
+    req: UpdateUserRoleRequest,
+) -> AppResult<AdminUserResponse> {
+    let admin_id = parse_user_id(admin_id)?;
+    let user = get_user(db, user_id).await?;
+
+    if !PLATFORM_ROLES.contains(&req.role.as_str()) {
+        return Err(AppError::BadRequest("Role must be either user or admin".to_string()));
+    }
+    if user.id == admin_id {
+        return Err(AppError::BadRequest("You cannot change your own role".to_string()));
+    }
+
+    let previous_role = user.role.clone().unwrap_or_else(|| "user".to_string());
+    if previous_role == req.role {
+        return with_project_count(db, user).await;
+    }
+
+    let txn = db.begin().await?;
+    let mut active: users::ActiveModel = user.into();
+    active.role = Set(Some(req.role.clone()));
+    active.updated_at = Set(Some(Utc::now().naive_utc()));
+    let user = active.update(&txn).await?;
+
+    record_audit_event(
+        &txn,
+        Some(admin_id),
+        Some(user.id),
+        AuditAction::RoleChanged,
+        Some(json!({ "from": previous_role, "to": req.role })),
+    )
+    .await?;
+    txn.commit().await?;
+
+    with_project_count(db, user).await
+}
+
+/// Issue a short-lived access token for acting as a user while helping them.
+///
+/// The token names the admin in its `impersonator` claim and comes without a
+/// refresh token. Other admins and deactivated accounts cannot be impersonated.
+pub async fn impersonate_user(
+    db: &DatabaseConnection,
+    admin_id: &str,
+    user_id: &str,
+    config: &Config,
+) -> AppResult<ImpersonationResponse> {
+    let admin_id = parse_user_id(admin_id)?;
+    let user = get_user(db, user_id).await?;
+
+    if user.id == admin_id || user.role.as_deref() == Some("admin") {
+        return Err(AppError::Forbidden("Admins cannot be impersonated".to_string()));
+    }
+    if !user.is_active.unwrap_or(true) {
+        return Err(AppError::BadRequest("Deactivated users cannot be impersonated".to_string()));
+    }
+
+    let token = generate_impersonation_token(
+        &user,
+        admin_id,
+        &config.jwt_secret,
+        config.impersonation_expiration_minutes,
+    )?;
+
+    record_audit_event(db, Some(admin_id), Some(user.id), AuditAction::UserImpersonated, None).await?;
+
+    Ok(ImpersonationResponse {
+        token,
+        expires_in: config.impersonation_expiration_minutes as u64 * 60,
+        user: user_response(user),
+    })
+}
+
+/// List every project with its owner, member and table counts and disk usage
+pub async fn list_all_projects(db: &DatabaseConnection, search: Option<&str>) -> AppResult<Vec<AdminProjectResponse>> {
+    let mut query = Projects::find().find_also_related(Users);
+    if let Some(pattern) = search_pattern(search) {
+        query = query.filter(
+            Condition::any()
+                .add(Expr::expr(Func::lower(Expr::col((projects::Entity, projects::Column::Name)))).like(&pattern))
+                .add(Expr::expr(Func::lower(Expr::col((projects::Entity, projects::Column::Slug)))).like(&pattern))
+                .add(Expr::expr(Func::lower(Expr::col((users::Entity, users::Column::Email)))).like(&pattern)),
+        );
+    }
+
+    let projects = query.order_by_desc(projects::Column::CreatedAt).all(db).await?;
+
+    let member_counts: HashMap<Uuid, i64> = ProjectMembers::find()
+        .select_only()
+        .column(project_members::Column::ProjectId)
+        .column_as(project_members::Column::Id.count(), "count")
+        .group_by(project_members::Column::ProjectId)
+        .into_tuple::<(Uuid, i64)>()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect();
+
+    let table_counts: HashMap<Uuid, i64> = ProjectTables::find()
+        .select_only()
+        .column(project_tables::Column::ProjectId)
+        .column_as(project_tables::Column::Id.count(), "count")
+        .group_by(project_tables::Column::ProjectId)
+        .into_tuple::<(Uuid, i64)>()
+        .all(db)
+        .await?
+        .into_iter()
+        .collect();
+
+    let storage = project_storage(db).await?;
+
+    Ok(projects
+        .into_iter()
+        .map(|(project, owner)| AdminProjectResponse {
+            id: project.id.to_string(),
+            member_count: member_counts.get(&project.id).copied().unwrap_or(0) as u64,
+            table_count: table_counts.get(&project.id).copied().unwrap_or(0) as u64,
+            storage_bytes: storage.get(&project.id.simple().to_string()).copied().unwrap_or(0),
+            name: project.name,
+            slug: project.slug,
+            owner_id: project.owner_id.to_string(),
+            owner_email: owner.map(|owner| owner.email),
+            created_at: project.created_at.map(|dt| dt.to_string()).unwrap_or_default(),
+        })
+        .collect())
+}
+
+/// Delete any project, whoever owns it
+pub async fn force_delete_project(db: &DatabaseConnection, admin_id: &str, slug: &str) -> AppResult<()> {
+    let admin_id = parse_user_id(admin_id)?;
+    let project = Projects::find()
+        .filter(projects::Column::Slug.eq(slug))
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Project not found".to_string()))?;
+
+    let details = json!({ "project_id": project.id, "slug": project.slug, "name": project.name });
+    let owner_id = project.owner_id;
+
+    let txn = db.begin().await?;
+    drop_project(&txn, project).await?;
+    record_audit_event(&txn, Some(admin_id), Some(owner_id), AuditAction::ProjectDeleted, Some(details)).await?;
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Disk space used by each project's relations, keyed by the simple form of the project id.
+///
+/// Project relations are named `project_{id}_{name}`, so the catalog can be grouped by that prefix.
+async fn project_storage(db: &DatabaseConnection) -> AppResult<HashMap<String, i64>> {
+    let rows = db
+        .query_all(Statement::from_string(
+            DatabaseBackend::Postgres,
+            r#"SELECT substring(c.relname FROM 9 FOR 32) AS project_key,
+                      sum(pg_total_relation_size(c.oid))::bigint AS bytes
+               FROM pg_class c
+               WHERE c.relkind IN ('r', 'm', 'p')
+                 AND c.relname ~ '^project_[0-9a-f]{32}_'
+                 AND c.relnamespace = current_schema()::regnamespace
+               GROUP BY 1"#,
+        ))
+        .await?;
+
+    rows.into_iter()
+        .map(|row| Ok((row.try_get("", "project_key")?, row.try_get("", "bytes")?)))
+        .collect()
+}
+
+fn search_pattern(search: Option<&str>) -> Option<String> {
+    let search = search.map(str::trim).filter(|search| !search.is_empty())?;
+    let escaped = search
+        .to_lowercase()
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_");
+
+    Some(format!("%{}%", escaped))
+}
+
+fn parse_user_id(user_id: &str) -> AppResult<Uuid> {
+    Uuid::parse_str(user_id).map_err(|_| AppError::BadRequest("Invalid user ID format".to_string()))
+}
+
+async fn get_user(db: &DatabaseConnection, user_id: &str) -> AppResult<users::Model> {
+    let not_found = || AppError::NotFound("User not found".to_string());
+    let user_id = Uuid::parse_str(user_id).map_err(|_| not_found())?;
+
+    Users::find_by_id(user_id).one(db).await?.ok_or_else(not_found)
+}
+
+async fn set_user_active<C: ConnectionTrait>(db: &C, user: users::Model, is_active: bool) -> AppResult<users::Model> {
+    let mut active: users::ActiveModel = user.into();
+    active.is_active = Set(Some(is_active));
+    active.updated_at = Set(Some(Utc::now().naive_utc()));
+
+    Ok(active.update(db).await?)
+}
+
+async fn with_project_count(db: &DatabaseConnection, user: users::Model) -> AppResult<AdminUserResponse> {
+    let project_count = ProjectMembers::find()
+        .filter(project_members::Column::UserId.eq(user.id))
+        .count(db)
+        .await?;
+
+    Ok(admin_user_response(user, project_count))
+}
+
+fn admin_user_response(user: users::Model, project_count: u64) -> AdminUserResponse {
+    AdminUserResponse {
+        id: user.id.to_string(),
+        email: user.email,
+        name: user.name,
+        role: user.role.unwrap_or_else(|| "user".to_string()),
+        email_verified: user.email_verified.unwrap_or(false),
+        is_active: user.is_active.unwrap_or(true),
+        project_count,
+        created_at: user.created_at.map(|t| t.to_string()).unwrap_or_default(),
+    }
+}
@@ -0,0 +1,195 @@
+use chrono::Utc;
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+use database::entities::{project_api_keys, projects};
+use shared::models::{ApiKeyKind, ApiKeyResponse, Claims, CreateApiKeyRequest, CreatedApiKeyResponse};
+use crate::error::{AppError, AppResult};
+use crate::services::auth_service::{generate_secret_token, hash_secret_token};
+use crate::services::authorization_service::{authorize_project, Capability};
+use uuid::Uuid;
+
+/// Start of every API key, so keys can be told apart from JWTs
+const API_KEY_PREFIX: &str = "fb_";
+
+/// Last-used timestamps are only written when older than this, to keep reads cheap
+const LAST_USED_RESOLUTION_SECONDS: i64 = 60;
+
+/// Who a data or SQL API request acts as
+#[derive(Debug, Clone)]
+pub enum Principal {
+    /// A signed-in user, acting on the projects they are a member of
+    User(Claims),
+    /// A project API key, acting on the one project it belongs to
+    ApiKey { project_id: Uuid, kind: ApiKeyKind },
+}
+
+impl Principal {
+    /// Claims exposed to row-level security policies through `request.jwt.claims`
+    pub fn policy_claims(&self) -> AppResult<String> {
+        let claims = match self {
+            Principal::User(claims) => serde_json::to_value(claims)
+                .map_err(|e| AppError::InternalServerError(e.to_string()))?,
+            Principal::ApiKey { kind: ApiKeyKind::Anon, .. } => serde_json::json!({ "role": "anon" }),
+            Principal::ApiKey { kind: ApiKeyKind::Service, .. } => serde_json::json!({ "role": "service_role" }),
+        };
+
+        Ok(claims.to_string())
+    }
+
+    /// Find a project by slug that this principal may act on with `capability`.
+    ///
+    /// Users are checked against their project role; API keys are limited to their
+    /// own project and, for anon keys, to row-level security.
+    pub async fn find_project(
+        &self,
+        db: &DatabaseConnection,
+        project_slug: &str,
+        capability: Capability,
+    ) -> AppResult<projects::Model> {
+        match self {
+            Principal::User(claims) => Ok(authorize_project(db, &claims.sub, project_slug, capability).await?.project),
+            Principal::ApiKey { project_id, .. } => projects::Entity::find()
+                .filter(projects::Column::Slug.eq(project_slug))
+                .filter(projects::Column::Id.eq(*project_id))
+                .one(db)
+                .await?
+                .ok_or_else(|| AppError::NotFound("Project not found".to_string())),
+        }
+    }
+}
+
+/// Whether a bearer token is an API key rather than a JWT
+pub fn is_api_key(token: &str) -> bool {
+    token.starts_with(API_KEY_PREFIX)
+}
+
+/// List the API keys of a project, including revoked ones
+pub async fn list_api_keys(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+) -> AppResult<Vec<ApiKeyResponse>> {
+    let project = authorize_project(db, user_id, project_slug, Capability::ManageProject).await?.project;
+
+    let keys = project_api_keys::Entity::find()
+        .filter(project_api_keys::Column::ProjectId.eq(project.id))
+        .order_by_desc(project_api_keys::Column::CreatedAt)
+        .all(db)
+        .await?;
+
+    keys.into_iter().map(api_key_response).collect()
+}
+
+/// Create an API key; only its hash is stored, so the key is returned this once
+pub async fn create_api_key(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    req: CreateApiKeyRequest,
+) -> AppResult<CreatedApiKeyResponse> {
+    let access = authorize_project(db, user_id, project_slug, Capability::ManageProject).await?;
+    let project = access.project;
+
+    let name = req.name.trim();
+    if name.is_empty() || name.len() > 100 {
+        return Err(AppError::ValidationError("API key name must be 1 to 100 characters".to_string()));
+    }
+
+    let secret = generate_secret_token();
+    let key = format!("{}{}_{}", API_KEY_PREFIX, req.kind.as_str(), secret);
+    let key_prefix = format!("{}{}_{}", API_KEY_PREFIX, req.kind.as_str(), &secret[..6]);
+
+    let api_key = project_api_keys::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        project_id: Set(project.id),
+        name: Set(name.to_string()),
+        kind: Set(req.kind.as_str().to_string()),
+        key_prefix: Set(key_prefix),
+        key_hash: Set(hash_secret_token(&key)),
+        created_by: Set(Some(access.user_id)),
+        created_at: Set(Some(Utc::now().naive_utc())),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    Ok(CreatedApiKeyResponse {
+        key,
+        api_key: api_key_response(api_key)?,
+    })
+}
+
+/// Revoke an API key; requests using it are rejected from then on
+pub async fn revoke_api_key(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    api_key_id: &str,
+) -> AppResult<ApiKeyResponse> {
+    let project = authorize_project(db, user_id, project_slug, Capability::ManageProject).await?.project;
+
+    let id = Uuid::parse_str(api_key_id)
+        .map_err(|_| AppError::NotFound("API key not found".to_string()))?;
+
+    let api_key = project_api_keys::Entity::find_by_id(id)
+        .filter(project_api_keys::Column::ProjectId.eq(project.id))
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("API key not found".to_string()))?;
+
+    if api_key.revoked_at.is_some() {
+        return api_key_response(api_key);
+    }
+
+    let mut active: project_api_keys::ActiveModel = api_key.into();
+    active.revoked_at = Set(Some(Utc::now().naive_utc()));
+    let api_key = active.update(db).await?;
+
+    api_key_response(api_key)
+}
+
+/// Resolve an API key to the project-scoped principal it acts as, recording its use
+pub async fn authenticate_api_key(db: &DatabaseConnection, key: &str) -> AppResult<Principal> {
+    let invalid = || AppError::Unauthorized("Invalid API key".to_string());
+
+    let api_key = project_api_keys::Entity::find()
+        .filter(project_api_keys::Column::KeyHash.eq(hash_secret_token(key)))
+        .filter(project_api_keys::Column::RevokedAt.is_null())
+        .one(db)
+        .await?
+        .ok_or_else(invalid)?;
+
+    let kind = ApiKeyKind::parse(&api_key.kind).ok_or_else(invalid)?;
+
+    let now = Utc::now().naive_utc();
+    let stale = api_key
+        .last_used_at
+        .is_none_or(|used| now - used >= chrono::Duration::seconds(LAST_USED_RESOLUTION_SECONDS));
+    if stale {
+        project_api_keys::Entity::update_many()
+            .col_expr(project_api_keys::Column::LastUsedAt, Expr::value(now))
+            .filter(project_api_keys::Column::Id.eq(api_key.id))
+            .exec(db)
+            .await?;
+    }
+
+    Ok(Principal::ApiKey {
+        project_id: api_key.project_id,
+        kind,
+    })
+}
+
+fn api_key_response(api_key: project_api_keys::Model) -> AppResult<ApiKeyResponse> {
+    let kind = ApiKeyKind::parse(&api_key.kind)
+        .ok_or_else(|| AppError::InternalServerError(format!("Unknown API key kind: {}", api_key.kind)))?;
+
+    Ok(ApiKeyResponse {
+        id: api_key.id.to_string(),
+        name: api_key.name,
+        kind,
+        key_prefix: api_key.key_prefix,
+        created_at: api_key.created_at.map(|dt| dt.to_string()).unwrap_or_default(),
+        last_used_at: api_key.last_used_at.map(|dt| dt.to_string()),
+        revoked_at: api_key.revoked_at.map(|dt| dt.to_string()),
+    })
+}
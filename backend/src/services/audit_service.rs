@@ -0,0 +1,73 @@
+use sea_orm::*;
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use database::entities::audit_logs;
+
+/// Security-relevant account events kept in the audit trail
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AuditAction {
+    PasswordChanged,
+    PasswordResetRequested,
+    PasswordReset,
+    EmailVerified,
+    UserDeactivated,
+    UserReactivated,
+    RoleChanged,
+    UserImpersonated,
+    ProjectDeleted,
+    IdentityLinked,
+    MfaEnabled,
+    MfaDisabled,
+    RecoveryCodesRegenerated,
+    RecoveryCodeUsed,
+    AccountLocked,
+}
+
+impl AuditAction {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            AuditAction::PasswordChanged => "password_changed",
+            AuditAction::PasswordResetRequested => "password_reset_requested",
+            AuditAction::PasswordReset => "password_reset",
+            AuditAction::EmailVerified => "email_verified",
+            AuditAction::UserDeactivated => "user_deactivated",
+            AuditAction::UserReactivated => "user_reactivated",
+            AuditAction::RoleChanged => "role_changed",
+            AuditAction::UserImpersonated => "user_impersonated",
+            AuditAction::ProjectDeleted => "project_deleted",
+            AuditAction::IdentityLinked => "identity_linked",
+            AuditAction::MfaEnabled => "mfa_enabled",
+            AuditAction::MfaDisabled => "mfa_disabled",
+            AuditAction::RecoveryCodesRegenerated => "recovery_codes_regenerated",
+            AuditAction::RecoveryCodeUsed => "recovery_code_used",
+            AuditAction::AccountLocked => "account_locked",
+        }
+    }
+}
+
+/// Record an event in the audit trail.
+///
+/// `actor_id` is the user who acted and `user_id` the account the event concerns;
+/// they are the same for changes users make to their own account.
+pub async fn record_audit_event<C: ConnectionTrait>(
+    db: &C,
+    actor_id: Option<Uuid>,
+    user_id: Option<Uuid>,
+    action: AuditAction,
+    details: Option<JsonValue>,
+) -> AppResult<()> {
+    audit_logs::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        actor_id: Set(actor_id),
+        user_id: Set(user_id),
+        action: Set(action.as_str().to_owned()),
+        details: Set(details.map(|details| details.to_string())),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    Ok(())
+}
@@ -1,13 +1,29 @@
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
 use bcrypt::{hash, verify, DEFAULT_COST};
 use chrono::Utc;
 use jsonwebtoken::{decode, encode, DecodingKey, EncodingKey, Header, Validation};
-use sea_orm::{ActiveModelTrait, ColumnTrait, DatabaseConnection, EntityTrait, QueryFilter, Set};
+use sea_orm::sea_query::Expr;
+use sea_orm::{
+    ActiveModelTrait, ColumnTrait, ConnectionTrait, DatabaseConnection, EntityTrait, QueryFilter, Set,
+    TransactionTrait,
+};
+use sha2::{Digest, Sha256};
+use std::net::IpAddr;
 use uuid::Uuid; 
 
+use crate::config::Config;
 use crate::error::{AppError, AppResult};
+use crate::mailer::Mailer;
+use crate::services::audit_service::{record_audit_event, AuditAction};
+use crate::services::email_verification_service::send_verification_email;
+use crate::services::login_throttle_service::{clear_login_failures, ensure_login_allowed, record_login_failure};
+use crate::services::mfa_service::start_session;
 use database::entities::prelude::*;
-use database::entities::users;
-use shared::models::{AuthResponse, Claims, LoginRequest, RegisterRequest, UserResponse};
+use database::entities::{refresh_tokens, revoked_tokens, users};
+use shared::models::{
+    AuthResponse, ChangePasswordRequest, Claims, LoginRequest, LoginResponse, LogoutRequest, RefreshTokenRequest,
+    RegisterRequest, UserResponse,
+};
 
 /// Hash a plain text password
 pub fn hash_password(password: &str) -> AppResult<String> {
@@ -19,10 +35,74 @@ pub fn verify_password(password: &str, hash: &str) -> AppResult<bool> {
     verify(password, hash).map_err(AppError::from)
 }
 
-/// Generate a JWT token for a user
-pub fn generate_token(user_id: &str, email: &str, role: &str, jwt_secret: &str, expiration_hours: usize) -> AppResult<String> {
+/// Longest password bcrypt can tell apart; longer ones would be silently truncated
+const MAX_PASSWORD_BYTES: usize = 72;
+
+/// Check a new password against the password policy
+pub fn validate_password(password: &str, email: &str) -> AppResult<()> {
+    if password.chars().count() < 8 {
+        return Err(AppError::ValidationError(
+            "Password must be at least 8 characters long".to_string(),
+        ));
+    }
+
+    if password.len() > MAX_PASSWORD_BYTES {
+        return Err(AppError::ValidationError(format!(
+            "Password must be at most {} bytes long",
+            MAX_PASSWORD_BYTES
+        )));
+    }
+
+    if !password.chars().any(char::is_alphabetic) || password.chars().all(char::is_alphabetic) {
+        return Err(AppError::ValidationError(
+            "Password must contain at least one letter and one number or symbol".to_string(),
+        ));
+    }
+
+    if password.eq_ignore_ascii_case(email) {
+        return Err(AppError::ValidationError(
+            "Password must not be the same as the email address".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Authentication methods that count as a second factor
+const SECOND_FACTOR_METHODS: [&str; 2] = ["otp", "recovery"];
+
+/// Authenticator assurance level reached by signing in with the methods in `amr`
+pub fn assurance_level(amr: &[String]) -> &'static str {
+    if amr.iter().any(|method| SECOND_FACTOR_METHODS.contains(&method.as_str())) {
+        "aal2"
+    } else {
+        "aal1"
+    }
+}
+
+/// Refresh tokens and MFA challenges keep their methods as a comma-separated list
+pub(crate) fn encode_amr(amr: &[String]) -> String {
+    amr.join(",")
+}
+
+pub(crate) fn decode_amr(amr: &str) -> Vec<String> {
+    amr.split(',')
+        .filter(|method| !method.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+/// Generate a JWT access token for a user who signed in with the methods in `amr`
+pub fn generate_token(
+    user_id: &str,
+    email: &str,
+    role: &str,
+    amr: &[String],
+    jwt_secret: &str,
+    expiration_minutes: usize,
+) -> AppResult<String> {
     let now = Utc::now();
-    let exp = (now + chrono::Duration::hours(expiration_hours as i64)).timestamp() as usize;
+    let exp = (now + chrono::Duration::minutes(expiration_minutes as i64)).timestamp() as usize;
     let iat = now.timestamp() as usize;
 
     let claims = Claims {
@@ -31,11 +111,46 @@ pub fn generate_token(user_id: &str, email: &str, role: &str, jwt_secret: &str,
         role: role.to_string(),
         exp,
         iat,
+        jti: Uuid::new_v4().to_string(),
+        iat_us: now.timestamp_micros(),
+        impersonator: None,
+        amr: amr.to_vec(),
+        aal: assurance_level(amr).to_string(),
+    };
+
+    encode_token(&claims, jwt_secret)
+}
+
+/// Generate an access token that lets an admin act as another user
+pub fn generate_impersonation_token(
+    user: &users::Model,
+    admin_id: Uuid,
+    jwt_secret: &str,
+    expiration_minutes: usize,
+) -> AppResult<String> {
+    let now = Utc::now();
+
+    let claims = Claims {
+        sub: user.id.to_string(),
+        email: user.email.clone(),
+        role: user.role.clone().unwrap_or_else(|| "user".to_string()),
+        exp: (now + chrono::Duration::minutes(expiration_minutes as i64)).timestamp() as usize,
+        iat: now.timestamp() as usize,
+        jti: Uuid::new_v4().to_string(),
+        iat_us: now.timestamp_micros(),
+        impersonator: Some(admin_id.to_string()),
+        // The admin never proved the user's second factor
+        amr: Vec::new(),
+        aal: assurance_level(&[]).to_string(),
     };
 
+    encode_token(&claims, jwt_secret)
+}
+
+fn encode_token(claims: &Claims, jwt_secret: &str) -> AppResult<String> {
     encode(
         &Header::default(),
-        &claims,
+        claims,
         &EncodingKey::from_secret(jwt_secret.as_bytes()),
     )
     .map_err(AppError::from)
@@ -55,21 +170,16 @@ pub fn verify_token(token: &str, jwt_secret: &str) -> AppResult<Claims> {
 /// Register a new user
 pub async fn register_user(
     db: &DatabaseConnection,
+    mailer: &dyn Mailer,
     req: RegisterRequest,
-    jwt_secret: &str,
-    expiration_hours: usize,
+    config: &Config,
 ) -> AppResult<AuthResponse> {
     // Validate email format
     if !req.email.contains('@') {
         return Err(AppError::ValidationError("Invalid email format".to_string()));
     }
 
-    // Validate password strength (minimum 8 characters)
-    if req.password.len() < 8 {
-        return Err(AppError::ValidationError(
-            "Password must be at least 8 characters long".to_string(),
-        ));
-    }
+    validate_password(&req.password, &req.email)?;
 
     // Check if user already exists
     let existing_user = Users::find()
@@ -99,75 +209,360 @@ pub async fn register_user(
 
     let user = new_user.insert(db).await?;
 
-    // Generate JWT token
-    let token = generate_token(
-        &user.id.to_string(),
-        &user.email,
-        &user.role.clone().unwrap_or_else(|| "user".to_string()),
-        jwt_secret,
-        expiration_hours,
-    )?;
+    // The account exists either way; the user can ask for another link
+    if let Err(e) = send_verification_email(db, mailer, &user, config).await {
+        tracing::error!("Failed to send verification email: {}", e);
+    }
 
-    Ok(AuthResponse {
-        token,
-        user: UserResponse {
-            id: user.id.to_string(),
-            email: user.email,
-            name: user.name,
-            role: user.role.unwrap_or_else(|| "user".to_string()),
-            email_verified: user.email_verified.unwrap_or(false),
-            created_at: user.created_at.map(|t| t.to_string()).unwrap_or_default(),
-        },
-    })
+    // Each login starts a new refresh token family
+    issue_tokens(db, user, Uuid::new_v4(), Uuid::new_v4(), &["pwd".to_string()], config).await
 }
 
-/// Login a user
+/// Login a user.
+///
+/// Users with multi-factor authentication enabled get an MFA challenge instead of
+/// a session, to be completed with `verify_mfa_challenge`. Failed attempts are
+/// throttled per email address and per `client_ip`.
 pub async fn login_user(
     db: &DatabaseConnection,
     req: LoginRequest,
-    jwt_secret: &str,
-    expiration_hours: usize,
-) -> AppResult<AuthResponse> {
+    client_ip: IpAddr,
+    config: &Config,
+) -> AppResult<LoginResponse> {
+    ensure_login_allowed(db, &req.email, client_ip).await?;
+
     // Find user by email
-    let user = Users::find()
+    let Some(user) = Users::find()
         .filter(users::Column::Email.eq(&req.email))
         .one(db)
         .await?
-        .ok_or_else(|| AppError::Unauthorized("Invalid email or password".to_string()))?;
+    else {
+        return Err(record_login_failure(db, &req.email, client_ip, None, config).await?);
+    };
 
-    // Check if user is active
+    // Verify password
+    let password_valid = verify_password(&req.password, &user.password_hash)?;
+    if !password_valid {
+        return Err(record_login_failure(db, &req.email, client_ip, Some(&user), config).await?);
+    }
+
+    // Only someone who knows the password learns that the account is disabled
     if !user.is_active.unwrap_or(true) {
         return Err(AppError::Unauthorized("Account is disabled".to_string()));
     }
 
-    // Verify password
-    let password_valid = verify_password(&req.password, &user.password_hash)?;
-    if !password_valid {
-        return Err(AppError::Unauthorized("Invalid email or password".to_string()));
+    clear_login_failures(db, &req.email).await?;
+
+    start_session(db, user, "pwd", config).await
+}
+
+/// Exchange a refresh token for a new access token and refresh token.
+///
+/// Refresh tokens are single use. Presenting one that was already rotated means
+/// it leaked, so every token descended from the same login is revoked.
+pub async fn refresh_session(
+    db: &DatabaseConnection,
+    req: RefreshTokenRequest,
+    config: &Config,
+) -> AppResult<AuthResponse> {
+    let invalid = || AppError::Unauthorized("Invalid refresh token".to_string());
+
+    let token = RefreshTokens::find()
+        .filter(refresh_tokens::Column::TokenHash.eq(hash_secret_token(&req.refresh_token)))
+        .one(db)
+        .await?
+        .ok_or_else(invalid)?;
+
+    if token.revoked_at.is_some() {
+        tracing::warn!("Refresh token reuse detected; revoking token family {}", token.family_id);
+        revoke_token_family(db, token.family_id).await?;
+        return Err(AppError::Unauthorized(
+            "Refresh token is no longer valid; please sign in again".to_string(),
+        ));
+    }
+
+    if token.expires_at <= Utc::now().naive_utc() {
+        return Err(AppError::Unauthorized("Refresh token has expired".to_string()));
+    }
+
+    let user = Users::find_by_id(token.user_id)
+        .one(db)
+        .await?
+        .ok_or_else(invalid)?;
+
+    if !user.is_active.unwrap_or(true) {
+        return Err(AppError::Unauthorized("Account is disabled".to_string()));
     }
 
-    // Generate JWT token
+    let txn = db.begin().await?;
+
+    // Only one concurrent refresh can claim the token; the loser is treated as reuse
+    let next_token_id = Uuid::new_v4();
+    let claimed = RefreshTokens::update_many()
+        .col_expr(refresh_tokens::Column::RevokedAt, Expr::value(Utc::now().naive_utc()))
+        .col_expr(refresh_tokens::Column::ReplacedBy, Expr::value(next_token_id))
+        .filter(refresh_tokens::Column::Id.eq(token.id))
+        .filter(refresh_tokens::Column::RevokedAt.is_null())
+        .exec(&txn)
+        .await?;
+
+    if claimed.rows_affected == 0 {
+        txn.rollback().await?;
+        tracing::warn!("Refresh token reuse detected; revoking token family {}", token.family_id);
+        revoke_token_family(db, token.family_id).await?;
+        return Err(AppError::Unauthorized(
+            "Refresh token is no longer valid; please sign in again".to_string(),
+        ));
+    }
+
+    // The new access token proves the same factors as the login it descends from
+    let amr = decode_amr(&token.amr);
+    let response = issue_tokens(&txn, user, next_token_id, token.family_id, &amr, config).await?;
+    txn.commit().await?;
+
+    Ok(response)
+}
+
+/// Revoke every live refresh token descended from the same login
+async fn revoke_token_family<C: ConnectionTrait>(db: &C, family_id: Uuid) -> AppResult<()> {
+    RefreshTokens::update_many()
+        .col_expr(refresh_tokens::Column::RevokedAt, Expr::value(Utc::now().naive_utc()))
+        .filter(refresh_tokens::Column::FamilyId.eq(family_id))
+        .filter(refresh_tokens::Column::RevokedAt.is_null())
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Check that a verified token has not been revoked since it was issued, returning its user.
+///
+/// A token is rejected when it was logged out on its own, when it predates the
+/// user's last "log out everywhere", or when the account has been deactivated.
+pub async fn ensure_token_active(db: &DatabaseConnection, claims: &Claims) -> AppResult<users::Model> {
+    let revoked = || AppError::Unauthorized("Token has been revoked".to_string());
+
+    let user_id = Uuid::parse_str(&claims.sub).map_err(|_| revoked())?;
+    let jti = Uuid::parse_str(&claims.jti).map_err(|_| revoked())?;
+
+    let user = Users::find_by_id(user_id).one(db).await?.ok_or_else(revoked)?;
+
+    if !user.is_active.unwrap_or(true) {
+        return Err(AppError::Unauthorized("Account is disabled".to_string()));
+    }
+
+    // `iat_us` has the microsecond precision of the cutoff, so a token issued in the same
+    // second as a "log out everywhere" is still caught; tokens without it fall back to `iat`
+    let issued_at = match claims.iat_us {
+        0 => (claims.iat as i64).saturating_mul(1_000_000),
+        iat_us => iat_us,
+    };
+    let cutoff = user.tokens_revoked_at.map(|cutoff| cutoff.and_utc().timestamp_micros());
+    if cutoff.is_some_and(|cutoff| issued_at <= cutoff) {
+        return Err(revoked());
+    }
+
+    if RevokedTokens::find_by_id(jti).one(db).await?.is_some() {
+        return Err(revoked());
+    }
+
+    Ok(user)
+}
+
+/// Log out the session behind an access token.
+///
+/// The access token is revoked until it expires, and so is the refresh token
+/// family it was issued with when the client passes its refresh token.
+pub async fn logout_user(db: &DatabaseConnection, claims: &Claims, req: LogoutRequest) -> AppResult<()> {
+    let user_id = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::BadRequest("Invalid user ID format".to_string()))?;
+    let jti = Uuid::parse_str(&claims.jti)
+        .map_err(|_| AppError::BadRequest("Invalid token ID".to_string()))?;
+    let expires_at = chrono::DateTime::from_timestamp(claims.exp as i64, 0)
+        .ok_or_else(|| AppError::BadRequest("Invalid token expiry".to_string()))?;
+
+    RevokedTokens::insert(revoked_tokens::ActiveModel {
+        jti: Set(jti),
+        user_id: Set(user_id),
+        expires_at: Set(expires_at.naive_utc()),
+        ..Default::default()
+    })
+    .on_conflict(
+        sea_orm::sea_query::OnConflict::column(revoked_tokens::Column::Jti)
+            .do_nothing()
+            .to_owned(),
+    )
+    .exec_without_returning(db)
+    .await?;
+
+    if let Some(refresh_token) = req.refresh_token {
+        let token = RefreshTokens::find()
+            .filter(refresh_tokens::Column::TokenHash.eq(hash_secret_token(&refresh_token)))
+            .filter(refresh_tokens::Column::UserId.eq(user_id))
+            .one(db)
+            .await?;
+
+        if let Some(token) = token {
+            revoke_token_family(db, token.family_id).await?;
+        }
+    }
+
+    // Revoked tokens past their expiry would be rejected anyway
+    RevokedTokens::delete_many()
+        .filter(revoked_tokens::Column::ExpiresAt.lt(Utc::now().naive_utc()))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Revoke every access and refresh token a user holds, logging them out everywhere
+pub async fn revoke_user_sessions<C: ConnectionTrait>(db: &C, user_id: Uuid) -> AppResult<()> {
+    let now = Utc::now().naive_utc();
+
+    Users::update_many()
+        .col_expr(users::Column::TokensRevokedAt, Expr::value(now))
+        .filter(users::Column::Id.eq(user_id))
+        .exec(db)
+        .await?;
+
+    RefreshTokens::update_many()
+        .col_expr(refresh_tokens::Column::RevokedAt, Expr::value(now))
+        .filter(refresh_tokens::Column::UserId.eq(user_id))
+        .filter(refresh_tokens::Column::RevokedAt.is_null())
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Keep an admin acting as a user away from the user's credentials and other sessions
+pub fn ensure_not_impersonating(claims: &Claims) -> AppResult<()> {
+    if claims.impersonator.is_some() {
+        return Err(AppError::Forbidden("Not allowed while impersonating a user".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Log a user out of every session
+pub async fn logout_all_sessions(db: &DatabaseConnection, user_id: &str) -> AppResult<()> {
+    let user_uuid = Uuid::parse_str(user_id)
+        .map_err(|_| AppError::BadRequest("Invalid user ID format".to_string()))?;
+
+    revoke_user_sessions(db, user_uuid).await
+}
+
+/// Issue an access token and a new refresh token in `family_id` for a user who signed in with `amr`
+pub(crate) async fn issue_tokens<C: ConnectionTrait>(
+    db: &C,
+    user: users::Model,
+    refresh_token_id: Uuid,
+    family_id: Uuid,
+    amr: &[String],
+    config: &Config,
+) -> AppResult<AuthResponse> {
     let token = generate_token(
         &user.id.to_string(),
         &user.email,
         &user.role.clone().unwrap_or_else(|| "user".to_string()),
-        jwt_secret,
-        expiration_hours,
+        amr,
+        &config.jwt_secret,
+        config.access_token_expiration_minutes,
     )?;
 
+    // The client gets the token itself once; only its hash is stored
+    let refresh_token = generate_secret_token();
+    let expires_at = Utc::now() + chrono::Duration::days(config.refresh_token_expiration_days as i64);
+
+    refresh_tokens::ActiveModel {
+        id: Set(refresh_token_id),
+        user_id: Set(user.id),
+        family_id: Set(family_id),
+        token_hash: Set(hash_secret_token(&refresh_token)),
+        expires_at: Set(expires_at.naive_utc()),
+        amr: Set(encode_amr(amr)),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
     Ok(AuthResponse {
         token,
-        user: UserResponse {
-            id: user.id.to_string(),
-            email: user.email,
-            name: user.name,
-            role: user.role.unwrap_or_else(|| "user".to_string()),
-            email_verified: user.email_verified.unwrap_or(false),
-            created_at: user.created_at.map(|t| t.to_string()).unwrap_or_default(),
-        },
+        refresh_token,
+        expires_in: config.access_token_expiration_minutes as u64 * 60,
+        user: user_response(user),
     })
 }
 
+/// Generate a random opaque token, such as a refresh or password reset token
+pub(crate) fn generate_secret_token() -> String {
+    URL_SAFE_NO_PAD.encode(rand::random::<[u8; 32]>())
+}
+
+/// Opaque tokens are random, so a fast unsalted hash is enough to keep them unusable if the table leaks
+pub(crate) fn hash_secret_token(token: &str) -> String {
+    hex::encode(Sha256::digest(token.as_bytes()))
+}
+
+pub(crate) fn user_response(user: users::Model) -> UserResponse {
+    UserResponse {
+        id: user.id.to_string(),
+        email: user.email,
+        name: user.name,
+        role: user.role.unwrap_or_else(|| "user".to_string()),
+        email_verified: user.email_verified.unwrap_or(false),
+        created_at: user.created_at.map(|t| t.to_string()).unwrap_or_default(),
+    }
+}
+
+/// Change the password of a signed-in user.
+///
+/// Every existing session is revoked, and the caller gets a fresh pair of tokens
+/// so that only the session that made the change stays signed in.
+pub async fn change_password(
+    db: &DatabaseConnection,
+    claims: &Claims,
+    req: ChangePasswordRequest,
+    config: &Config,
+) -> AppResult<AuthResponse> {
+    let user_uuid = Uuid::parse_str(&claims.sub)
+        .map_err(|_| AppError::BadRequest("Invalid user ID format".to_string()))?;
+
+    let user = Users::find_by_id(user_uuid)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    if !verify_password(&req.old_password, &user.password_hash)? {
+        return Err(AppError::BadRequest("Current password is incorrect".to_string()));
+    }
+
+    if req.new_password == req.old_password {
+        return Err(AppError::ValidationError(
+            "New password must be different from the current password".to_string(),
+        ));
+    }
+
+    validate_password(&req.new_password, &user.email)?;
+    let password_hash = hash_password(&req.new_password)?;
+
+    let txn = db.begin().await?;
+
+    let mut active: users::ActiveModel = user.into();
+    active.password_hash = Set(password_hash);
+    active.updated_at = Set(Some(Utc::now().naive_utc()));
+    let user = active.update(&txn).await?;
+
+    revoke_user_sessions(&txn, user.id).await?;
+    record_audit_event(&txn, Some(user.id), Some(user.id), AuditAction::PasswordChanged, None).await?;
+    let response = issue_tokens(&txn, user, Uuid::new_v4(), Uuid::new_v4(), &claims.amr, config).await?;
+
+    txn.commit().await?;
+
+    Ok(response)
+}
+
 /// Get user by ID
 pub async fn get_user_by_id(db: &DatabaseConnection, user_id: &str) -> AppResult<UserResponse> {
     let user_uuid = Uuid::parse_str(user_id)
@@ -178,12 +573,5 @@ pub async fn get_user_by_id(db: &DatabaseConnection, user_id: &str) -> AppResult
         .await?
         .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
 
-    Ok(UserResponse {
-        id: user.id.to_string(),
-        email: user.email,
-        name: user.name,
-        role: user.role.unwrap_or_else(|| "user".to_string()),
-        email_verified: user.email_verified.unwrap_or(false),
-        created_at: user.created_at.map(|t| t.to_string()).unwrap_or_default(),
-    })
+    Ok(user_response(user))
 }
@@ -0,0 +1,107 @@
+use sea_orm::*;
+use database::entities::{project_members, projects};
+use shared::models::ProjectRole;
+use crate::error::{AppError, AppResult};
+use uuid::Uuid;
+
+/// What a request does to a project; each role grants a set of these
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Capability {
+    /// See the project, its schema and data, and run read-only SQL
+    Read,
+    /// Change rows, run any SQL, call functions and manage saved queries
+    WriteData,
+    /// Change tables, views, functions and row-level security policies
+    ManageSchema,
+    /// Change project settings and API keys
+    ManageProject,
+    /// Add, update and remove members
+    ManageMembers,
+    /// Delete the project
+    DeleteProject,
+}
+
+/// A user's verified access to a project
+#[derive(Debug, Clone)]
+pub struct ProjectAccess {
+    pub user_id: Uuid,
+    pub project: projects::Model,
+    pub role: ProjectRole,
+}
+
+/// Whether a role grants a capability
+pub fn role_allows(role: ProjectRole, capability: Capability) -> bool {
+    match role {
+        ProjectRole::Owner => true,
+        ProjectRole::Admin => capability != Capability::DeleteProject,
+        ProjectRole::Developer => matches!(
+            capability,
+            Capability::Read | Capability::WriteData | Capability::ManageSchema
+        ),
+        ProjectRole::Viewer => capability == Capability::Read,
+    }
+}
+
+/// Reject the request unless the role grants the capability
+pub fn ensure_role_allows(role: ProjectRole, capability: Capability) -> AppResult<()> {
+    if !role_allows(role, capability) {
+        return Err(AppError::Forbidden(format!(
+            "The {} role does not allow this action",
+            role.as_str()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Find a project by slug and check that the user's role in it grants `capability`.
+///
+/// Projects the user is not a member of are reported as not found, so their
+/// existence is not revealed.
+pub async fn authorize_project<C: ConnectionTrait>(
+    db: &C,
+    user_id: &str,
+    project_slug: &str,
+    capability: Capability,
+) -> AppResult<ProjectAccess> {
+    let user_uuid = Uuid::parse_str(user_id)
+        .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
+
+    let not_found = || AppError::NotFound("Project not found".to_string());
+
+    let project = projects::Entity::find()
+        .filter(projects::Column::Slug.eq(project_slug))
+        .one(db)
+        .await?
+        .ok_or_else(not_found)?;
+
+    let role = project_role(db, project.id, user_uuid).await?.ok_or_else(not_found)?;
+
+    ensure_role_allows(role, capability)?;
+
+    Ok(ProjectAccess {
+        user_id: user_uuid,
+        project,
+        role,
+    })
+}
+
+/// The user's role in a project, if they are a member
+pub async fn project_role<C: ConnectionTrait>(
+    db: &C,
+    project_id: Uuid,
+    user_id: Uuid,
+) -> AppResult<Option<ProjectRole>> {
+    let member = project_members::Entity::find()
+        .filter(project_members::Column::ProjectId.eq(project_id))
+        .filter(project_members::Column::UserId.eq(user_id))
+        .one(db)
+        .await?;
+
+    member
+        .map(|member| {
+            ProjectRole::parse(&member.role)
+                .ok_or_else(|| AppError::InternalServerError(format!("Unknown project role: {}", member.role)))
+        })
+        .transpose()
+}
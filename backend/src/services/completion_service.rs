@@ -0,0 +1,129 @@
+use std::collections::HashMap;
+use sea_orm::*;
+use database::entities::project_functions;
+use shared::models::{CompletionColumn, CompletionFunction, CompletionRelation, FunctionArgument, SqlCompletions};
+use crate::error::AppResult;
+use crate::services::authorization_service::{authorize_project, Capability};
+use crate::services::sql_rewrite::project_relations;
+
+/// Type names offered when declaring casts and parameters
+const COMPLETION_TYPES: &[&str] = &[
+    "bigint",
+    "boolean",
+    "bytea",
+    "date",
+    "double precision",
+    "integer",
+    "interval",
+    "json",
+    "jsonb",
+    "numeric",
+    "real",
+    "smallint",
+    "text",
+    "time",
+    "timestamp",
+    "timestamptz",
+    "uuid",
+    "varchar",
+];
+
+/// Keywords of the statements the SQL editor runs
+const COMPLETION_KEYWORDS: &[&str] = &[
+    "SELECT", "FROM", "WHERE", "AND", "OR", "NOT", "IN", "IS", "NULL", "LIKE", "ILIKE", "BETWEEN", "EXISTS",
+    "AS", "DISTINCT", "JOIN", "INNER", "LEFT", "RIGHT", "FULL", "CROSS", "ON", "USING", "GROUP BY", "HAVING",
+    "ORDER BY", "ASC", "DESC", "NULLS FIRST", "NULLS LAST", "LIMIT", "OFFSET", "UNION", "UNION ALL",
+    "INTERSECT", "EXCEPT", "WITH", "RECURSIVE", "CASE", "WHEN", "THEN", "ELSE", "END", "CAST", "INSERT INTO",
+    "VALUES", "DEFAULT", "RETURNING", "UPDATE", "SET", "DELETE FROM", "MERGE INTO", "MATCHED", "TRUNCATE",
+    "ON CONFLICT", "DO NOTHING", "DO UPDATE", "EXPLAIN", "ANALYZE", "TRUE", "FALSE", "COUNT", "SUM", "AVG",
+    "MIN", "MAX", "COALESCE", "NOW",
+];
+
+/// Get the project's relations, columns and functions under their logical names,
+/// along with type names and keywords, for SQL editor completion
+pub async fn get_sql_completions(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+) -> AppResult<SqlCompletions> {
+    let project = authorize_project(db, user_id, project_slug, Capability::Read).await?.project;
+
+    let relations = project_relations(db, project.id).await?;
+    let logical_names: HashMap<&str, &str> = relations
+        .iter()
+        .map(|(logical, physical)| (physical.as_str(), logical.as_str()))
+        .collect();
+    let physical_names: Vec<String> = relations.values().cloned().collect();
+
+    // The catalog gives the columns of tables and views alike, in column order
+    let rows = db
+        .query_all(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r#"SELECT c.relname AS relation, c.relkind::text AS kind, a.attname AS column_name,
+                      format_type(a.atttypid, a.atttypmod) AS data_type
+               FROM pg_class c
+               JOIN pg_attribute a ON a.attrelid = c.oid AND a.attnum > 0 AND NOT a.attisdropped
+               WHERE c.relname = ANY($1) AND c.relnamespace = current_schema()::regnamespace
+               ORDER BY c.relname, a.attnum"#,
+            [physical_names.into()],
+        ))
+        .await?;
+
+    let mut completions: Vec<CompletionRelation> = Vec::new();
+    for row in rows {
+        let physical: String = row.try_get("", "relation")?;
+        let Some(name) = logical_names.get(physical.as_str()) else {
+            continue;
+        };
+
+        let column = CompletionColumn {
+            name: row.try_get("", "column_name")?,
+            data_type: row.try_get("", "data_type")?,
+        };
+
+        match completions.last_mut() {
+            Some(relation) if relation.name == *name => relation.columns.push(column),
+            _ => {
+                let kind: String = row.try_get("", "kind")?;
+                completions.push(CompletionRelation {
+                    name: (*name).to_owned(),
+                    kind: match kind.as_str() {
+                        "v" => "view",
+                        "m" => "materialized_view",
+                        _ => "table",
+                    }
+                    .to_string(),
+                    columns: vec![column],
+                });
+            }
+        }
+    }
+    completions.sort_by(|a, b| a.name.cmp(&b.name));
+
+    let functions = project_functions::Entity::find()
+        .filter(project_functions::Column::ProjectId.eq(project.id))
+        .order_by_asc(project_functions::Column::FunctionName)
+        .all(db)
+        .await?
+        .into_iter()
+        .map(|function| {
+            let arguments: Vec<FunctionArgument> = serde_json::from_str(&function.arguments).unwrap_or_default();
+            CompletionFunction {
+                name: function.function_name,
+                arguments: arguments
+                    .iter()
+                    .map(|arg| format!("{} {}", arg.name, arg.data_type))
+                    .collect::<Vec<_>>()
+                    .join(", "),
+                return_type: function.return_type,
+            }
+        })
+        .collect();
+
+    Ok(SqlCompletions {
+        relations: completions,
+        functions,
+        types: COMPLETION_TYPES.iter().map(|t| (*t).to_owned()).collect(),
+        keywords: COMPLETION_KEYWORDS.iter().map(|k| (*k).to_owned()).collect(),
+    })
+}
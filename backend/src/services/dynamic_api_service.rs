@@ -1,38 +1,45 @@
 use sea_orm::*;
 use database::entities::{project_tables, project_columns, projects};
 use crate::error::{AppError, AppResult};
+use crate::services::policy_service::{begin_request_transaction, policy_error};
+use crate::services::view_service::is_project_view;
 use uuid::Uuid;
 use serde_json::{Value as JsonValue};
+use crate::services::api_key_service::Principal;
+use crate::services::authorization_service::Capability;
 
 /// Execute a SELECT query on a user-defined table
 pub async fn query_table(
     db: &DatabaseConnection,
-    user_id: &str,
+    principal: &Principal,
     project_slug: &str,
     table_name: &str,
     _filters: Option<Vec<(String, String)>>,
     limit: Option<u64>,
     offset: Option<u64>,
 ) -> AppResult<Vec<JsonValue>> {
-    let owner_uuid = Uuid::parse_str(user_id)
-        .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
-
-    // Verify project ownership and get table
-    let (project, _table) = verify_table_access(db, owner_uuid, project_slug, table_name).await?;
+    // Verify project access and get table (views can be read too)
+    let (project, table) = verify_readable_relation(db, principal, project_slug, table_name).await?;
     
     // Build the actual PostgreSQL table name
     let pg_table_name = format!("project_{}_{}", project.id.as_simple(), table_name);
     
+    // Views have no guaranteed created_at column, so they keep their own ordering
+    let order_by = if table.is_some() { " ORDER BY created_at DESC" } else { "" };
+
     // Use row_to_json to convert rows to JSON automatically
     let query = format!(
-        "SELECT row_to_json(t) FROM (SELECT * FROM \"{}\" ORDER BY created_at DESC LIMIT {} OFFSET {}) t",
+        "SELECT row_to_json(t) FROM (SELECT * FROM \"{}\"{} LIMIT {} OFFSET {}) t",
         pg_table_name,
+        order_by,
         limit.unwrap_or(100).min(1000),
         offset.unwrap_or(0)
     );
     
     let stmt = Statement::from_string(DatabaseBackend::Postgres, query);
-    let result = db.query_all(stmt).await?;
+    let txn = begin_request_transaction(db, principal, &pg_table_name).await?;
+    let result = txn.query_all(stmt).await?;
+    txn.commit().await?;
     
     // Convert rows to JSON
     let rows: Vec<JsonValue> = result
@@ -48,15 +55,12 @@ pub async fn query_table(
 /// Get a single row by ID from a user-defined table
 pub async fn get_table_row(
     db: &DatabaseConnection,
-    user_id: &str,
+    principal: &Principal,
     project_slug: &str,
     table_name: &str,
     row_id: &str,
 ) -> AppResult<JsonValue> {
-    let owner_uuid = Uuid::parse_str(user_id)
-        .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
-
-    let (project, _table) = verify_table_access(db, owner_uuid, project_slug, table_name).await?;
+    let (project, _table) = verify_readable_relation(db, principal, project_slug, table_name).await?;
     
     let pg_table_name = format!("project_{}_{}", project.id.as_simple(), table_name);
     
@@ -70,9 +74,13 @@ pub async fn get_table_row(
     );
     
     let stmt = Statement::from_string(DatabaseBackend::Postgres, query);
+    let txn = begin_request_transaction(db, principal, &pg_table_name).await?;
     
-    let result = db.query_one(stmt).await?
+    // Views without an id column cannot be looked up by ID
+    let result = txn.query_one(stmt).await
+        .map_err(|e| AppError::BadRequest(format!("Failed to fetch row: {}", e)))?
         .ok_or_else(|| AppError::NotFound("Row not found".to_string()))?;
+    txn.commit().await?;
     
     let row = result.try_get::<JsonValue>("", "row_to_json")
         .map_err(|_| AppError::NotFound("Row not found".to_string()))?;
@@ -83,15 +91,12 @@ pub async fn get_table_row(
 /// Insert a new row into a user-defined table
 pub async fn insert_table_row(
     db: &DatabaseConnection,
-    user_id: &str,
+    principal: &Principal,
     project_slug: &str,
     table_name: &str,
     data: JsonValue,
 ) -> AppResult<JsonValue> {
-    let owner_uuid = Uuid::parse_str(user_id)
-        .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
-
-    let (project, table) = verify_table_access(db, owner_uuid, project_slug, table_name).await?;
+    let (project, table) = verify_table_access(db, principal, project_slug, table_name).await?;
     
     let pg_table_name = format!("project_{}_{}", project.id.as_simple(), table_name);
     
@@ -141,9 +146,12 @@ pub async fn insert_table_row(
     );
     
     let stmt = Statement::from_string(DatabaseBackend::Postgres, query);
+    let txn = begin_request_transaction(db, principal, &pg_table_name).await?;
     
-    let result = db.query_one(stmt).await?
+    let result = txn.query_one(stmt).await
+        .map_err(policy_error)?
         .ok_or_else(|| AppError::BadRequest("Failed to insert row".to_string()))?;
+    txn.commit().await?;
     
     let row = result.try_get::<JsonValue>("", "row_to_json")
         .map_err(|_| AppError::BadRequest("Failed to parse result".to_string()))?;
@@ -154,16 +162,13 @@ pub async fn insert_table_row(
 /// Update a row in a user-defined table
 pub async fn update_table_row(
     db: &DatabaseConnection,
-    user_id: &str,
+    principal: &Principal,
     project_slug: &str,
     table_name: &str,
     row_id: &str,
     data: JsonValue,
 ) -> AppResult<JsonValue> {
-    let owner_uuid = Uuid::parse_str(user_id)
-        .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
-
-    let (project, table) = verify_table_access(db, owner_uuid, project_slug, table_name).await?;
+    let (project, table) = verify_table_access(db, principal, project_slug, table_name).await?;
     
     let pg_table_name = format!("project_{}_{}", project.id.as_simple(), table_name);
     
@@ -215,9 +220,13 @@ pub async fn update_table_row(
     );
     
     let stmt = Statement::from_string(DatabaseBackend::Postgres, query);
+    let txn = begin_request_transaction(db, principal, &pg_table_name).await?;
     
-    let result = db.query_one(stmt).await?
+    // Rows hidden by a policy are reported as missing
+    let result = txn.query_one(stmt).await
+        .map_err(policy_error)?
         .ok_or_else(|| AppError::NotFound("Row not found".to_string()))?;
+    txn.commit().await?;
     
     let row = result.try_get::<JsonValue>("", "row_to_json")
         .map_err(|_| AppError::BadRequest("Failed to parse result".to_string()))?;
@@ -228,15 +237,12 @@ pub async fn update_table_row(
 /// Delete a row from a user-defined table
 pub async fn delete_table_row(
     db: &DatabaseConnection,
-    user_id: &str,
+    principal: &Principal,
     project_slug: &str,
     table_name: &str,
     row_id: &str,
 ) -> AppResult<()> {
-    let owner_uuid = Uuid::parse_str(user_id)
-        .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
-
-    let (project, _table) = verify_table_access(db, owner_uuid, project_slug, table_name).await?;
+    let (project, _table) = verify_table_access(db, principal, project_slug, table_name).await?;
     
     let pg_table_name = format!("project_{}_{}", project.id.as_simple(), table_name);
     
@@ -245,8 +251,10 @@ pub async fn delete_table_row(
     
     let query = format!("DELETE FROM \"{}\" WHERE id = '{}'", pg_table_name, row_id);
     let stmt = Statement::from_string(DatabaseBackend::Postgres, query);
+    let txn = begin_request_transaction(db, principal, &pg_table_name).await?;
     
-    let result = db.execute(stmt).await?;
+    let result = txn.execute(stmt).await.map_err(policy_error)?;
+    txn.commit().await?;
     
     if result.rows_affected() == 0 {
         return Err(AppError::NotFound("Row not found".to_string()));
@@ -255,121 +263,50 @@ pub async fn delete_table_row(
     Ok(())
 }
 
-/// Execute arbitrary SQL query within project context
-/// This allows users to run custom SQL queries but only on their own project tables
-pub async fn execute_sql(
+/// Helper function to verify the principal may write to a table of the project
+async fn verify_table_access(
     db: &DatabaseConnection,
-    user_id: &str,
+    principal: &Principal,
     project_slug: &str,
-    sql_query: &str,
-) -> AppResult<(Vec<JsonValue>, Option<u64>)> {
-    let owner_uuid = Uuid::parse_str(user_id)
-        .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
+    table_name: &str,
+) -> AppResult<(projects::Model, project_tables::Model)> {
+    // Get a project the principal may write data in
+    let project = principal.find_project(db, project_slug, Capability::WriteData).await?;
 
-    // Verify project ownership
-    let _project = projects::Entity::find()
-        .filter(projects::Column::Slug.eq(project_slug))
-        .filter(projects::Column::OwnerId.eq(owner_uuid))
+    // Get table and verify it belongs to this project
+    let table = project_tables::Entity::find()
+        .filter(project_tables::Column::ProjectId.eq(project.id))
+        .filter(project_tables::Column::TableName.eq(table_name))
         .one(db)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Project not found".to_string()))?;
-
-    // Basic SQL injection prevention - reject dangerous keywords
-    let sql_lower = sql_query.to_lowercase();
-    let dangerous_patterns = [
-        "drop database",
-        "drop schema", 
-        "create database",
-        "create schema",
-        "alter database",
-        "alter schema",
-    ];
-    
-    for pattern in &dangerous_patterns {
-        if sql_lower.contains(pattern) {
-            return Err(AppError::BadRequest(
-                format!("Query contains forbidden pattern: {}", pattern)
-            ));
-        }
-    }
-
-    // Check if query accesses system tables/schemas that should be restricted
-    let restricted_patterns = [
-        "pg_authid",
-        "pg_shadow",
-        "pg_user",
-    ];
-    
-    for pattern in &restricted_patterns {
-        if sql_lower.contains(pattern) {
-            return Err(AppError::BadRequest(
-                format!("Access to {} is not allowed", pattern)
-            ));
-        }
-    }
+        .await?;
 
-    // Determine if this is a SELECT query or a modification query
-    let is_select = sql_lower.trim().starts_with("select") || 
-                    sql_lower.trim().starts_with("with") ||
-                    sql_lower.trim().starts_with("show") ||
-                    sql_lower.trim().starts_with("explain");
-    
-    if is_select {
-        // For SELECT queries, wrap in a subquery to convert to JSON
-        let wrapped_query = format!(
-            "SELECT row_to_json(t) as data FROM ({}) t",
-            sql_query
-        );
-        
-        let stmt = Statement::from_string(DatabaseBackend::Postgres, wrapped_query);
-        let result = db.query_all(stmt).await
-            .map_err(|e| AppError::BadRequest(format!("SQL execution error: {}", e)))?;
-        
-        // Convert rows to JSON and get count before moving
-        let row_count = result.len() as u64;
-        let rows: Vec<JsonValue> = result
-            .into_iter()
-            .filter_map(|row| {
-                row.try_get::<JsonValue>("", "data").ok()
-            })
-            .collect();
-        
-        Ok((rows, Some(row_count)))
-    } else {
-        // For INSERT, UPDATE, DELETE, CREATE TABLE etc.
-        let stmt = Statement::from_string(DatabaseBackend::Postgres, sql_query.to_string());
-        let result = db.execute(stmt).await
-            .map_err(|e| AppError::BadRequest(format!("SQL execution error: {}", e)))?;
-        
-        let rows_affected = result.rows_affected();
-        
-        // Return empty result set with row count
-        Ok((vec![], Some(rows_affected)))
+    match table {
+        Some(table) => Ok((project, table)),
+        None if is_project_view(db, project.id, table_name).await? => Err(AppError::BadRequest(
+            format!("{} is a view and is read-only", table_name),
+        )),
+        None => Err(AppError::NotFound("Table not found".to_string())),
     }
 }
 
-/// Helper function to verify table access and ownership
-async fn verify_table_access(
+/// Like `verify_table_access`, but also accepts views (which have no table record)
+async fn verify_readable_relation(
     db: &DatabaseConnection,
-    owner_uuid: Uuid,
+    principal: &Principal,
     project_slug: &str,
     table_name: &str,
-) -> AppResult<(projects::Model, project_tables::Model)> {
-    // Get project and verify ownership
-    let project = projects::Entity::find()
-        .filter(projects::Column::Slug.eq(project_slug))
-        .filter(projects::Column::OwnerId.eq(owner_uuid))
-        .one(db)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Project not found".to_string()))?;
+) -> AppResult<(projects::Model, Option<project_tables::Model>)> {
+    let project = principal.find_project(db, project_slug, Capability::Read).await?;
 
-    // Get table and verify it belongs to this project
     let table = project_tables::Entity::find()
         .filter(project_tables::Column::ProjectId.eq(project.id))
         .filter(project_tables::Column::TableName.eq(table_name))
         .one(db)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Table not found".to_string()))?;
+        .await?;
+
+    if table.is_none() && !is_project_view(db, project.id, table_name).await? {
+        return Err(AppError::NotFound("Table not found".to_string()));
+    }
 
     Ok((project, table))
 }
@@ -0,0 +1,153 @@
+use chrono::Utc;
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use crate::mailer::{Email, Mailer};
+use crate::services::audit_service::{record_audit_event, AuditAction};
+use crate::services::auth_service::{generate_secret_token, hash_secret_token};
+use database::entities::prelude::*;
+use database::entities::{email_verification_tokens, users};
+use shared::models::VerifyEmailRequest;
+
+/// Minimum time between two verification emails to the same user
+const RESEND_COOLDOWN_SECONDS: i64 = 60;
+/// Maximum number of verification emails sent to the same user per hour
+const RESEND_HOURLY_LIMIT: u64 = 5;
+
+/// Email a verification link to the user's address, invalidating earlier links
+pub async fn send_verification_email(
+    db: &DatabaseConnection,
+    mailer: &dyn Mailer,
+    user: &users::Model,
+    config: &Config,
+) -> AppResult<()> {
+    // Only the most recent link works
+    EmailVerificationTokens::update_many()
+        .col_expr(email_verification_tokens::Column::UsedAt, Expr::value(Utc::now().naive_utc()))
+        .filter(email_verification_tokens::Column::UserId.eq(user.id))
+        .filter(email_verification_tokens::Column::UsedAt.is_null())
+        .exec(db)
+        .await?;
+
+    let token = generate_secret_token();
+    let expires_at = Utc::now() + chrono::Duration::hours(config.email_verification_expiration_hours as i64);
+
+    email_verification_tokens::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user.id),
+        token_hash: Set(hash_secret_token(&token)),
+        expires_at: Set(expires_at.naive_utc()),
+        created_at: Set(Some(Utc::now().naive_utc())),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    let link = format!("{}/verify-email?token={}", config.app_url.trim_end_matches('/'), token);
+    let email = Email {
+        to: user.email.clone(),
+        subject: "Verify your Ferrisbase email address".to_string(),
+        body: format!(
+            "Welcome to Ferrisbase!\n\n\
+             Open this link to verify your email address:\n{}\n\n\
+             The link expires in {} hours. \
+             If you did not create an account, you can ignore this email.\n",
+            link, config.email_verification_expiration_hours
+        ),
+    };
+
+    mailer.send(email).await
+}
+
+/// Send a new verification link to a signed-in user, throttled per user
+pub async fn resend_verification_email(
+    db: &DatabaseConnection,
+    mailer: &dyn Mailer,
+    user_id: &str,
+    config: &Config,
+) -> AppResult<()> {
+    let user_uuid = Uuid::parse_str(user_id)
+        .map_err(|_| AppError::BadRequest("Invalid user ID format".to_string()))?;
+
+    let user = Users::find_by_id(user_uuid)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    if user.email_verified.unwrap_or(false) {
+        return Err(AppError::BadRequest("Email address is already verified".to_string()));
+    }
+
+    let now = Utc::now().naive_utc();
+    let recent = EmailVerificationTokens::find()
+        .filter(email_verification_tokens::Column::UserId.eq(user.id))
+        .filter(email_verification_tokens::Column::CreatedAt.gt(now - chrono::Duration::hours(1)))
+        .order_by_desc(email_verification_tokens::Column::CreatedAt)
+        .all(db)
+        .await?;
+
+    let last_sent = recent.first().and_then(|token| token.created_at);
+    if last_sent.is_some_and(|sent| now - sent < chrono::Duration::seconds(RESEND_COOLDOWN_SECONDS)) {
+        return Err(AppError::TooManyRequests(format!(
+            "Please wait {} seconds before requesting another verification email",
+            RESEND_COOLDOWN_SECONDS
+        )));
+    }
+
+    if recent.len() as u64 >= RESEND_HOURLY_LIMIT {
+        return Err(AppError::TooManyRequests(
+            "Too many verification emails requested; please try again later".to_string(),
+        ));
+    }
+
+    send_verification_email(db, mailer, &user, config).await
+}
+
+/// Mark the user's email address as verified using a token from a verification link
+pub async fn verify_email(db: &DatabaseConnection, req: VerifyEmailRequest) -> AppResult<()> {
+    let invalid = || AppError::BadRequest("Verification link is invalid or has expired".to_string());
+
+    let token = EmailVerificationTokens::find()
+        .filter(email_verification_tokens::Column::TokenHash.eq(hash_secret_token(&req.token)))
+        .one(db)
+        .await?
+        .ok_or_else(invalid)?;
+
+    let now = Utc::now().naive_utc();
+    if token.used_at.is_some() || token.expires_at <= now {
+        return Err(invalid());
+    }
+
+    let user = Users::find_by_id(token.user_id)
+        .one(db)
+        .await?
+        .ok_or_else(invalid)?;
+
+    let txn = db.begin().await?;
+
+    // Claiming the token first keeps it single use under concurrent requests
+    let claimed = EmailVerificationTokens::update_many()
+        .col_expr(email_verification_tokens::Column::UsedAt, Expr::value(now))
+        .filter(email_verification_tokens::Column::Id.eq(token.id))
+        .filter(email_verification_tokens::Column::UsedAt.is_null())
+        .exec(&txn)
+        .await?;
+
+    if claimed.rows_affected == 0 {
+        return Err(invalid());
+    }
+
+    let mut active: users::ActiveModel = user.into();
+    active.email_verified = Set(Some(true));
+    active.updated_at = Set(Some(now));
+    let user = active.update(&txn).await?;
+
+    record_audit_event(&txn, Some(user.id), Some(user.id), AuditAction::EmailVerified, None).await?;
+
+    txn.commit().await?;
+
+    Ok(())
+}
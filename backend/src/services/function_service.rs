@@ -0,0 +1,538 @@
+use sea_orm::*;
+use database::entities::{project_functions, projects};
+use shared::models::{ApiKeyKind, CreateFunctionRequest, FunctionArgument, FunctionLanguage, FunctionResponse};
+use sqlparser::ast::{ArrayElemTypeDef, DataType};
+use sqlparser::dialect::PostgreSqlDialect;
+use sqlparser::keywords::Keyword;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::Token;
+use crate::error::{AppError, AppResult};
+use crate::services::api_key_service::Principal;
+use crate::services::authorization_service::{authorize_project, Capability};
+use crate::services::policy_service::{begin_function_transaction, policy_error};
+use crate::services::project_service::set_project_owner;
+use crate::services::sql_rewrite::{project_functions, project_relations, resolve_relations};
+use crate::services::table_service::physical_table_name;
+use serde_json::{Map, Value as JsonValue};
+use std::collections::HashMap;
+use uuid::Uuid;
+
+/// Dollar-quote tag wrapping function bodies
+const BODY_QUOTE: &str = "$function$";
+
+/// Postgres types that sqlparser only knows as custom type names
+const EXTRA_TYPES: &[&str] = &["void", "record", "inet", "cidr", "macaddr", "money", "tsvector", "tsquery", "xml"];
+
+/// List all functions for a project
+pub async fn list_project_functions(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+) -> AppResult<Vec<FunctionResponse>> {
+    let project = authorize_project(db, user_id, project_slug, Capability::Read).await?.project;
+
+    let functions = project_functions::Entity::find()
+        .filter(project_functions::Column::ProjectId.eq(project.id))
+        .order_by_asc(project_functions::Column::FunctionName)
+        .all(db)
+        .await?;
+
+    functions.into_iter().map(function_response).collect()
+}
+
+/// Get a specific function
+pub async fn get_project_function(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    function_name: &str,
+) -> AppResult<FunctionResponse> {
+    let project = authorize_project(db, user_id, project_slug, Capability::Read).await?.project;
+    let function = find_function(db, &project, function_name).await?;
+
+    function_response(function)
+}
+
+/// Create a SQL function in the project's namespace
+pub async fn create_project_function(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    req: CreateFunctionRequest,
+) -> AppResult<FunctionResponse> {
+    if !is_valid_identifier(&req.function_name) {
+        return Err(AppError::BadRequest(
+            "Function name can only contain letters, numbers, and underscores".to_string(),
+        ));
+    }
+
+    let project = authorize_project(db, user_id, project_slug, Capability::ManageSchema).await?.project;
+
+    let existing = project_functions::Entity::find()
+        .filter(project_functions::Column::ProjectId.eq(project.id))
+        .filter(project_functions::Column::FunctionName.eq(&req.function_name))
+        .one(db)
+        .await?;
+
+    if existing.is_some() {
+        return Err(AppError::BadRequest("Function with this name already exists".to_string()));
+    }
+
+    let relations = project_relations(db, project.id).await?;
+    let functions = project_functions(db, project.id, &[FunctionLanguage::Sql]).await?;
+
+    // Build the argument list, normalizing defaults through the parser
+    let mut arguments = Vec::with_capacity(req.arguments.len());
+    for arg in &req.arguments {
+        if !is_valid_identifier(&arg.name) {
+            return Err(AppError::BadRequest(format!("Invalid argument name: {}", arg.name)));
+        }
+        let data_type = parse_type(&arg.data_type, &relations).map_err(|e| {
+            AppError::BadRequest(format!("Invalid type for argument {}: {}: {}", arg.name, arg.data_type, e))
+        })?;
+
+        let default_value = arg
+            .default_value
+            .as_deref()
+            .map(|default| parse_default(default, &relations, &functions))
+            .transpose()?;
+
+        arguments.push(FunctionArgument {
+            name: arg.name.clone(),
+            data_type,
+            default_value,
+        });
+    }
+
+    let return_type = resolve_return_type(&req.return_type, &relations)?;
+
+    // Table names in SQL bodies are mapped to physical tables. PL/pgSQL bodies cannot be
+    // checked that way (dynamic SQL can name any table), so they are kept as written and
+    // only ever run as the project's role, which owns nothing outside the project.
+    let body = match req.language {
+        FunctionLanguage::Sql => {
+            let statements = Parser::parse_sql(&PostgreSqlDialect {}, &req.definition)
+                .map_err(|e| AppError::BadRequest(format!("Failed to parse function body: {}", e)))?;
+
+            let mut resolved = Vec::with_capacity(statements.len());
+            for mut statement in statements {
+                resolve_relations(&mut statement, &relations, &functions).map_err(AppError::BadRequest)?;
+                resolved.push(statement.to_string());
+            }
+            resolved.join(";\n")
+        }
+        FunctionLanguage::Plpgsql => req.definition.clone(),
+    };
+
+    if body.contains(BODY_QUOTE) {
+        return Err(AppError::BadRequest(format!("Function body cannot contain {}", BODY_QUOTE)));
+    }
+
+    let argument_defs: Vec<String> = arguments
+        .iter()
+        .map(|arg| match &arg.default_value {
+            Some(default) => format!("\"{}\" {} DEFAULT {}", arg.name, arg.data_type, default),
+            None => format!("\"{}\" {}", arg.name, arg.data_type),
+        })
+        .collect();
+
+    let pg_function_name = physical_table_name(&project.id, &req.function_name);
+    let create_function_sql = format!(
+        "CREATE FUNCTION \"{}\"({}) RETURNS {} LANGUAGE {} AS {}\n{}\n{}",
+        pg_function_name,
+        argument_defs.join(", "),
+        return_type,
+        req.language.as_str(),
+        BODY_QUOTE,
+        body,
+        BODY_QUOTE
+    );
+
+    let txn = db.begin().await?;
+
+    txn.execute(Statement::from_string(DatabaseBackend::Postgres, create_function_sql))
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Failed to create function: {}", e)))?;
+    set_project_owner(&txn, &project.id, &format!("FUNCTION \"{}\"", pg_function_name)).await?;
+
+    // Keep PL/pgSQL functions away from the policy role, which can reach every project
+    if req.language == FunctionLanguage::Plpgsql {
+        txn.execute(Statement::from_string(
+            DatabaseBackend::Postgres,
+            format!("REVOKE EXECUTE ON FUNCTION \"{}\" FROM PUBLIC", pg_function_name),
+        ))
+        .await?;
+    }
+
+    let now = chrono::Utc::now().naive_utc();
+    let function = project_functions::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        project_id: Set(project.id),
+        function_name: Set(req.function_name.clone()),
+        description: Set(req.description.clone()),
+        arguments: Set(serde_json::to_string(&arguments)
+            .map_err(|e| AppError::InternalServerError(e.to_string()))?),
+        return_type: Set(req.return_type.trim().to_string()),
+        language: Set(req.language.as_str().to_string()),
+        definition: Set(req.definition.clone()),
+        created_at: Set(Some(now)),
+        updated_at: Set(Some(now)),
+    }
+    .insert(&txn)
+    .await?;
+
+    txn.commit().await?;
+
+    function_response(function)
+}
+
+/// Delete a function
+pub async fn delete_project_function(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    function_name: &str,
+) -> AppResult<()> {
+    let project = authorize_project(db, user_id, project_slug, Capability::ManageSchema).await?.project;
+    let function = find_function(db, &project, function_name).await?;
+
+    // Function names are unique per project, so no signature is needed
+    let drop_function_sql = format!(
+        "DROP FUNCTION IF EXISTS \"{}\"",
+        physical_table_name(&project.id, &function.function_name)
+    );
+
+    db.execute(Statement::from_string(DatabaseBackend::Postgres, drop_function_sql))
+        .await?;
+
+    function.delete(db).await?;
+
+    Ok(())
+}
+
+/// Call a project function with named JSON arguments.
+///
+/// The call runs in a request transaction, so row-level security policies apply
+/// to the tables the function uses. PL/pgSQL functions can only be called with a
+/// service key, as they run as the project's role. Set-returning functions produce an array,
+/// other functions a single value (an object for composite results) and `void`
+/// functions `null`.
+pub async fn call_project_function(
+    db: &DatabaseConnection,
+    principal: &Principal,
+    project_slug: &str,
+    function_name: &str,
+    args: Map<String, JsonValue>,
+) -> AppResult<JsonValue> {
+    let project = principal.find_project(db, project_slug, Capability::WriteData).await?;
+    let function = find_function(db, &project, function_name).await?;
+    let arguments = parse_arguments(&function)?;
+
+    // The policy role that users call functions as can reach every project's tables,
+    // which a PL/pgSQL body could name through dynamic SQL
+    let is_service_key = matches!(principal, Principal::ApiKey { kind: ApiKeyKind::Service, .. });
+    if function.language != FunctionLanguage::Sql.as_str() && !is_service_key {
+        return Err(AppError::Forbidden(format!(
+            "Function {} is written in {} and can only be called with a service key",
+            function.function_name, function.language
+        )));
+    }
+
+    if let Some(unknown) = args.keys().find(|key| !arguments.iter().any(|arg| &arg.name == *key)) {
+        return Err(AppError::BadRequest(format!("Unknown argument: {}", unknown)));
+    }
+
+    // Use named notation so omitted arguments fall back to their defaults
+    let mut named_args = Vec::new();
+    let mut values: Vec<Value> = Vec::new();
+    for arg in &arguments {
+        let value = match args.get(&arg.name) {
+            Some(value) => value,
+            None if arg.default_value.is_some() => continue,
+            None => return Err(AppError::BadRequest(format!("Missing argument: {}", arg.name))),
+        };
+
+        // Arguments are bound as text and cast to the declared type
+        values.push(match value {
+            JsonValue::Null => Option::<String>::None.into(),
+            JsonValue::String(s) => s.clone().into(),
+            other => other.to_string().into(),
+        });
+        named_args.push(format!("\"{}\" => ${}::{}", arg.name, values.len(), arg.data_type));
+    }
+
+    let call = format!(
+        "\"{}\"({})",
+        physical_table_name(&project.id, &function.function_name),
+        named_args.join(", ")
+    );
+
+    let return_type = function.return_type.to_lowercase();
+    let txn = begin_function_transaction(db, principal, &project.id).await?;
+
+    if return_type == "void" {
+        let stmt = Statement::from_sql_and_values(DatabaseBackend::Postgres, format!("SELECT {}", call), values);
+        txn.execute(stmt).await.map_err(call_error)?;
+        txn.commit().await?;
+        return Ok(JsonValue::Null);
+    }
+
+    let stmt = Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        format!("SELECT to_jsonb(r) AS data FROM {} AS r", call),
+        values,
+    );
+
+    let rows = txn
+        .query_all(stmt)
+        .await
+        .map_err(call_error)?
+        .into_iter()
+        .map(|row| row.try_get::<Option<JsonValue>>("", "data").map(Option::unwrap_or_default))
+        .collect::<Result<Vec<_>, DbErr>>()?;
+
+    txn.commit().await?;
+
+    if returns_set(&return_type) {
+        Ok(JsonValue::Array(rows))
+    } else {
+        Ok(rows.into_iter().next().unwrap_or_default())
+    }
+}
+
+fn call_error(e: DbErr) -> AppError {
+    match policy_error(e) {
+        AppError::Database(e) => AppError::BadRequest(format!("Function call failed: {}", e)),
+        rejected => rejected,
+    }
+}
+
+fn returns_set(return_type: &str) -> bool {
+    return_type.starts_with("setof ") || return_type.starts_with("table(") || return_type.starts_with("table (")
+}
+
+fn is_valid_identifier(name: &str) -> bool {
+    !name.is_empty() && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+}
+
+/// Parse a single type name. Type names are spliced into DDL and casts, so what is spliced
+/// is rendered back from the parsed type rather than taken from the request.
+fn parse_type(data_type: &str, relations: &HashMap<String, String>) -> Result<String, String> {
+    let mut parser = Parser::new(&PostgreSqlDialect {})
+        .try_with_sql(data_type)
+        .map_err(|e| e.to_string())?;
+
+    let parsed = parser.parse_data_type().map_err(|e| e.to_string())?;
+
+    if parser.peek_token().token != Token::EOF {
+        return Err("expected a single type".to_string());
+    }
+
+    render_type(&parsed, relations)
+}
+
+/// Render a parsed type, mapping table names to the physical table's row type
+fn render_type(data_type: &DataType, relations: &HashMap<String, String>) -> Result<String, String> {
+    match data_type {
+        DataType::Custom(name, modifiers) => {
+            let [ident] = name.0.as_slice() else {
+                return Err(format!("unknown type {}", name));
+            };
+            let key = ident.value.to_lowercase();
+
+            if !modifiers.is_empty() {
+                Err(format!("type {} takes no modifiers", name))
+            } else if let Some(physical) = relations.get(&key) {
+                Ok(format!("\"{}\"", physical))
+            } else if EXTRA_TYPES.contains(&key.as_str()) {
+                Ok(key)
+            } else {
+                Err(format!("unknown type {}", name))
+            }
+        }
+        DataType::Array(ArrayElemTypeDef::SquareBracket(inner, size)) => {
+            let inner = render_type(inner, relations)?;
+            Ok(match size {
+                Some(size) => format!("{}[{}]", inner, size),
+                None => format!("{}[]", inner),
+            })
+        }
+        DataType::Character(_)
+        | DataType::Char(_)
+        | DataType::CharacterVarying(_)
+        | DataType::CharVarying(_)
+        | DataType::Varchar(_)
+        | DataType::Text
+        | DataType::Uuid
+        | DataType::Numeric(_)
+        | DataType::Decimal(_)
+        | DataType::Dec(_)
+        | DataType::Float(_)
+        | DataType::Real
+        | DataType::Float4
+        | DataType::Float8
+        | DataType::DoublePrecision
+        | DataType::SmallInt(_)
+        | DataType::Int2(_)
+        | DataType::Int(_)
+        | DataType::Int4(_)
+        | DataType::Integer(_)
+        | DataType::BigInt(_)
+        | DataType::Int8(_)
+        | DataType::Bool
+        | DataType::Boolean
+        | DataType::Date
+        | DataType::Time(_, _)
+        | DataType::Timestamp(_, _)
+        | DataType::Interval
+        | DataType::JSON
+        | DataType::JSONB
+        | DataType::Bytea
+        | DataType::Bit(_)
+        | DataType::BitVarying(_) => Ok(data_type.to_string()),
+        other => Err(format!("unsupported type {}", other)),
+    }
+}
+
+/// Validate the return type: a type, `setof <type>` or `table(<column> <type>, ...)`,
+/// where table names map to the physical table's row type
+fn resolve_return_type(return_type: &str, relations: &HashMap<String, String>) -> AppResult<String> {
+    let invalid = |e: String| AppError::BadRequest(format!("Invalid return type {}: {}", return_type, e));
+
+    let mut parser = Parser::new(&PostgreSqlDialect {})
+        .try_with_sql(return_type)
+        .map_err(|e| invalid(e.to_string()))?;
+
+    let resolved = if parser.parse_keyword(Keyword::TABLE) {
+        let (columns, constraints) = parser.parse_columns().map_err(|e| invalid(e.to_string()))?;
+        if columns.is_empty() || !constraints.is_empty() {
+            return Err(invalid("expected a list of columns and their types".to_string()));
+        }
+
+        let columns = columns
+            .iter()
+            .map(|column| {
+                if !is_valid_identifier(&column.name.value) || column.collation.is_some() || !column.options.is_empty() {
+                    return Err(format!("invalid column {}", column));
+                }
+                Ok(format!("\"{}\" {}", column.name.value, render_type(&column.data_type, relations)?))
+            })
+            .collect::<Result<Vec<_>, String>>()
+            .map_err(invalid)?;
+
+        format!("TABLE({})", columns.join(", "))
+    } else {
+        let setof = match parser.peek_token().token {
+            Token::Word(word) if word.value.eq_ignore_ascii_case("setof") => {
+                parser.next_token();
+                "SETOF "
+            }
+            _ => "",
+        };
+        let data_type = parser.parse_data_type().map_err(|e| invalid(e.to_string()))?;
+        format!("{}{}", setof, render_type(&data_type, relations).map_err(invalid)?)
+    };
+
+    if parser.peek_token().token != Token::EOF {
+        return Err(invalid("expected a single type".to_string()));
+    }
+
+    Ok(resolved)
+}
+
+/// Parse an argument default as a single SQL expression, checked like the function body
+fn parse_default(
+    default: &str,
+    relations: &HashMap<String, String>,
+    functions: &HashMap<String, String>,
+) -> AppResult<String> {
+    let invalid = |e: String| AppError::BadRequest(format!("Invalid default value {}: {}", default, e));
+
+    let mut parser = Parser::new(&PostgreSqlDialect {})
+        .try_with_sql(default)
+        .map_err(|e| invalid(e.to_string()))?;
+
+    let mut expr = parser.parse_expr().map_err(|e| invalid(e.to_string()))?;
+
+    if parser.peek_token().token != Token::EOF {
+        return Err(invalid("expected a single expression".to_string()));
+    }
+
+    resolve_relations(&mut expr, relations, functions).map_err(invalid)?;
+
+    Ok(expr.to_string())
+}
+
+fn parse_arguments(function: &project_functions::Model) -> AppResult<Vec<FunctionArgument>> {
+    serde_json::from_str(&function.arguments)
+        .map_err(|e| AppError::InternalServerError(format!("Invalid function arguments: {}", e)))
+}
+
+async fn find_function(
+    db: &DatabaseConnection,
+    project: &projects::Model,
+    function_name: &str,
+) -> AppResult<project_functions::Model> {
+    project_functions::Entity::find()
+        .filter(project_functions::Column::ProjectId.eq(project.id))
+        .filter(project_functions::Column::FunctionName.eq(function_name))
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Function not found".to_string()))
+}
+
+fn function_response(function: project_functions::Model) -> AppResult<FunctionResponse> {
+    let arguments = parse_arguments(&function)?;
+    let returns_set = returns_set(&function.return_type.to_lowercase());
+
+    Ok(FunctionResponse {
+        id: function.id.to_string(),
+        function_name: function.function_name,
+        description: function.description,
+        arguments,
+        return_type: function.return_type,
+        language: function.language,
+        definition: function.definition,
+        returns_set,
+        created_at: function.created_at.map(|dt| dt.to_string()).unwrap_or_default(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn relations() -> HashMap<String, String> {
+        HashMap::from([(String::from("notes"), String::from("project_x_notes"))])
+    }
+
+    #[test]
+    fn types_are_rendered_from_the_parsed_type() {
+        let relations = relations();
+
+        assert_eq!(parse_type("numeric(10, 2)", &relations).unwrap(), "NUMERIC(10,2)");
+        assert_eq!(parse_type("text[]", &relations).unwrap(), "TEXT[]");
+        assert_eq!(parse_type("Notes", &relations).unwrap(), "\"project_x_notes\"");
+        assert_eq!(parse_type("inet", &relations).unwrap(), "inet");
+
+        assert!(parse_type("int) RETURNS int LANGUAGE sql AS 'select 1'; --", &relations).is_err());
+        assert!(parse_type("users", &relations).is_err());
+        assert!(parse_type("public.notes", &relations).is_err());
+        assert!(parse_type("\"int\" DEFAULT 1", &relations).is_err());
+    }
+
+    #[test]
+    fn return_types_accept_sets_and_tables() {
+        let relations = relations();
+
+        assert_eq!(resolve_return_type("setof notes", &relations).unwrap(), "SETOF \"project_x_notes\"");
+        assert_eq!(
+            resolve_return_type("table(id uuid, total numeric)", &relations).unwrap(),
+            "TABLE(\"id\" UUID, \"total\" NUMERIC)"
+        );
+
+        assert!(resolve_return_type("int, y int) RETURNS int AS 'x' --", &relations).is_err());
+        assert!(resolve_return_type("table(id uuid default 1)", &relations).is_err());
+        assert!(resolve_return_type("setof users", &relations).is_err());
+    }
+}
@@ -0,0 +1,316 @@
+use chrono::Utc;
+use sea_orm::sea_query::{Expr, Func};
+use sea_orm::*;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use crate::mailer::{Email, Mailer};
+use crate::services::audit_service::{record_audit_event, AuditAction};
+use crate::services::auth_service::{generate_secret_token, hash_secret_token};
+use crate::services::authorization_service::{authorize_project, project_role, Capability};
+use crate::services::member_service::ensure_can_assign;
+use crate::services::project_service::project_response;
+use database::entities::prelude::*;
+use database::entities::{project_invitations, project_members, projects, users};
+use shared::models::{
+    CreateInvitationRequest, InvitationDetailsResponse, InvitationResponse, InvitationTokenRequest,
+    ProjectResponse, ProjectRole,
+};
+
+/// List a project's invitations that are still waiting for an answer
+pub async fn list_project_invitations(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+) -> AppResult<Vec<InvitationResponse>> {
+    let access = authorize_project(db, user_id, project_slug, Capability::ManageMembers).await?;
+
+    let invitations = ProjectInvitations::find()
+        .find_also_related(Users)
+        .filter(project_invitations::Column::ProjectId.eq(access.project.id))
+        .filter(project_invitations::Column::AcceptedAt.is_null())
+        .filter(project_invitations::Column::DeclinedAt.is_null())
+        .filter(project_invitations::Column::ExpiresAt.gt(Utc::now().naive_utc()))
+        .order_by_desc(project_invitations::Column::CreatedAt)
+        .all(db)
+        .await?;
+
+    invitations
+        .into_iter()
+        .map(|(invitation, inviter)| invitation_response(invitation, inviter.map(|user| user.email)))
+        .collect()
+}
+
+/// Invite someone to a project by email, replacing any pending invitation to the same address.
+///
+/// The invitation is only stored if the email could be sent.
+pub async fn create_project_invitation(
+    db: &DatabaseConnection,
+    mailer: &dyn Mailer,
+    user_id: &str,
+    project_slug: &str,
+    req: CreateInvitationRequest,
+    config: &Config,
+) -> AppResult<InvitationResponse> {
+    let access = authorize_project(db, user_id, project_slug, Capability::ManageMembers).await?;
+    ensure_can_assign(&access, req.role)?;
+
+    let email = req.email.trim().to_lowercase();
+    if !email.contains('@') || email.len() > 255 {
+        return Err(AppError::BadRequest("Please enter a valid email address".to_string()));
+    }
+
+    if let Some(user) = find_user_by_email(db, &email).await?
+        && project_role(db, access.project.id, user.id).await?.is_some()
+    {
+        return Err(AppError::BadRequest("User is already a member of this project".to_string()));
+    }
+
+    let inviter = Users::find_by_id(access.user_id)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    let txn = db.begin().await?;
+
+    // Only the most recent invitation to an address stays open
+    ProjectInvitations::delete_many()
+        .filter(project_invitations::Column::ProjectId.eq(access.project.id))
+        .filter(project_invitations::Column::Email.eq(&email))
+        .filter(project_invitations::Column::AcceptedAt.is_null())
+        .filter(project_invitations::Column::DeclinedAt.is_null())
+        .exec(&txn)
+        .await?;
+
+    let token = generate_secret_token();
+    let expires_at = Utc::now() + chrono::Duration::hours(config.invitation_expiration_hours as i64);
+
+    let invitation = project_invitations::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        project_id: Set(access.project.id),
+        email: Set(email.clone()),
+        role: Set(req.role.as_str().to_string()),
+        token_hash: Set(hash_secret_token(&token)),
+        invited_by: Set(Some(inviter.id)),
+        expires_at: Set(expires_at.naive_utc()),
+        created_at: Set(Some(Utc::now().naive_utc())),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+
+    let link = format!("{}/invitations?token={}", config.app_url.trim_end_matches('/'), token);
+    let inviter_name = inviter.name.clone().unwrap_or_else(|| inviter.email.clone());
+    mailer
+        .send(Email {
+            to: email,
+            subject: format!("You have been invited to {} on Ferrisbase", access.project.name),
+            body: format!(
+                "{} invited you to join the {} project on Ferrisbase as {}.\n\n\
+                 Open this link to accept or decline the invitation:\n{}\n\n\
+                 If you do not have an account yet, you can create one with this email address \
+                 from the same link. The invitation expires in {} hours.\n",
+                inviter_name,
+                access.project.name,
+                req.role.as_str(),
+                link,
+                config.invitation_expiration_hours
+            ),
+        })
+        .await?;
+
+    txn.commit().await?;
+
+    invitation_response(invitation, Some(inviter.email))
+}
+
+/// Withdraw a pending invitation so its link stops working
+pub async fn revoke_project_invitation(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    invitation_id: &str,
+) -> AppResult<()> {
+    let access = authorize_project(db, user_id, project_slug, Capability::ManageMembers).await?;
+
+    let not_found = || AppError::NotFound("Invitation not found".to_string());
+    let id = Uuid::parse_str(invitation_id).map_err(|_| not_found())?;
+
+    let invitation = ProjectInvitations::find_by_id(id)
+        .filter(project_invitations::Column::ProjectId.eq(access.project.id))
+        .filter(project_invitations::Column::AcceptedAt.is_null())
+        .filter(project_invitations::Column::DeclinedAt.is_null())
+        .one(db)
+        .await?
+        .ok_or_else(not_found)?;
+
+    ensure_can_assign(&access, parse_role(&invitation.role)?)?;
+
+    invitation.delete(db).await?;
+
+    Ok(())
+}
+
+/// Describe the project and role an invitation link offers
+pub async fn get_invitation_details(
+    db: &DatabaseConnection,
+    req: InvitationTokenRequest,
+) -> AppResult<InvitationDetailsResponse> {
+    let (invitation, project) = find_pending_invitation(db, &req.token).await?;
+
+    let inviter = match invitation.invited_by {
+        Some(inviter_id) => Users::find_by_id(inviter_id).one(db).await?.map(|user| user.email),
+        None => None,
+    };
+
+    Ok(InvitationDetailsResponse {
+        project_name: project.name,
+        email: invitation.email,
+        role: parse_role(&invitation.role)?,
+        invited_by: inviter,
+        expires_at: invitation.expires_at.to_string(),
+    })
+}
+
+/// Join a project through an invitation sent to the signed-in user's email address.
+///
+/// Holding the emailed link proves the address, so it is marked verified as well.
+pub async fn accept_invitation(
+    db: &DatabaseConnection,
+    user_id: &str,
+    req: InvitationTokenRequest,
+) -> AppResult<ProjectResponse> {
+    let user_uuid = Uuid::parse_str(user_id)
+        .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
+
+    let user = Users::find_by_id(user_uuid)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    let (invitation, project) = find_pending_invitation(db, &req.token).await?;
+
+    if !user.email.trim().eq_ignore_ascii_case(&invitation.email) {
+        return Err(AppError::Forbidden(
+            "This invitation was sent to a different email address".to_string(),
+        ));
+    }
+
+    let invited_role = parse_role(&invitation.role)?;
+    let now = Utc::now().naive_utc();
+    let txn = db.begin().await?;
+
+    claim_invitation(&txn, &invitation, project_invitations::Column::AcceptedAt, now).await?;
+
+    // Someone who already joined keeps the role they have
+    let role = match project_role(&txn, project.id, user.id).await? {
+        Some(role) => role,
+        None => {
+            project_members::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                project_id: Set(project.id),
+                user_id: Set(user.id),
+                role: Set(invited_role.as_str().to_string()),
+                created_at: Set(Some(now)),
+            }
+            .insert(&txn)
+            .await?;
+            invited_role
+        }
+    };
+
+    if !user.email_verified.unwrap_or(false) {
+        let mut active: users::ActiveModel = user.into();
+        active.email_verified = Set(Some(true));
+        active.updated_at = Set(Some(now));
+        let user = active.update(&txn).await?;
+
+        record_audit_event(&txn, Some(user.id), Some(user.id), AuditAction::EmailVerified, None).await?;
+    }
+
+    txn.commit().await?;
+
+    Ok(project_response(project, role))
+}
+
+/// Turn down an invitation; the link is enough, so this works without an account
+pub async fn decline_invitation(db: &DatabaseConnection, req: InvitationTokenRequest) -> AppResult<()> {
+    let (invitation, _) = find_pending_invitation(db, &req.token).await?;
+
+    claim_invitation(db, &invitation, project_invitations::Column::DeclinedAt, Utc::now().naive_utc()).await
+}
+
+/// Find an unanswered, unexpired invitation and its project by the token from its link
+async fn find_pending_invitation(
+    db: &DatabaseConnection,
+    token: &str,
+) -> AppResult<(project_invitations::Model, projects::Model)> {
+    let invalid = || AppError::BadRequest("Invitation is invalid or has expired".to_string());
+
+    let (invitation, project) = ProjectInvitations::find()
+        .find_also_related(Projects)
+        .filter(project_invitations::Column::TokenHash.eq(hash_secret_token(token)))
+        .one(db)
+        .await?
+        .ok_or_else(invalid)?;
+
+    let project = project.ok_or_else(invalid)?;
+
+    if invitation.accepted_at.is_some()
+        || invitation.declined_at.is_some()
+        || invitation.expires_at <= Utc::now().naive_utc()
+    {
+        return Err(invalid());
+    }
+
+    Ok((invitation, project))
+}
+
+/// Record the answer to an invitation, failing if it was answered concurrently
+async fn claim_invitation<C: ConnectionTrait>(
+    db: &C,
+    invitation: &project_invitations::Model,
+    answered_at: project_invitations::Column,
+    now: chrono::NaiveDateTime,
+) -> AppResult<()> {
+    let claimed = ProjectInvitations::update_many()
+        .col_expr(answered_at, Expr::value(now))
+        .filter(project_invitations::Column::Id.eq(invitation.id))
+        .filter(project_invitations::Column::AcceptedAt.is_null())
+        .filter(project_invitations::Column::DeclinedAt.is_null())
+        .exec(db)
+        .await?;
+
+    if claimed.rows_affected == 0 {
+        return Err(AppError::BadRequest("Invitation is invalid or has expired".to_string()));
+    }
+
+    Ok(())
+}
+
+async fn find_user_by_email(db: &DatabaseConnection, email: &str) -> AppResult<Option<users::Model>> {
+    Ok(Users::find()
+        .filter(Expr::expr(Func::lower(Expr::col(users::Column::Email))).eq(email))
+        .one(db)
+        .await?)
+}
+
+fn parse_role(role: &str) -> AppResult<ProjectRole> {
+    ProjectRole::parse(role)
+        .ok_or_else(|| AppError::InternalServerError(format!("Unknown project role: {}", role)))
+}
+
+fn invitation_response(
+    invitation: project_invitations::Model,
+    invited_by: Option<String>,
+) -> AppResult<InvitationResponse> {
+    Ok(InvitationResponse {
+        id: invitation.id.to_string(),
+        email: invitation.email,
+        role: parse_role(&invitation.role)?,
+        invited_by,
+        expires_at: invitation.expires_at.to_string(),
+        created_at: invitation.created_at.map(|dt| dt.to_string()).unwrap_or_default(),
+    })
+}
@@ -0,0 +1,330 @@
+use std::net::IpAddr;
+
+use chrono::Utc;
+use sea_orm::*;
+use serde_json::json;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use crate::services::audit_service::{record_audit_event, AuditAction};
+use database::entities::prelude::*;
+use database::entities::{login_throttles, users};
+
+const ACCOUNT_SCOPE: &str = "account";
+const IP_SCOPE: &str = "ip";
+const MFA_SCOPE: &str = "mfa";
+
+/// Longest lockout, whatever the configuration, so deadlines stay within timestamp range
+const MAX_LOCKOUT_SECS: i64 = 365 * 24 * 60 * 60;
+
+/// Reject a sign-in while its email address or the client's IP address is locked out.
+///
+/// Accounts are keyed by email address whether or not a user has it, so unknown
+/// addresses are throttled just like real ones and lockouts do not reveal which exist.
+pub async fn ensure_login_allowed(db: &DatabaseConnection, email: &str, ip: IpAddr) -> AppResult<()> {
+    let now = Utc::now().naive_utc();
+
+    let locked_until = LoginThrottles::find()
+        .filter(
+            Condition::any()
+                .add(throttle_filter(ACCOUNT_SCOPE, &account_identifier(email)))
+                .add(throttle_filter(IP_SCOPE, &ip.to_string())),
+        )
+        .filter(login_throttles::Column::LockedUntil.gt(now))
+        .all(db)
+        .await?
+        .into_iter()
+        .filter_map(|throttle| throttle.locked_until)
+        .max();
+
+    match locked_until {
+        Some(locked_until) => Err(locked_out((locked_until - now).num_seconds().max(1) as u64)),
+        None => Ok(()),
+    }
+}
+
+/// Count a failed sign-in against the email address and the client's IP address.
+///
+/// Returns the error to answer with: invalid credentials, or a lockout once
+/// either of them has run out of attempts.
+pub async fn record_login_failure(
+    db: &DatabaseConnection,
+    email: &str,
+    ip: IpAddr,
+    user: Option<&users::Model>,
+    config: &Config,
+) -> AppResult<AppError> {
+    let now = Utc::now().naive_utc();
+
+    // Throttles that have been quiet for a whole window no longer count for anything
+    let window_start = now - chrono::Duration::minutes(config.login_failure_window_minutes as i64);
+    LoginThrottles::delete_many()
+        .filter(login_throttles::Column::LastFailedAt.lt(window_start))
+        .filter(
+            Condition::any()
+                .add(login_throttles::Column::LockedUntil.is_null())
+                .add(login_throttles::Column::LockedUntil.lt(now)),
+        )
+        .exec(db)
+        .await?;
+
+    let account = account_identifier(email);
+    let account_failures = count_failure(db, ACCOUNT_SCOPE, &account, config).await?;
+    let ip_failures = count_failure(db, IP_SCOPE, &ip.to_string(), config).await?;
+
+    let account_lockout = lockout_seconds(account_failures, config.login_max_failures_per_account, config);
+    let ip_lockout = lockout_seconds(ip_failures, config.login_max_failures_per_ip, config);
+
+    if let Some(seconds) = account_lockout {
+        lock(db, ACCOUNT_SCOPE, &account, seconds).await?;
+        tracing::warn!(
+            "Locked out sign-ins for {} for {}s after {} failed attempts (last from {})",
+            account, seconds, account_failures, ip
+        );
+
+        if let Some(user) = user {
+            record_audit_event(
+                db,
+                None,
+                Some(user.id),
+                AuditAction::AccountLocked,
+                Some(json!({ "failed_attempts": account_failures, "locked_seconds": seconds, "ip": ip.to_string() })),
+            )
+            .await?;
+        }
+    }
+
+    if let Some(seconds) = ip_lockout {
+        lock(db, IP_SCOPE, &ip.to_string(), seconds).await?;
+        tracing::warn!(
+            "Locked out sign-ins from {} for {}s after {} failed attempts",
+            ip, seconds, ip_failures
+        );
+    }
+
+    Ok(match account_lockout.max(ip_lockout) {
+        Some(seconds) => locked_out(seconds),
+        None => AppError::Unauthorized("Invalid email or password".to_string()),
+    })
+}
+
+/// Forget the failed sign-ins of an email address after a successful one.
+///
+/// The IP address keeps its count, so signing in to one account between guesses
+/// at others does not reset the limit.
+pub async fn clear_login_failures(db: &DatabaseConnection, email: &str) -> AppResult<()> {
+    LoginThrottles::delete_many()
+        .filter(throttle_filter(ACCOUNT_SCOPE, &account_identifier(email)))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Reject a second-factor code while the user is locked out of entering them
+pub async fn ensure_mfa_allowed(db: &DatabaseConnection, user_id: Uuid) -> AppResult<()> {
+    let now = Utc::now().naive_utc();
+
+    let locked_until = LoginThrottles::find()
+        .filter(throttle_filter(MFA_SCOPE, &user_id.to_string()))
+        .filter(login_throttles::Column::LockedUntil.gt(now))
+        .one(db)
+        .await?
+        .and_then(|throttle| throttle.locked_until);
+
+    match locked_until {
+        Some(locked_until) => Err(locked_out((locked_until - now).num_seconds().max(1) as u64)),
+        None => Ok(()),
+    }
+}
+
+/// Count a wrong second-factor code against the user, across all of their challenges.
+///
+/// A correct password resets the account's count, so wrong codes are counted apart
+/// from it until a second factor succeeds. Once they run out, sign-ins to the account
+/// are locked out too. Returns the error to answer with: `invalid`, or the lockout.
+pub async fn record_mfa_failure(
+    db: &DatabaseConnection,
+    user: &users::Model,
+    invalid: AppError,
+    config: &Config,
+) -> AppResult<AppError> {
+    let failures = count_failure(db, MFA_SCOPE, &user.id.to_string(), config).await?;
+
+    let Some(seconds) = lockout_seconds(failures, config.login_max_failures_per_account, config) else {
+        return Ok(invalid);
+    };
+
+    lock(db, MFA_SCOPE, &user.id.to_string(), seconds).await?;
+    lock(db, ACCOUNT_SCOPE, &account_identifier(&user.email), seconds).await?;
+    tracing::warn!(
+        "Locked out sign-ins for {} for {}s after {} incorrect second-factor codes",
+        user.email, seconds, failures
+    );
+
+    record_audit_event(
+        db,
+        None,
+        Some(user.id),
+        AuditAction::AccountLocked,
+        Some(json!({ "failed_codes": failures, "locked_seconds": seconds })),
+    )
+    .await?;
+
+    Ok(locked_out(seconds))
+}
+
+/// Forget a user's wrong second-factor codes after a correct one
+pub async fn clear_mfa_failures(db: &DatabaseConnection, user_id: Uuid) -> AppResult<()> {
+    LoginThrottles::delete_many()
+        .filter(throttle_filter(MFA_SCOPE, &user_id.to_string()))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Add a failure to a throttle, starting over if the last one is older than the window
+async fn count_failure(db: &DatabaseConnection, scope: &str, identifier: &str, config: &Config) -> AppResult<u32> {
+    let now = Utc::now().naive_utc();
+    let window_start = now - chrono::Duration::minutes(config.login_failure_window_minutes as i64);
+
+    let row = db
+        .query_one(Statement::from_sql_and_values(
+            DbBackend::Postgres,
+            r#"INSERT INTO login_throttles (scope, identifier, failed_attempts, last_failed_at)
+               VALUES ($1, $2, 1, $3)
+               ON CONFLICT (scope, identifier) DO UPDATE SET
+                   failed_attempts = CASE
+                       WHEN login_throttles.last_failed_at < $4 THEN 1
+                       ELSE login_throttles.failed_attempts + 1
+                   END,
+                   last_failed_at = EXCLUDED.last_failed_at
+               RETURNING failed_attempts"#,
+            [scope.into(), identifier.into(), now.into(), window_start.into()],
+        ))
+        .await?
+        .ok_or_else(|| AppError::InternalServerError("Failed to count sign-in attempt".to_string()))?;
+
+    let failures: i32 = row.try_get("", "failed_attempts")?;
+    Ok(failures.max(0) as u32)
+}
+
+/// Lock a throttle, creating it if needed: a correct password may have just cleared the account's
+async fn lock(db: &DatabaseConnection, scope: &str, identifier: &str, seconds: u64) -> AppResult<()> {
+    let now = Utc::now().naive_utc();
+    let seconds = i64::try_from(seconds).unwrap_or(MAX_LOCKOUT_SECS).min(MAX_LOCKOUT_SECS);
+    let locked_until = now
+        .checked_add_signed(chrono::Duration::seconds(seconds))
+        .ok_or_else(|| AppError::InternalServerError("Lockout ends out of range".to_string()))?;
+
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        r#"INSERT INTO login_throttles (scope, identifier, failed_attempts, locked_until, last_failed_at)
+           VALUES ($1, $2, 0, $3, $4)
+           ON CONFLICT (scope, identifier) DO UPDATE SET locked_until = EXCLUDED.locked_until"#,
+        [scope.into(), identifier.into(), locked_until.into(), now.into()],
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Lockout after `failures`: none below the limit, then doubling from the base up to the maximum
+fn lockout_seconds(failures: u32, max_failures: u32, config: &Config) -> Option<u64> {
+    let max_failures = max_failures.max(1);
+    if failures < max_failures {
+        return None;
+    }
+
+    let doublings = (failures - max_failures).min(32);
+    Some(
+        config
+            .login_lockout_base_seconds
+            .saturating_mul(1 << doublings)
+            .min(config.login_lockout_max_seconds)
+            .min(MAX_LOCKOUT_SECS as u64),
+    )
+}
+
+fn locked_out(seconds: u64) -> AppError {
+    AppError::RetryLater(
+        format!("Too many failed sign-in attempts; try again in {} seconds", seconds),
+        seconds,
+    )
+}
+
+fn account_identifier(email: &str) -> String {
+    email.trim().to_lowercase()
+}
+
+fn throttle_filter(scope: &str, identifier: &str) -> Condition {
+    Condition::all()
+        .add(login_throttles::Column::Scope.eq(scope))
+        .add(login_throttles::Column::Identifier.eq(identifier))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn throttle_config(base: u64, max: u64) -> Config {
+        Config {
+            database_url: String::new(),
+            jwt_secret: String::new(),
+            access_token_expiration_minutes: 15,
+            refresh_token_expiration_days: 30,
+            impersonation_expiration_minutes: 15,
+            server_host: String::new(),
+            server_port: 0,
+            app_url: String::new(),
+            smtp_url: None,
+            mail_from: String::new(),
+            mail_outbox_dir: String::new(),
+            password_reset_expiration_minutes: 60,
+            require_email_verification: false,
+            email_verification_expiration_hours: 24,
+            invitation_expiration_hours: 72,
+            oidc_providers: Vec::new(),
+            login_max_failures_per_account: 5,
+            login_max_failures_per_ip: 20,
+            login_lockout_base_seconds: base,
+            login_lockout_max_seconds: max,
+            login_failure_window_minutes: 15,
+            trusted_proxy_hops: 0,
+        }
+    }
+
+    #[test]
+    fn no_lockout_below_the_limit() {
+        let config = throttle_config(30, 3600);
+        assert_eq!(lockout_seconds(0, 5, &config), None);
+        assert_eq!(lockout_seconds(4, 5, &config), None);
+    }
+
+    #[test]
+    fn lockout_doubles_after_the_limit() {
+        let config = throttle_config(30, 3600);
+        assert_eq!(lockout_seconds(5, 5, &config), Some(30));
+        assert_eq!(lockout_seconds(6, 5, &config), Some(60));
+        assert_eq!(lockout_seconds(7, 5, &config), Some(120));
+    }
+
+    #[test]
+    fn lockout_is_capped() {
+        let config = throttle_config(30, 3600);
+        assert_eq!(lockout_seconds(12, 5, &config), Some(3600));
+        assert_eq!(lockout_seconds(u32::MAX, 5, &config), Some(3600));
+
+        let config = throttle_config(u64::MAX / 2, u64::MAX);
+        assert_eq!(lockout_seconds(10, 5, &config), Some(MAX_LOCKOUT_SECS as u64));
+    }
+
+    #[test]
+    fn a_zero_limit_locks_out_on_the_first_failure() {
+        let config = throttle_config(30, 3600);
+        assert_eq!(lockout_seconds(0, 0, &config), None);
+        assert_eq!(lockout_seconds(1, 0, &config), Some(30));
+    }
+}
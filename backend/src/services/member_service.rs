@@ -0,0 +1,228 @@
+use sea_orm::*;
+use database::entities::{project_members, projects, users};
+use shared::models::{AddMemberRequest, MemberResponse, ProjectRole, UpdateMemberRequest};
+use crate::error::{AppError, AppResult};
+use crate::services::authorization_service::{authorize_project, ensure_role_allows, Capability, ProjectAccess};
+use uuid::Uuid;
+
+/// List the members of a project, owners first
+pub async fn list_project_members(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+) -> AppResult<Vec<MemberResponse>> {
+    let access = authorize_project(db, user_id, project_slug, Capability::Read).await?;
+
+    let members = project_members::Entity::find()
+        .find_also_related(users::Entity)
+        .filter(project_members::Column::ProjectId.eq(access.project.id))
+        .order_by_asc(project_members::Column::CreatedAt)
+        .all(db)
+        .await?;
+
+    let mut members = members
+        .into_iter()
+        .filter_map(|(member, user)| user.map(|user| member_response(member, user)))
+        .collect::<AppResult<Vec<_>>>()?;
+
+    members.sort_by_key(|member| member.role);
+
+    Ok(members)
+}
+
+/// Add an existing user to a project by email
+pub async fn add_project_member(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    req: AddMemberRequest,
+) -> AppResult<MemberResponse> {
+    let access = authorize_project(db, user_id, project_slug, Capability::ManageMembers).await?;
+    ensure_can_assign(&access, req.role)?;
+
+    let user = users::Entity::find()
+        .filter(users::Column::Email.eq(req.email.trim()))
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("No user with that email address".to_string()))?;
+
+    if find_member(db, &access, user.id).await?.is_some() {
+        return Err(AppError::BadRequest("User is already a member of this project".to_string()));
+    }
+
+    let member = project_members::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        project_id: Set(access.project.id),
+        user_id: Set(user.id),
+        role: Set(req.role.as_str().to_string()),
+        created_at: Set(Some(chrono::Utc::now().naive_utc())),
+    }
+    .insert(db)
+    .await?;
+
+    member_response(member, user)
+}
+
+/// Change a member's role; a project always keeps at least one owner
+pub async fn update_project_member(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    member_user_id: &str,
+    req: UpdateMemberRequest,
+) -> AppResult<MemberResponse> {
+    let access = authorize_project(db, user_id, project_slug, Capability::ManageMembers).await?;
+    let (member, user) = get_member(db, &access, member_user_id).await?;
+    let current_role = parse_role(&member.role)?;
+
+    ensure_can_assign(&access, current_role)?;
+    ensure_can_assign(&access, req.role)?;
+
+    let demoted = current_role == ProjectRole::Owner && req.role != ProjectRole::Owner;
+    if demoted {
+        ensure_other_owner(db, &access).await?;
+    }
+
+    let txn = db.begin().await?;
+
+    let mut active: project_members::ActiveModel = member.into();
+    active.role = Set(req.role.as_str().to_string());
+    let member = active.update(&txn).await?;
+
+    if demoted {
+        reassign_project_owner(&txn, &access.project, member.user_id).await?;
+    }
+
+    txn.commit().await?;
+
+    member_response(member, user)
+}
+
+/// Remove a member from a project; any member may remove themselves
+pub async fn remove_project_member(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    member_user_id: &str,
+) -> AppResult<()> {
+    let access = authorize_project(db, user_id, project_slug, Capability::Read).await?;
+    let (member, _) = get_member(db, &access, member_user_id).await?;
+    let role = parse_role(&member.role)?;
+
+    if member.user_id != access.user_id {
+        ensure_role_allows(access.role, Capability::ManageMembers)?;
+        ensure_can_assign(&access, role)?;
+    }
+
+    if role == ProjectRole::Owner {
+        ensure_other_owner(db, &access).await?;
+    }
+
+    let txn = db.begin().await?;
+
+    let member_user_id = member.user_id;
+    member.delete(&txn).await?;
+
+    if role == ProjectRole::Owner {
+        reassign_project_owner(&txn, &access.project, member_user_id).await?;
+    }
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Only owners can grant the owner role or change and remove other owners
+pub(crate) fn ensure_can_assign(access: &ProjectAccess, role: ProjectRole) -> AppResult<()> {
+    if role == ProjectRole::Owner && access.role != ProjectRole::Owner {
+        return Err(AppError::Forbidden("Only owners can manage owners".to_string()));
+    }
+
+    Ok(())
+}
+
+/// Point `projects.owner_id` at the longest-standing remaining owner when it names
+/// `former_owner`, who is no longer one
+async fn reassign_project_owner<C: ConnectionTrait>(
+    db: &C,
+    project: &projects::Model,
+    former_owner: Uuid,
+) -> AppResult<()> {
+    if project.owner_id != former_owner {
+        return Ok(());
+    }
+
+    let owner = project_members::Entity::find()
+        .filter(project_members::Column::ProjectId.eq(project.id))
+        .filter(project_members::Column::Role.eq(ProjectRole::Owner.as_str()))
+        .filter(project_members::Column::UserId.ne(former_owner))
+        .order_by_asc(project_members::Column::CreatedAt)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("A project must keep at least one owner".to_string()))?;
+
+    let mut active: projects::ActiveModel = project.clone().into();
+    active.owner_id = Set(owner.user_id);
+    active.update(db).await?;
+
+    Ok(())
+}
+
+/// Reject removing or demoting the project's last owner
+async fn ensure_other_owner(db: &DatabaseConnection, access: &ProjectAccess) -> AppResult<()> {
+    let owners = project_members::Entity::find()
+        .filter(project_members::Column::ProjectId.eq(access.project.id))
+        .filter(project_members::Column::Role.eq(ProjectRole::Owner.as_str()))
+        .count(db)
+        .await?;
+
+    if owners <= 1 {
+        return Err(AppError::BadRequest("A project must keep at least one owner".to_string()));
+    }
+
+    Ok(())
+}
+
+async fn find_member(
+    db: &DatabaseConnection,
+    access: &ProjectAccess,
+    member_user_id: Uuid,
+) -> AppResult<Option<project_members::Model>> {
+    Ok(project_members::Entity::find()
+        .filter(project_members::Column::ProjectId.eq(access.project.id))
+        .filter(project_members::Column::UserId.eq(member_user_id))
+        .one(db)
+        .await?)
+}
+
+async fn get_member(
+    db: &DatabaseConnection,
+    access: &ProjectAccess,
+    member_user_id: &str,
+) -> AppResult<(project_members::Model, users::Model)> {
+    let not_found = || AppError::NotFound("Member not found".to_string());
+
+    let member_user_id = Uuid::parse_str(member_user_id).map_err(|_| not_found())?;
+    let member = find_member(db, access, member_user_id).await?.ok_or_else(not_found)?;
+    let user = users::Entity::find_by_id(member.user_id)
+        .one(db)
+        .await?
+        .ok_or_else(not_found)?;
+
+    Ok((member, user))
+}
+
+fn parse_role(role: &str) -> AppResult<ProjectRole> {
+    ProjectRole::parse(role)
+        .ok_or_else(|| AppError::InternalServerError(format!("Unknown project role: {}", role)))
+}
+
+fn member_response(member: project_members::Model, user: users::Model) -> AppResult<MemberResponse> {
+    Ok(MemberResponse {
+        user_id: user.id.to_string(),
+        email: user.email,
+        name: user.name,
+        role: parse_role(&member.role)?,
+        created_at: member.created_at.map(|dt| dt.to_string()).unwrap_or_default(),
+    })
+}
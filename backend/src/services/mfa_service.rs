@@ -0,0 +1,544 @@
+use std::fmt::Write;
+
+use chrono::Utc;
+use qrcodegen::{QrCode, QrCodeEcc};
+use rand::Rng;
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+use totp_rs::{Algorithm, Secret, TOTP};
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use crate::services::audit_service::{record_audit_event, AuditAction};
+use crate::services::auth_service::{
+    decode_amr, encode_amr, generate_secret_token, hash_secret_token, issue_tokens,
+};
+use crate::services::login_throttle_service::{clear_mfa_failures, ensure_mfa_allowed, record_mfa_failure};
+use database::entities::prelude::*;
+use database::entities::{mfa_challenges, mfa_recovery_codes, totp_factors, users};
+use shared::models::{
+    AuthResponse, Claims, LoginResponse, MfaChallengeResponse, MfaCodeRequest, MfaEnabledResponse,
+    MfaStatusResponse, MfaVerifyRequest, RecoveryCodesResponse, TotpEnrollmentResponse,
+};
+
+/// Name authenticator apps show next to the account
+const TOTP_ISSUER: &str = "Ferrisbase";
+const TOTP_DIGITS: usize = 6;
+const TOTP_STEP_SECONDS: u64 = 30;
+
+/// How long a user has to enter their code after the first factor
+const CHALLENGE_EXPIRATION_MINUTES: i64 = 5;
+/// Wrong codes allowed per challenge before the user has to sign in again
+const MAX_CHALLENGE_ATTEMPTS: i32 = 5;
+
+const RECOVERY_CODE_COUNT: usize = 10;
+/// Lowercase letters and digits without the easily confused `0`, `1`, `i`, `l` and `o`
+const RECOVERY_CODE_ALPHABET: &[u8] = b"abcdefghjkmnpqrstuvwxyz23456789";
+
+/// Finish the first factor of a sign-in with `method`.
+///
+/// Users without multi-factor authentication get a session right away; the others
+/// get a short-lived challenge to complete with a code.
+pub(crate) async fn start_session<C: ConnectionTrait>(
+    db: &C,
+    user: users::Model,
+    method: &str,
+    config: &Config,
+) -> AppResult<LoginResponse> {
+    let amr = vec![method.to_string()];
+
+    if find_confirmed_factor(db, user.id).await?.is_none() {
+        // Each login starts a new refresh token family
+        let response = issue_tokens(db, user, Uuid::new_v4(), Uuid::new_v4(), &amr, config).await?;
+        return Ok(LoginResponse::Authenticated(response));
+    }
+
+    // Abandoned challenges are of no use once expired
+    MfaChallenges::delete_many()
+        .filter(mfa_challenges::Column::ExpiresAt.lt(Utc::now().naive_utc()))
+        .exec(db)
+        .await?;
+
+    let mfa_token = generate_secret_token();
+    mfa_challenges::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user.id),
+        token_hash: Set(hash_secret_token(&mfa_token)),
+        amr: Set(encode_amr(&amr)),
+        attempts: Set(0),
+        expires_at: Set((Utc::now() + chrono::Duration::minutes(CHALLENGE_EXPIRATION_MINUTES)).naive_utc()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    Ok(LoginResponse::MfaRequired(MfaChallengeResponse {
+        mfa_required: true,
+        mfa_token,
+        expires_in: CHALLENGE_EXPIRATION_MINUTES as u64 * 60,
+    }))
+}
+
+/// Complete an MFA challenge with a code from the authenticator app or a recovery code
+pub async fn verify_mfa_challenge(
+    db: &DatabaseConnection,
+    req: MfaVerifyRequest,
+    config: &Config,
+) -> AppResult<AuthResponse> {
+    let expired = || AppError::Unauthorized("Sign-in has expired; please sign in again".to_string());
+
+    let challenge = MfaChallenges::find()
+        .filter(mfa_challenges::Column::TokenHash.eq(hash_secret_token(&req.mfa_token)))
+        .one(db)
+        .await?
+        .ok_or_else(expired)?;
+
+    if challenge.expires_at <= Utc::now().naive_utc() {
+        return Err(expired());
+    }
+
+    // Attempts are counted before checking the code, so concurrent guesses share the limit
+    let counted = MfaChallenges::update_many()
+        .col_expr(mfa_challenges::Column::Attempts, Expr::col(mfa_challenges::Column::Attempts).add(1))
+        .filter(mfa_challenges::Column::Id.eq(challenge.id))
+        .filter(mfa_challenges::Column::Attempts.lt(MAX_CHALLENGE_ATTEMPTS))
+        .exec(db)
+        .await?;
+
+    if counted.rows_affected == 0 {
+        MfaChallenges::delete_by_id(challenge.id).exec(db).await?;
+        return Err(AppError::TooManyRequests(
+            "Too many incorrect codes; please sign in again".to_string(),
+        ));
+    }
+
+    let user = Users::find_by_id(challenge.user_id)
+        .one(db)
+        .await?
+        .ok_or_else(expired)?;
+
+    if !user.is_active.unwrap_or(true) {
+        return Err(AppError::Unauthorized("Account is disabled".to_string()));
+    }
+
+    // MFA may have been turned off since the challenge was issued
+    let factor = find_confirmed_factor(db, user.id).await?.ok_or_else(expired)?;
+
+    // Wrong codes also count across challenges, as each correct password starts a new one
+    ensure_mfa_allowed(db, user.id).await?;
+    let method = match verify_second_factor(db, &user, &factor, &req.code).await {
+        Ok(method) => method,
+        Err(invalid @ AppError::Unauthorized(_)) => return Err(record_mfa_failure(db, &user, invalid, config).await?),
+        Err(e) => return Err(e),
+    };
+
+    let txn = db.begin().await?;
+
+    // Each challenge can only be completed once, even by concurrent requests
+    let claimed = MfaChallenges::delete_many()
+        .filter(mfa_challenges::Column::Id.eq(challenge.id))
+        .exec(&txn)
+        .await?;
+    if claimed.rows_affected == 0 {
+        return Err(expired());
+    }
+
+    let mut amr = decode_amr(&challenge.amr);
+    amr.push(method.to_string());
+
+    // Each login starts a new refresh token family
+    let user_id = user.id;
+    let response = issue_tokens(&txn, user, Uuid::new_v4(), Uuid::new_v4(), &amr, config).await?;
+    txn.commit().await?;
+
+    clear_mfa_failures(db, user_id).await?;
+
+    Ok(response)
+}
+
+/// Get the multi-factor authentication settings of a user
+pub async fn get_mfa_status(db: &DatabaseConnection, user_id: &str) -> AppResult<MfaStatusResponse> {
+    let user_uuid = parse_user_id(user_id)?;
+
+    let enabled = find_confirmed_factor(db, user_uuid).await?.is_some();
+    let recovery_codes_remaining = if enabled {
+        MfaRecoveryCodes::find()
+            .filter(mfa_recovery_codes::Column::UserId.eq(user_uuid))
+            .filter(mfa_recovery_codes::Column::UsedAt.is_null())
+            .count(db)
+            .await?
+    } else {
+        0
+    };
+
+    Ok(MfaStatusResponse { enabled, recovery_codes_remaining })
+}
+
+/// Start setting up an authenticator app, replacing any setup that was never confirmed
+pub async fn enroll_totp(db: &DatabaseConnection, user_id: &str) -> AppResult<TotpEnrollmentResponse> {
+    let user_uuid = parse_user_id(user_id)?;
+
+    let user = Users::find_by_id(user_uuid)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    if find_confirmed_factor(db, user.id).await?.is_some() {
+        return Err(AppError::BadRequest("Multi-factor authentication is already enabled".to_string()));
+    }
+
+    let totp = build_totp(rand::random::<[u8; 20]>().to_vec(), &user.email)?;
+    let secret = totp.get_secret_base32();
+    let provisioning_uri = totp.get_url();
+
+    let txn = db.begin().await?;
+
+    TotpFactors::delete_many()
+        .filter(totp_factors::Column::UserId.eq(user.id))
+        .exec(&txn)
+        .await?;
+
+    totp_factors::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user.id),
+        secret: Set(secret.clone()),
+        ..Default::default()
+    }
+    .insert(&txn)
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(TotpEnrollmentResponse {
+        qr_code_svg: qr_code_svg(&provisioning_uri)?,
+        secret,
+        provisioning_uri,
+    })
+}
+
+/// Confirm an authenticator app with its current code, enabling multi-factor authentication.
+///
+/// The caller gets recovery codes and a new session that counts as signed in with both factors.
+pub async fn confirm_totp(
+    db: &DatabaseConnection,
+    claims: &Claims,
+    req: MfaCodeRequest,
+    config: &Config,
+) -> AppResult<MfaEnabledResponse> {
+    let user_uuid = parse_user_id(&claims.sub)?;
+
+    let user = Users::find_by_id(user_uuid)
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("User not found".to_string()))?;
+
+    let factor = TotpFactors::find()
+        .filter(totp_factors::Column::UserId.eq(user.id))
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::BadRequest("Set up an authenticator app first".to_string()))?;
+
+    if factor.confirmed_at.is_some() {
+        return Err(AppError::BadRequest("Multi-factor authentication is already enabled".to_string()));
+    }
+
+    if !verify_totp_code(db, &user, &factor, &req.code).await? {
+        return Err(AppError::BadRequest("Invalid code".to_string()));
+    }
+
+    let recovery_codes = generate_recovery_codes();
+
+    let txn = db.begin().await?;
+
+    TotpFactors::update_many()
+        .col_expr(totp_factors::Column::ConfirmedAt, Expr::value(Utc::now().naive_utc()))
+        .filter(totp_factors::Column::Id.eq(factor.id))
+        .exec(&txn)
+        .await?;
+
+    replace_recovery_codes(&txn, user.id, &recovery_codes).await?;
+    record_audit_event(&txn, Some(user.id), Some(user.id), AuditAction::MfaEnabled, None).await?;
+
+    let mut amr = claims.amr.clone();
+    if !amr.iter().any(|method| method == "otp") {
+        amr.push("otp".to_string());
+    }
+    let session = issue_tokens(&txn, user, Uuid::new_v4(), Uuid::new_v4(), &amr, config).await?;
+
+    txn.commit().await?;
+
+    Ok(MfaEnabledResponse { recovery_codes, session })
+}
+
+/// Replace a user's recovery codes with a new set
+pub async fn regenerate_recovery_codes(db: &DatabaseConnection, claims: &Claims) -> AppResult<RecoveryCodesResponse> {
+    let user_uuid = parse_user_id(&claims.sub)?;
+
+    if find_confirmed_factor(db, user_uuid).await?.is_none() {
+        return Err(AppError::BadRequest("Multi-factor authentication is not enabled".to_string()));
+    }
+    ensure_mfa_verified(db, claims).await?;
+
+    let recovery_codes = generate_recovery_codes();
+
+    let txn = db.begin().await?;
+    replace_recovery_codes(&txn, user_uuid, &recovery_codes).await?;
+    record_audit_event(&txn, Some(user_uuid), Some(user_uuid), AuditAction::RecoveryCodesRegenerated, None).await?;
+    txn.commit().await?;
+
+    Ok(RecoveryCodesResponse { recovery_codes })
+}
+
+/// Turn off multi-factor authentication, removing the authenticator app and recovery codes
+pub async fn disable_mfa(db: &DatabaseConnection, claims: &Claims) -> AppResult<()> {
+    let user_uuid = parse_user_id(&claims.sub)?;
+
+    if find_confirmed_factor(db, user_uuid).await?.is_none() {
+        return Err(AppError::BadRequest("Multi-factor authentication is not enabled".to_string()));
+    }
+    ensure_mfa_verified(db, claims).await?;
+
+    let txn = db.begin().await?;
+
+    TotpFactors::delete_many()
+        .filter(totp_factors::Column::UserId.eq(user_uuid))
+        .exec(&txn)
+        .await?;
+    MfaRecoveryCodes::delete_many()
+        .filter(mfa_recovery_codes::Column::UserId.eq(user_uuid))
+        .exec(&txn)
+        .await?;
+    MfaChallenges::delete_many()
+        .filter(mfa_challenges::Column::UserId.eq(user_uuid))
+        .exec(&txn)
+        .await?;
+    record_audit_event(&txn, Some(user_uuid), Some(user_uuid), AuditAction::MfaDisabled, None).await?;
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Require users with multi-factor authentication enabled to have used it for this session.
+///
+/// Sensitive actions call this, so a stolen password alone, or a session from
+/// before MFA was enabled, is not enough to perform them.
+pub async fn ensure_mfa_verified(db: &DatabaseConnection, claims: &Claims) -> AppResult<()> {
+    if claims.aal == "aal2" {
+        return Ok(());
+    }
+
+    let user_uuid = parse_user_id(&claims.sub)?;
+    if find_confirmed_factor(db, user_uuid).await?.is_some() {
+        return Err(AppError::Forbidden(
+            "This action requires signing in with multi-factor authentication".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+async fn find_confirmed_factor<C: ConnectionTrait>(db: &C, user_id: Uuid) -> AppResult<Option<totp_factors::Model>> {
+    Ok(TotpFactors::find()
+        .filter(totp_factors::Column::UserId.eq(user_id))
+        .filter(totp_factors::Column::ConfirmedAt.is_not_null())
+        .one(db)
+        .await?)
+}
+
+/// Check a code from the authenticator app or a recovery code, returning the `amr` method it proves
+async fn verify_second_factor(
+    db: &DatabaseConnection,
+    user: &users::Model,
+    factor: &totp_factors::Model,
+    code: &str,
+) -> AppResult<&'static str> {
+    let code = code.trim();
+    let invalid = || AppError::Unauthorized("Invalid code".to_string());
+
+    if code.len() == TOTP_DIGITS && code.chars().all(|c| c.is_ascii_digit()) {
+        return if verify_totp_code(db, user, factor, code).await? {
+            Ok("otp")
+        } else {
+            Err(invalid())
+        };
+    }
+
+    let used = MfaRecoveryCodes::update_many()
+        .col_expr(mfa_recovery_codes::Column::UsedAt, Expr::value(Utc::now().naive_utc()))
+        .filter(mfa_recovery_codes::Column::UserId.eq(user.id))
+        .filter(mfa_recovery_codes::Column::CodeHash.eq(hash_recovery_code(code)))
+        .filter(mfa_recovery_codes::Column::UsedAt.is_null())
+        .exec(db)
+        .await?;
+
+    if used.rows_affected == 0 {
+        return Err(invalid());
+    }
+
+    record_audit_event(db, Some(user.id), Some(user.id), AuditAction::RecoveryCodeUsed, None).await?;
+    Ok("recovery")
+}
+
+/// Check a TOTP code, allowing one step of clock drift either way.
+///
+/// A code is only accepted once: its time step must be later than the last one used.
+async fn verify_totp_code(
+    db: &DatabaseConnection,
+    user: &users::Model,
+    factor: &totp_factors::Model,
+    code: &str,
+) -> AppResult<bool> {
+    let secret = Secret::Encoded(factor.secret.clone())
+        .to_bytes()
+        .map_err(|e| AppError::InternalServerError(format!("Stored TOTP secret is invalid: {:?}", e)))?;
+    let totp = build_totp(secret, &user.email)?;
+
+    let Some(step) = matching_step(&totp, code, Utc::now().timestamp() as u64) else {
+        return Ok(false);
+    };
+
+    let claimed = TotpFactors::update_many()
+        .col_expr(totp_factors::Column::LastUsedStep, Expr::value(step as i64))
+        .filter(totp_factors::Column::Id.eq(factor.id))
+        .filter(
+            Condition::any()
+                .add(totp_factors::Column::LastUsedStep.is_null())
+                .add(totp_factors::Column::LastUsedStep.lt(step as i64)),
+        )
+        .exec(db)
+        .await?;
+
+    Ok(claimed.rows_affected == 1)
+}
+
+/// Time step within one step of `now` (in seconds) whose code is `code`
+fn matching_step(totp: &TOTP, code: &str, now: u64) -> Option<u64> {
+    let current_step = now / TOTP_STEP_SECONDS;
+    [current_step - 1, current_step, current_step + 1]
+        .into_iter()
+        .find(|step| totp.check(code.trim(), step * TOTP_STEP_SECONDS))
+}
+
+/// TOTP as understood by common authenticator apps: SHA-1, six digits, 30-second steps.
+///
+/// Skew is handled by `verify_totp_code`, which needs to know the step that matched.
+fn build_totp(secret: Vec<u8>, email: &str) -> AppResult<TOTP> {
+    TOTP::new(
+        Algorithm::SHA1,
+        TOTP_DIGITS,
+        0,
+        TOTP_STEP_SECONDS,
+        secret,
+        Some(TOTP_ISSUER.to_string()),
+        email.to_string(),
+    )
+    .map_err(|e| AppError::InternalServerError(format!("Failed to set up TOTP: {:?}", e)))
+}
+
+fn generate_recovery_codes() -> Vec<String> {
+    let mut rng = rand::thread_rng();
+    let mut random_part = || -> String {
+        (0..5)
+            .map(|_| RECOVERY_CODE_ALPHABET[rng.gen_range(0..RECOVERY_CODE_ALPHABET.len())] as char)
+            .collect()
+    };
+
+    (0..RECOVERY_CODE_COUNT)
+        .map(|_| format!("{}-{}", random_part(), random_part()))
+        .collect()
+}
+
+/// Recovery codes are compared without case, spaces or dashes, however the user typed them
+fn hash_recovery_code(code: &str) -> String {
+    let normalized: String = code
+        .chars()
+        .filter(|c| c.is_ascii_alphanumeric())
+        .map(|c| c.to_ascii_lowercase())
+        .collect();
+    hash_secret_token(&normalized)
+}
+
+async fn replace_recovery_codes<C: ConnectionTrait>(db: &C, user_id: Uuid, codes: &[String]) -> AppResult<()> {
+    MfaRecoveryCodes::delete_many()
+        .filter(mfa_recovery_codes::Column::UserId.eq(user_id))
+        .exec(db)
+        .await?;
+
+    MfaRecoveryCodes::insert_many(codes.iter().map(|code| mfa_recovery_codes::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user_id),
+        code_hash: Set(hash_recovery_code(code)),
+        ..Default::default()
+    }))
+    .exec_without_returning(db)
+    .await?;
+
+    Ok(())
+}
+
+/// Render text as an SVG QR code, with the quiet zone scanners expect around it
+fn qr_code_svg(text: &str) -> AppResult<String> {
+    const BORDER: i32 = 4;
+
+    let qr = QrCode::encode_text(text, QrCodeEcc::Medium)
+        .map_err(|e| AppError::InternalServerError(format!("Failed to render QR code: {:?}", e)))?;
+
+    let mut path = String::new();
+    for y in 0..qr.size() {
+        for x in 0..qr.size() {
+            if qr.get_module(x, y) {
+                let _ = write!(path, "M{},{}h1v1h-1z", x + BORDER, y + BORDER);
+            }
+        }
+    }
+
+    let dimension = qr.size() + BORDER * 2;
+    Ok(format!(
+        r##"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 {dimension} {dimension}" stroke="none"><rect width="100%" height="100%" fill="#ffffff"/><path d="{path}" fill="#000000"/></svg>"##
+    ))
+}
+
+fn parse_user_id(user_id: &str) -> AppResult<Uuid> {
+    Uuid::parse_str(user_id).map_err(|_| AppError::BadRequest("Invalid user ID format".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const NOW: u64 = 1_700_000_010;
+
+    fn totp() -> TOTP {
+        build_totp(b"12345678901234567890".to_vec(), "user@example.com").unwrap()
+    }
+
+    #[test]
+    fn accepts_codes_within_one_step() {
+        let totp = totp();
+        let step = NOW / TOTP_STEP_SECONDS;
+
+        for offset in [-1i64, 0, 1] {
+            let code = totp.generate(NOW.saturating_add_signed(offset * TOTP_STEP_SECONDS as i64));
+            assert_eq!(matching_step(&totp, &code, NOW), Some(step.saturating_add_signed(offset)));
+        }
+    }
+
+    #[test]
+    fn rejects_codes_outside_the_window() {
+        let totp = totp();
+
+        for offset in [-2i64, 2] {
+            let code = totp.generate(NOW.saturating_add_signed(offset * TOTP_STEP_SECONDS as i64));
+            assert_eq!(matching_step(&totp, &code, NOW), None);
+        }
+    }
+
+    #[test]
+    fn ignores_surrounding_whitespace() {
+        let totp = totp();
+        let code = format!(" {}\n", totp.generate(NOW));
+        assert_eq!(matching_step(&totp, &code, NOW), Some(NOW / TOTP_STEP_SECONDS));
+    }
+}
@@ -2,8 +2,45 @@ pub mod auth_service;
 pub mod project_service;
 pub mod table_service;
 pub mod dynamic_api_service;
+pub mod sql_service;
+pub mod schema_service;
+pub mod sql_rewrite;
+pub mod view_service;
+pub mod function_service;
+pub mod policy_service;
+pub mod saved_query_service;
+pub mod completion_service;
+pub mod audit_service;
+pub mod password_reset_service;
+pub mod email_verification_service;
+pub mod api_key_service;
+pub mod authorization_service;
+pub mod member_service;
+pub mod invitation_service;
+pub mod admin_service;
+pub mod oidc_service;
+pub mod mfa_service;
+pub mod login_throttle_service;
 
 pub use auth_service::*;
 pub use project_service::*;
 pub use table_service::*;
 pub use dynamic_api_service::*;
+pub use sql_service::*;
+pub use schema_service::*;
+pub use view_service::*;
+pub use function_service::*;
+pub use policy_service::*;
+pub use saved_query_service::*;
+pub use completion_service::*;
+pub use audit_service::*;
+pub use password_reset_service::*;
+pub use email_verification_service::*;
+pub use api_key_service::*;
+pub use authorization_service::*;
+pub use member_service::*;
+pub use invitation_service::*;
+pub use admin_service::*;
+pub use oidc_service::*;
+pub use mfa_service::*;
+pub use login_throttle_service::*;
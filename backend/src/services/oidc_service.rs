@@ -0,0 +1,464 @@
+use std::str::FromStr;
+use std::time::Duration;
+
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::Utc;
+use jsonwebtoken::jwk::{AlgorithmParameters, EllipticCurve, Jwk, JwkSet};
+use jsonwebtoken::{decode, decode_header, Algorithm, DecodingKey, Validation};
+use sea_orm::sea_query::{Expr, Func};
+use sea_orm::*;
+use serde::{Deserialize, Deserializer};
+use sha2::{Digest, Sha256};
+use url::Url;
+use uuid::Uuid;
+
+use crate::config::{Config, OidcProvider};
+use crate::error::{AppError, AppResult};
+use crate::services::audit_service::{record_audit_event, AuditAction};
+use crate::services::auth_service::{generate_secret_token, hash_password, hash_secret_token, revoke_user_sessions};
+use crate::services::mfa_service::start_session;
+use database::entities::prelude::*;
+use database::entities::{identities, mfa_recovery_codes, oidc_login_states, totp_factors, users};
+use shared::models::{
+    IdentityResponse, LoginResponse, OidcAuthorizeResponse, OidcCallbackRequest, OidcProviderResponse,
+};
+
+/// How long a user has to finish signing in at the provider
+const LOGIN_EXPIRATION_MINUTES: i64 = 10;
+const PROVIDER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// The parts of a provider's discovery document used for the authorization code flow
+#[derive(Debug, Deserialize)]
+struct ProviderMetadata {
+    issuer: String,
+    authorization_endpoint: String,
+    token_endpoint: String,
+    jwks_uri: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct TokenResponse {
+    id_token: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct IdTokenClaims {
+    sub: String,
+    email: Option<String>,
+    #[serde(default, deserialize_with = "bool_or_string")]
+    email_verified: bool,
+    name: Option<String>,
+    nonce: Option<String>,
+}
+
+/// List the providers users can sign in with
+pub fn list_oidc_providers(config: &Config) -> Vec<OidcProviderResponse> {
+    config
+        .oidc_providers
+        .iter()
+        .map(|provider| OidcProviderResponse { id: provider.id.clone(), name: provider.name.clone() })
+        .collect()
+}
+
+/// Start signing in with a provider, returning the URL to send the browser to.
+///
+/// The `state`, PKCE code verifier and nonce are kept until the provider redirects back.
+pub async fn start_oidc_login(
+    db: &DatabaseConnection,
+    provider_id: &str,
+    config: &Config,
+) -> AppResult<OidcAuthorizeResponse> {
+    let provider = find_provider(config, provider_id)?;
+    let metadata = discover(provider).await?;
+
+    let state = generate_secret_token();
+    let nonce = generate_secret_token();
+    let code_verifier = generate_secret_token();
+    let code_challenge = URL_SAFE_NO_PAD.encode(Sha256::digest(code_verifier.as_bytes()));
+
+    // Abandoned sign-ins are of no use once expired
+    OidcLoginStates::delete_many()
+        .filter(oidc_login_states::Column::ExpiresAt.lt(Utc::now().naive_utc()))
+        .exec(db)
+        .await?;
+
+    oidc_login_states::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        provider: Set(provider.id.clone()),
+        state_hash: Set(hash_secret_token(&state)),
+        code_verifier: Set(code_verifier),
+        nonce: Set(nonce.clone()),
+        expires_at: Set((Utc::now() + chrono::Duration::minutes(LOGIN_EXPIRATION_MINUTES)).naive_utc()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    let mut url = Url::parse(&metadata.authorization_endpoint)
+        .map_err(|e| AppError::Upstream(format!("Provider has an invalid authorization endpoint: {}", e)))?;
+    url.query_pairs_mut()
+        .append_pair("response_type", "code")
+        .append_pair("client_id", &provider.client_id)
+        .append_pair("redirect_uri", &redirect_uri(config))
+        .append_pair("scope", &provider.scopes)
+        .append_pair("state", &state)
+        .append_pair("nonce", &nonce)
+        .append_pair("code_challenge", &code_challenge)
+        .append_pair("code_challenge_method", "S256");
+
+    Ok(OidcAuthorizeResponse { authorization_url: url.into(), state })
+}
+
+/// Finish signing in after the provider redirected back with an authorization code.
+///
+/// The provider account is matched to a user through its linked identity. A new
+/// identity is linked to the user with the same email address if the provider
+/// verified that address, and otherwise a new user is created for it. Users with
+/// multi-factor authentication enabled still have to enter a code afterwards.
+pub async fn complete_oidc_login(
+    db: &DatabaseConnection,
+    req: OidcCallbackRequest,
+    config: &Config,
+) -> AppResult<LoginResponse> {
+    let invalid = || AppError::BadRequest("Sign-in link is invalid or has expired; please try again".to_string());
+
+    let login = OidcLoginStates::find()
+        .filter(oidc_login_states::Column::StateHash.eq(hash_secret_token(&req.state)))
+        .one(db)
+        .await?
+        .ok_or_else(invalid)?;
+
+    // Each state can only be used once, even by concurrent callbacks
+    let claimed = OidcLoginStates::delete_many()
+        .filter(oidc_login_states::Column::Id.eq(login.id))
+        .exec(db)
+        .await?;
+    if claimed.rows_affected == 0 || login.expires_at <= Utc::now().naive_utc() {
+        return Err(invalid());
+    }
+
+    let provider = find_provider(config, &login.provider)?;
+    let metadata = discover(provider).await?;
+    let id_token = exchange_code(provider, &metadata, &req.code, &login.code_verifier, config).await?;
+    let claims = verify_id_token(provider, &metadata, &id_token).await?;
+
+    if claims.nonce.as_deref() != Some(login.nonce.as_str()) {
+        return Err(AppError::Unauthorized("ID token nonce does not match".to_string()));
+    }
+
+    let txn = db.begin().await?;
+    let user = find_or_create_user(&txn, provider, claims).await?;
+
+    if !user.is_active.unwrap_or(true) {
+        return Err(AppError::Unauthorized("Account is disabled".to_string()));
+    }
+
+    let response = start_session(&txn, user, "oidc", config).await?;
+    txn.commit().await?;
+
+    Ok(response)
+}
+
+/// List the provider accounts linked to a user
+pub async fn list_user_identities(
+    db: &DatabaseConnection,
+    user_id: &str,
+    config: &Config,
+) -> AppResult<Vec<IdentityResponse>> {
+    let user_uuid = Uuid::parse_str(user_id)
+        .map_err(|_| AppError::BadRequest("Invalid user ID format".to_string()))?;
+
+    let identities = Identities::find()
+        .filter(identities::Column::UserId.eq(user_uuid))
+        .order_by_asc(identities::Column::CreatedAt)
+        .all(db)
+        .await?;
+
+    Ok(identities
+        .into_iter()
+        .map(|identity| IdentityResponse {
+            id: identity.id.to_string(),
+            provider_name: config
+                .oidc_providers
+                .iter()
+                .find(|provider| provider.id == identity.provider)
+                .map(|provider| provider.name.clone()),
+            provider: identity.provider,
+            email: identity.email,
+            created_at: identity.created_at.map(|t| t.to_string()).unwrap_or_default(),
+            last_sign_in_at: identity.last_sign_in_at.map(|t| t.to_string()),
+        })
+        .collect())
+}
+
+async fn find_or_create_user<C: ConnectionTrait>(
+    db: &C,
+    provider: &OidcProvider,
+    claims: IdTokenClaims,
+) -> AppResult<users::Model> {
+    let now = Utc::now().naive_utc();
+    let email = claims.email.as_deref().map(|email| email.trim().to_lowercase());
+
+    let identity = Identities::find()
+        .filter(identities::Column::Provider.eq(&provider.id))
+        .filter(identities::Column::Subject.eq(&claims.sub))
+        .one(db)
+        .await?;
+
+    if let Some(identity) = identity {
+        let user_id = identity.user_id;
+        let mut active: identities::ActiveModel = identity.into();
+        active.last_sign_in_at = Set(Some(now));
+        if email.is_some() {
+            active.email = Set(email);
+        }
+        active.update(db).await?;
+
+        return Users::find_by_id(user_id)
+            .one(db)
+            .await?
+            .ok_or_else(|| AppError::NotFound("User not found".to_string()));
+    }
+
+    let email = email.ok_or_else(|| {
+        AppError::BadRequest(format!("{} did not share an email address", provider.name))
+    })?;
+
+    let existing = Users::find()
+        .filter(Expr::expr(Func::lower(Expr::col(users::Column::Email))).eq(&email))
+        .one(db)
+        .await?;
+
+    let user = match existing {
+        // Linking on an unverified address would let anyone claim the account
+        Some(_) if !claims.email_verified => {
+            return Err(AppError::Forbidden(format!(
+                "An account with this email already exists, and {} has not verified the address. \
+                 Sign in with your password instead.",
+                provider.name
+            )));
+        }
+        // Whoever registered the unverified address may not own it: their password,
+        // second factors and sessions are dropped before the provider's user takes over
+        Some(user) if !user.email_verified.unwrap_or(false) => {
+            let mut active: users::ActiveModel = user.into();
+            active.password_hash = Set(hash_password(&generate_secret_token())?);
+            active.email_verified = Set(Some(true));
+            active.updated_at = Set(Some(now));
+            let user = active.update(db).await?;
+
+            revoke_user_sessions(db, user.id).await?;
+            TotpFactors::delete_many()
+                .filter(totp_factors::Column::UserId.eq(user.id))
+                .exec(db)
+                .await?;
+            MfaRecoveryCodes::delete_many()
+                .filter(mfa_recovery_codes::Column::UserId.eq(user.id))
+                .exec(db)
+                .await?;
+
+            record_audit_event(db, Some(user.id), Some(user.id), AuditAction::EmailVerified, None).await?;
+            user
+        }
+        Some(user) => user,
+        None => {
+            // The account has no password of its own until the user resets it
+            users::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                email: Set(email.clone()),
+                password_hash: Set(hash_password(&generate_secret_token())?),
+                name: Set(claims.name.clone()),
+                role: Set(Some("user".to_string())),
+                email_verified: Set(Some(claims.email_verified)),
+                is_active: Set(Some(true)),
+                ..Default::default()
+            }
+            .insert(db)
+            .await?
+        }
+    };
+
+    identities::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user.id),
+        provider: Set(provider.id.clone()),
+        subject: Set(claims.sub),
+        email: Set(Some(email)),
+        created_at: Set(Some(now)),
+        last_sign_in_at: Set(Some(now)),
+    }
+    .insert(db)
+    .await?;
+
+    record_audit_event(
+        db,
+        Some(user.id),
+        Some(user.id),
+        AuditAction::IdentityLinked,
+        Some(serde_json::json!({ "provider": provider.id })),
+    )
+    .await?;
+
+    Ok(user)
+}
+
+fn find_provider<'a>(config: &'a Config, provider_id: &str) -> AppResult<&'a OidcProvider> {
+    config
+        .oidc_providers
+        .iter()
+        .find(|provider| provider.id == provider_id)
+        .ok_or_else(|| AppError::NotFound("Unknown sign-in provider".to_string()))
+}
+
+/// The frontend page providers redirect back to
+fn redirect_uri(config: &Config) -> String {
+    format!("{}/auth/callback", config.app_url.trim_end_matches('/'))
+}
+
+fn http_client() -> AppResult<reqwest::Client> {
+    reqwest::Client::builder()
+        .timeout(PROVIDER_TIMEOUT)
+        .build()
+        .map_err(|e| AppError::InternalServerError(format!("Failed to create HTTP client: {}", e)))
+}
+
+async fn discover(provider: &OidcProvider) -> AppResult<ProviderMetadata> {
+    let url = format!("{}/.well-known/openid-configuration", provider.issuer);
+    let unavailable = |e: reqwest::Error| AppError::Upstream(format!("Could not reach {}: {}", provider.name, e));
+
+    let metadata: ProviderMetadata = http_client()?
+        .get(&url)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(unavailable)?
+        .json()
+        .await
+        .map_err(unavailable)?;
+
+    if metadata.issuer.trim_end_matches('/') != provider.issuer {
+        return Err(AppError::Upstream(format!(
+            "{} reports issuer {} instead of the configured {}",
+            provider.name, metadata.issuer, provider.issuer
+        )));
+    }
+
+    Ok(metadata)
+}
+
+/// Trade the authorization code for the provider's ID token
+async fn exchange_code(
+    provider: &OidcProvider,
+    metadata: &ProviderMetadata,
+    code: &str,
+    code_verifier: &str,
+    config: &Config,
+) -> AppResult<String> {
+    let redirect_uri = redirect_uri(config);
+    let mut form = vec![
+        ("grant_type", "authorization_code"),
+        ("code", code),
+        ("redirect_uri", redirect_uri.as_str()),
+        ("client_id", provider.client_id.as_str()),
+        ("code_verifier", code_verifier),
+    ];
+    if let Some(secret) = &provider.client_secret {
+        form.push(("client_secret", secret.as_str()));
+    }
+
+    let response = http_client()?
+        .post(&metadata.token_endpoint)
+        .form(&form)
+        .send()
+        .await
+        .map_err(|e| AppError::Upstream(format!("Could not reach {}: {}", provider.name, e)))?;
+
+    if !response.status().is_success() {
+        let status = response.status();
+        let body = response.text().await.unwrap_or_default();
+        tracing::warn!("{} rejected the authorization code ({}): {}", provider.name, status, body);
+        return Err(AppError::Unauthorized(format!("{} did not accept the sign-in; please try again", provider.name)));
+    }
+
+    let tokens: TokenResponse = response
+        .json()
+        .await
+        .map_err(|e| AppError::Upstream(format!("{} returned no ID token: {}", provider.name, e)))?;
+
+    Ok(tokens.id_token)
+}
+
+/// Check the ID token's signature against the provider's published keys, and its issuer, audience and expiry
+async fn verify_id_token(provider: &OidcProvider, metadata: &ProviderMetadata, id_token: &str) -> AppResult<IdTokenClaims> {
+    let invalid = |reason: String| AppError::Unauthorized(format!("Invalid ID token: {}", reason));
+
+    let header = decode_header(id_token).map_err(|e| invalid(e.to_string()))?;
+
+    let jwks: JwkSet = http_client()?
+        .get(&metadata.jwks_uri)
+        .send()
+        .await
+        .and_then(reqwest::Response::error_for_status)
+        .map_err(|e| AppError::Upstream(format!("Could not fetch the keys of {}: {}", provider.name, e)))?
+        .json()
+        .await
+        .map_err(|e| AppError::Upstream(format!("{} published invalid keys: {}", provider.name, e)))?;
+
+    let jwk = match &header.kid {
+        Some(kid) => jwks.find(kid),
+        None if jwks.keys.len() == 1 => jwks.keys.first(),
+        None => None,
+    }
+    .ok_or_else(|| invalid("signed with an unknown key".to_string()))?;
+
+    // The key decides the algorithm; a token naming another one is not trusted
+    let algorithm = key_algorithm(jwk).ok_or_else(|| invalid("signed with an unsupported key".to_string()))?;
+    if header.alg != algorithm {
+        return Err(invalid(format!("signed with {:?}, but the key is for {:?}", header.alg, algorithm)));
+    }
+
+    let key = DecodingKey::from_jwk(jwk).map_err(|e| invalid(e.to_string()))?;
+
+    let mut validation = Validation::new(algorithm);
+    validation.set_issuer(&[&metadata.issuer]);
+    validation.set_audience(&[&provider.client_id]);
+
+    let token = decode::<IdTokenClaims>(id_token, &key, &validation).map_err(|e| invalid(e.to_string()))?;
+
+    Ok(token.claims)
+}
+
+/// Signature algorithm of a provider's key: its `alg`, or the usual one for its key type.
+/// Shared-secret algorithms are refused, since published keys are public.
+fn key_algorithm(jwk: &Jwk) -> Option<Algorithm> {
+    let algorithm = match &jwk.common.key_algorithm {
+        Some(alg) => Algorithm::from_str(&alg.to_string()).ok()?,
+        None => match &jwk.algorithm {
+            AlgorithmParameters::RSA(_) => Algorithm::RS256,
+            AlgorithmParameters::EllipticCurve(params) if params.curve == EllipticCurve::P256 => Algorithm::ES256,
+            AlgorithmParameters::EllipticCurve(params) if params.curve == EllipticCurve::P384 => Algorithm::ES384,
+            AlgorithmParameters::OctetKeyPair(_) => Algorithm::EdDSA,
+            _ => return None,
+        },
+    };
+
+    match algorithm {
+        Algorithm::HS256 | Algorithm::HS384 | Algorithm::HS512 => None,
+        algorithm => Some(algorithm),
+    }
+}
+
+/// Some providers send `email_verified` as the string `"true"`
+fn bool_or_string<'de, D: Deserializer<'de>>(deserializer: D) -> Result<bool, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum BoolOrString {
+        Bool(bool),
+        String(String),
+    }
+
+    Ok(match BoolOrString::deserialize(deserializer)? {
+        BoolOrString::Bool(value) => value,
+        BoolOrString::String(value) => value.eq_ignore_ascii_case("true"),
+    })
+}
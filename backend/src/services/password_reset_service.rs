@@ -0,0 +1,131 @@
+use chrono::Utc;
+use sea_orm::sea_query::Expr;
+use sea_orm::*;
+use uuid::Uuid;
+
+use crate::config::Config;
+use crate::error::{AppError, AppResult};
+use crate::mailer::{Email, Mailer};
+use crate::services::audit_service::{record_audit_event, AuditAction};
+use crate::services::auth_service::{
+    generate_secret_token, hash_password, hash_secret_token, revoke_user_sessions, validate_password,
+};
+use database::entities::prelude::*;
+use database::entities::{password_reset_tokens, users};
+use shared::models::{ForgotPasswordRequest, ResetPasswordRequest};
+
+/// Email a password reset link to the account with the given address.
+///
+/// The outcome is the same whether or not the account exists, so the endpoint
+/// cannot be used to find out which addresses are registered.
+pub async fn request_password_reset(
+    db: &DatabaseConnection,
+    mailer: &dyn Mailer,
+    req: ForgotPasswordRequest,
+    config: &Config,
+) -> AppResult<()> {
+    let user = Users::find()
+        .filter(users::Column::Email.eq(req.email.trim()))
+        .one(db)
+        .await?;
+
+    let Some(user) = user.filter(|user| user.is_active.unwrap_or(true)) else {
+        return Ok(());
+    };
+
+    // Only the most recent link works
+    PasswordResetTokens::update_many()
+        .col_expr(password_reset_tokens::Column::UsedAt, Expr::value(Utc::now().naive_utc()))
+        .filter(password_reset_tokens::Column::UserId.eq(user.id))
+        .filter(password_reset_tokens::Column::UsedAt.is_null())
+        .exec(db)
+        .await?;
+
+    let token = generate_secret_token();
+    let expires_at = Utc::now() + chrono::Duration::minutes(config.password_reset_expiration_minutes as i64);
+
+    password_reset_tokens::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        user_id: Set(user.id),
+        token_hash: Set(hash_secret_token(&token)),
+        expires_at: Set(expires_at.naive_utc()),
+        ..Default::default()
+    }
+    .insert(db)
+    .await?;
+
+    record_audit_event(db, None, Some(user.id), AuditAction::PasswordResetRequested, None).await?;
+
+    let link = format!("{}/reset-password?token={}", config.app_url.trim_end_matches('/'), token);
+    let email = Email {
+        to: user.email,
+        subject: "Reset your Ferrisbase password".to_string(),
+        body: format!(
+            "Someone asked to reset the password of your Ferrisbase account.\n\n\
+             Open this link to choose a new password:\n{}\n\n\
+             The link expires in {} minutes and can be used once. \
+             If you did not ask for a reset, you can ignore this email.\n",
+            link, config.password_reset_expiration_minutes
+        ),
+    };
+
+    // A delivery failure must not tell the caller that the account exists
+    if let Err(e) = mailer.send(email).await {
+        tracing::error!("Failed to send password reset email: {}", e);
+    }
+
+    Ok(())
+}
+
+/// Set a new password using a reset token, which is used up in the process.
+///
+/// Every session of the user is revoked, so they sign in again with the new password.
+pub async fn reset_password(db: &DatabaseConnection, req: ResetPasswordRequest) -> AppResult<()> {
+    let invalid = || AppError::BadRequest("Password reset link is invalid or has expired".to_string());
+
+    let token = PasswordResetTokens::find()
+        .filter(password_reset_tokens::Column::TokenHash.eq(hash_secret_token(&req.token)))
+        .one(db)
+        .await?
+        .ok_or_else(invalid)?;
+
+    let now = Utc::now().naive_utc();
+    if token.used_at.is_some() || token.expires_at <= now {
+        return Err(invalid());
+    }
+
+    let user = Users::find_by_id(token.user_id)
+        .one(db)
+        .await?
+        .filter(|user| user.is_active.unwrap_or(true))
+        .ok_or_else(invalid)?;
+
+    validate_password(&req.new_password, &user.email)?;
+    let password_hash = hash_password(&req.new_password)?;
+
+    let txn = db.begin().await?;
+
+    // Claiming the token first keeps it single use under concurrent requests
+    let claimed = PasswordResetTokens::update_many()
+        .col_expr(password_reset_tokens::Column::UsedAt, Expr::value(now))
+        .filter(password_reset_tokens::Column::Id.eq(token.id))
+        .filter(password_reset_tokens::Column::UsedAt.is_null())
+        .exec(&txn)
+        .await?;
+
+    if claimed.rows_affected == 0 {
+        return Err(invalid());
+    }
+
+    let mut active: users::ActiveModel = user.into();
+    active.password_hash = Set(password_hash);
+    active.updated_at = Set(Some(now));
+    let user = active.update(&txn).await?;
+
+    revoke_user_sessions(&txn, user.id).await?;
+    record_audit_event(&txn, Some(user.id), Some(user.id), AuditAction::PasswordReset, None).await?;
+
+    txn.commit().await?;
+
+    Ok(())
+}
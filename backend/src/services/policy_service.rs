@@ -0,0 +1,451 @@
+use sea_orm::*;
+use database::entities::{project_policies, project_tables, projects};
+use shared::models::{ApiKeyKind, CreatePolicyRequest, FunctionLanguage, PolicyCommand, PolicyResponse};
+use sqlparser::ast::Expr;
+use sqlparser::dialect::PostgreSqlDialect;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::Token;
+use crate::error::{AppError, AppResult};
+use crate::services::api_key_service::Principal;
+use crate::services::authorization_service::{authorize_project, Capability};
+use crate::services::project_service::project_role;
+use crate::services::sql_rewrite::{logical_names, project_functions, project_relations, resolve_relations};
+use crate::services::table_service::physical_table_name;
+use uuid::Uuid;
+
+/// Database role that policies apply to (created by the policies migration)
+pub(crate) const AUTHENTICATED_ROLE: &str = "ferrisbase_authenticated";
+
+/// List the row-level security policies of a table
+pub async fn list_table_policies(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    table_name: &str,
+) -> AppResult<Vec<PolicyResponse>> {
+    let (_project, table) = find_project_table(db, user_id, project_slug, table_name, Capability::Read).await?;
+
+    let policies = project_policies::Entity::find()
+        .filter(project_policies::Column::ProjectTableId.eq(table.id))
+        .order_by_asc(project_policies::Column::PolicyName)
+        .all(db)
+        .await?;
+
+    policies.into_iter().map(policy_response).collect()
+}
+
+/// Add a policy to a table, enabling row-level security on it if needed
+pub async fn create_table_policy(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    table_name: &str,
+    req: CreatePolicyRequest,
+) -> AppResult<PolicyResponse> {
+    if req.policy_name.is_empty() || !req.policy_name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return Err(AppError::BadRequest(
+            "Policy name can only contain letters, numbers, and underscores".to_string(),
+        ));
+    }
+
+    // Postgres only evaluates the expressions that make sense for each command
+    match (req.command, &req.using_expression, &req.check_expression) {
+        (_, None, None) => {
+            return Err(AppError::BadRequest(
+                "A policy needs a USING or WITH CHECK expression".to_string(),
+            ))
+        }
+        (PolicyCommand::Insert, Some(_), _) => {
+            return Err(AppError::BadRequest(
+                "INSERT policies only support a WITH CHECK expression".to_string(),
+            ))
+        }
+        (PolicyCommand::Select | PolicyCommand::Delete, _, Some(_)) => {
+            return Err(AppError::BadRequest(
+                "SELECT and DELETE policies only support a USING expression".to_string(),
+            ))
+        }
+        _ => {}
+    }
+
+    let (project, table) = find_project_table(db, user_id, project_slug, table_name, Capability::ManageSchema).await?;
+
+    let existing = project_policies::Entity::find()
+        .filter(project_policies::Column::ProjectTableId.eq(table.id))
+        .filter(project_policies::Column::PolicyName.eq(&req.policy_name))
+        .one(db)
+        .await?;
+
+    if existing.is_some() {
+        return Err(AppError::BadRequest("Policy with this name already exists".to_string()));
+    }
+
+    // Subqueries in expressions may reference other project tables by name
+    let relations = project_relations(db, project.id).await?;
+    let functions = project_functions(db, project.id, &[FunctionLanguage::Sql]).await?;
+    let using_sql = req
+        .using_expression
+        .as_deref()
+        .map(|expr| parse_policy_expression(expr, &relations, &functions))
+        .transpose()?;
+    let check_sql = req
+        .check_expression
+        .as_deref()
+        .map(|expr| parse_policy_expression(expr, &relations, &functions))
+        .transpose()?;
+
+    let pg_table_name = physical_table_name(&project.id, &table.table_name);
+    let warnings = check_dependent_views(db, &project, &pg_table_name).await?;
+
+    let mut create_policy_sql = format!(
+        "CREATE POLICY \"{}\" ON \"{}\" FOR {} TO {}",
+        req.policy_name,
+        pg_table_name,
+        req.command.to_postgres_command(),
+        AUTHENTICATED_ROLE
+    );
+    if let Some(using_sql) = &using_sql {
+        create_policy_sql.push_str(&format!(" USING ({})", using_sql));
+    }
+    if let Some(check_sql) = &check_sql {
+        create_policy_sql.push_str(&format!(" WITH CHECK ({})", check_sql));
+    }
+
+    // Enabling is idempotent; the grant lets the policy role reach the table at all
+    let mut statements = vec![
+        format!("ALTER TABLE \"{}\" ENABLE ROW LEVEL SECURITY", pg_table_name),
+        format!("GRANT SELECT, INSERT, UPDATE, DELETE ON \"{}\" TO {}", pg_table_name, AUTHENTICATED_ROLE),
+    ];
+
+    // Subqueries in the expressions are evaluated as the policy role too
+    statements.extend(relations.values().filter(|physical| **physical != pg_table_name).map(|physical| {
+        format!("GRANT SELECT ON \"{}\" TO {}", physical, AUTHENTICATED_ROLE)
+    }));
+    statements.push(create_policy_sql);
+
+    let txn = db.begin().await?;
+
+    for sql in statements {
+        txn.execute(Statement::from_string(DatabaseBackend::Postgres, sql))
+            .await
+            .map_err(|e| AppError::BadRequest(format!("Failed to create policy: {}", e)))?;
+    }
+
+    let now = chrono::Utc::now().naive_utc();
+    let policy = project_policies::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        project_table_id: Set(table.id),
+        policy_name: Set(req.policy_name.clone()),
+        command: Set(req.command.to_postgres_command().to_lowercase()),
+        using_expression: Set(req.using_expression.clone()),
+        check_expression: Set(req.check_expression.clone()),
+        created_at: Set(Some(now)),
+        updated_at: Set(Some(now)),
+    }
+    .insert(&txn)
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(PolicyResponse { warnings, ..policy_response(policy)? })
+}
+
+/// Remove a policy; row-level security is turned off again once a table has no policies left
+pub async fn delete_table_policy(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    table_name: &str,
+    policy_name: &str,
+) -> AppResult<()> {
+    let (project, table) = find_project_table(db, user_id, project_slug, table_name, Capability::ManageSchema).await?;
+
+    let policy = project_policies::Entity::find()
+        .filter(project_policies::Column::ProjectTableId.eq(table.id))
+        .filter(project_policies::Column::PolicyName.eq(policy_name))
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Policy not found".to_string()))?;
+
+    let pg_table_name = physical_table_name(&project.id, &table.table_name);
+
+    let txn = db.begin().await?;
+
+    txn.execute(Statement::from_string(
+        DatabaseBackend::Postgres,
+        format!("DROP POLICY IF EXISTS \"{}\" ON \"{}\"", policy.policy_name, pg_table_name),
+    ))
+    .await?;
+
+    policy.delete(&txn).await?;
+
+    let remaining = project_policies::Entity::find()
+        .filter(project_policies::Column::ProjectTableId.eq(table.id))
+        .count(&txn)
+        .await?;
+
+    if remaining == 0 {
+        txn.execute(Statement::from_string(
+            DatabaseBackend::Postgres,
+            format!("ALTER TABLE \"{}\" DISABLE ROW LEVEL SECURITY", pg_table_name),
+        ))
+        .await?;
+    }
+
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Start the transaction a data API request runs in.
+///
+/// The caller's claims are exposed to policies through `request.jwt.claims`, and
+/// tables with row-level security are accessed as the policy role, since the
+/// connection's role owns the tables and would bypass their policies. Service keys
+/// bypass policies on purpose, while anon keys only reach tables that have them.
+///
+/// Views count as protected when a table they read has row-level security. A
+/// materialized view stores rows its policies never filtered, so only service keys
+/// can read one that is built on a protected table.
+pub(crate) async fn begin_request_transaction(
+    db: &DatabaseConnection,
+    principal: &Principal,
+    pg_table_name: &str,
+) -> AppResult<DatabaseTransaction> {
+    let txn = begin_with_claims(db, principal).await?;
+
+    // Walk the relation and everything its view definitions read, noting whether a
+    // protected table is only reached through a materialized view
+    let (rls_enabled, rls_bypassed) = match txn
+        .query_one(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r#"WITH RECURSIVE relations(oid, via_materialized) AS (
+                   SELECT to_regclass(quote_ident($1))::oid, false
+                   UNION
+                   SELECT d.refobjid, r.via_materialized OR parent.relkind = 'm'
+                   FROM relations r
+                   JOIN pg_class parent ON parent.oid = r.oid
+                   JOIN pg_rewrite rw ON rw.ev_class = r.oid
+                   JOIN pg_depend d ON d.classid = 'pg_rewrite'::regclass AND d.objid = rw.oid
+                       AND d.refclassid = 'pg_class'::regclass AND d.refobjid <> r.oid
+               )
+               SELECT coalesce(bool_or(c.relrowsecurity AND NOT r.via_materialized), false) AS rls_enabled,
+                      coalesce(bool_or(c.relrowsecurity AND r.via_materialized), false) AS rls_bypassed
+               FROM relations r
+               JOIN pg_class c ON c.oid = r.oid"#,
+            [pg_table_name.into()],
+        ))
+        .await?
+    {
+        Some(row) => (row.try_get::<bool>("", "rls_enabled")?, row.try_get::<bool>("", "rls_bypassed")?),
+        None => (false, false),
+    };
+
+    let apply_policies = match principal {
+        Principal::ApiKey { kind: ApiKeyKind::Service, .. } => false,
+        _ if rls_bypassed => {
+            return Err(AppError::Forbidden(
+                "This materialized view reads tables protected by row-level security; only service keys can read it"
+                    .to_string(),
+            ));
+        }
+        Principal::User(_) => rls_enabled,
+        Principal::ApiKey { kind: ApiKeyKind::Anon, .. } if !rls_enabled => {
+            return Err(AppError::Forbidden(
+                "Anon keys can only access tables protected by row-level security policies".to_string(),
+            ));
+        }
+        Principal::ApiKey { kind: ApiKeyKind::Anon, .. } => true,
+    };
+
+    if apply_policies {
+        set_policy_role(&txn).await?;
+    }
+
+    Ok(txn)
+}
+
+/// Start the transaction an RPC call runs in.
+///
+/// A function may touch any of the project's tables, so users always run it as the
+/// policy role: it is granted every project table, and tables with row-level security
+/// only show it the rows their policies allow. Service keys run it as the project's
+/// role, which owns the project's tables and so bypasses policies as usual.
+pub(crate) async fn begin_function_transaction(
+    db: &DatabaseConnection,
+    principal: &Principal,
+    project_id: &Uuid,
+) -> AppResult<DatabaseTransaction> {
+    if let Principal::ApiKey { kind: ApiKeyKind::Anon, .. } = principal {
+        return Err(AppError::Forbidden("Anon keys cannot call functions".to_string()));
+    }
+
+    let txn = begin_with_claims(db, principal).await?;
+
+    match principal {
+        Principal::User(_) => set_policy_role(&txn).await?,
+        Principal::ApiKey { .. } => {
+            txn.execute(Statement::from_string(
+                DatabaseBackend::Postgres,
+                format!("SET LOCAL ROLE \"{}\"", project_role(project_id)),
+            ))
+            .await?;
+        }
+    }
+
+    Ok(txn)
+}
+
+async fn begin_with_claims(db: &DatabaseConnection, principal: &Principal) -> AppResult<DatabaseTransaction> {
+    let txn = db.begin().await?;
+
+    txn.execute(Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        "SELECT set_config('request.jwt.claims', $1, true)",
+        [principal.policy_claims()?.into()],
+    ))
+    .await?;
+
+    Ok(txn)
+}
+
+async fn set_policy_role(txn: &DatabaseTransaction) -> AppResult<()> {
+    txn.execute(Statement::from_string(
+        DatabaseBackend::Postgres,
+        format!("SET LOCAL ROLE {}", AUTHENTICATED_ROLE),
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Report writes rejected by a policy as access errors rather than database failures
+pub(crate) fn policy_error(e: DbErr) -> AppError {
+    if e.to_string().contains("row-level security policy") {
+        AppError::Forbidden("Row violates a row-level security policy".to_string())
+    } else {
+        AppError::Database(e)
+    }
+}
+
+/// Parse a policy expression, mapping table names in subqueries to physical tables
+fn parse_policy_expression(
+    expression: &str,
+    relations: &std::collections::HashMap<String, String>,
+    functions: &std::collections::HashMap<String, String>,
+) -> AppResult<String> {
+    let invalid = |e: String| AppError::BadRequest(format!("Invalid policy expression: {}", e));
+
+    let mut parser = Parser::new(&PostgreSqlDialect {})
+        .try_with_sql(expression)
+        .map_err(|e| invalid(e.to_string()))?;
+
+    let mut expr: Expr = parser.parse_expr().map_err(|e| invalid(e.to_string()))?;
+
+    if parser.peek_token().token != Token::EOF {
+        return Err(invalid("expected a single expression".to_string()));
+    }
+
+    resolve_relations(&mut expr, relations, functions).map_err(AppError::BadRequest)?;
+
+    Ok(expr.to_string())
+}
+
+/// Make sure the views built on a table will not expose rows its policies hide.
+///
+/// Views that read the table with their owner's privileges would skip the policies
+/// entirely, so the policy is refused until they are recreated. Materialized views
+/// only produce a warning, since the data API stops serving them to anyone but
+/// service keys once the table is protected.
+async fn check_dependent_views(
+    db: &DatabaseConnection,
+    project: &projects::Model,
+    pg_table_name: &str,
+) -> AppResult<Vec<String>> {
+    let views = db
+        .query_all(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r#"WITH RECURSIVE dependents(oid) AS (
+                   SELECT to_regclass(quote_ident($1))::oid
+                   UNION
+                   SELECT rw.ev_class
+                   FROM dependents d
+                   JOIN pg_depend dep ON dep.refclassid = 'pg_class'::regclass AND dep.refobjid = d.oid
+                       AND dep.classid = 'pg_rewrite'::regclass
+                   JOIN pg_rewrite rw ON rw.oid = dep.objid
+                   WHERE rw.ev_class <> d.oid
+               )
+               SELECT c.relname::text AS name,
+                      c.relkind = 'm' AS materialized,
+                      EXISTS (
+                          SELECT 1 FROM unnest(c.reloptions) AS option
+                          WHERE lower(option) IN ('security_invoker=true', 'security_invoker=on', 'security_invoker=1')
+                      ) AS security_invoker
+               FROM dependents d
+               JOIN pg_class c ON c.oid = d.oid
+               WHERE c.relkind IN ('v', 'm')
+               ORDER BY c.relname"#,
+            [pg_table_name.into()],
+        ))
+        .await?;
+
+    let mut bypassing = Vec::new();
+    let mut warnings = Vec::new();
+
+    for view in views {
+        let name = logical_names(&view.try_get::<String>("", "name")?, &project.id);
+
+        if view.try_get::<bool>("", "materialized")? {
+            warnings.push(format!(
+                "Materialized view {} reads this table; only service keys can read it through the data API now",
+                name
+            ));
+        } else if !view.try_get::<bool>("", "security_invoker")? {
+            bypassing.push(name);
+        }
+    }
+
+    if !bypassing.is_empty() {
+        return Err(AppError::BadRequest(format!(
+            "Views {} read this table without applying its policies; recreate them before adding a policy",
+            bypassing.join(", ")
+        )));
+    }
+
+    Ok(warnings)
+}
+
+/// Get a table and verify the user's role in its project grants `capability`
+async fn find_project_table(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    table_name: &str,
+    capability: Capability,
+) -> AppResult<(projects::Model, project_tables::Model)> {
+    let project = authorize_project(db, user_id, project_slug, capability).await?.project;
+
+    let table = project_tables::Entity::find()
+        .filter(project_tables::Column::ProjectId.eq(project.id))
+        .filter(project_tables::Column::TableName.eq(table_name))
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Table not found".to_string()))?;
+
+    Ok((project, table))
+}
+
+fn policy_response(policy: project_policies::Model) -> AppResult<PolicyResponse> {
+    let command = serde_json::from_value(serde_json::Value::String(policy.command.clone()))
+        .map_err(|_| AppError::InternalServerError(format!("Unknown policy command: {}", policy.command)))?;
+
+    Ok(PolicyResponse {
+        id: policy.id.to_string(),
+        policy_name: policy.policy_name,
+        command,
+        using_expression: policy.using_expression,
+        check_expression: policy.check_expression,
+        created_at: policy.created_at.map(|dt| dt.to_string()).unwrap_or_default(),
+        warnings: Vec::new(),
+    })
+}
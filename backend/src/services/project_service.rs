@@ -1,10 +1,17 @@
 use sea_orm::*;
-use database::entities::projects;
-use shared::models::{CreateProjectRequest, ProjectResponse, UpdateProjectRequest};
+use database::entities::{project_members, projects};
+use shared::models::{CreateProjectRequest, ProjectResponse, ProjectRole, UpdateProjectRequest};
 use crate::error::{AppError, AppResult};
+use crate::services::authorization_service::{authorize_project, Capability};
+use crate::services::table_service::physical_table_name;
 use uuid::Uuid;
 use slug::slugify;
 
+/// Statement timeout for new projects' SQL editor queries
+const DEFAULT_STATEMENT_TIMEOUT_MS: i32 = 30_000;
+const MIN_STATEMENT_TIMEOUT_MS: i32 = 100;
+const MAX_STATEMENT_TIMEOUT_MS: i32 = 600_000;
+
 /// Create a new project
 pub async fn create_project(
     db: &DatabaseConnection,
@@ -48,79 +55,66 @@ pub async fn create_project(
         slug: Set(slug.clone()),
         owner_id: Set(owner_uuid),
         database_status: Set(Some("pending".to_string())),
+        statement_timeout_ms: Set(DEFAULT_STATEMENT_TIMEOUT_MS),
         created_at: Set(Some(chrono::Utc::now().naive_utc())),
         updated_at: Set(Some(chrono::Utc::now().naive_utc())),
     };
 
-    let result = project.insert(db).await?;
-
-    Ok(ProjectResponse {
-        id: result.id.to_string(),
-        name: result.name,
-        description: result.description,
-        slug: result.slug,
-        owner_id: result.owner_id.to_string(),
-        database_status: result.database_status.unwrap_or_else(|| "pending".to_string()),
-        created_at: result.created_at.map(|dt| dt.to_string()).unwrap_or_default(),
-        updated_at: result.updated_at.map(|dt| dt.to_string()),
-    })
+    let txn = db.begin().await?;
+    let result = project.insert(&txn).await?;
+    create_project_role(&txn, &result.id).await?;
+
+    // The creator is the project's first owner
+    project_members::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        project_id: Set(result.id),
+        user_id: Set(owner_uuid),
+        role: Set(ProjectRole::Owner.as_str().to_string()),
+        created_at: Set(Some(chrono::Utc::now().naive_utc())),
+    }
+    .insert(&txn)
+    .await?;
+
+    txn.commit().await?;
+
+    Ok(project_response(result, ProjectRole::Owner))
 }
 
-/// List all projects for a user
+/// List all projects the user is a member of
 pub async fn list_user_projects(
     db: &DatabaseConnection,
     user_id: &str,
 ) -> AppResult<Vec<ProjectResponse>> {
-    let owner_uuid = Uuid::parse_str(user_id)
+    let user_uuid = Uuid::parse_str(user_id)
         .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
 
     let projects_list = projects::Entity::find()
-        .filter(projects::Column::OwnerId.eq(owner_uuid))
+        .find_also_related(project_members::Entity)
+        .filter(project_members::Column::UserId.eq(user_uuid))
         .order_by_desc(projects::Column::CreatedAt)
         .all(db)
         .await?;
 
-    Ok(projects_list
+    projects_list
         .into_iter()
-        .map(|p| ProjectResponse {
-            id: p.id.to_string(),
-            name: p.name,
-            description: p.description,
-            slug: p.slug,
-            owner_id: p.owner_id.to_string(),
-            database_status: p.database_status.unwrap_or_else(|| "pending".to_string()),
-            created_at: p.created_at.map(|dt| dt.to_string()).unwrap_or_default(),
-            updated_at: p.updated_at.map(|dt| dt.to_string()),
+        .filter_map(|(project, member)| member.map(|member| (project, member)))
+        .map(|(project, member)| {
+            let role = ProjectRole::parse(&member.role)
+                .ok_or_else(|| AppError::InternalServerError(format!("Unknown project role: {}", member.role)))?;
+            Ok(project_response(project, role))
         })
-        .collect())
+        .collect()
 }
 
-/// Get a project by slug (verify ownership)
+/// Get a project by slug (verify membership)
 pub async fn get_project_by_slug(
     db: &DatabaseConnection,
     user_id: &str,
     slug: &str,
 ) -> AppResult<ProjectResponse> {
-    let owner_uuid = Uuid::parse_str(user_id)
-        .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
-
-    let project = projects::Entity::find()
-        .filter(projects::Column::Slug.eq(slug))
-        .filter(projects::Column::OwnerId.eq(owner_uuid))
-        .one(db)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Project not found".to_string()))?;
+    let access = authorize_project(db, user_id, slug, Capability::Read).await?;
 
-    Ok(ProjectResponse {
-        id: project.id.to_string(),
-        name: project.name,
-        description: project.description,
-        slug: project.slug,
-        owner_id: project.owner_id.to_string(),
-        database_status: project.database_status.unwrap_or_else(|| "pending".to_string()),
-        created_at: project.created_at.map(|dt| dt.to_string()).unwrap_or_default(),
-        updated_at: project.updated_at.map(|dt| dt.to_string()),
-    })
+    Ok(project_response(access.project, access.role))
 }
 
 /// Update a project
@@ -130,19 +124,10 @@ pub async fn update_project(
     slug: &str,
     req: UpdateProjectRequest,
 ) -> AppResult<ProjectResponse> {
-    let owner_uuid = Uuid::parse_str(user_id)
-        .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
-
-    // Find the project
-    let project = projects::Entity::find()
-        .filter(projects::Column::Slug.eq(slug))
-        .filter(projects::Column::OwnerId.eq(owner_uuid))
-        .one(db)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Project not found".to_string()))?;
+    let access = authorize_project(db, user_id, slug, Capability::ManageProject).await?;
 
     // Update fields
-    let mut project: projects::ActiveModel = project.into();
+    let mut project: projects::ActiveModel = access.project.into();
     
     if let Some(name) = req.name {
         if name.trim().is_empty() {
@@ -154,21 +139,22 @@ pub async fn update_project(
     if let Some(description) = req.description {
         project.description = Set(Some(description));
     }
+
+    if let Some(timeout_ms) = req.statement_timeout_ms {
+        if !(MIN_STATEMENT_TIMEOUT_MS..=MAX_STATEMENT_TIMEOUT_MS).contains(&timeout_ms) {
+            return Err(AppError::BadRequest(format!(
+                "Statement timeout must be between {} and {} ms",
+                MIN_STATEMENT_TIMEOUT_MS, MAX_STATEMENT_TIMEOUT_MS
+            )));
+        }
+        project.statement_timeout_ms = Set(timeout_ms);
+    }
     
     project.updated_at = Set(Some(chrono::Utc::now().naive_utc()));
 
     let updated = project.update(db).await?;
 
-    Ok(ProjectResponse {
-        id: updated.id.to_string(),
-        name: updated.name,
-        description: updated.description,
-        slug: updated.slug,
-        owner_id: updated.owner_id.to_string(),
-        database_status: updated.database_status.unwrap_or_else(|| "pending".to_string()),
-        created_at: updated.created_at.map(|dt| dt.to_string()).unwrap_or_default(),
-        updated_at: updated.updated_at.map(|dt| dt.to_string()),
-    })
+    Ok(project_response(updated, access.role))
 }
 
 /// Delete a project
@@ -177,19 +163,107 @@ pub async fn delete_project(
     user_id: &str,
     slug: &str,
 ) -> AppResult<()> {
-    let owner_uuid = Uuid::parse_str(user_id)
-        .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
+    let access = authorize_project(db, user_id, slug, Capability::DeleteProject).await?;
 
-    // Find the project
-    let project = projects::Entity::find()
-        .filter(projects::Column::Slug.eq(slug))
-        .filter(projects::Column::OwnerId.eq(owner_uuid))
-        .one(db)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Project not found".to_string()))?;
+    let txn = db.begin().await?;
+    drop_project(&txn, access.project).await?;
+    txn.commit().await?;
+
+    Ok(())
+}
+
+/// Drop a project's tables, views and functions, then the project itself.
+///
+/// The metadata rows go with the project; the physical objects are found in the
+/// catalog by their `project_{id}_` prefix, so none are left behind.
+pub(crate) async fn drop_project<C: ConnectionTrait>(db: &C, project: projects::Model) -> AppResult<()> {
+    let prefix = physical_table_name(&project.id, "");
+
+    let statements = db
+        .query_all(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r#"SELECT format('DROP %s IF EXISTS %I CASCADE',
+                             CASE c.relkind WHEN 'v' THEN 'VIEW' WHEN 'm' THEN 'MATERIALIZED VIEW' ELSE 'TABLE' END,
+                             c.relname) AS statement
+               FROM pg_class c
+               WHERE c.relnamespace = current_schema()::regnamespace
+                 AND c.relkind IN ('r', 'p', 'v', 'm')
+                 AND starts_with(c.relname, $1)
+               UNION ALL
+               SELECT format('DROP FUNCTION IF EXISTS %s CASCADE', p.oid::regprocedure)
+               FROM pg_proc p
+               WHERE p.pronamespace = current_schema()::regnamespace
+                 AND starts_with(p.proname, $1)"#,
+            [prefix.into()],
+        ))
+        .await?;
+
+    for row in statements {
+        let statement: String = row.try_get("", "statement")?;
+        db.execute_unprepared(&statement).await?;
+    }
+
+    db.execute_unprepared(&format!(
+        r#"DO $$
+           BEGIN
+               IF EXISTS (SELECT 1 FROM pg_roles WHERE rolname = '{role}') THEN
+                   DROP OWNED BY "{role}";
+                   DROP ROLE "{role}";
+               END IF;
+           END
+           $$"#,
+        role = project_role(&project.id)
+    ))
+    .await?;
 
-    // Delete the project
     project.delete(db).await?;
 
     Ok(())
 }
+
+/// Role that owns a project's tables, views and functions; SQL editor scripts run as it
+pub(crate) fn project_role(project_id: &Uuid) -> String {
+    format!("ferrisbase_project_{}", project_id.simple())
+}
+
+/// Create the role of a new project. The connection's role joins it to manage the project's objects.
+async fn create_project_role<C: ConnectionTrait>(db: &C, project_id: &Uuid) -> AppResult<()> {
+    let role = project_role(project_id);
+
+    db.execute_unprepared(&format!(
+        r#"DO $$
+           BEGIN
+               IF NOT EXISTS (SELECT 1 FROM pg_roles WHERE rolname = '{role}') THEN
+                   CREATE ROLE "{role}" NOLOGIN;
+               END IF;
+           END
+           $$;
+           GRANT "{role}" TO CURRENT_USER"#
+    ))
+    .await?;
+
+    Ok(())
+}
+
+/// Hand a newly created object, such as `TABLE "project_..._notes"`, to the project's role
+pub(crate) async fn set_project_owner<C: ConnectionTrait>(db: &C, project_id: &Uuid, object: &str) -> AppResult<()> {
+    db.execute_unprepared(&format!("ALTER {} OWNER TO \"{}\"", object, project_role(project_id)))
+        .await?;
+
+    Ok(())
+}
+
+pub(crate) fn project_response(project: projects::Model, role: ProjectRole) -> ProjectResponse {
+    ProjectResponse {
+        id: project.id.to_string(),
+        name: project.name,
+        description: project.description,
+        slug: project.slug,
+        owner_id: project.owner_id.to_string(),
+        database_status: project.database_status.unwrap_or_else(|| "pending".to_string()),
+        statement_timeout_ms: project.statement_timeout_ms,
+        created_at: project.created_at.map(|dt| dt.to_string()).unwrap_or_default(),
+        updated_at: project.updated_at.map(|dt| dt.to_string()),
+        role,
+    }
+}
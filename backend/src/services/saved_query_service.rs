@@ -0,0 +1,256 @@
+use sea_orm::*;
+use database::entities::{projects, saved_queries};
+use shared::models::{
+    CreateSavedQueryRequest, ExecuteSavedQueryRequest, ExecuteSqlRequest, ExecuteSqlResponse, QueryParameter,
+    SavedQueryResponse,
+};
+use crate::error::{AppError, AppResult};
+use crate::services::authorization_service::{authorize_project, Capability};
+use crate::services::api_key_service::Principal;
+use crate::services::sql_service::{
+    authorize_script, parse_parameter_type, prepare_script, run_script, BoundParameter, QueryRegistry,
+};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// List the saved queries of a project
+pub async fn list_saved_queries(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+) -> AppResult<Vec<SavedQueryResponse>> {
+    let project = authorize_project(db, user_id, project_slug, Capability::Read).await?.project;
+
+    let saved = saved_queries::Entity::find()
+        .filter(saved_queries::Column::ProjectId.eq(project.id))
+        .order_by_asc(saved_queries::Column::Name)
+        .all(db)
+        .await?;
+
+    saved.into_iter().map(saved_query_response).collect()
+}
+
+/// Get a saved query by ID
+pub async fn get_saved_query(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    saved_query_id: &str,
+) -> AppResult<SavedQueryResponse> {
+    let project = authorize_project(db, user_id, project_slug, Capability::Read).await?.project;
+    let saved = find_saved_query(db, &project, saved_query_id).await?;
+
+    saved_query_response(saved)
+}
+
+/// Save a named script; it is checked like an editor script but not run
+pub async fn create_saved_query(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    req: CreateSavedQueryRequest,
+) -> AppResult<SavedQueryResponse> {
+    let project = authorize_project(db, user_id, project_slug, Capability::WriteData).await?.project;
+
+    validate_saved_query(db, &project, &req).await?;
+    ensure_unique_name(db, &project, &req.name, None).await?;
+
+    let now = chrono::Utc::now().naive_utc();
+    let saved = saved_queries::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        project_id: Set(project.id),
+        name: Set(req.name.trim().to_string()),
+        description: Set(req.description),
+        query: Set(req.query),
+        parameters: Set(serialize_parameters(&req.parameters)?),
+        created_at: Set(Some(now)),
+        updated_at: Set(Some(now)),
+    }
+    .insert(db)
+    .await?;
+
+    saved_query_response(saved)
+}
+
+/// Replace the name, description, script and parameters of a saved query
+pub async fn update_saved_query(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    saved_query_id: &str,
+    req: CreateSavedQueryRequest,
+) -> AppResult<SavedQueryResponse> {
+    let project = authorize_project(db, user_id, project_slug, Capability::WriteData).await?.project;
+    let saved = find_saved_query(db, &project, saved_query_id).await?;
+
+    validate_saved_query(db, &project, &req).await?;
+    ensure_unique_name(db, &project, &req.name, Some(saved.id)).await?;
+
+    let mut active: saved_queries::ActiveModel = saved.into();
+    active.name = Set(req.name.trim().to_string());
+    active.description = Set(req.description);
+    active.query = Set(req.query);
+    active.parameters = Set(serialize_parameters(&req.parameters)?);
+    active.updated_at = Set(Some(chrono::Utc::now().naive_utc()));
+
+    let saved = active.update(db).await?;
+
+    saved_query_response(saved)
+}
+
+/// Delete a saved query; its history entries are kept
+pub async fn delete_saved_query(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    saved_query_id: &str,
+) -> AppResult<()> {
+    let project = authorize_project(db, user_id, project_slug, Capability::WriteData).await?.project;
+    let saved = find_saved_query(db, &project, saved_query_id).await?;
+
+    saved.delete(db).await?;
+
+    Ok(())
+}
+
+/// Run a saved query with parameter values keyed by parameter name.
+///
+/// Values are sent as text and cast to the declared types; JSON `null` binds SQL `NULL`.
+pub async fn execute_saved_query(
+    db: &DatabaseConnection,
+    queries: &QueryRegistry,
+    principal: &Principal,
+    project_slug: &str,
+    saved_query_id: &str,
+    req: ExecuteSavedQueryRequest,
+) -> AppResult<ExecuteSqlResponse> {
+    let (project, user_id) = authorize_script(db, principal, project_slug, req.read_only).await?;
+    let saved = find_saved_query(db, &project, saved_query_id).await?;
+    let parameters = parse_parameters(&saved)?;
+
+    if let Some(unknown) = req.parameters.keys().find(|key| !parameters.iter().any(|param| &param.name == *key)) {
+        return Err(AppError::BadRequest(format!("Unknown parameter: {}", unknown)));
+    }
+
+    let mut bound = Vec::with_capacity(parameters.len());
+    for param in &parameters {
+        let value = req
+            .parameters
+            .get(&param.name)
+            .ok_or_else(|| AppError::BadRequest(format!("Missing parameter: {}", param.name)))?;
+
+        bound.push(BoundParameter {
+            data_type: parse_parameter_type(&param.data_type).map_err(AppError::InternalServerError)?,
+            value: match value {
+                JsonValue::Null => None,
+                JsonValue::String(s) => Some(s.clone()),
+                other => Some(other.to_string()),
+            },
+        });
+    }
+
+    let script = ExecuteSqlRequest {
+        query: saved.query.clone(),
+        read_only: req.read_only,
+        transaction: req.transaction,
+        explain: false,
+        query_id: req.query_id,
+    };
+
+    run_script(db, queries, user_id, &project, &script, &bound, Some(saved.id)).await
+}
+
+/// Check the name and parameters, and that the script would be accepted by the SQL editor
+async fn validate_saved_query(
+    db: &DatabaseConnection,
+    project: &projects::Model,
+    req: &CreateSavedQueryRequest,
+) -> AppResult<()> {
+    let name = req.name.trim();
+    if name.is_empty() || name.len() > 100 {
+        return Err(AppError::BadRequest("Saved query name must be 1 to 100 characters".to_string()));
+    }
+
+    let mut parameter_types = Vec::with_capacity(req.parameters.len());
+    for (index, param) in req.parameters.iter().enumerate() {
+        if param.name.is_empty() || !param.name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+            return Err(AppError::BadRequest(
+                "Parameter names can only contain letters, numbers, and underscores".to_string(),
+            ));
+        }
+        if req.parameters[..index].iter().any(|other| other.name == param.name) {
+            return Err(AppError::BadRequest(format!("Duplicate parameter: {}", param.name)));
+        }
+        parameter_types.push(parse_parameter_type(&param.data_type).map_err(AppError::BadRequest)?);
+    }
+
+    prepare_script(db, project, &req.query, &parameter_types, false).await?;
+
+    Ok(())
+}
+
+async fn ensure_unique_name(
+    db: &DatabaseConnection,
+    project: &projects::Model,
+    name: &str,
+    except: Option<Uuid>,
+) -> AppResult<()> {
+    let mut existing = saved_queries::Entity::find()
+        .filter(saved_queries::Column::ProjectId.eq(project.id))
+        .filter(saved_queries::Column::Name.eq(name.trim()));
+
+    if let Some(id) = except {
+        existing = existing.filter(saved_queries::Column::Id.ne(id));
+    }
+
+    if existing.one(db).await?.is_some() {
+        return Err(AppError::BadRequest("Saved query with this name already exists".to_string()));
+    }
+    Ok(())
+}
+
+fn serialize_parameters(parameters: &[QueryParameter]) -> AppResult<String> {
+    let parameters: Vec<QueryParameter> = parameters
+        .iter()
+        .map(|param| QueryParameter {
+            name: param.name.clone(),
+            data_type: param.data_type.trim().to_string(),
+        })
+        .collect();
+
+    serde_json::to_string(&parameters).map_err(|e| AppError::InternalServerError(e.to_string()))
+}
+
+fn parse_parameters(saved: &saved_queries::Model) -> AppResult<Vec<QueryParameter>> {
+    serde_json::from_str(&saved.parameters)
+        .map_err(|e| AppError::InternalServerError(format!("Invalid saved query parameters: {}", e)))
+}
+
+async fn find_saved_query(
+    db: &DatabaseConnection,
+    project: &projects::Model,
+    saved_query_id: &str,
+) -> AppResult<saved_queries::Model> {
+    let id = Uuid::parse_str(saved_query_id)
+        .map_err(|_| AppError::NotFound("Saved query not found".to_string()))?;
+
+    saved_queries::Entity::find_by_id(id)
+        .filter(saved_queries::Column::ProjectId.eq(project.id))
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("Saved query not found".to_string()))
+}
+
+fn saved_query_response(saved: saved_queries::Model) -> AppResult<SavedQueryResponse> {
+    let parameters = parse_parameters(&saved)?;
+
+    Ok(SavedQueryResponse {
+        id: saved.id.to_string(),
+        name: saved.name,
+        description: saved.description,
+        query: saved.query,
+        parameters,
+        created_at: saved.created_at.map(|dt| dt.to_string()).unwrap_or_default(),
+        updated_at: saved.updated_at.map(|dt| dt.to_string()).unwrap_or_default(),
+    })
+}
@@ -0,0 +1,807 @@
+use sea_orm::*;
+use database::entities::{project_columns, project_tables, projects};
+use shared::models::{
+    ApplySchemaDiffRequest, ColumnDataType, ColumnDefinition, CreateTableRequest,
+    ImportSchemaResponse, MigrationAction, MigrationStep, SchemaDiffResponse,
+};
+use sqlparser::ast::{ColumnOption, CreateTable, DataType, TableConstraint};
+use sqlparser::dialect::PostgreSqlDialect;
+use sqlparser::parser::Parser;
+use sha2::{Digest, Sha256};
+use crate::error::{AppError, AppResult};
+use crate::services::authorization_service::{authorize_project, Capability};
+use crate::services::table_service::{build_create_table_sql, create_table_for_project, physical_table_name};
+use uuid::Uuid;
+
+/// Import tables from a script of `CREATE TABLE` statements.
+/// All tables are created in a single transaction, so a failing statement leaves the project untouched.
+pub async fn import_schema(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    sql: &str,
+) -> AppResult<ImportSchemaResponse> {
+    let project = authorize_project(db, user_id, project_slug, Capability::ManageSchema).await?.project;
+
+    let statements = Parser::parse_sql(&PostgreSqlDialect {}, sql)
+        .map_err(|e| AppError::BadRequest(format!("Failed to parse SQL: {}", e)))?;
+
+    if statements.is_empty() {
+        return Err(AppError::BadRequest("Script does not contain any statements".to_string()));
+    }
+
+    // Convert every statement before touching the database
+    let mut warnings = Vec::new();
+    let mut requests: Vec<CreateTableRequest> = Vec::new();
+
+    for statement in statements {
+        let create = match statement {
+            sqlparser::ast::Statement::CreateTable(create) => create,
+            other => {
+                return Err(AppError::BadRequest(format!(
+                    "Only CREATE TABLE statements can be imported, found: {}",
+                    other
+                )))
+            }
+        };
+
+        let req = table_request_from_ddl(&create, &mut warnings)?;
+
+        if requests.iter().any(|r| r.table_name == req.table_name) {
+            return Err(AppError::BadRequest(format!(
+                "Table {} is defined more than once",
+                req.table_name
+            )));
+        }
+
+        requests.push(req);
+    }
+
+    let txn = db.begin().await?;
+    let mut tables = Vec::with_capacity(requests.len());
+
+    for req in requests {
+        tables.push(create_table_for_project(&txn, &project, req).await?);
+    }
+
+    txn.commit().await?;
+
+    Ok(ImportSchemaResponse { tables, warnings })
+}
+
+/// Translate a parsed `CREATE TABLE` into the table editor's request format
+fn table_request_from_ddl(
+    create: &CreateTable,
+    warnings: &mut Vec<String>,
+) -> AppResult<CreateTableRequest> {
+    // Schema qualifiers are dropped; every table lives in the project namespace
+    let table_name = create
+        .name
+        .0
+        .last()
+        .map(|ident| ident.value.clone())
+        .ok_or_else(|| AppError::BadRequest("Table name is missing".to_string()))?;
+
+    validate_identifier(&table_name)?;
+
+    // Single-column table constraints map onto column flags. Composite keys have no
+    // equivalent, and dropping one would silently change what the rows are keyed by.
+    let mut primary_keys: Vec<String> = Vec::new();
+    let mut unique_columns: Vec<String> = Vec::new();
+
+    for constraint in &create.constraints {
+        match constraint {
+            TableConstraint::PrimaryKey { columns, .. } if columns.len() == 1 => {
+                primary_keys.push(columns[0].value.clone());
+            }
+            TableConstraint::Unique { columns, .. } if columns.len() == 1 => {
+                unique_columns.push(columns[0].value.clone());
+            }
+            TableConstraint::PrimaryKey { .. } | TableConstraint::Unique { .. } => {
+                return Err(AppError::BadRequest(format!(
+                    "{}: composite keys are not supported, found {}",
+                    table_name, constraint
+                )));
+            }
+            other => warnings.push(format!("{}: skipped constraint {}", table_name, other)),
+        }
+    }
+
+    let mut columns = Vec::new();
+
+    for column in &create.columns {
+        let name = column.name.value.clone();
+        validate_identifier(&name)?;
+
+        // Timestamps are added to every table automatically
+        if name == "created_at" || name == "updated_at" {
+            warnings.push(format!("{}.{}: replaced by the built-in timestamp column", table_name, name));
+            continue;
+        }
+
+        let data_type = map_data_type(&column.data_type).ok_or_else(|| {
+            AppError::BadRequest(format!(
+                "Unsupported type {} for column {}.{}",
+                column.data_type, table_name, name
+            ))
+        })?;
+
+        let mut definition = ColumnDefinition {
+            display_name: display_name_for(&name),
+            name: name.clone(),
+            data_type,
+            is_nullable: true,
+            is_primary_key: primary_keys.contains(&name),
+            is_unique: unique_columns.contains(&name),
+            default_value: None,
+        };
+
+        for option in &column.options {
+            match &option.option {
+                ColumnOption::Null => definition.is_nullable = true,
+                ColumnOption::NotNull => definition.is_nullable = false,
+                ColumnOption::Default(expr) => definition.default_value = Some(expr.to_string()),
+                ColumnOption::Unique { is_primary: true, .. } => definition.is_primary_key = true,
+                ColumnOption::Unique { is_primary: false, .. } => definition.is_unique = true,
+                other => warnings.push(format!("{}.{}: skipped option {}", table_name, name, other)),
+            }
+        }
+
+        if definition.is_primary_key {
+            definition.is_nullable = false;
+        }
+
+        columns.push(definition);
+    }
+
+    Ok(CreateTableRequest {
+        display_name: display_name_for(&table_name),
+        table_name,
+        description: None,
+        columns,
+    })
+}
+
+/// Map a parsed SQL type onto the column types the table editor supports
+fn map_data_type(data_type: &DataType) -> Option<ColumnDataType> {
+    match data_type {
+        DataType::Text
+        | DataType::Varchar(_)
+        | DataType::CharacterVarying(_)
+        | DataType::CharVarying(_)
+        | DataType::Char(_)
+        | DataType::Character(_)
+        | DataType::String(_) => Some(ColumnDataType::Text),
+        DataType::Int(_)
+        | DataType::Integer(_)
+        | DataType::Int4(_)
+        | DataType::SmallInt(_)
+        | DataType::Int2(_) => Some(ColumnDataType::Integer),
+        DataType::BigInt(_) | DataType::Int8(_) => Some(ColumnDataType::BigInt),
+        DataType::Numeric(_)
+        | DataType::Decimal(_)
+        | DataType::Dec(_)
+        | DataType::Real
+        | DataType::Float(_)
+        | DataType::Float4
+        | DataType::Float8
+        | DataType::Double
+        | DataType::DoublePrecision => Some(ColumnDataType::Decimal),
+        DataType::Bool | DataType::Boolean => Some(ColumnDataType::Boolean),
+        DataType::Timestamp(_, _) | DataType::Datetime(_) => Some(ColumnDataType::Timestamp),
+        DataType::Date => Some(ColumnDataType::Date),
+        DataType::JSON | DataType::JSONB => Some(ColumnDataType::Json),
+        DataType::Uuid => Some(ColumnDataType::Uuid),
+        DataType::Custom(name, _) => match name.to_string().to_lowercase().as_str() {
+            "timestamptz" => Some(ColumnDataType::Timestamp),
+            "citext" => Some(ColumnDataType::Text),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+/// Same rule the table editor applies to table names
+fn validate_identifier(name: &str) -> AppResult<()> {
+    if name.is_empty() || !name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return Err(AppError::BadRequest(format!(
+            "Invalid identifier {}: only letters, numbers, and underscores are allowed",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Turn `order_items` into `Order Items`
+fn display_name_for(name: &str) -> String {
+    name.split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// Placeholder that stands in for a project's physical table prefix in normalized constraints
+const PREFIX_PLACEHOLDER: &str = "{project}_";
+
+/// Physical constraint read from pg_catalog, with the project prefix normalized away
+#[derive(Debug, Clone, PartialEq)]
+struct ConstraintSnapshot {
+    name: String,
+    kind: String,
+    definition: String,
+}
+
+#[derive(Debug, Clone)]
+struct TableSnapshot {
+    table: project_tables::Model,
+    columns: Vec<project_columns::Model>,
+    constraints: Vec<ConstraintSnapshot>,
+}
+
+/// What has to happen to the target project when a step is applied
+enum StepOp {
+    CreateTable(CreateTableRequest),
+    DropTable(project_tables::Model),
+    AddColumn { table_id: Uuid, column: project_columns::Model },
+    DropColumn(project_columns::Model),
+    SyncColumn { target: project_columns::Model, source: project_columns::Model },
+    SyncTable { target: project_tables::Model, source: project_tables::Model },
+    Constraint,
+}
+
+struct PlannedStep {
+    step: MigrationStep,
+    op: StepOp,
+}
+
+/// Compare the schema of `source_slug` against `target_slug` and describe the
+/// migration that would make the target match the source
+pub async fn diff_project_schemas(
+    db: &DatabaseConnection,
+    user_id: &str,
+    source_slug: &str,
+    target_slug: &str,
+) -> AppResult<SchemaDiffResponse> {
+    let source = authorize_project(db, user_id, source_slug, Capability::Read).await?.project;
+    let target = authorize_project(db, user_id, target_slug, Capability::Read).await?.project;
+
+    let planned = plan_migration(db, &source, &target).await?;
+
+    Ok(diff_response(&source, &target, &planned))
+}
+
+/// Apply the migration plan from `source_slug` to `target_slug` in a single transaction
+pub async fn apply_schema_diff(
+    db: &DatabaseConnection,
+    user_id: &str,
+    source_slug: &str,
+    target_slug: &str,
+    req: ApplySchemaDiffRequest,
+) -> AppResult<SchemaDiffResponse> {
+    let source = authorize_project(db, user_id, source_slug, Capability::Read).await?.project;
+    let target = authorize_project(db, user_id, target_slug, Capability::ManageSchema).await?.project;
+
+    let planned = plan_migration(db, &source, &target).await?;
+    let mut response = diff_response(&source, &target, &planned);
+
+    if req.fingerprint.as_ref().is_some_and(|f| *f != response.fingerprint) {
+        return Err(AppError::BadRequest(
+            "Schemas changed since the plan was generated; review the new diff before applying".to_string(),
+        ));
+    }
+
+    if response.has_destructive_changes && !req.allow_destructive {
+        return Err(AppError::BadRequest(
+            "Plan contains destructive changes; set allow_destructive to apply it".to_string(),
+        ));
+    }
+
+    let txn = db.begin().await?;
+
+    for planned_step in planned {
+        apply_step(&txn, &target, planned_step).await?;
+    }
+
+    txn.commit().await?;
+
+    response.applied = true;
+    Ok(response)
+}
+
+/// Load table and column metadata plus physical constraints for a project
+async fn load_snapshot(
+    db: &DatabaseConnection,
+    project: &projects::Model,
+) -> AppResult<Vec<TableSnapshot>> {
+    let tables = project_tables::Entity::find()
+        .filter(project_tables::Column::ProjectId.eq(project.id))
+        .order_by_asc(project_tables::Column::TableName)
+        .all(db)
+        .await?;
+
+    let prefix = physical_table_name(&project.id, "");
+    let mut snapshots = Vec::with_capacity(tables.len());
+
+    for table in tables {
+        let columns = project_columns::Entity::find()
+            .filter(project_columns::Column::ProjectTableId.eq(table.id))
+            .order_by_asc(project_columns::Column::ColumnOrder)
+            .all(db)
+            .await?;
+
+        // Not-null constraints are tracked through column metadata instead
+        let stmt = Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            r#"SELECT con.conname AS name, con.contype::text AS kind, pg_get_constraintdef(con.oid) AS definition
+               FROM pg_constraint con
+               JOIN pg_class rel ON rel.oid = con.conrelid
+               JOIN pg_namespace nsp ON nsp.oid = rel.relnamespace
+               WHERE rel.relname = $1 AND nsp.nspname = current_schema() AND con.contype <> 'n'
+               ORDER BY con.conname"#,
+            [physical_table_name(&project.id, &table.table_name).into()],
+        );
+
+        let constraints = db
+            .query_all(stmt)
+            .await?
+            .into_iter()
+            .map(|row| {
+                Ok(ConstraintSnapshot {
+                    name: row.try_get::<String>("", "name")?.replace(&prefix, PREFIX_PLACEHOLDER),
+                    kind: row.try_get("", "kind")?,
+                    definition: row.try_get::<String>("", "definition")?.replace(&prefix, PREFIX_PLACEHOLDER),
+                })
+            })
+            .collect::<Result<Vec<_>, DbErr>>()?;
+
+        snapshots.push(TableSnapshot { table, columns, constraints });
+    }
+
+    Ok(snapshots)
+}
+
+/// Build the ordered list of steps that turn the target schema into the source schema
+async fn plan_migration(
+    db: &DatabaseConnection,
+    source: &projects::Model,
+    target: &projects::Model,
+) -> AppResult<Vec<PlannedStep>> {
+    let source_tables = load_snapshot(db, source).await?;
+    let target_tables = load_snapshot(db, target).await?;
+    let target_prefix = physical_table_name(&target.id, "");
+
+    // Steps are bucketed so that dependent DDL runs in a safe order
+    let mut drop_constraints = Vec::new();
+    let mut create_tables = Vec::new();
+    let mut add_columns = Vec::new();
+    let mut alter_columns = Vec::new();
+    let mut add_constraints = Vec::new();
+    let mut drop_columns = Vec::new();
+    let mut drop_tables = Vec::new();
+    let mut metadata = Vec::new();
+
+    for source_table in &source_tables {
+        let name = &source_table.table.table_name;
+        let pg_table_name = physical_table_name(&target.id, name);
+
+        let existing_constraints = match target_tables.iter().find(|t| &t.table.table_name == name) {
+            None => {
+                let req = CreateTableRequest {
+                    table_name: name.clone(),
+                    display_name: source_table.table.display_name.clone(),
+                    description: source_table.table.description.clone(),
+                    columns: source_table
+                        .columns
+                        .iter()
+                        .filter(|c| c.column_name != "created_at" && c.column_name != "updated_at")
+                        .map(column_definition)
+                        .collect::<AppResult<Vec<_>>>()?,
+                };
+
+                create_tables.push(PlannedStep {
+                    step: MigrationStep {
+                        action: MigrationAction::CreateTable,
+                        table_name: name.clone(),
+                        description: format!("Create table {}", name),
+                        sql: Some(build_create_table_sql(&pg_table_name, &req.columns)),
+                        destructive: false,
+                    },
+                    op: StepOp::CreateTable(req),
+                });
+
+                // The new table will only have the key constraints CREATE TABLE adds
+                implied_constraints(source_table)
+            }
+            Some(target_table) => {
+                diff_columns(
+                    source_table,
+                    target_table,
+                    &pg_table_name,
+                    &mut add_columns,
+                    &mut alter_columns,
+                    &mut drop_columns,
+                    &mut metadata,
+                )?;
+
+                if source_table.table.display_name != target_table.table.display_name
+                    || source_table.table.description != target_table.table.description
+                {
+                    metadata.push(PlannedStep {
+                        step: MigrationStep {
+                            action: MigrationAction::UpdateMetadata,
+                            table_name: name.clone(),
+                            description: format!("Update display name and description of {}", name),
+                            sql: None,
+                            destructive: false,
+                        },
+                        op: StepOp::SyncTable {
+                            target: target_table.table.clone(),
+                            source: source_table.table.clone(),
+                        },
+                    });
+                }
+
+                target_table.constraints.clone()
+            }
+        };
+
+        for constraint in &existing_constraints {
+            if !source_table.constraints.iter().any(|c| same_constraint(c, constraint))
+                && target_tables.iter().any(|t| &t.table.table_name == name)
+            {
+                let constraint_name = constraint.name.replace(PREFIX_PLACEHOLDER, &target_prefix);
+                drop_constraints.push(PlannedStep {
+                    step: MigrationStep {
+                        action: MigrationAction::DropConstraint,
+                        table_name: name.clone(),
+                        description: format!("Drop constraint {} on {}", constraint_name, name),
+                        sql: Some(format!(
+                            "ALTER TABLE \"{}\" DROP CONSTRAINT IF EXISTS \"{}\"",
+                            pg_table_name, constraint_name
+                        )),
+                        destructive: false,
+                    },
+                    op: StepOp::Constraint,
+                });
+            }
+        }
+
+        for constraint in &source_table.constraints {
+            if !existing_constraints.iter().any(|c| same_constraint(c, constraint)) {
+                let constraint_name = constraint.name.replace(PREFIX_PLACEHOLDER, &target_prefix);
+                add_constraints.push(PlannedStep {
+                    step: MigrationStep {
+                        action: MigrationAction::AddConstraint,
+                        table_name: name.clone(),
+                        description: format!("Add constraint {} on {}", constraint_name, name),
+                        sql: Some(format!(
+                            "ALTER TABLE \"{}\" ADD CONSTRAINT \"{}\" {}",
+                            pg_table_name,
+                            constraint_name,
+                            constraint.definition.replace(PREFIX_PLACEHOLDER, &target_prefix)
+                        )),
+                        destructive: false,
+                    },
+                    op: StepOp::Constraint,
+                });
+            }
+        }
+    }
+
+    for target_table in &target_tables {
+        let name = &target_table.table.table_name;
+        if source_tables.iter().any(|t| &t.table.table_name == name) {
+            continue;
+        }
+
+        drop_tables.push(PlannedStep {
+            step: MigrationStep {
+                action: MigrationAction::DropTable,
+                table_name: name.clone(),
+                description: format!("Drop table {} and all of its rows", name),
+                sql: Some(format!(
+                    "DROP TABLE IF EXISTS \"{}\" CASCADE",
+                    physical_table_name(&target.id, name)
+                )),
+                destructive: true,
+            },
+            op: StepOp::DropTable(target_table.table.clone()),
+        });
+    }
+
+    let mut steps = drop_constraints;
+    steps.extend(create_tables);
+    steps.extend(add_columns);
+    steps.extend(alter_columns);
+    steps.extend(add_constraints);
+    steps.extend(drop_columns);
+    steps.extend(drop_tables);
+    steps.extend(metadata);
+
+    Ok(steps)
+}
+
+/// Compare the columns of a table that exists in both projects
+fn diff_columns(
+    source_table: &TableSnapshot,
+    target_table: &TableSnapshot,
+    pg_table_name: &str,
+    add_columns: &mut Vec<PlannedStep>,
+    alter_columns: &mut Vec<PlannedStep>,
+    drop_columns: &mut Vec<PlannedStep>,
+    metadata: &mut Vec<PlannedStep>,
+) -> AppResult<()> {
+    let table_name = &source_table.table.table_name;
+
+    for source_col in &source_table.columns {
+        let column = &source_col.column_name;
+        let source_type = column_data_type(source_col)?;
+
+        let Some(target_col) = target_table.columns.iter().find(|c| &c.column_name == column) else {
+            // Keys and uniqueness are added by the constraint steps
+            let mut col_def = format!("\"{}\" {}", column, source_type.to_postgres_type());
+            if !source_col.is_nullable.unwrap_or(true) {
+                col_def.push_str(" NOT NULL");
+            }
+            if let Some(ref default_val) = source_col.default_value {
+                col_def.push_str(&format!(" DEFAULT {}", default_val));
+            }
+
+            add_columns.push(PlannedStep {
+                step: MigrationStep {
+                    action: MigrationAction::AddColumn,
+                    table_name: table_name.clone(),
+                    description: format!("Add column {}.{}", table_name, column),
+                    sql: Some(format!("ALTER TABLE \"{}\" ADD COLUMN {}", pg_table_name, col_def)),
+                    destructive: false,
+                },
+                op: StepOp::AddColumn {
+                    table_id: target_table.table.id,
+                    column: source_col.clone(),
+                },
+            });
+            continue;
+        };
+
+        let mut changes = Vec::new();
+        let mut destructive = false;
+
+        if source_col.data_type != target_col.data_type {
+            changes.push(format!(
+                "ALTER COLUMN \"{}\" TYPE {} USING \"{}\"::{}",
+                column,
+                source_type.to_postgres_type(),
+                column,
+                source_type.to_postgres_type()
+            ));
+            destructive = true;
+        }
+
+        let source_nullable = source_col.is_nullable.unwrap_or(true);
+        if source_nullable != target_col.is_nullable.unwrap_or(true) && !source_col.is_primary_key.unwrap_or(false) {
+            changes.push(format!(
+                "ALTER COLUMN \"{}\" {} NOT NULL",
+                column,
+                if source_nullable { "DROP" } else { "SET" }
+            ));
+        }
+
+        if source_col.default_value != target_col.default_value {
+            changes.push(match source_col.default_value {
+                Some(ref default_val) => format!("ALTER COLUMN \"{}\" SET DEFAULT {}", column, default_val),
+                None => format!("ALTER COLUMN \"{}\" DROP DEFAULT", column),
+            });
+        }
+
+        let op = StepOp::SyncColumn {
+            target: target_col.clone(),
+            source: source_col.clone(),
+        };
+
+        if !changes.is_empty() {
+            alter_columns.push(PlannedStep {
+                step: MigrationStep {
+                    action: MigrationAction::AlterColumn,
+                    table_name: table_name.clone(),
+                    description: if destructive {
+                        format!(
+                            "Change type of {}.{} from {} to {} (existing values are converted)",
+                            table_name, column, target_col.data_type, source_col.data_type
+                        )
+                    } else {
+                        format!("Alter column {}.{}", table_name, column)
+                    },
+                    sql: Some(format!("ALTER TABLE \"{}\" {}", pg_table_name, changes.join(", "))),
+                    destructive,
+                },
+                op,
+            });
+        } else if source_col.display_name != target_col.display_name
+            || source_col.is_primary_key != target_col.is_primary_key
+            || source_col.is_unique != target_col.is_unique
+        {
+            metadata.push(PlannedStep {
+                step: MigrationStep {
+                    action: MigrationAction::UpdateMetadata,
+                    table_name: table_name.clone(),
+                    description: format!("Update metadata of column {}.{}", table_name, column),
+                    sql: None,
+                    destructive: false,
+                },
+                op,
+            });
+        }
+    }
+
+    for target_col in &target_table.columns {
+        let column = &target_col.column_name;
+        if source_table.columns.iter().any(|c| &c.column_name == column) {
+            continue;
+        }
+
+        drop_columns.push(PlannedStep {
+            step: MigrationStep {
+                action: MigrationAction::DropColumn,
+                table_name: table_name.clone(),
+                description: format!("Drop column {}.{} and its data", table_name, column),
+                sql: Some(format!("ALTER TABLE \"{}\" DROP COLUMN IF EXISTS \"{}\"", pg_table_name, column)),
+                destructive: true,
+            },
+            op: StepOp::DropColumn(target_col.clone()),
+        });
+    }
+
+    Ok(())
+}
+
+/// Execute one planned step against the target project
+async fn apply_step(
+    txn: &DatabaseTransaction,
+    target: &projects::Model,
+    planned: PlannedStep,
+) -> AppResult<()> {
+    let PlannedStep { step, op } = planned;
+    let failed = |e: DbErr| AppError::BadRequest(format!("Failed to {}: {}", step.description.to_lowercase(), e));
+
+    if let StepOp::CreateTable(req) = op {
+        create_table_for_project(txn, target, req).await?;
+        return Ok(());
+    }
+
+    if let Some(ref sql) = step.sql {
+        txn.execute(Statement::from_string(DatabaseBackend::Postgres, sql.clone()))
+            .await
+            .map_err(failed)?;
+    }
+
+    match op {
+        StepOp::CreateTable(_) | StepOp::Constraint => {}
+        StepOp::DropTable(table) => {
+            table.delete(txn).await?;
+        }
+        StepOp::AddColumn { table_id, column } => {
+            let next_order = project_columns::Entity::find()
+                .filter(project_columns::Column::ProjectTableId.eq(table_id))
+                .order_by_desc(project_columns::Column::ColumnOrder)
+                .one(txn)
+                .await?
+                .map(|c| c.column_order + 1)
+                .unwrap_or(0);
+
+            project_columns::ActiveModel {
+                id: Set(Uuid::new_v4()),
+                project_table_id: Set(table_id),
+                column_name: Set(column.column_name),
+                display_name: Set(column.display_name),
+                data_type: Set(column.data_type),
+                is_nullable: Set(column.is_nullable),
+                is_primary_key: Set(column.is_primary_key),
+                is_unique: Set(column.is_unique),
+                default_value: Set(column.default_value),
+                column_order: Set(next_order),
+                created_at: Set(Some(chrono::Utc::now().naive_utc())),
+            }
+            .insert(txn)
+            .await?;
+        }
+        StepOp::DropColumn(column) => {
+            column.delete(txn).await?;
+        }
+        StepOp::SyncColumn { target, source } => {
+            let mut column: project_columns::ActiveModel = target.into();
+            column.display_name = Set(source.display_name);
+            column.data_type = Set(source.data_type);
+            column.is_nullable = Set(source.is_nullable);
+            column.is_primary_key = Set(source.is_primary_key);
+            column.is_unique = Set(source.is_unique);
+            column.default_value = Set(source.default_value);
+            column.update(txn).await?;
+        }
+        StepOp::SyncTable { target, source } => {
+            let mut table: project_tables::ActiveModel = target.into();
+            table.display_name = Set(source.display_name);
+            table.description = Set(source.description);
+            table.updated_at = Set(Some(chrono::Utc::now().naive_utc()));
+            table.update(txn).await?;
+        }
+    }
+
+    Ok(())
+}
+
+fn diff_response(
+    source: &projects::Model,
+    target: &projects::Model,
+    planned: &[PlannedStep],
+) -> SchemaDiffResponse {
+    let steps: Vec<MigrationStep> = planned.iter().map(|p| p.step.clone()).collect();
+
+    // The fingerprint covers everything the reviewer saw in the plan. The JSON form of
+    // the steps is canonical (fields in declaration order), so it survives upgrades.
+    let canonical = serde_json::to_vec(&steps).unwrap_or_default();
+
+    SchemaDiffResponse {
+        source_project: source.slug.clone(),
+        target_project: target.slug.clone(),
+        has_destructive_changes: steps.iter().any(|s| s.destructive),
+        fingerprint: hex::encode(Sha256::digest(&canonical)),
+        steps,
+        applied: false,
+    }
+}
+
+/// Constraints CREATE TABLE produces for a table built from the source metadata
+fn implied_constraints(table: &TableSnapshot) -> Vec<ConstraintSnapshot> {
+    let mut constraints = Vec::new();
+
+    for column in &table.columns {
+        if column.is_primary_key.unwrap_or(false) {
+            constraints.push(ConstraintSnapshot {
+                name: format!("{}{}_pkey", PREFIX_PLACEHOLDER, table.table.table_name),
+                kind: "p".to_string(),
+                definition: format!("PRIMARY KEY ({})", column.column_name),
+            });
+        } else if column.is_unique.unwrap_or(false) {
+            constraints.push(ConstraintSnapshot {
+                name: format!("{}{}_{}_key", PREFIX_PLACEHOLDER, table.table.table_name, column.column_name),
+                kind: "u".to_string(),
+                definition: format!("UNIQUE ({})", column.column_name),
+            });
+        }
+    }
+
+    constraints
+}
+
+/// Constraints are matched on what they enforce, not on their generated names
+fn same_constraint(a: &ConstraintSnapshot, b: &ConstraintSnapshot) -> bool {
+    a.kind == b.kind && a.definition == b.definition
+}
+
+fn column_data_type(column: &project_columns::Model) -> AppResult<ColumnDataType> {
+    serde_json::from_value(serde_json::Value::String(column.data_type.clone())).map_err(|_| {
+        AppError::InternalServerError(format!(
+            "Unknown data type {} for column {}",
+            column.data_type, column.column_name
+        ))
+    })
+}
+
+fn column_definition(column: &project_columns::Model) -> AppResult<ColumnDefinition> {
+    Ok(ColumnDefinition {
+        name: column.column_name.clone(),
+        display_name: column.display_name.clone(),
+        data_type: column_data_type(column)?,
+        is_nullable: column.is_nullable.unwrap_or(true),
+        is_primary_key: column.is_primary_key.unwrap_or(false),
+        is_unique: column.is_unique.unwrap_or(false),
+        default_value: column.default_value.clone(),
+    })
+}
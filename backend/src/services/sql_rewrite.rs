@@ -0,0 +1,433 @@
+use std::collections::{HashMap, HashSet};
+use std::ops::ControlFlow;
+use sea_orm::*;
+use database::entities::{project_functions, project_tables, project_views};
+use shared::models::FunctionLanguage;
+use sqlparser::ast::{
+    Expr, Ident, ObjectName, Query, SelectItem, SetExpr, TableFactor, VisitMut, VisitorMut, With,
+};
+use crate::error::AppResult;
+use crate::services::table_service::physical_table_name;
+use uuid::Uuid;
+
+/// Built-in functions that user SQL may call.
+///
+/// Everything else is refused: some built-ins run SQL passed in as a string
+/// (`query_to_xml`), which the relation checks never see, and others reach the
+/// server itself or objects of other projects.
+const BUILTIN_FUNCTIONS: &[&str] = &[
+    // Aggregates
+    "array_agg", "avg", "bit_and", "bit_or", "bool_and", "bool_or", "corr", "count", "covar_pop",
+    "covar_samp", "every", "json_agg", "json_object_agg", "jsonb_agg", "jsonb_object_agg", "max", "min",
+    "mode", "percentile_cont", "percentile_disc", "stddev", "stddev_pop", "stddev_samp", "string_agg",
+    "sum", "var_pop", "var_samp", "variance",
+    // Window functions
+    "cume_dist", "dense_rank", "first_value", "lag", "last_value", "lead", "nth_value", "ntile",
+    "percent_rank", "rank", "row_number",
+    // Conditionals and arrays built from subqueries
+    "array", "coalesce", "greatest", "least", "nullif",
+    // Math
+    "abs", "acos", "asin", "atan", "atan2", "cbrt", "ceil", "ceiling", "cos", "cot", "degrees", "div",
+    "exp", "floor", "gcd", "lcm", "ln", "log", "log10", "mod", "pi", "power", "radians", "random",
+    "round", "scale", "sign", "sin", "sqrt", "tan", "trunc", "width_bucket",
+    // Strings
+    "ascii", "btrim", "char_length", "character_length", "chr", "concat", "concat_ws", "decode",
+    "encode", "format", "initcap", "left", "length", "lower", "lpad", "ltrim", "md5", "octet_length",
+    "overlay", "position", "quote_ident", "quote_literal", "quote_nullable", "regexp_count",
+    "regexp_instr", "regexp_like", "regexp_match", "regexp_matches", "regexp_replace",
+    "regexp_split_to_array", "regexp_split_to_table", "regexp_substr", "repeat", "replace", "reverse",
+    "right", "rpad", "rtrim", "sha224", "sha256", "sha384", "sha512", "split_part", "starts_with",
+    "string_to_array", "string_to_table", "strpos", "substr", "substring", "to_hex", "translate", "trim",
+    "upper",
+    // Formatting
+    "to_char", "to_date", "to_number", "to_timestamp",
+    // Dates and times
+    "age", "clock_timestamp", "current_date", "current_time", "current_timestamp", "date_bin",
+    "date_part", "date_trunc", "extract", "isfinite", "justify_days", "justify_hours",
+    "justify_interval", "localtime", "localtimestamp", "make_date", "make_interval", "make_time",
+    "make_timestamp", "make_timestamptz", "now", "statement_timestamp", "timezone",
+    "transaction_timestamp",
+    // JSON
+    "array_to_json", "json_array_elements", "json_array_elements_text", "json_array_length",
+    "json_build_array", "json_build_object", "json_each", "json_each_text", "json_extract_path",
+    "json_extract_path_text", "json_object", "json_object_keys", "json_populate_record",
+    "json_populate_recordset", "json_strip_nulls", "json_to_record", "json_to_recordset", "json_typeof",
+    "jsonb_array_elements", "jsonb_array_elements_text", "jsonb_array_length", "jsonb_build_array",
+    "jsonb_build_object", "jsonb_each", "jsonb_each_text", "jsonb_extract_path",
+    "jsonb_extract_path_text", "jsonb_insert", "jsonb_object", "jsonb_object_keys", "jsonb_path_exists",
+    "jsonb_path_match", "jsonb_path_query", "jsonb_path_query_array", "jsonb_path_query_first",
+    "jsonb_populate_record", "jsonb_populate_recordset", "jsonb_pretty", "jsonb_set", "jsonb_set_lax",
+    "jsonb_strip_nulls", "jsonb_to_record", "jsonb_to_recordset", "jsonb_typeof", "row_to_json",
+    "to_json", "to_jsonb",
+    // Arrays and sets
+    "array_append", "array_cat", "array_dims", "array_fill", "array_length", "array_lower",
+    "array_ndims", "array_position", "array_positions", "array_prepend", "array_remove",
+    "array_replace", "array_to_string", "array_upper", "cardinality", "generate_series",
+    "generate_subscripts", "trim_array", "unnest",
+    // Full-text search
+    "phraseto_tsquery", "plainto_tsquery", "setweight", "to_tsquery", "to_tsvector", "ts_headline",
+    "ts_rank", "ts_rank_cd", "websearch_to_tsquery",
+    // Identifiers, types and request claims
+    "auth_uid", "current_setting", "gen_random_uuid", "pg_typeof",
+];
+
+/// Names of a project's tables and views mapped to their physical relation names
+pub(crate) async fn project_relations<C: ConnectionTrait>(
+    db: &C,
+    project_id: Uuid,
+) -> AppResult<HashMap<String, String>> {
+    let tables = project_tables::Entity::find()
+        .filter(project_tables::Column::ProjectId.eq(project_id))
+        .all(db)
+        .await?;
+
+    let views = project_views::Entity::find()
+        .filter(project_views::Column::ProjectId.eq(project_id))
+        .all(db)
+        .await?;
+
+    Ok(tables
+        .into_iter()
+        .map(|t| t.table_name)
+        .chain(views.into_iter().map(|v| v.view_name))
+        .map(|name| {
+            let physical = physical_table_name(&project_id, &name);
+            (name, physical)
+        })
+        .collect())
+}
+
+/// Map a project's functions written in `languages` from their logical names to their
+/// physical names; they are the only project functions user SQL may call
+pub(crate) async fn project_functions<C: ConnectionTrait>(
+    db: &C,
+    project_id: Uuid,
+    languages: &[FunctionLanguage],
+) -> AppResult<HashMap<String, String>> {
+    let functions = project_functions::Entity::find()
+        .filter(project_functions::Column::ProjectId.eq(project_id))
+        .filter(project_functions::Column::Language.is_in(languages.iter().map(FunctionLanguage::as_str)))
+        .all(db)
+        .await?;
+
+    Ok(functions
+        .into_iter()
+        .map(|f| {
+            let physical = physical_table_name(&project_id, &f.function_name);
+            (f.function_name, physical)
+        })
+        .collect())
+}
+
+/// Replace a project's physical relation names in `text` with their logical names,
+/// so that Postgres messages and plans read the way the user wrote the query
+pub(crate) fn logical_names(text: &str, project_id: &Uuid) -> String {
+    text.replace(&physical_table_name(project_id, ""), "")
+}
+
+/// Rewrite every relation referenced by `node` (a statement or expression) to the physical name it resolves to.
+///
+/// `relations` maps the names a statement may use onto physical relation names.
+/// Names of CTEs in scope are left alone; any other relation is rejected with a user-facing message.
+/// Function calls must name one of `functions`, which are rewritten the same way,
+/// or an allowed built-in function.
+pub(crate) fn resolve_relations<T: VisitMut>(
+    node: &mut T,
+    relations: &HashMap<String, String>,
+    functions: &HashMap<String, String>,
+) -> Result<(), String> {
+    let mut resolver = RelationResolver {
+        relations,
+        functions,
+        cte_scopes: Vec::new(),
+        hidden_with: Vec::new(),
+        in_function: false,
+    };
+
+    match node.visit(&mut resolver) {
+        ControlFlow::Continue(()) => Ok(()),
+        ControlFlow::Break(message) => Err(message),
+    }
+}
+
+/// Postgres folds unquoted identifiers to lower case
+fn ident_key(ident: &Ident) -> String {
+    match ident.quote_style {
+        Some(_) => ident.value.clone(),
+        None => ident.value.to_lowercase(),
+    }
+}
+
+/// Look up the physical name `ident` refers to.
+///
+/// Quoted identifiers must match exactly. Unquoted ones are folded to lower case like
+/// Postgres does; names created with capitals in the table editor still match them
+/// when no other name differs only in case.
+fn lookup<'a>(names: &'a HashMap<String, String>, ident: &Ident) -> Result<Option<&'a String>, String> {
+    let key = ident_key(ident);
+    if let Some(physical) = names.get(&key) {
+        return Ok(Some(physical));
+    }
+    if ident.quote_style.is_some() {
+        return Ok(None);
+    }
+
+    let mut matches = names.iter().filter(|(name, _)| name.to_lowercase() == key);
+    match (matches.next(), matches.next()) {
+        (Some((_, physical)), None) => Ok(Some(physical)),
+        (Some(_), Some(_)) => Err(format!("{} is ambiguous; quote the name to pick one", ident)),
+        (None, _) => Ok(None),
+    }
+}
+
+struct RelationResolver<'a> {
+    relations: &'a HashMap<String, String>,
+    functions: &'a HashMap<String, String>,
+    /// CTE names visible at the current point, one scope per enclosing query
+    cte_scopes: Vec<HashSet<String>>,
+    /// `WITH` clauses taken out of the queries being visited, put back once their bodies are done
+    hidden_with: Vec<Option<With>>,
+    in_function: bool,
+}
+
+impl RelationResolver<'_> {
+    fn is_cte(&self, key: &str) -> bool {
+        self.cte_scopes.iter().any(|scope| scope.contains(key))
+    }
+
+    /// Visit the CTEs of a `WITH` clause, each seeing only the names Postgres lets it see:
+    /// the CTEs before it, or all of them (itself included) in a `WITH RECURSIVE`.
+    fn visit_with(&mut self, with: &mut With) -> ControlFlow<String> {
+        let names: Vec<String> = with.cte_tables.iter().map(|cte| ident_key(&cte.alias.name)).collect();
+
+        for (index, cte) in with.cte_tables.iter_mut().enumerate() {
+            let visible = if with.recursive { &names[..] } else { &names[..index] };
+            self.cte_scopes.push(visible.iter().cloned().collect());
+            let result = cte.query.visit(self);
+            self.cte_scopes.pop();
+            result?;
+        }
+
+        ControlFlow::Continue(())
+    }
+
+    fn resolve_function(&self, name: &mut ObjectName) -> ControlFlow<String> {
+        let [ident] = name.0.as_slice() else {
+            return ControlFlow::Break(format!("Function {} is outside of this project", name));
+        };
+
+        match lookup(self.functions, ident) {
+            Ok(Some(physical)) => {
+                name.0 = vec![Ident::with_quote('"', physical.clone())];
+                ControlFlow::Continue(())
+            }
+            Ok(None) if BUILTIN_FUNCTIONS.contains(&ident_key(ident).as_str()) => ControlFlow::Continue(()),
+            Ok(None) => ControlFlow::Break(format!("Function {} is not allowed", name)),
+            Err(message) => ControlFlow::Break(message),
+        }
+    }
+
+    /// Keep the logical name as the column name of a bare project function call;
+    /// Postgres would otherwise name the column after the physical function
+    fn name_function_columns(&self, query: &mut Query) {
+        let mut body = query.body.as_mut();
+        let select = loop {
+            match body {
+                SetExpr::Select(select) => break select,
+                SetExpr::SetOperation { left, .. } => body = left.as_mut(),
+                SetExpr::Query(query) => body = query.body.as_mut(),
+                _ => return,
+            }
+        };
+
+        for item in &mut select.projection {
+            let SelectItem::UnnamedExpr(Expr::Function(function)) = item else {
+                continue;
+            };
+            let [ident] = function.name.0.as_slice() else {
+                continue;
+            };
+
+            let column = ident_key(ident);
+            if let Ok(Some(physical)) = lookup(self.functions, ident)
+                && *physical != column
+            {
+                *item = SelectItem::ExprWithAlias {
+                    expr: Expr::Function(function.clone()),
+                    alias: Ident::with_quote('"', column),
+                };
+            }
+        }
+    }
+}
+
+impl VisitorMut for RelationResolver<'_> {
+    type Break = String;
+
+    fn pre_visit_query(&mut self, query: &mut Query) -> ControlFlow<Self::Break> {
+        // The CTEs are visited here with their own scopes; the rest of the query sees them all
+        let mut with = query.with.take();
+        let mut scope = HashSet::new();
+        if let Some(with) = with.as_mut() {
+            self.visit_with(with)?;
+            scope.extend(with.cte_tables.iter().map(|cte| ident_key(&cte.alias.name)));
+        }
+        self.cte_scopes.push(scope);
+        self.hidden_with.push(with);
+
+        self.name_function_columns(query);
+        ControlFlow::Continue(())
+    }
+
+    fn post_visit_query(&mut self, query: &mut Query) -> ControlFlow<Self::Break> {
+        self.cte_scopes.pop();
+        query.with = self.hidden_with.pop().flatten();
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_table_factor(&mut self, table_factor: &mut TableFactor) -> ControlFlow<Self::Break> {
+        // Table-valued functions such as generate_series() are not relations
+        self.in_function = matches!(table_factor, TableFactor::Table { args: Some(_), .. });
+
+        match table_factor {
+            TableFactor::Table { name, args: Some(_), .. } | TableFactor::Function { name, .. } => {
+                self.resolve_function(name)
+            }
+            _ => ControlFlow::Continue(()),
+        }
+    }
+
+    fn pre_visit_expr(&mut self, expr: &mut Expr) -> ControlFlow<Self::Break> {
+        match expr {
+            Expr::Function(function) => self.resolve_function(&mut function.name),
+            _ => ControlFlow::Continue(()),
+        }
+    }
+
+    fn post_visit_table_factor(&mut self, _table_factor: &mut TableFactor) -> ControlFlow<Self::Break> {
+        self.in_function = false;
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_relation(&mut self, relation: &mut ObjectName) -> ControlFlow<Self::Break> {
+        if self.in_function {
+            return ControlFlow::Continue(());
+        }
+
+        let [ident] = relation.0.as_slice() else {
+            return ControlFlow::Break(format!("Relation {} is outside of this project", relation));
+        };
+
+        if self.is_cte(&ident_key(ident)) {
+            return ControlFlow::Continue(());
+        }
+
+        match lookup(self.relations, ident) {
+            Ok(Some(physical)) => {
+                relation.0 = vec![Ident::with_quote('"', physical.clone())];
+                ControlFlow::Continue(())
+            }
+            Ok(None) => ControlFlow::Break(format!("Relation {} does not exist in this project", relation)),
+            Err(message) => ControlFlow::Break(message),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlparser::dialect::PostgreSqlDialect;
+    use sqlparser::parser::Parser;
+
+    fn names(pairs: &[(&str, &str)]) -> HashMap<String, String> {
+        pairs.iter().map(|&(name, physical)| (String::from(name), String::from(physical))).collect()
+    }
+
+    fn resolve(sql: &str) -> Result<String, String> {
+        let relations = names(&[("notes", "project_1_notes"), ("Tags", "project_1_Tags")]);
+        let functions = names(&[("my_fn", "project_1_my_fn")]);
+
+        let mut statement = Parser::parse_sql(&PostgreSqlDialect {}, sql).unwrap().remove(0);
+        resolve_relations(&mut statement, &relations, &functions)?;
+        Ok(statement.to_string())
+    }
+
+    #[test]
+    fn rewrites_relations_to_physical_names() {
+        assert_eq!(resolve("SELECT * FROM notes").unwrap(), r#"SELECT * FROM "project_1_notes""#);
+        assert_eq!(
+            resolve("WITH n AS (SELECT 1) SELECT * FROM n, Notes").unwrap(),
+            r#"WITH n AS (SELECT 1) SELECT * FROM n, "project_1_notes""#
+        );
+    }
+
+    #[test]
+    fn ctes_only_shadow_relations_in_their_scope() {
+        for sql in [
+            "SELECT (WITH users AS (SELECT 1) SELECT 1), email, password_hash FROM users",
+            "WITH x AS (SELECT (WITH users AS (SELECT 1) SELECT 1)) UPDATE users SET role = 'admin'",
+            "WITH users AS (SELECT * FROM users) SELECT * FROM users",
+            "WITH a AS (SELECT * FROM b), b AS (SELECT 1) SELECT * FROM a",
+            "SELECT * FROM (WITH users AS (SELECT 1) SELECT * FROM users) t, users",
+        ] {
+            assert!(resolve(sql).is_err(), "{} should be rejected", sql);
+        }
+
+        assert_eq!(
+            resolve("WITH a AS (SELECT 1), b AS (SELECT * FROM a) SELECT * FROM b").unwrap(),
+            "WITH a AS (SELECT 1), b AS (SELECT * FROM a) SELECT * FROM b"
+        );
+        assert_eq!(
+            resolve("WITH RECURSIVE n AS (SELECT 1 UNION ALL SELECT * FROM n) SELECT * FROM n, notes").unwrap(),
+            r#"WITH RECURSIVE n AS (SELECT 1 UNION ALL SELECT * FROM n) SELECT * FROM n, "project_1_notes""#
+        );
+        assert_eq!(
+            resolve("WITH notes AS (SELECT * FROM notes) SELECT * FROM notes").unwrap(),
+            r#"WITH notes AS (SELECT * FROM "project_1_notes") SELECT * FROM notes"#
+        );
+    }
+
+    #[test]
+    fn folds_unquoted_names_like_postgres() {
+        assert_eq!(resolve("SELECT * FROM TAGS").unwrap(), r#"SELECT * FROM "project_1_Tags""#);
+        assert_eq!(resolve(r#"SELECT * FROM "Tags""#).unwrap(), r#"SELECT * FROM "project_1_Tags""#);
+        assert!(resolve(r#"SELECT * FROM "NOTES""#).is_err());
+    }
+
+    #[test]
+    fn rejects_relations_outside_the_project() {
+        assert!(resolve("SELECT * FROM users").is_err());
+        assert!(resolve("SELECT * FROM public.project_1_notes").is_err());
+        assert!(resolve("SELECT * FROM pg_catalog.pg_authid").is_err());
+        assert!(resolve("SELECT (SELECT count(*) FROM project_2_notes)").is_err());
+    }
+
+    #[test]
+    fn rewrites_project_functions() {
+        assert_eq!(resolve("SELECT my_fn()").unwrap(), r#"SELECT "project_1_my_fn"() AS "my_fn""#);
+        assert_eq!(resolve("SELECT * FROM MY_FN()").unwrap(), r#"SELECT * FROM "project_1_my_fn"()"#);
+    }
+
+    #[test]
+    fn allows_built_in_functions() {
+        assert!(resolve("SELECT count(*), now(), coalesce(NULL, 1) FROM notes").is_ok());
+        assert!(resolve("SELECT * FROM generate_series(1, 3)").is_ok());
+        assert!(resolve("SELECT CURRENT_TIMESTAMP").is_ok());
+    }
+
+    #[test]
+    fn rejects_other_functions() {
+        for sql in [
+            "SELECT query_to_xml('SELECT * FROM users', true, false, '')",
+            "SELECT * FROM table_to_xml('users', true, false, '')",
+            "SELECT pg_read_file('/etc/passwd')",
+            "SELECT set_config('role', 'postgres', false)",
+            "SELECT project_2_other_fn()",
+            "SELECT * FROM project_2_other_fn()",
+            "SELECT pg_catalog.now()",
+            "SELECT * FROM notes WHERE id IN (SELECT dblink('', ''))",
+        ] {
+            assert!(resolve(sql).is_err(), "{} should be rejected", sql);
+        }
+    }
+}
@@ -0,0 +1,872 @@
+use std::collections::HashMap;
+use std::ops::ControlFlow;
+use std::sync::Mutex;
+use sea_orm::*;
+use database::entities::{projects, query_history};
+use shared::models::{
+    ApiKeyKind, CancelQueryResponse, ExecuteSqlRequest, ExecuteSqlResponse, ExplainPlan, FunctionLanguage, PlanNode,
+    QueryHistoryEntry, SqlColumn, SqlStatementKind, SqlStatementResult,
+};
+use sqlx::postgres::PgConnection;
+use sqlx::Describe;
+use sqlx::{Column, Connection, Executor, Row, TypeInfo};
+use sqlparser::ast::{
+    CastKind, DataType, Expr, Query, SetExpr, Visit, VisitMut, Visitor, VisitorMut,
+};
+use sqlparser::dialect::PostgreSqlDialect;
+use sqlparser::parser::Parser;
+use sqlparser::tokenizer::Token;
+use crate::error::{AppError, AppResult};
+use crate::services::authorization_service::{authorize_project, Capability};
+use crate::services::api_key_service::Principal;
+use crate::services::project_service::project_role;
+use crate::services::sql_rewrite::{logical_names, project_functions, project_relations, resolve_relations};
+use serde_json::Value as JsonValue;
+use uuid::Uuid;
+
+/// Number of history entries listed when the client does not ask for a limit
+const DEFAULT_HISTORY_LIMIT: u64 = 50;
+const MAX_HISTORY_LIMIT: u64 = 500;
+
+/// SQL editor statements that are currently running, keyed by query ID
+#[derive(Default)]
+pub struct QueryRegistry {
+    running: Mutex<HashMap<String, RunningQuery>>,
+}
+
+struct RunningQuery {
+    project_id: Uuid,
+    /// Only the user who started a query can cancel it
+    user_id: Uuid,
+    backend_pid: i32,
+}
+
+impl QueryRegistry {
+    fn register(&self, query_id: &str, query: RunningQuery) -> AppResult<RunningQueryGuard<'_>> {
+        let mut running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+
+        if running.contains_key(query_id) {
+            return Err(AppError::BadRequest("A query with this ID is already running".to_string()));
+        }
+        running.insert(query_id.to_string(), query);
+
+        Ok(RunningQueryGuard {
+            registry: self,
+            query_id: query_id.to_string(),
+        })
+    }
+
+    fn backend_pid(&self, query_id: &str, project_id: Uuid, user_id: Uuid) -> Option<i32> {
+        let running = self.running.lock().unwrap_or_else(|e| e.into_inner());
+
+        running
+            .get(query_id)
+            .filter(|query| query.project_id == project_id && query.user_id == user_id)
+            .map(|query| query.backend_pid)
+    }
+}
+
+/// Removes a query from the registry once it stops running, however execution ends
+struct RunningQueryGuard<'a> {
+    registry: &'a QueryRegistry,
+    query_id: String,
+}
+
+impl Drop for RunningQueryGuard<'_> {
+    fn drop(&mut self) {
+        let mut running = self.registry.running.lock().unwrap_or_else(|e| e.into_inner());
+        running.remove(&self.query_id);
+    }
+}
+
+/// Execute a SQL script from the SQL editor within project context.
+///
+/// Every statement is parsed and classified before anything runs: only queries and
+/// data changes on the project's own tables and views are allowed, and `read_only`
+/// requests run inside read-only transactions. Statements run in order, each in its
+/// own transaction unless `transaction` asks for a single one that is rolled back on
+/// the first failure. Statements are limited by the project's statement timeout and
+/// the script can be cancelled through its query ID while it runs.
+pub async fn execute_sql(
+    db: &DatabaseConnection,
+    queries: &QueryRegistry,
+    principal: &Principal,
+    project_slug: &str,
+    req: &ExecuteSqlRequest,
+) -> AppResult<ExecuteSqlResponse> {
+    let (project, user_id) = authorize_script(db, principal, project_slug, req.read_only).await?;
+    run_script(db, queries, user_id, &project, req, &[], None).await
+}
+
+/// Find the project a script runs in and the user whose history records it
+pub(crate) async fn authorize_script(
+    db: &DatabaseConnection,
+    principal: &Principal,
+    project_slug: &str,
+    read_only: bool,
+) -> AppResult<(projects::Model, Uuid)> {
+    if let Principal::ApiKey { kind: ApiKeyKind::Anon, .. } = principal {
+        return Err(AppError::Forbidden("Anon keys cannot execute SQL; use a service key".to_string()));
+    }
+
+    // Read-only scripts only need read access; anything else may change data
+    let capability = if read_only { Capability::Read } else { Capability::WriteData };
+    let project = principal.find_project(db, project_slug, capability).await?;
+
+    // Scripts run with a service key are recorded in the project owner's history
+    let user_id = match principal {
+        Principal::User(claims) => Uuid::parse_str(&claims.sub)
+            .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?,
+        Principal::ApiKey { .. } => project.owner_id,
+    };
+
+    Ok((project, user_id))
+}
+
+/// Value bound to a `$n` placeholder, sent as text and cast to its declared type
+pub(crate) struct BoundParameter {
+    pub data_type: DataType,
+    pub value: Option<String>,
+}
+
+/// Run a script for a user of a verified project and record it in their query history
+pub(crate) async fn run_script(
+    db: &DatabaseConnection,
+    queries: &QueryRegistry,
+    user_id: Uuid,
+    project: &projects::Model,
+    req: &ExecuteSqlRequest,
+    parameters: &[BoundParameter],
+    saved_query_id: Option<Uuid>,
+) -> AppResult<ExecuteSqlResponse> {
+    let start_time = std::time::Instant::now();
+
+    // Clients pick the ID up front so they can cancel before the response arrives
+    let query_id = match &req.query_id {
+        Some(id) if id.is_empty() || id.len() > 64 => {
+            return Err(AppError::BadRequest("Query ID must be 1 to 64 characters".to_string()))
+        }
+        Some(id) => id.clone(),
+        None => Uuid::new_v4().to_string(),
+    };
+
+    let parameter_types: Vec<DataType> = parameters.iter().map(|param| param.data_type.clone()).collect();
+    let script = prepare_script(db, project, &req.query, &parameter_types, req.read_only).await?;
+    let values: Vec<Option<String>> = parameters.iter().map(|param| param.value.clone()).collect();
+
+    let explained = script
+        .iter()
+        .position(|statement| matches!(statement.statement, sqlparser::ast::Statement::Explain { .. }));
+    if let (true, Some(index)) = (req.explain, explained) {
+        return Err(AppError::BadRequest(format!(
+            "Statement {} is already an EXPLAIN and cannot run in explain mode",
+            index + 1
+        )));
+    }
+
+    // Explained writes really run, so explain mode always rolls its single transaction back
+    let single_transaction = req.transaction || req.explain;
+
+    // The script runs on one connection so its backend can be cancelled
+    let mut conn = db
+        .get_postgres_connection_pool()
+        .acquire()
+        .await
+        .map_err(|e| AppError::Database(DbErr::Conn(RuntimeErr::SqlxError(e))))?;
+
+    let backend_pid: i32 = sqlx::query_scalar("SELECT pg_backend_pid()")
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(|e| AppError::Database(DbErr::Query(RuntimeErr::SqlxError(e))))?;
+
+    let guard = queries.register(&query_id, RunningQuery {
+        project_id: project.id,
+        user_id,
+        backend_pid,
+    })?;
+
+    let settings = ScriptSettings {
+        read_only: req.read_only,
+        explain: req.explain,
+        statement_timeout_ms: project.statement_timeout_ms,
+        role: project_role(&project.id),
+    };
+    let outcome = if single_transaction {
+        run_in_transaction(&mut conn, &script, &values, &settings).await
+    } else {
+        run_each_committed(&mut conn, &script, &values, &settings).await
+    };
+
+    // Unregister before the connection goes back to the pool, so a late cancel cannot hit another query
+    drop(guard);
+
+    let outcome = outcome.map(|mut results| {
+        for plan in results.iter_mut().filter_map(|result| result.plan.as_mut()) {
+            logical_plan_names(&mut plan.root, &project.id);
+        }
+        results
+    });
+
+    let outcome = outcome.map_err(|(index, e)| {
+        let message = logical_names(&execution_error(e, project.statement_timeout_ms), &project.id);
+        match (script.len() > 1, single_transaction) {
+            (false, _) => message,
+            (true, true) => format!(
+                "Statement {} failed and the transaction was rolled back: {}",
+                index + 1,
+                message
+            ),
+            (true, false) => format!(
+                "Statement {} failed; {} earlier statement(s) were committed: {}",
+                index + 1,
+                index,
+                message
+            ),
+        }
+    });
+
+    let execution_time_ms = start_time.elapsed().as_millis();
+
+    let history = query_history::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        project_id: Set(project.id),
+        user_id: Set(user_id),
+        saved_query_id: Set(saved_query_id),
+        query: Set(req.query.clone()),
+        statement_count: Set(script.len() as i32),
+        row_count: Set(outcome.as_ref().ok().map(|results| {
+            results.iter().filter_map(|result| result.rows_affected).sum::<u64>() as i64
+        })),
+        execution_time_ms: Set(execution_time_ms as i64),
+        error: Set(outcome.as_ref().err().cloned()),
+        executed_at: Set(Some(chrono::Utc::now().naive_utc())),
+    };
+
+    // A failure to record history should not hide the script's own outcome
+    if let Err(e) = history.insert(db).await {
+        tracing::warn!("Failed to record query history: {}", e);
+    }
+
+    Ok(ExecuteSqlResponse {
+        query_id,
+        results: outcome.map_err(AppError::BadRequest)?,
+        transaction: single_transaction,
+        execution_time_ms,
+    })
+}
+
+/// Parse, classify and bind a script without running it, rejecting it as a whole
+/// if any statement is not allowed.
+///
+/// `$n` placeholders are cast to the n-th declared parameter type and renumbered
+/// per statement, since each statement is sent to Postgres on its own.
+pub(crate) async fn prepare_script(
+    db: &DatabaseConnection,
+    project: &projects::Model,
+    sql: &str,
+    parameter_types: &[DataType],
+    read_only: bool,
+) -> AppResult<Vec<ScriptStatement>> {
+    let statements = Parser::parse_sql(&PostgreSqlDialect {}, sql)
+        .map_err(|e| AppError::BadRequest(format!("SQL parse error: {}", e)))?;
+
+    if statements.is_empty() {
+        return Err(AppError::BadRequest("Query is empty".to_string()));
+    }
+
+    // Queries refer to the project's tables, views and functions by their logical names;
+    // physical names keep working for scripts written before they could
+    let mut relations = project_relations(db, project.id).await?;
+    let physical: Vec<String> = relations.values().cloned().collect();
+    relations.extend(physical.into_iter().map(|name| (name.clone(), name)));
+    // Scripts run as the project's role, so they may also call PL/pgSQL functions
+    let mut functions = project_functions(db, project.id, &[FunctionLanguage::Sql, FunctionLanguage::Plpgsql]).await?;
+    let physical: Vec<String> = functions.values().cloned().collect();
+    functions.extend(physical.into_iter().map(|name| (name.clone(), name)));
+
+    let script_error = |index: usize, message: String| {
+        if statements.len() > 1 {
+            AppError::BadRequest(format!("Statement {}: {}", index + 1, message))
+        } else {
+            AppError::BadRequest(message)
+        }
+    };
+
+    let mut script = Vec::with_capacity(statements.len());
+    for (index, mut statement) in statements.iter().cloned().enumerate() {
+        let text = statement.to_string();
+
+        let statement_kind = classify_statement(&statement).map_err(|e| script_error(index, e))?;
+
+        if read_only && statement_kind == SqlStatementKind::Write {
+            return Err(script_error(
+                index,
+                "Statement modifies data and cannot run in read-only mode".to_string(),
+            ));
+        }
+
+        resolve_relations(&mut statement, &relations, &functions).map_err(|e| script_error(index, e))?;
+
+        let mut binder = PlaceholderBinder {
+            parameter_types,
+            bound: Vec::new(),
+        };
+        if let ControlFlow::Break(message) = VisitMut::visit(&mut statement, &mut binder) {
+            return Err(script_error(index, message));
+        }
+
+        script.push(ScriptStatement {
+            text,
+            statement,
+            statement_kind,
+            parameters: binder.bound,
+        });
+    }
+
+    Ok(script)
+}
+
+/// Parse a declared parameter type such as `integer` or `timestamp with time zone`
+pub(crate) fn parse_parameter_type(data_type: &str) -> Result<DataType, String> {
+    let invalid = || format!("Invalid parameter type: {}", data_type);
+
+    let mut parser = Parser::new(&PostgreSqlDialect {})
+        .try_with_sql(data_type)
+        .map_err(|_| invalid())?;
+
+    let parsed = parser.parse_data_type().map_err(|_| invalid())?;
+
+    if parser.peek_token().token != Token::EOF {
+        return Err(invalid());
+    }
+    Ok(parsed)
+}
+
+/// List the current user's most recent SQL editor executions in a project
+pub async fn list_query_history(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    limit: Option<u64>,
+) -> AppResult<Vec<QueryHistoryEntry>> {
+    let access = authorize_project(db, user_id, project_slug, Capability::Read).await?;
+
+    let entries = query_history::Entity::find()
+        .filter(query_history::Column::ProjectId.eq(access.project.id))
+        .filter(query_history::Column::UserId.eq(access.user_id))
+        .order_by_desc(query_history::Column::ExecutedAt)
+        .limit(limit.unwrap_or(DEFAULT_HISTORY_LIMIT).min(MAX_HISTORY_LIMIT))
+        .all(db)
+        .await?;
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| QueryHistoryEntry {
+            id: entry.id.to_string(),
+            query: entry.query,
+            saved_query_id: entry.saved_query_id.map(|id| id.to_string()),
+            statement_count: entry.statement_count,
+            row_count: entry.row_count,
+            execution_time_ms: entry.execution_time_ms,
+            error: entry.error,
+            executed_at: entry.executed_at.map(|dt| dt.to_string()).unwrap_or_default(),
+        })
+        .collect())
+}
+
+/// Delete the current user's SQL editor history in a project
+pub async fn clear_query_history(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+) -> AppResult<()> {
+    let access = authorize_project(db, user_id, project_slug, Capability::Read).await?;
+
+    query_history::Entity::delete_many()
+        .filter(query_history::Column::ProjectId.eq(access.project.id))
+        .filter(query_history::Column::UserId.eq(access.user_id))
+        .exec(db)
+        .await?;
+
+    Ok(())
+}
+
+/// Cancel a running SQL editor statement by its query ID
+pub async fn cancel_sql_query(
+    db: &DatabaseConnection,
+    queries: &QueryRegistry,
+    user_id: &str,
+    project_slug: &str,
+    query_id: &str,
+) -> AppResult<CancelQueryResponse> {
+    let access = authorize_project(db, user_id, project_slug, Capability::Read).await?;
+
+    let backend_pid = queries
+        .backend_pid(query_id, access.project.id, access.user_id)
+        .ok_or_else(|| AppError::NotFound("Query is not running".to_string()))?;
+
+    let cancelled = db
+        .query_one(Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "SELECT pg_cancel_backend($1) AS cancelled",
+            [backend_pid.into()],
+        ))
+        .await?
+        .map(|row| row.try_get::<bool>("", "cancelled"))
+        .transpose()?
+        .unwrap_or(false);
+
+    Ok(CancelQueryResponse {
+        query_id: query_id.to_string(),
+        cancelled,
+    })
+}
+
+/// Name given to the statement being run when its rows are converted to JSON
+const RESULT_RELATION: &str = "ferrisbase_result";
+
+struct ScriptSettings {
+    read_only: bool,
+    explain: bool,
+    statement_timeout_ms: i32,
+    /// The project's own role, which can only reach the project's objects
+    role: String,
+}
+
+/// A checked statement of a script, with the parameters it binds in placeholder order
+pub(crate) struct ScriptStatement {
+    /// The statement as the user wrote it, before relations were resolved
+    text: String,
+    statement: sqlparser::ast::Statement,
+    statement_kind: SqlStatementKind,
+    parameters: Vec<usize>,
+}
+
+impl ScriptStatement {
+    fn bind_values(&self, values: &[Option<String>]) -> Vec<Option<String>> {
+        self.parameters.iter().map(|index| values[*index].clone()).collect()
+    }
+}
+
+/// Run every statement in one transaction, rolled back when a statement fails or in explain mode
+async fn run_in_transaction(
+    conn: &mut PgConnection,
+    script: &[ScriptStatement],
+    values: &[Option<String>],
+    settings: &ScriptSettings,
+) -> Result<Vec<SqlStatementResult>, (usize, sqlx::Error)> {
+    let mut txn = begin_script_transaction(conn, settings).await.map_err(|e| (0, e))?;
+
+    let mut results = Vec::with_capacity(script.len());
+    for (index, statement) in script.iter().enumerate() {
+        let values = statement.bind_values(values);
+        let result = if settings.explain {
+            explain_statement(&mut txn, statement, &values).await
+        } else {
+            run_statement(&mut txn, statement, &values).await
+        };
+        results.push(result.map_err(|e| (index, e))?);
+    }
+
+    if settings.explain {
+        txn.rollback().await.map_err(|e| (script.len() - 1, e))?;
+    } else {
+        txn.commit().await.map_err(|e| (script.len() - 1, e))?;
+    }
+
+    Ok(results)
+}
+
+/// Run and commit the statements one at a time, stopping at the first failure
+async fn run_each_committed(
+    conn: &mut PgConnection,
+    script: &[ScriptStatement],
+    values: &[Option<String>],
+    settings: &ScriptSettings,
+) -> Result<Vec<SqlStatementResult>, (usize, sqlx::Error)> {
+    let mut results = Vec::with_capacity(script.len());
+
+    for (index, statement) in script.iter().enumerate() {
+        let mut txn = begin_script_transaction(&mut *conn, settings)
+            .await
+            .map_err(|e| (index, e))?;
+        let result = run_statement(&mut txn, statement, &statement.bind_values(values))
+            .await
+            .map_err(|e| (index, e))?;
+        txn.commit().await.map_err(|e| (index, e))?;
+        results.push(result);
+    }
+
+    Ok(results)
+}
+
+async fn begin_script_transaction<'c>(
+    conn: &'c mut PgConnection,
+    settings: &ScriptSettings,
+) -> Result<sqlx::Transaction<'c, sqlx::Postgres>, sqlx::Error> {
+    let mut txn = conn.begin().await?;
+
+    if settings.read_only {
+        sqlx::query("SET TRANSACTION READ ONLY").execute(&mut *txn).await?;
+    }
+
+    sqlx::query(&format!("SET LOCAL statement_timeout = {}", settings.statement_timeout_ms))
+        .execute(&mut *txn)
+        .await?;
+
+    // Statements are checked against the project's relations before they get here;
+    // the role keeps the platform's tables out of reach should a check be missed
+    sqlx::query(&format!("SET LOCAL ROLE \"{}\"", settings.role))
+        .execute(&mut *txn)
+        .await?;
+
+    Ok(txn)
+}
+
+/// Run a parsed statement, returning its columns, its rows as JSON and the row count
+async fn run_statement(
+    conn: &mut PgConnection,
+    script_statement: &ScriptStatement,
+    values: &[Option<String>],
+) -> Result<SqlStatementResult, sqlx::Error> {
+    let start_time = std::time::Instant::now();
+    let statement = &script_statement.statement;
+    let sql = statement.to_string();
+
+    // Describing the statement gives its result columns without running it
+    let described = (&mut *conn).describe(&sql).await?;
+    let columns = describe_columns(conn, &described).await?;
+
+    let (rows, rows_affected) = match statement {
+        sqlparser::ast::Statement::Explain { .. } => {
+            // Text plans come back one line per row, JSON plans as a single value
+            let rows: Vec<JsonValue> = values
+                .iter()
+                .fold(sqlx::query(&sql), |query, value| query.bind(value))
+                .persistent(false)
+                .fetch_all(&mut *conn)
+                .await?
+                .into_iter()
+                .filter_map(|row| {
+                    row.try_get::<String, _>(0)
+                        .map(JsonValue::String)
+                        .or_else(|_| row.try_get::<JsonValue, _>(0))
+                        .ok()
+                })
+                .map(|plan| serde_json::json!({ "QUERY PLAN": plan }))
+                .collect();
+
+            let row_count = rows.len() as u64;
+            (rows, Some(row_count))
+        }
+        _ if !columns.is_empty() => {
+            let wrapped_sql = json_rows_sql(statement);
+            let rows: Vec<JsonValue> = values
+                .iter()
+                .fold(sqlx::query_scalar(&wrapped_sql), |query, value| query.bind(value))
+                .persistent(false)
+                .fetch_all(&mut *conn)
+                .await?;
+
+            let row_count = rows.len() as u64;
+            (rows, Some(row_count))
+        }
+        _ => {
+            let result = values
+                .iter()
+                .fold(sqlx::query(&sql), |query, value| query.bind(value))
+                .persistent(false)
+                .execute(&mut *conn)
+                .await?;
+
+            // Return empty result set with row count
+            (vec![], Some(result.rows_affected()))
+        }
+    };
+
+    Ok(SqlStatementResult {
+        statement: script_statement.text.clone(),
+        statement_kind: script_statement.statement_kind,
+        columns,
+        rows,
+        rows_affected,
+        execution_time_ms: start_time.elapsed().as_millis(),
+        plan: None,
+    })
+}
+
+/// Profile a statement with `EXPLAIN ANALYZE`, returning its plan tree instead of its rows
+async fn explain_statement(
+    conn: &mut PgConnection,
+    script_statement: &ScriptStatement,
+    values: &[Option<String>],
+) -> Result<SqlStatementResult, sqlx::Error> {
+    let start_time = std::time::Instant::now();
+    let sql = script_statement.statement.to_string();
+
+    let explain_sql = format!("EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON) {}", sql);
+    let output: JsonValue = values
+        .iter()
+        .fold(sqlx::query_scalar(&explain_sql), |query, value| query.bind(value))
+        .persistent(false)
+        .fetch_one(&mut *conn)
+        .await?;
+
+    // The output is a one-element array holding the plan and the overall timings
+    let explained = &output[0];
+    let plan = ExplainPlan {
+        planning_time_ms: explained["Planning Time"].as_f64(),
+        execution_time_ms: explained["Execution Time"].as_f64(),
+        root: plan_node(&explained["Plan"]),
+    };
+
+    Ok(SqlStatementResult {
+        statement: script_statement.text.clone(),
+        statement_kind: script_statement.statement_kind,
+        columns: vec![],
+        rows: vec![],
+        rows_affected: None,
+        execution_time_ms: start_time.elapsed().as_millis(),
+        plan: Some(plan),
+    })
+}
+
+fn plan_node(node: &JsonValue) -> PlanNode {
+    let text = |key: &str| node[key].as_str().map(str::to_string);
+
+    let condition = ["Index Cond", "Hash Cond", "Merge Cond", "Join Filter", "Recheck Cond", "Filter"]
+        .into_iter()
+        .find_map(text);
+
+    PlanNode {
+        node_type: text("Node Type").unwrap_or_default(),
+        relation_name: text("Relation Name"),
+        index_name: text("Index Name"),
+        condition,
+        startup_cost: node["Startup Cost"].as_f64().unwrap_or_default(),
+        total_cost: node["Total Cost"].as_f64().unwrap_or_default(),
+        plan_rows: node["Plan Rows"].as_f64().unwrap_or_default(),
+        actual_rows: node["Actual Rows"].as_f64(),
+        actual_loops: node["Actual Loops"].as_f64(),
+        actual_total_time_ms: node["Actual Total Time"].as_f64(),
+        shared_hit_blocks: node["Shared Hit Blocks"].as_i64(),
+        shared_read_blocks: node["Shared Read Blocks"].as_i64(),
+        children: node["Plans"]
+            .as_array()
+            .map(|children| children.iter().map(plan_node).collect())
+            .unwrap_or_default(),
+    }
+}
+
+/// Show the relations and conditions of a plan tree under their logical names
+fn logical_plan_names(node: &mut PlanNode, project_id: &Uuid) {
+    for name in [&mut node.relation_name, &mut node.index_name, &mut node.condition]
+        .into_iter()
+        .flatten()
+    {
+        *name = logical_names(name, project_id);
+    }
+
+    for child in &mut node.children {
+        logical_plan_names(child, project_id);
+    }
+}
+
+/// Wrap a statement that returns rows so each row comes back as a JSON object.
+///
+/// The statement becomes a CTE rather than a subquery, since data-modifying
+/// statements (`RETURNING` clauses and writes inside `WITH`) are only allowed at
+/// the top level of a `WITH`; a statement's own CTEs are hoisted next to it.
+fn json_rows_sql(statement: &sqlparser::ast::Statement) -> String {
+    let (with, body) = match statement {
+        sqlparser::ast::Statement::Query(query) => {
+            let mut body = query.as_ref().clone();
+            (body.with.take(), body.to_string())
+        }
+        other => (None, other.to_string()),
+    };
+
+    let ctes = match with {
+        Some(with) => format!(
+            "{}{}, ",
+            if with.recursive { "RECURSIVE " } else { "" },
+            with.cte_tables.iter().map(|cte| cte.to_string()).collect::<Vec<_>>().join(", ")
+        ),
+        None => String::new(),
+    };
+
+    format!(
+        "WITH {ctes}{result} AS ({body}) SELECT row_to_json({result}) AS data FROM {result}",
+        result = RESULT_RELATION
+    )
+}
+
+/// Build the ordered column descriptors of a described statement.
+///
+/// Nullability comes from the source column when the result column is a plain
+/// table column, and is unknown (`None`) for computed columns.
+async fn describe_columns(
+    conn: &mut PgConnection,
+    described: &Describe<sqlx::Postgres>,
+) -> Result<Vec<SqlColumn>, sqlx::Error> {
+    let columns = described.columns();
+    let oids: Vec<i64> = columns
+        .iter()
+        .map(|column| column.type_info().oid().map(|oid| oid.0 as i64).unwrap_or(0))
+        .collect();
+
+    let type_names: HashMap<i64, String> =
+        sqlx::query_as("SELECT oid::int8, format_type(oid, NULL) FROM unnest($1::int8[]::oid[]) AS oid")
+            .bind(&oids)
+            .fetch_all(&mut *conn)
+            .await?
+            .into_iter()
+            .collect();
+
+    Ok(columns
+        .iter()
+        .zip(oids)
+        .enumerate()
+        .map(|(index, (column, oid))| SqlColumn {
+            name: column.name().to_string(),
+            type_oid: oid as u32,
+            nullable: described.nullable(index),
+            data_type: type_names
+                .get(&oid)
+                .cloned()
+                .unwrap_or_else(|| column.type_info().name().to_string()),
+        })
+        .collect())
+}
+
+fn execution_error(e: sqlx::Error, statement_timeout_ms: i32) -> String {
+    let message = match &e {
+        sqlx::Error::Database(e) => e.message().to_string(),
+        other => other.to_string(),
+    };
+
+    if message.contains("canceling statement due to user request") {
+        "Query was cancelled".to_string()
+    } else if message.contains("canceling statement due to statement timeout") {
+        format!("Query exceeded the project's statement timeout of {} ms", statement_timeout_ms)
+    } else {
+        format!("SQL execution error: {}", message)
+    }
+}
+
+/// Classify a statement as a read or a write, rejecting statements the SQL editor does not run
+fn classify_statement(statement: &sqlparser::ast::Statement) -> Result<SqlStatementKind, String> {
+    use sqlparser::ast::Statement as Sql;
+
+    match statement {
+        Sql::Query(_)
+        | Sql::Insert(_)
+        | Sql::Update { .. }
+        | Sql::Delete(_)
+        | Sql::Merge { .. }
+        | Sql::Truncate { .. } => {
+            let mut classifier = StatementClassifier { writes: false };
+            match statement.visit(&mut classifier) {
+                ControlFlow::Break(message) => Err(message),
+                ControlFlow::Continue(()) if classifier.writes => Ok(SqlStatementKind::Write),
+                ControlFlow::Continue(()) => Ok(SqlStatementKind::Read),
+            }
+        }
+        Sql::Explain { statement: inner, analyze, options, .. } => {
+            let kind = classify_statement(inner)?;
+
+            // Only EXPLAIN ANALYZE actually runs the statement
+            let analyze = *analyze
+                || options.iter().flatten().any(|option| option.name.value.eq_ignore_ascii_case("analyze"));
+
+            Ok(if analyze { kind } else { SqlStatementKind::Read })
+        }
+        other => {
+            let keyword = other
+                .to_string()
+                .split_whitespace()
+                .next()
+                .unwrap_or_default()
+                .to_uppercase();
+
+            Err(format!(
+                "{} statements are not allowed in the SQL editor; only SELECT, INSERT, UPDATE, DELETE, MERGE, TRUNCATE and EXPLAIN are supported",
+                keyword
+            ))
+        }
+    }
+}
+
+/// Finds data-modifying statements (including ones nested in CTEs) and rejected constructs
+struct StatementClassifier {
+    writes: bool,
+}
+
+impl Visitor for StatementClassifier {
+    type Break = String;
+
+    fn pre_visit_statement(&mut self, statement: &sqlparser::ast::Statement) -> ControlFlow<Self::Break> {
+        use sqlparser::ast::Statement as Sql;
+
+        if matches!(
+            statement,
+            Sql::Insert(_) | Sql::Update { .. } | Sql::Delete(_) | Sql::Merge { .. } | Sql::Truncate { .. }
+        ) {
+            self.writes = true;
+        }
+        ControlFlow::Continue(())
+    }
+
+    fn pre_visit_query(&mut self, query: &Query) -> ControlFlow<Self::Break> {
+        match query.body.as_ref() {
+            SetExpr::Select(select) if select.into.is_some() => {
+                ControlFlow::Break("SELECT INTO is not allowed in the SQL editor".to_string())
+            }
+            _ => ControlFlow::Continue(()),
+        }
+    }
+}
+
+/// Casts `$n` placeholders to their declared types and renumbers them in order of first use
+struct PlaceholderBinder<'a> {
+    parameter_types: &'a [DataType],
+    bound: Vec<usize>,
+}
+
+impl VisitorMut for PlaceholderBinder<'_> {
+    type Break = String;
+
+    fn post_visit_expr(&mut self, expr: &mut Expr) -> ControlFlow<Self::Break> {
+        let Expr::Value(sqlparser::ast::Value::Placeholder(placeholder)) = expr else {
+            return ControlFlow::Continue(());
+        };
+
+        let Some(index) = placeholder
+            .strip_prefix('$')
+            .and_then(|n| n.parse::<usize>().ok())
+            .filter(|n| *n >= 1)
+        else {
+            return ControlFlow::Break(format!("Unsupported placeholder {}; use $1, $2, ...", placeholder));
+        };
+
+        let Some(data_type) = self.parameter_types.get(index - 1) else {
+            return ControlFlow::Break(format!("Parameter {} is not declared", placeholder));
+        };
+
+        let position = match self.bound.iter().position(|bound| *bound == index - 1) {
+            Some(position) => position + 1,
+            None => {
+                self.bound.push(index - 1);
+                self.bound.len()
+            }
+        };
+
+        *expr = Expr::Cast {
+            kind: CastKind::DoubleColon,
+            expr: Box::new(Expr::Value(sqlparser::ast::Value::Placeholder(format!("${}", position)))),
+            data_type: data_type.clone(),
+            format: None,
+        };
+        ControlFlow::Continue(())
+    }
+}
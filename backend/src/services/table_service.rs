@@ -1,7 +1,12 @@
 use sea_orm::*;
 use database::entities::{project_tables, project_columns, projects};
-use shared::models::{CreateTableRequest, TableResponse, TableSummary, ColumnResponse};
+use shared::models::{ColumnDefinition, CreateTableRequest, TableResponse, TableSummary, ColumnResponse};
 use crate::error::{AppError, AppResult};
+use crate::services::authorization_service::{authorize_project, Capability};
+use crate::services::policy_service::AUTHENTICATED_ROLE;
+use crate::services::project_service::set_project_owner;
+use crate::services::sql_rewrite::project_relations;
+use crate::services::view_service::prune_dropped_views;
 use uuid::Uuid;
 
 /// List all tables for a project
@@ -10,16 +15,7 @@ pub async fn list_project_tables(
     user_id: &str,
     project_slug: &str,
 ) -> AppResult<Vec<TableSummary>> {
-    let owner_uuid = Uuid::parse_str(user_id)
-        .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
-
-    // Get project and verify ownership
-    let project = projects::Entity::find()
-        .filter(projects::Column::Slug.eq(project_slug))
-        .filter(projects::Column::OwnerId.eq(owner_uuid))
-        .one(db)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Project not found".to_string()))?;
+    let project = authorize_project(db, user_id, project_slug, Capability::Read).await?.project;
 
     // Get all tables for this project
     let tables = project_tables::Entity::find()
@@ -48,16 +44,7 @@ pub async fn get_project_table(
     project_slug: &str,
     table_name: &str,
 ) -> AppResult<TableResponse> {
-    let owner_uuid = Uuid::parse_str(user_id)
-        .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
-
-    // Get project and verify ownership
-    let project = projects::Entity::find()
-        .filter(projects::Column::Slug.eq(project_slug))
-        .filter(projects::Column::OwnerId.eq(owner_uuid))
-        .one(db)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Project not found".to_string()))?;
+    let project = authorize_project(db, user_id, project_slug, Capability::Read).await?.project;
 
     // Get table
     let table = project_tables::Entity::find()
@@ -108,9 +95,18 @@ pub async fn create_project_table(
     project_slug: &str,
     req: CreateTableRequest,
 ) -> AppResult<TableResponse> {
-    let owner_uuid = Uuid::parse_str(user_id)
-        .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
+    let project = authorize_project(db, user_id, project_slug, Capability::ManageSchema).await?.project;
+
+    create_table_for_project(db, &project, req).await
+}
 
+/// Create a table for a project whose access has already been verified.
+/// Generic over the connection so callers can create several tables in one transaction.
+pub(crate) async fn create_table_for_project<C: ConnectionTrait>(
+    db: &C,
+    project: &projects::Model,
+    req: CreateTableRequest,
+) -> AppResult<TableResponse> {
     // Validate table name (alphanumeric + underscores only)
     if !req.table_name.chars().all(|c| c.is_alphanumeric() || c == '_') {
         return Err(AppError::BadRequest(
@@ -122,72 +118,16 @@ pub async fn create_project_table(
         return Err(AppError::BadRequest("Table must have at least one column".to_string()));
     }
 
-    // Get project and verify ownership
-    let project = projects::Entity::find()
-        .filter(projects::Column::Slug.eq(project_slug))
-        .filter(projects::Column::OwnerId.eq(owner_uuid))
-        .one(db)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Project not found".to_string()))?;
-
-    // Check if table already exists
-    let existing = project_tables::Entity::find()
-        .filter(project_tables::Column::ProjectId.eq(project.id))
-        .filter(project_tables::Column::TableName.eq(&req.table_name))
-        .one(db)
-        .await?;
-
-    if existing.is_some() {
-        return Err(AppError::BadRequest("Table with this name already exists".to_string()));
+    // Tables and views share the project namespace
+    let relations = project_relations(db, project.id).await?;
+    if relations.contains_key(&req.table_name) {
+        return Err(AppError::BadRequest("A table or view with this name already exists".to_string()));
     }
 
     // Build the actual PostgreSQL table name (prefixed with project ID to avoid conflicts)
-    let pg_table_name = format!("project_{}_{}", project.id.simple(), req.table_name);
-
-    // Build CREATE TABLE SQL
-    let mut column_defs: Vec<String> = Vec::new();
-    let mut has_primary_key = false;
-
-    for (_idx, col) in req.columns.iter().enumerate() {
-        let mut col_def = format!("\"{}\" {}", col.name, col.data_type.to_postgres_type());
-
-        if col.is_primary_key {
-            col_def.push_str(" PRIMARY KEY");
-            has_primary_key = true;
-        }
-
-        if !col.is_nullable && !col.is_primary_key {
-            col_def.push_str(" NOT NULL");
-        }
-
-        if col.is_unique && !col.is_primary_key {
-            col_def.push_str(" UNIQUE");
-        }
-
-        if let Some(ref default_val) = col.default_value {
-            col_def.push_str(&format!(" DEFAULT {}", default_val));
-        }
-
-        column_defs.push(col_def);
-    }
-
-    // Add auto-increment ID if no primary key specified
-    if !has_primary_key {
-        column_defs.insert(
-            0,
-            "\"id\" UUID PRIMARY KEY DEFAULT gen_random_uuid()".to_string(),
-        );
-    }
-
-    // Add timestamps
-    column_defs.push("\"created_at\" TIMESTAMP DEFAULT CURRENT_TIMESTAMP".to_string());
-    column_defs.push("\"updated_at\" TIMESTAMP DEFAULT CURRENT_TIMESTAMP".to_string());
-
-    let create_table_sql = format!(
-        "CREATE TABLE IF NOT EXISTS \"{}\" ({})",
-        pg_table_name,
-        column_defs.join(", ")
-    );
+    let pg_table_name = physical_table_name(&project.id, &req.table_name);
+    let has_primary_key = req.columns.iter().any(|col| col.is_primary_key);
+    let create_table_sql = build_create_table_sql(&pg_table_name, &req.columns);
 
     // Execute the CREATE TABLE statement
     db.execute(Statement::from_string(
@@ -196,6 +136,15 @@ pub async fn create_project_table(
     ))
     .await?;
 
+    set_project_owner(db, &project.id, &format!("TABLE \"{}\"", pg_table_name)).await?;
+
+    // Requests that apply policies run as the policy role; RLS decides what it sees
+    db.execute(Statement::from_string(
+        DatabaseBackend::Postgres,
+        format!("GRANT SELECT, INSERT, UPDATE, DELETE ON \"{}\" TO {}", pg_table_name, AUTHENTICATED_ROLE),
+    ))
+    .await?;
+
     // Create record in project_tables
     let table_id = Uuid::new_v4();
     let project_table = project_tables::ActiveModel {
@@ -327,16 +276,7 @@ pub async fn delete_project_table(
     project_slug: &str,
     table_name: &str,
 ) -> AppResult<()> {
-    let owner_uuid = Uuid::parse_str(user_id)
-        .map_err(|_| AppError::BadRequest("Invalid user ID".to_string()))?;
-
-    // Get project and verify ownership
-    let project = projects::Entity::find()
-        .filter(projects::Column::Slug.eq(project_slug))
-        .filter(projects::Column::OwnerId.eq(owner_uuid))
-        .one(db)
-        .await?
-        .ok_or_else(|| AppError::NotFound("Project not found".to_string()))?;
+    let project = authorize_project(db, user_id, project_slug, Capability::ManageSchema).await?.project;
 
     // Get table
     let table = project_tables::Entity::find()
@@ -359,5 +299,61 @@ pub async fn delete_project_table(
     // Delete the table record (columns will cascade delete)
     table.delete(db).await?;
 
+    // CASCADE also drops views built on this table
+    prune_dropped_views(db, project.id).await?;
+
     Ok(())
 }
+
+/// Physical PostgreSQL name of a project table
+pub(crate) fn physical_table_name(project_id: &Uuid, table_name: &str) -> String {
+    format!("project_{}_{}", project_id.simple(), table_name)
+}
+
+/// Build the CREATE TABLE statement for a table definition, adding the
+/// generated id column (when no primary key is given) and the timestamp columns
+pub(crate) fn build_create_table_sql(pg_table_name: &str, columns: &[ColumnDefinition]) -> String {
+    let mut column_defs: Vec<String> = Vec::new();
+    let mut has_primary_key = false;
+
+    for col in columns {
+        let mut col_def = format!("\"{}\" {}", col.name, col.data_type.to_postgres_type());
+
+        if col.is_primary_key {
+            col_def.push_str(" PRIMARY KEY");
+            has_primary_key = true;
+        }
+
+        if !col.is_nullable && !col.is_primary_key {
+            col_def.push_str(" NOT NULL");
+        }
+
+        if col.is_unique && !col.is_primary_key {
+            col_def.push_str(" UNIQUE");
+        }
+
+        if let Some(ref default_val) = col.default_value {
+            col_def.push_str(&format!(" DEFAULT {}", default_val));
+        }
+
+        column_defs.push(col_def);
+    }
+
+    // Add auto-increment ID if no primary key specified
+    if !has_primary_key {
+        column_defs.insert(
+            0,
+            "\"id\" UUID PRIMARY KEY DEFAULT gen_random_uuid()".to_string(),
+        );
+    }
+
+    // Add timestamps
+    column_defs.push("\"created_at\" TIMESTAMP DEFAULT CURRENT_TIMESTAMP".to_string());
+    column_defs.push("\"updated_at\" TIMESTAMP DEFAULT CURRENT_TIMESTAMP".to_string());
+
+    format!(
+        "CREATE TABLE \"{}\" ({})",
+        pg_table_name,
+        column_defs.join(", ")
+    )
+}
@@ -0,0 +1,374 @@
+use std::sync::Arc;
+use std::time::Duration;
+use sea_orm::*;
+use database::entities::{project_views, projects};
+use shared::models::{CreateViewRequest, FunctionLanguage, ViewColumn, ViewRefreshScheduleRequest, ViewResponse};
+use sqlparser::dialect::PostgreSqlDialect;
+use sqlparser::parser::Parser;
+use crate::error::{AppError, AppResult};
+use crate::services::authorization_service::{authorize_project, Capability};
+use crate::services::policy_service::AUTHENTICATED_ROLE;
+use crate::services::project_service::set_project_owner;
+use crate::services::sql_rewrite::{project_functions, project_relations, resolve_relations};
+use crate::services::table_service::physical_table_name;
+use uuid::Uuid;
+
+/// How often the scheduler looks for materialized views that are due for a refresh
+const REFRESH_CHECK_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Shortest allowed automatic refresh interval
+const MIN_REFRESH_INTERVAL_SECONDS: i32 = 60;
+
+/// List all views for a project
+pub async fn list_project_views(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+) -> AppResult<Vec<ViewResponse>> {
+    let project = authorize_project(db, user_id, project_slug, Capability::Read).await?.project;
+
+    let views = project_views::Entity::find()
+        .filter(project_views::Column::ProjectId.eq(project.id))
+        .order_by_asc(project_views::Column::ViewName)
+        .all(db)
+        .await?;
+
+    let mut responses = Vec::with_capacity(views.len());
+    for view in views {
+        responses.push(view_response(db, view).await?);
+    }
+
+    Ok(responses)
+}
+
+/// Get a specific view with its columns
+pub async fn get_project_view(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    view_name: &str,
+) -> AppResult<ViewResponse> {
+    let project = authorize_project(db, user_id, project_slug, Capability::Read).await?.project;
+    let view = find_view(db, &project, view_name).await?;
+
+    view_response(db, view).await
+}
+
+/// Create a view (or materialized view) from a SELECT over the project's tables
+pub async fn create_project_view(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    req: CreateViewRequest,
+) -> AppResult<ViewResponse> {
+    // Validate view name (alphanumeric + underscores only)
+    if req.view_name.is_empty() || !req.view_name.chars().all(|c| c.is_alphanumeric() || c == '_') {
+        return Err(AppError::BadRequest(
+            "View name can only contain letters, numbers, and underscores".to_string(),
+        ));
+    }
+
+    validate_refresh_interval(req.materialized, req.refresh_interval_seconds)?;
+
+    let project = authorize_project(db, user_id, project_slug, Capability::ManageSchema).await?.project;
+
+    // Tables and views share the project namespace
+    let relations = project_relations(db, project.id).await?;
+    let functions = project_functions(db, project.id, &[FunctionLanguage::Sql]).await?;
+    if relations.contains_key(&req.view_name) {
+        return Err(AppError::BadRequest("A table or view with this name already exists".to_string()));
+    }
+
+    // The definition must be a single query; table names are mapped to physical tables
+    let mut statements = Parser::parse_sql(&PostgreSqlDialect {}, &req.definition)
+        .map_err(|e| AppError::BadRequest(format!("Failed to parse view definition: {}", e)))?;
+
+    let mut query = match (statements.pop(), statements.is_empty()) {
+        (Some(query @ sqlparser::ast::Statement::Query(_)), true) => query,
+        _ => {
+            return Err(AppError::BadRequest(
+                "View definition must be a single SELECT query".to_string(),
+            ))
+        }
+    };
+
+    resolve_relations(&mut query, &relations, &functions).map_err(AppError::BadRequest)?;
+
+    // Plain views read their tables as the querying role, so the tables' policies apply
+    // to them. Materialized views cannot; the data API keeps them from bypassing policies.
+    let pg_view_name = physical_table_name(&project.id, &req.view_name);
+    let create_view_sql = if req.materialized {
+        format!("CREATE MATERIALIZED VIEW \"{}\" AS {}", pg_view_name, query)
+    } else {
+        format!("CREATE VIEW \"{}\" WITH (security_invoker = true) AS {}", pg_view_name, query)
+    };
+
+    let txn = db.begin().await?;
+
+    txn.execute(Statement::from_string(DatabaseBackend::Postgres, create_view_sql))
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Failed to create view: {}", e)))?;
+
+    let kind = if req.materialized { "MATERIALIZED VIEW" } else { "VIEW" };
+    set_project_owner(&txn, &project.id, &format!("{} \"{}\"", kind, pg_view_name)).await?;
+
+    txn.execute(Statement::from_string(
+        DatabaseBackend::Postgres,
+        format!("GRANT SELECT ON \"{}\" TO {}", pg_view_name, AUTHENTICATED_ROLE),
+    ))
+    .await?;
+
+    let now = chrono::Utc::now().naive_utc();
+    let view = project_views::ActiveModel {
+        id: Set(Uuid::new_v4()),
+        project_id: Set(project.id),
+        view_name: Set(req.view_name.clone()),
+        display_name: Set(req.display_name.clone()),
+        description: Set(req.description.clone()),
+        definition: Set(req.definition.clone()),
+        is_materialized: Set(req.materialized),
+        refresh_interval_seconds: Set(req.refresh_interval_seconds),
+        // Materialized views are populated on creation
+        last_refreshed_at: Set(req.materialized.then_some(now)),
+        created_at: Set(Some(now)),
+        updated_at: Set(Some(now)),
+    }
+    .insert(&txn)
+    .await?;
+
+    txn.commit().await?;
+
+    view_response(db, view).await
+}
+
+/// Delete a view, along with any views that depend on it
+pub async fn delete_project_view(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    view_name: &str,
+) -> AppResult<()> {
+    let project = authorize_project(db, user_id, project_slug, Capability::ManageSchema).await?.project;
+    let view = find_view(db, &project, view_name).await?;
+
+    let drop_view_sql = format!(
+        "DROP {}VIEW IF EXISTS \"{}\" CASCADE",
+        if view.is_materialized { "MATERIALIZED " } else { "" },
+        physical_table_name(&project.id, &view.view_name)
+    );
+
+    db.execute(Statement::from_string(DatabaseBackend::Postgres, drop_view_sql))
+        .await?;
+
+    view.delete(db).await?;
+
+    // CASCADE may have removed dependent views as well
+    prune_dropped_views(db, project.id).await?;
+
+    Ok(())
+}
+
+/// Refresh a materialized view now
+pub async fn refresh_project_view(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    view_name: &str,
+) -> AppResult<ViewResponse> {
+    let project = authorize_project(db, user_id, project_slug, Capability::WriteData).await?.project;
+    let view = find_view(db, &project, view_name).await?;
+
+    if !view.is_materialized {
+        return Err(AppError::BadRequest(
+            "Only materialized views can be refreshed".to_string(),
+        ));
+    }
+
+    let view = refresh_materialized_view(db, view).await?;
+    view_response(db, view).await
+}
+
+/// Change or disable the automatic refresh interval of a materialized view
+pub async fn set_view_refresh_schedule(
+    db: &DatabaseConnection,
+    user_id: &str,
+    project_slug: &str,
+    view_name: &str,
+    req: ViewRefreshScheduleRequest,
+) -> AppResult<ViewResponse> {
+    let project = authorize_project(db, user_id, project_slug, Capability::ManageSchema).await?.project;
+    let view = find_view(db, &project, view_name).await?;
+
+    validate_refresh_interval(view.is_materialized, req.refresh_interval_seconds)?;
+
+    let mut view: project_views::ActiveModel = view.into();
+    view.refresh_interval_seconds = Set(req.refresh_interval_seconds);
+    view.updated_at = Set(Some(chrono::Utc::now().naive_utc()));
+
+    let updated = view.update(db).await?;
+    view_response(db, updated).await
+}
+
+/// Refresh every materialized view whose refresh interval has elapsed
+pub async fn refresh_due_views(db: &DatabaseConnection) -> AppResult<()> {
+    let scheduled = project_views::Entity::find()
+        .filter(project_views::Column::IsMaterialized.eq(true))
+        .filter(project_views::Column::RefreshIntervalSeconds.is_not_null())
+        .all(db)
+        .await?;
+
+    let now = chrono::Utc::now().naive_utc();
+
+    for view in scheduled {
+        let interval = chrono::Duration::seconds(view.refresh_interval_seconds.unwrap_or_default() as i64);
+        let is_due = view.last_refreshed_at.is_none_or(|last| last + interval <= now);
+
+        if !is_due {
+            continue;
+        }
+
+        let view_id = view.id;
+        if let Err(e) = refresh_materialized_view(db, view).await {
+            tracing::warn!("Scheduled refresh of view {} failed: {}", view_id, e);
+        }
+    }
+
+    Ok(())
+}
+
+/// Background task that keeps scheduled materialized views up to date
+pub async fn run_view_refresh_scheduler(db: Arc<DatabaseConnection>) {
+    let mut interval = tokio::time::interval(REFRESH_CHECK_INTERVAL);
+
+    loop {
+        interval.tick().await;
+
+        if let Err(e) = refresh_due_views(&db).await {
+            tracing::error!("Materialized view scheduler error: {}", e);
+        }
+    }
+}
+
+/// Remove metadata for views whose relation no longer exists (e.g. after a DROP ... CASCADE)
+pub(crate) async fn prune_dropped_views<C: ConnectionTrait>(db: &C, project_id: Uuid) -> AppResult<()> {
+    let views = project_views::Entity::find()
+        .filter(project_views::Column::ProjectId.eq(project_id))
+        .all(db)
+        .await?;
+
+    for view in views {
+        let stmt = Statement::from_sql_and_values(
+            DatabaseBackend::Postgres,
+            "SELECT to_regclass(quote_ident($1)) IS NOT NULL AS present",
+            [physical_table_name(&project_id, &view.view_name).into()],
+        );
+
+        let present = match db.query_one(stmt).await? {
+            Some(row) => row.try_get::<bool>("", "present")?,
+            None => false,
+        };
+
+        if !present {
+            view.delete(db).await?;
+        }
+    }
+
+    Ok(())
+}
+
+async fn refresh_materialized_view(
+    db: &DatabaseConnection,
+    view: project_views::Model,
+) -> AppResult<project_views::Model> {
+    let refresh_sql = format!(
+        "REFRESH MATERIALIZED VIEW \"{}\"",
+        physical_table_name(&view.project_id, &view.view_name)
+    );
+
+    db.execute(Statement::from_string(DatabaseBackend::Postgres, refresh_sql))
+        .await
+        .map_err(|e| AppError::BadRequest(format!("Failed to refresh view: {}", e)))?;
+
+    let mut view: project_views::ActiveModel = view.into();
+    view.last_refreshed_at = Set(Some(chrono::Utc::now().naive_utc()));
+
+    Ok(view.update(db).await?)
+}
+
+fn validate_refresh_interval(materialized: bool, interval: Option<i32>) -> AppResult<()> {
+    match interval {
+        Some(_) if !materialized => Err(AppError::BadRequest(
+            "Only materialized views can be refreshed on a schedule".to_string(),
+        )),
+        Some(seconds) if seconds < MIN_REFRESH_INTERVAL_SECONDS => Err(AppError::BadRequest(format!(
+            "Refresh interval must be at least {} seconds",
+            MIN_REFRESH_INTERVAL_SECONDS
+        ))),
+        _ => Ok(()),
+    }
+}
+
+async fn find_view(
+    db: &DatabaseConnection,
+    project: &projects::Model,
+    view_name: &str,
+) -> AppResult<project_views::Model> {
+    project_views::Entity::find()
+        .filter(project_views::Column::ProjectId.eq(project.id))
+        .filter(project_views::Column::ViewName.eq(view_name))
+        .one(db)
+        .await?
+        .ok_or_else(|| AppError::NotFound("View not found".to_string()))
+}
+
+/// Build the response for a view, reading its columns from the catalog
+async fn view_response(db: &DatabaseConnection, view: project_views::Model) -> AppResult<ViewResponse> {
+    // pg_attribute covers materialized views, which information_schema omits
+    let stmt = Statement::from_sql_and_values(
+        DatabaseBackend::Postgres,
+        r#"SELECT a.attname AS name, format_type(a.atttypid, a.atttypmod) AS data_type
+           FROM pg_attribute a
+           WHERE a.attrelid = to_regclass(quote_ident($1)) AND a.attnum > 0 AND NOT a.attisdropped
+           ORDER BY a.attnum"#,
+        [physical_table_name(&view.project_id, &view.view_name).into()],
+    );
+
+    let columns = db
+        .query_all(stmt)
+        .await?
+        .into_iter()
+        .map(|row| {
+            Ok(ViewColumn {
+                name: row.try_get("", "name")?,
+                data_type: row.try_get("", "data_type")?,
+            })
+        })
+        .collect::<Result<Vec<_>, DbErr>>()?;
+
+    Ok(ViewResponse {
+        id: view.id.to_string(),
+        view_name: view.view_name,
+        display_name: view.display_name,
+        description: view.description,
+        definition: view.definition,
+        is_materialized: view.is_materialized,
+        refresh_interval_seconds: view.refresh_interval_seconds,
+        last_refreshed_at: view.last_refreshed_at.map(|dt| dt.to_string()),
+        columns,
+        created_at: view.created_at.map(|dt| dt.to_string()).unwrap_or_default(),
+    })
+}
+
+/// Whether `name` is a view in the project (used to reject writes through the data API)
+pub(crate) async fn is_project_view(
+    db: &DatabaseConnection,
+    project_id: Uuid,
+    name: &str,
+) -> AppResult<bool> {
+    Ok(project_views::Entity::find()
+        .filter(project_views::Column::ProjectId.eq(project_id))
+        .filter(project_views::Column::ViewName.eq(name))
+        .one(db)
+        .await?
+        .is_some())
+}
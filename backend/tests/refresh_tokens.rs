@@ -0,0 +1,118 @@
+//! Refresh token rotation against a real database.
+//!
+//! Skipped unless `TEST_DATABASE_URL` points at a Postgres database.
+
+use std::net::{IpAddr, Ipv4Addr};
+
+use backend::error::AppError;
+use backend::services;
+use backend::Config;
+use database::entities::users;
+use sea_orm::*;
+use shared::models::{AuthResponse, LoginRequest, LoginResponse, RefreshTokenRequest};
+use uuid::Uuid;
+
+const PASSWORD: &str = "correct horse battery staple";
+
+async fn connect() -> Option<DatabaseConnection> {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("skipping: TEST_DATABASE_URL is not set");
+        return None;
+    };
+
+    Some(database::establish_connection(&url).await.expect("failed to connect to the test database"))
+}
+
+fn config() -> Config {
+    Config {
+        database_url: String::new(),
+        jwt_secret: "refresh-token-test-secret".to_string(),
+        access_token_expiration_minutes: 15,
+        refresh_token_expiration_days: 30,
+        impersonation_expiration_minutes: 15,
+        server_host: String::new(),
+        server_port: 0,
+        app_url: String::new(),
+        smtp_url: None,
+        mail_from: String::new(),
+        mail_outbox_dir: String::new(),
+        password_reset_expiration_minutes: 60,
+        require_email_verification: false,
+        email_verification_expiration_hours: 24,
+        invitation_expiration_hours: 72,
+        oidc_providers: Vec::new(),
+        login_max_failures_per_account: 5,
+        login_max_failures_per_ip: 20,
+        login_lockout_base_seconds: 30,
+        login_lockout_max_seconds: 3600,
+        login_failure_window_minutes: 15,
+        trusted_proxy_hops: 0,
+    }
+}
+
+async fn create_user(db: &DatabaseConnection) -> users::Model {
+    let id = Uuid::new_v4();
+    users::ActiveModel {
+        id: Set(id),
+        email: Set(format!("refresh-{}@example.com", id.simple())),
+        password_hash: Set(services::hash_password(PASSWORD).unwrap()),
+        role: Set(Some("user".to_string())),
+        email_verified: Set(Some(true)),
+        is_active: Set(Some(true)),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .expect("failed to create user")
+}
+
+async fn login(db: &DatabaseConnection, config: &Config, user: &users::Model) -> AuthResponse {
+    let request = LoginRequest { email: user.email.clone(), password: PASSWORD.to_string() };
+    match services::login_user(db, request, IpAddr::V4(Ipv4Addr::LOCALHOST), config).await.unwrap() {
+        LoginResponse::Authenticated(session) => session,
+        LoginResponse::MfaRequired(_) => panic!("the test user has no second factor"),
+    }
+}
+
+async fn refresh(db: &DatabaseConnection, config: &Config, session: &AuthResponse) -> Result<AuthResponse, AppError> {
+    let request = RefreshTokenRequest { refresh_token: session.refresh_token.clone() };
+    services::refresh_session(db, request, config).await
+}
+
+#[tokio::test]
+async fn refresh_tokens_rotate() {
+    let Some(db) = connect().await else { return };
+    let config = config();
+    let user = create_user(&db).await;
+    let first = login(&db, &config, &user).await;
+
+    let second = refresh(&db, &config, &first).await.unwrap();
+    assert_ne!(second.refresh_token, first.refresh_token);
+
+    let third = refresh(&db, &config, &second).await.unwrap();
+    assert_ne!(third.refresh_token, second.refresh_token);
+
+    user.delete(&db).await.unwrap();
+}
+
+#[tokio::test]
+async fn reusing_a_refresh_token_revokes_its_family() {
+    let Some(db) = connect().await else { return };
+    let config = config();
+    let user = create_user(&db).await;
+    let first = login(&db, &config, &user).await;
+    let other_login = login(&db, &config, &user).await;
+
+    let second = refresh(&db, &config, &first).await.unwrap();
+
+    // The rotated token is presented again, as a thief holding a copy would
+    let reused = refresh(&db, &config, &first).await;
+    assert!(matches!(reused, Err(AppError::Unauthorized(_))));
+
+    // Every token of that login is dead, but other logins are not
+    let descendant = refresh(&db, &config, &second).await;
+    assert!(matches!(descendant, Err(AppError::Unauthorized(_))));
+    assert!(refresh(&db, &config, &other_login).await.is_ok());
+
+    user.delete(&db).await.unwrap();
+}
@@ -0,0 +1,153 @@
+//! Row-level security checks against a real database.
+//!
+//! These tests need a Postgres database they may create projects in; they are
+//! skipped unless `TEST_DATABASE_URL` points at one.
+
+use backend::error::AppError;
+use backend::services::{self, Principal};
+use database::entities::users;
+use sea_orm::*;
+use shared::models::{
+    Claims, ColumnDataType, ColumnDefinition, CreatePolicyRequest, CreateProjectRequest, CreateTableRequest,
+    CreateViewRequest, PolicyCommand,
+};
+use uuid::Uuid;
+
+async fn connect() -> Option<DatabaseConnection> {
+    let Ok(url) = std::env::var("TEST_DATABASE_URL") else {
+        eprintln!("skipping: TEST_DATABASE_URL is not set");
+        return None;
+    };
+
+    Some(database::establish_connection(&url).await.expect("failed to connect to the test database"))
+}
+
+async fn create_user(db: &DatabaseConnection) -> users::Model {
+    let id = Uuid::new_v4();
+    users::ActiveModel {
+        id: Set(id),
+        email: Set(format!("rls-{}@example.com", id.simple())),
+        password_hash: Set(String::new()),
+        role: Set(Some("user".to_string())),
+        email_verified: Set(Some(true)),
+        is_active: Set(Some(true)),
+        ..Default::default()
+    }
+    .insert(db)
+    .await
+    .expect("failed to create user")
+}
+
+fn user_principal(user: &users::Model) -> Principal {
+    Principal::User(Claims {
+        sub: user.id.to_string(),
+        email: user.email.clone(),
+        role: "user".to_string(),
+        exp: usize::MAX,
+        iat: 0,
+        jti: Uuid::new_v4().to_string(),
+        iat_us: 0,
+        impersonator: None,
+        amr: vec!["pwd".to_string()],
+        aal: "aal1".to_string(),
+    })
+}
+
+fn column(name: &str, data_type: ColumnDataType) -> ColumnDefinition {
+    ColumnDefinition {
+        name: name.to_string(),
+        display_name: name.to_string(),
+        data_type,
+        is_nullable: true,
+        is_primary_key: false,
+        is_unique: false,
+        default_value: None,
+    }
+}
+
+fn view(view_name: &str, definition: &str, materialized: bool) -> CreateViewRequest {
+    CreateViewRequest {
+        view_name: view_name.to_string(),
+        display_name: view_name.to_string(),
+        description: None,
+        definition: definition.to_string(),
+        materialized,
+        refresh_interval_seconds: None,
+    }
+}
+
+#[tokio::test]
+async fn view_reads_apply_the_base_table_policies() {
+    let Some(db) = connect().await else { return };
+    let owner = create_user(&db).await;
+    let user_id = owner.id.to_string();
+
+    let project = services::create_project(
+        &db,
+        &user_id,
+        CreateProjectRequest { name: format!("rls {}", Uuid::new_v4().simple()), description: None },
+    )
+    .await
+    .unwrap();
+
+    services::create_project_table(
+        &db,
+        &user_id,
+        &project.slug,
+        CreateTableRequest {
+            table_name: "notes".to_string(),
+            display_name: "Notes".to_string(),
+            description: None,
+            columns: vec![column("owner", ColumnDataType::Uuid), column("body", ColumnDataType::Text)],
+        },
+    )
+    .await
+    .unwrap();
+
+    let project_id = Uuid::parse_str(&project.id).unwrap();
+    db.execute(Statement::from_sql_and_values(
+        DbBackend::Postgres,
+        format!(
+            "INSERT INTO \"project_{}_notes\" (owner, body) VALUES ($1, 'mine'), (gen_random_uuid(), 'theirs')",
+            project_id.simple()
+        ),
+        [owner.id.into()],
+    ))
+    .await
+    .unwrap();
+
+    services::create_project_view(&db, &user_id, &project.slug, view("notes_view", "SELECT body FROM notes", false))
+        .await
+        .unwrap();
+    services::create_project_view(&db, &user_id, &project.slug, view("notes_copy", "SELECT body FROM notes", true))
+        .await
+        .unwrap();
+
+    let policy = services::create_table_policy(
+        &db,
+        &user_id,
+        &project.slug,
+        "notes",
+        CreatePolicyRequest {
+            policy_name: "own_notes".to_string(),
+            command: PolicyCommand::Select,
+            using_expression: Some("owner = auth_uid()".to_string()),
+            check_expression: None,
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(policy.warnings.len(), 1, "the materialized view should be reported");
+
+    let principal = user_principal(&owner);
+    let rows = services::query_table(&db, &principal, &project.slug, "notes_view", None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(rows, vec![serde_json::json!({ "body": "mine" })]);
+
+    let materialized = services::query_table(&db, &principal, &project.slug, "notes_copy", None, None, None).await;
+    assert!(matches!(materialized, Err(AppError::Forbidden(_))));
+
+    services::delete_project(&db, &user_id, &project.slug).await.unwrap();
+    owner.delete(&db).await.unwrap();
+}
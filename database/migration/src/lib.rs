@@ -4,6 +4,29 @@ pub use sea_orm_migration::prelude::*;
 mod m002_create_users_table;
 mod m003_create_projects_table;
 mod m004_create_project_tables;
+mod m005_create_project_views;
+mod m006_create_project_functions;
+mod m007_create_project_policies;
+mod m008_add_project_statement_timeout;
+mod m009_create_saved_queries;
+mod m010_create_refresh_tokens;
+mod m011_create_revoked_tokens;
+mod m012_create_audit_logs;
+mod m013_create_password_reset_tokens;
+mod m014_create_email_verification_tokens;
+mod m015_create_project_api_keys;
+mod m016_create_project_members;
+mod m017_create_project_invitations;
+mod m018_create_identities;
+mod m019_create_oidc_login_states;
+mod m020_create_totp_factors;
+mod m021_create_mfa_recovery_codes;
+mod m022_create_mfa_challenges;
+mod m023_add_refresh_token_amr;
+mod m024_create_login_throttles;
+mod m025_secure_project_views;
+mod m026_create_project_roles;
+mod m027_sync_project_owners;
 
 pub struct Migrator;
 
@@ -14,6 +37,29 @@ impl MigratorTrait for Migrator {
             Box::new(m002_create_users_table::Migration),
             Box::new(m003_create_projects_table::Migration),
             Box::new(m004_create_project_tables::Migration),
+            Box::new(m005_create_project_views::Migration),
+            Box::new(m006_create_project_functions::Migration),
+            Box::new(m007_create_project_policies::Migration),
+            Box::new(m008_add_project_statement_timeout::Migration),
+            Box::new(m009_create_saved_queries::Migration),
+            Box::new(m010_create_refresh_tokens::Migration),
+            Box::new(m011_create_revoked_tokens::Migration),
+            Box::new(m012_create_audit_logs::Migration),
+            Box::new(m013_create_password_reset_tokens::Migration),
+            Box::new(m014_create_email_verification_tokens::Migration),
+            Box::new(m015_create_project_api_keys::Migration),
+            Box::new(m016_create_project_members::Migration),
+            Box::new(m017_create_project_invitations::Migration),
+            Box::new(m018_create_identities::Migration),
+            Box::new(m019_create_oidc_login_states::Migration),
+            Box::new(m020_create_totp_factors::Migration),
+            Box::new(m021_create_mfa_recovery_codes::Migration),
+            Box::new(m022_create_mfa_challenges::Migration),
+            Box::new(m023_add_refresh_token_amr::Migration),
+            Box::new(m024_create_login_throttles::Migration),
+            Box::new(m025_secure_project_views::Migration),
+            Box::new(m026_create_project_roles::Migration),
+            Box::new(m027_sync_project_owners::Migration),
         ]
     }
 }  
\ No newline at end of file
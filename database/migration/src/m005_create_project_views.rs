@@ -0,0 +1,87 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create project_views table to track user-defined views and materialized views
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProjectViews::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ProjectViews::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(ProjectViews::ProjectId).uuid().not_null())
+                    .col(ColumnDef::new(ProjectViews::ViewName).string().not_null())
+                    .col(ColumnDef::new(ProjectViews::DisplayName).string().not_null())
+                    .col(ColumnDef::new(ProjectViews::Description).text())
+                    .col(ColumnDef::new(ProjectViews::Definition).text().not_null())
+                    .col(ColumnDef::new(ProjectViews::IsMaterialized).boolean().not_null().default(false))
+                    .col(ColumnDef::new(ProjectViews::RefreshIntervalSeconds).integer())
+                    .col(ColumnDef::new(ProjectViews::LastRefreshedAt).timestamp())
+                    .col(ColumnDef::new(ProjectViews::CreatedAt).timestamp().default(Expr::current_timestamp()))
+                    .col(ColumnDef::new(ProjectViews::UpdatedAt).timestamp().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_project_views_project")
+                            .from(ProjectViews::Table, ProjectViews::ProjectId)
+                            .to(Projects::Table, Projects::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create unique constraint on project_id + view_name
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_project_views_unique")
+                    .table(ProjectViews::Table)
+                    .col(ProjectViews::ProjectId)
+                    .col(ProjectViews::ViewName)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ProjectViews::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ProjectViews {
+    Table,
+    Id,
+    ProjectId,
+    ViewName,
+    DisplayName,
+    Description,
+    Definition,
+    IsMaterialized,
+    RefreshIntervalSeconds,
+    LastRefreshedAt,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Projects {
+    Table,
+    Id,
+}
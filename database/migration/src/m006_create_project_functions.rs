@@ -0,0 +1,85 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create project_functions table to track user-defined functions exposed over RPC
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProjectFunctions::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ProjectFunctions::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(ProjectFunctions::ProjectId).uuid().not_null())
+                    .col(ColumnDef::new(ProjectFunctions::FunctionName).string().not_null())
+                    .col(ColumnDef::new(ProjectFunctions::Description).text())
+                    .col(ColumnDef::new(ProjectFunctions::Arguments).text().not_null())
+                    .col(ColumnDef::new(ProjectFunctions::ReturnType).string().not_null())
+                    .col(ColumnDef::new(ProjectFunctions::Language).string().not_null())
+                    .col(ColumnDef::new(ProjectFunctions::Definition).text().not_null())
+                    .col(ColumnDef::new(ProjectFunctions::CreatedAt).timestamp().default(Expr::current_timestamp()))
+                    .col(ColumnDef::new(ProjectFunctions::UpdatedAt).timestamp().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_project_functions_project")
+                            .from(ProjectFunctions::Table, ProjectFunctions::ProjectId)
+                            .to(Projects::Table, Projects::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create unique constraint on project_id + function_name
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_project_functions_unique")
+                    .table(ProjectFunctions::Table)
+                    .col(ProjectFunctions::ProjectId)
+                    .col(ProjectFunctions::FunctionName)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ProjectFunctions::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ProjectFunctions {
+    Table,
+    Id,
+    ProjectId,
+    FunctionName,
+    Description,
+    Arguments,
+    ReturnType,
+    Language,
+    Definition,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Projects {
+    Table,
+    Id,
+}
@@ -0,0 +1,122 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create project_policies table to track row-level security policies on project tables
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProjectPolicies::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ProjectPolicies::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(ProjectPolicies::ProjectTableId).uuid().not_null())
+                    .col(ColumnDef::new(ProjectPolicies::PolicyName).string().not_null())
+                    .col(ColumnDef::new(ProjectPolicies::Command).string().not_null())
+                    .col(ColumnDef::new(ProjectPolicies::UsingExpression).text())
+                    .col(ColumnDef::new(ProjectPolicies::CheckExpression).text())
+                    .col(ColumnDef::new(ProjectPolicies::CreatedAt).timestamp().default(Expr::current_timestamp()))
+                    .col(ColumnDef::new(ProjectPolicies::UpdatedAt).timestamp().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_project_policies_table")
+                            .from(ProjectPolicies::Table, ProjectPolicies::ProjectTableId)
+                            .to(ProjectTables::Table, ProjectTables::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create unique constraint on project_table_id + policy_name
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_project_policies_unique")
+                    .table(ProjectPolicies::Table)
+                    .col(ProjectPolicies::ProjectTableId)
+                    .col(ProjectPolicies::PolicyName)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Policies apply to this role; data API requests switch to it for tables with RLS enabled,
+        // since the connection's own role owns the tables and bypasses their policies
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                DO $$
+                BEGIN
+                    IF NOT EXISTS (SELECT 1 FROM pg_roles WHERE rolname = 'ferrisbase_authenticated') THEN
+                        CREATE ROLE ferrisbase_authenticated NOLOGIN;
+                    END IF;
+                END
+                $$;
+                GRANT ferrisbase_authenticated TO CURRENT_USER;
+                "#,
+            )
+            .await?;
+
+        // Helper for policy expressions: the calling user's ID from the request claims
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                CREATE OR REPLACE FUNCTION auth_uid() RETURNS uuid
+                LANGUAGE sql STABLE
+                AS $$ SELECT nullif(current_setting('request.jwt.claims', true)::jsonb ->> 'sub', '')::uuid $$;
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                DROP FUNCTION IF EXISTS auth_uid();
+                DROP OWNED BY ferrisbase_authenticated;
+                DROP ROLE IF EXISTS ferrisbase_authenticated;
+                "#,
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(ProjectPolicies::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ProjectPolicies {
+    Table,
+    Id,
+    ProjectTableId,
+    PolicyName,
+    Command,
+    UsingExpression,
+    CheckExpression,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum ProjectTables {
+    Table,
+    Id,
+}
@@ -0,0 +1,169 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create saved_queries table for named, parameterized SQL editor scripts
+        manager
+            .create_table(
+                Table::create()
+                    .table(SavedQueries::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(SavedQueries::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(SavedQueries::ProjectId).uuid().not_null())
+                    .col(ColumnDef::new(SavedQueries::Name).string().not_null())
+                    .col(ColumnDef::new(SavedQueries::Description).text())
+                    .col(ColumnDef::new(SavedQueries::Query).text().not_null())
+                    .col(ColumnDef::new(SavedQueries::Parameters).text().not_null())
+                    .col(ColumnDef::new(SavedQueries::CreatedAt).timestamp().default(Expr::current_timestamp()))
+                    .col(ColumnDef::new(SavedQueries::UpdatedAt).timestamp().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_saved_queries_project")
+                            .from(SavedQueries::Table, SavedQueries::ProjectId)
+                            .to(Projects::Table, Projects::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create unique constraint on project_id + name
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_saved_queries_unique")
+                    .table(SavedQueries::Table)
+                    .col(SavedQueries::ProjectId)
+                    .col(SavedQueries::Name)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        // Create query_history table recording each SQL editor execution per user
+        manager
+            .create_table(
+                Table::create()
+                    .table(QueryHistory::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(QueryHistory::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(QueryHistory::ProjectId).uuid().not_null())
+                    .col(ColumnDef::new(QueryHistory::UserId).uuid().not_null())
+                    .col(ColumnDef::new(QueryHistory::SavedQueryId).uuid())
+                    .col(ColumnDef::new(QueryHistory::Query).text().not_null())
+                    .col(ColumnDef::new(QueryHistory::StatementCount).integer().not_null())
+                    .col(ColumnDef::new(QueryHistory::RowCount).big_integer())
+                    .col(ColumnDef::new(QueryHistory::ExecutionTimeMs).big_integer().not_null())
+                    .col(ColumnDef::new(QueryHistory::Error).text())
+                    .col(ColumnDef::new(QueryHistory::ExecutedAt).timestamp().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_query_history_project")
+                            .from(QueryHistory::Table, QueryHistory::ProjectId)
+                            .to(Projects::Table, Projects::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade)
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_query_history_user")
+                            .from(QueryHistory::Table, QueryHistory::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade)
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_query_history_saved_query")
+                            .from(QueryHistory::Table, QueryHistory::SavedQueryId)
+                            .to(SavedQueries::Table, SavedQueries::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // History is listed newest first per user and project
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_query_history_user_project")
+                    .table(QueryHistory::Table)
+                    .col(QueryHistory::UserId)
+                    .col(QueryHistory::ProjectId)
+                    .col(QueryHistory::ExecutedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(QueryHistory::Table).to_owned())
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(SavedQueries::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum SavedQueries {
+    Table,
+    Id,
+    ProjectId,
+    Name,
+    Description,
+    Query,
+    Parameters,
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(DeriveIden)]
+enum QueryHistory {
+    Table,
+    Id,
+    ProjectId,
+    UserId,
+    SavedQueryId,
+    Query,
+    StatementCount,
+    RowCount,
+    ExecutionTimeMs,
+    Error,
+    ExecutedAt,
+}
+
+#[derive(DeriveIden)]
+enum Projects {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,83 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create revoked_tokens table listing access tokens logged out before they expire
+        manager
+            .create_table(
+                Table::create()
+                    .table(RevokedTokens::Table)
+                    .if_not_exists()
+                    .col(ColumnDef::new(RevokedTokens::Jti).uuid().not_null().primary_key())
+                    .col(ColumnDef::new(RevokedTokens::UserId).uuid().not_null())
+                    .col(ColumnDef::new(RevokedTokens::ExpiresAt).timestamp().not_null())
+                    .col(ColumnDef::new(RevokedTokens::RevokedAt).timestamp().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_revoked_tokens_user")
+                            .from(RevokedTokens::Table, RevokedTokens::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // Entries are pruned once the token would have expired anyway
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_revoked_tokens_expires_at")
+                    .table(RevokedTokens::Table)
+                    .col(RevokedTokens::ExpiresAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Tokens issued before this time are rejected, which logs a user out everywhere
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .add_column_if_not_exists(ColumnDef::new(Users::TokensRevokedAt).timestamp())
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .alter_table(
+                Table::alter()
+                    .table(Users::Table)
+                    .drop_column(Users::TokensRevokedAt)
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .drop_table(Table::drop().table(RevokedTokens::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum RevokedTokens {
+    Table,
+    Jti,
+    UserId,
+    ExpiresAt,
+    RevokedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+    TokensRevokedAt,
+}
@@ -0,0 +1,74 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create email_verification_tokens table holding hashed, single-use email verification tokens
+        manager
+            .create_table(
+                Table::create()
+                    .table(EmailVerificationTokens::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(EmailVerificationTokens::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(EmailVerificationTokens::UserId).uuid().not_null())
+                    .col(ColumnDef::new(EmailVerificationTokens::TokenHash).string().not_null().unique_key())
+                    .col(ColumnDef::new(EmailVerificationTokens::ExpiresAt).timestamp().not_null())
+                    .col(ColumnDef::new(EmailVerificationTokens::UsedAt).timestamp())
+                    .col(ColumnDef::new(EmailVerificationTokens::CreatedAt).timestamp().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_email_verification_tokens_user")
+                            .from(EmailVerificationTokens::Table, EmailVerificationTokens::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_email_verification_tokens_user")
+                    .table(EmailVerificationTokens::Table)
+                    .col(EmailVerificationTokens::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(EmailVerificationTokens::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum EmailVerificationTokens {
+    Table,
+    Id,
+    UserId,
+    TokenHash,
+    ExpiresAt,
+    UsedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
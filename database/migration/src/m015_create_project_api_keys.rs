@@ -0,0 +1,96 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create project_api_keys table holding hashed anon and service keys per project
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProjectApiKeys::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ProjectApiKeys::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(ProjectApiKeys::ProjectId).uuid().not_null())
+                    .col(ColumnDef::new(ProjectApiKeys::Name).string().not_null())
+                    .col(ColumnDef::new(ProjectApiKeys::Kind).string().not_null())
+                    .col(ColumnDef::new(ProjectApiKeys::KeyPrefix).string().not_null())
+                    .col(ColumnDef::new(ProjectApiKeys::KeyHash).string().not_null().unique_key())
+                    .col(ColumnDef::new(ProjectApiKeys::CreatedBy).uuid())
+                    .col(ColumnDef::new(ProjectApiKeys::LastUsedAt).timestamp())
+                    .col(ColumnDef::new(ProjectApiKeys::RevokedAt).timestamp())
+                    .col(ColumnDef::new(ProjectApiKeys::CreatedAt).timestamp().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_project_api_keys_project")
+                            .from(ProjectApiKeys::Table, ProjectApiKeys::ProjectId)
+                            .to(Projects::Table, Projects::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade)
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_project_api_keys_created_by")
+                            .from(ProjectApiKeys::Table, ProjectApiKeys::CreatedBy)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_project_api_keys_project")
+                    .table(ProjectApiKeys::Table)
+                    .col(ProjectApiKeys::ProjectId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ProjectApiKeys::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ProjectApiKeys {
+    Table,
+    Id,
+    ProjectId,
+    Name,
+    Kind,
+    KeyPrefix,
+    KeyHash,
+    CreatedBy,
+    LastUsedAt,
+    RevokedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Projects {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
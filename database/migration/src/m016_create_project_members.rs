@@ -0,0 +1,109 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create project_members table granting users a role in a project
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProjectMembers::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ProjectMembers::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(ProjectMembers::ProjectId).uuid().not_null())
+                    .col(ColumnDef::new(ProjectMembers::UserId).uuid().not_null())
+                    .col(ColumnDef::new(ProjectMembers::Role).string().not_null())
+                    .col(ColumnDef::new(ProjectMembers::CreatedAt).timestamp().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_project_members_project")
+                            .from(ProjectMembers::Table, ProjectMembers::ProjectId)
+                            .to(Projects::Table, Projects::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade)
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_project_members_user")
+                            .from(ProjectMembers::Table, ProjectMembers::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // A user has one role per project
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_project_members_unique")
+                    .table(ProjectMembers::Table)
+                    .col(ProjectMembers::ProjectId)
+                    .col(ProjectMembers::UserId)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_project_members_user")
+                    .table(ProjectMembers::Table)
+                    .col(ProjectMembers::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        // Existing project owners become owner members
+        manager
+            .get_connection()
+            .execute_unprepared(
+                "INSERT INTO project_members (project_id, user_id, role) \
+                 SELECT id, owner_id, 'owner' FROM projects \
+                 ON CONFLICT (project_id, user_id) DO NOTHING",
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ProjectMembers::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ProjectMembers {
+    Table,
+    Id,
+    ProjectId,
+    UserId,
+    Role,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Projects {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,96 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create project_invitations table; only a hash of each emailed token is stored
+        manager
+            .create_table(
+                Table::create()
+                    .table(ProjectInvitations::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(ProjectInvitations::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(ProjectInvitations::ProjectId).uuid().not_null())
+                    .col(ColumnDef::new(ProjectInvitations::Email).string().not_null())
+                    .col(ColumnDef::new(ProjectInvitations::Role).string().not_null())
+                    .col(ColumnDef::new(ProjectInvitations::TokenHash).string().not_null().unique_key())
+                    .col(ColumnDef::new(ProjectInvitations::InvitedBy).uuid())
+                    .col(ColumnDef::new(ProjectInvitations::ExpiresAt).timestamp().not_null())
+                    .col(ColumnDef::new(ProjectInvitations::AcceptedAt).timestamp())
+                    .col(ColumnDef::new(ProjectInvitations::DeclinedAt).timestamp())
+                    .col(ColumnDef::new(ProjectInvitations::CreatedAt).timestamp().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_project_invitations_project")
+                            .from(ProjectInvitations::Table, ProjectInvitations::ProjectId)
+                            .to(Projects::Table, Projects::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade)
+                    )
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_project_invitations_invited_by")
+                            .from(ProjectInvitations::Table, ProjectInvitations::InvitedBy)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::SetNull)
+                            .on_update(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_project_invitations_project")
+                    .table(ProjectInvitations::Table)
+                    .col(ProjectInvitations::ProjectId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(ProjectInvitations::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum ProjectInvitations {
+    Table,
+    Id,
+    ProjectId,
+    Email,
+    Role,
+    TokenHash,
+    InvitedBy,
+    ExpiresAt,
+    AcceptedAt,
+    DeclinedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Projects {
+    Table,
+    Id,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
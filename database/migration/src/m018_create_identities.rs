@@ -0,0 +1,89 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create identities table linking users to accounts at external OpenID Connect providers
+        manager
+            .create_table(
+                Table::create()
+                    .table(Identities::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(Identities::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(Identities::UserId).uuid().not_null())
+                    .col(ColumnDef::new(Identities::Provider).string().not_null())
+                    .col(ColumnDef::new(Identities::Subject).string().not_null())
+                    .col(ColumnDef::new(Identities::Email).string())
+                    .col(ColumnDef::new(Identities::CreatedAt).timestamp().default(Expr::current_timestamp()))
+                    .col(ColumnDef::new(Identities::LastSignInAt).timestamp())
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_identities_user")
+                            .from(Identities::Table, Identities::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        // A provider account belongs to one user
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_identities_provider_subject")
+                    .table(Identities::Table)
+                    .col(Identities::Provider)
+                    .col(Identities::Subject)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_identities_user")
+                    .table(Identities::Table)
+                    .col(Identities::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(Identities::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum Identities {
+    Table,
+    Id,
+    UserId,
+    Provider,
+    Subject,
+    Email,
+    CreatedAt,
+    LastSignInAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
@@ -0,0 +1,53 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create oidc_login_states table holding the PKCE verifier and nonce of each
+        // sign-in in progress; only a hash of the `state` parameter is stored
+        manager
+            .create_table(
+                Table::create()
+                    .table(OidcLoginStates::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(OidcLoginStates::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(OidcLoginStates::Provider).string().not_null())
+                    .col(ColumnDef::new(OidcLoginStates::StateHash).string().not_null().unique_key())
+                    .col(ColumnDef::new(OidcLoginStates::CodeVerifier).string().not_null())
+                    .col(ColumnDef::new(OidcLoginStates::Nonce).string().not_null())
+                    .col(ColumnDef::new(OidcLoginStates::ExpiresAt).timestamp().not_null())
+                    .col(ColumnDef::new(OidcLoginStates::CreatedAt).timestamp().default(Expr::current_timestamp()))
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(OidcLoginStates::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum OidcLoginStates {
+    Table,
+    Id,
+    Provider,
+    StateHash,
+    CodeVerifier,
+    Nonce,
+    ExpiresAt,
+    CreatedAt,
+}
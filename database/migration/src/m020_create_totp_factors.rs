@@ -0,0 +1,62 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create totp_factors table holding each user's authenticator app secret
+        manager
+            .create_table(
+                Table::create()
+                    .table(TotpFactors::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(TotpFactors::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(TotpFactors::UserId).uuid().not_null().unique_key())
+                    .col(ColumnDef::new(TotpFactors::Secret).string().not_null())
+                    .col(ColumnDef::new(TotpFactors::ConfirmedAt).timestamp())
+                    .col(ColumnDef::new(TotpFactors::LastUsedStep).big_integer())
+                    .col(ColumnDef::new(TotpFactors::CreatedAt).timestamp().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_totp_factors_user")
+                            .from(TotpFactors::Table, TotpFactors::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(TotpFactors::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum TotpFactors {
+    Table,
+    Id,
+    UserId,
+    Secret,
+    ConfirmedAt,
+    LastUsedStep,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
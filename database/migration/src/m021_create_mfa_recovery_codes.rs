@@ -0,0 +1,72 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create mfa_recovery_codes table holding hashed, single-use recovery codes
+        manager
+            .create_table(
+                Table::create()
+                    .table(MfaRecoveryCodes::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MfaRecoveryCodes::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(MfaRecoveryCodes::UserId).uuid().not_null())
+                    .col(ColumnDef::new(MfaRecoveryCodes::CodeHash).string().not_null())
+                    .col(ColumnDef::new(MfaRecoveryCodes::UsedAt).timestamp())
+                    .col(ColumnDef::new(MfaRecoveryCodes::CreatedAt).timestamp().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_mfa_recovery_codes_user")
+                            .from(MfaRecoveryCodes::Table, MfaRecoveryCodes::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_mfa_recovery_codes_user")
+                    .table(MfaRecoveryCodes::Table)
+                    .col(MfaRecoveryCodes::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MfaRecoveryCodes::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum MfaRecoveryCodes {
+    Table,
+    Id,
+    UserId,
+    CodeHash,
+    UsedAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
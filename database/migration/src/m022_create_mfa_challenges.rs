@@ -0,0 +1,76 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create mfa_challenges table for sign-ins waiting on a second factor
+        manager
+            .create_table(
+                Table::create()
+                    .table(MfaChallenges::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(MfaChallenges::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(MfaChallenges::UserId).uuid().not_null())
+                    .col(ColumnDef::new(MfaChallenges::TokenHash).string().not_null().unique_key())
+                    .col(ColumnDef::new(MfaChallenges::Amr).string().not_null())
+                    .col(ColumnDef::new(MfaChallenges::Attempts).integer().not_null().default(0))
+                    .col(ColumnDef::new(MfaChallenges::ExpiresAt).timestamp().not_null())
+                    .col(ColumnDef::new(MfaChallenges::CreatedAt).timestamp().default(Expr::current_timestamp()))
+                    .foreign_key(
+                        ForeignKey::create()
+                            .name("fk_mfa_challenges_user")
+                            .from(MfaChallenges::Table, MfaChallenges::UserId)
+                            .to(Users::Table, Users::Id)
+                            .on_delete(ForeignKeyAction::Cascade)
+                            .on_update(ForeignKeyAction::Cascade)
+                    )
+                    .to_owned(),
+            )
+            .await?;
+
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_mfa_challenges_user")
+                    .table(MfaChallenges::Table)
+                    .col(MfaChallenges::UserId)
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(MfaChallenges::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum MfaChallenges {
+    Table,
+    Id,
+    UserId,
+    TokenHash,
+    Amr,
+    Attempts,
+    ExpiresAt,
+    CreatedAt,
+}
+
+#[derive(DeriveIden)]
+enum Users {
+    Table,
+    Id,
+}
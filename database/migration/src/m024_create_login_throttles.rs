@@ -0,0 +1,63 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Create login_throttles table counting failed sign-ins per account and per IP address
+        manager
+            .create_table(
+                Table::create()
+                    .table(LoginThrottles::Table)
+                    .if_not_exists()
+                    .col(
+                        ColumnDef::new(LoginThrottles::Id)
+                            .uuid()
+                            .not_null()
+                            .primary_key()
+                            .default(Expr::cust("gen_random_uuid()"))
+                    )
+                    .col(ColumnDef::new(LoginThrottles::Scope).string().not_null())
+                    .col(ColumnDef::new(LoginThrottles::Identifier).string().not_null())
+                    .col(ColumnDef::new(LoginThrottles::FailedAttempts).integer().not_null().default(0))
+                    .col(ColumnDef::new(LoginThrottles::LockedUntil).timestamp())
+                    .col(ColumnDef::new(LoginThrottles::LastFailedAt).timestamp().not_null())
+                    .to_owned(),
+            )
+            .await?;
+
+        // Failures are counted with an upsert on this pair
+        manager
+            .create_index(
+                Index::create()
+                    .name("idx_login_throttles_scope_identifier")
+                    .table(LoginThrottles::Table)
+                    .col(LoginThrottles::Scope)
+                    .col(LoginThrottles::Identifier)
+                    .unique()
+                    .to_owned(),
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .drop_table(Table::drop().table(LoginThrottles::Table).to_owned())
+            .await
+    }
+}
+
+#[derive(DeriveIden)]
+enum LoginThrottles {
+    Table,
+    Id,
+    Scope,
+    Identifier,
+    FailedAttempts,
+    LockedUntil,
+    LastFailedAt,
+}
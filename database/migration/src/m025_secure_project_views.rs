@@ -0,0 +1,74 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Views read their tables with the privileges of the role querying them, so the
+        // policies of the tables apply to view reads too. The policy role can reach every
+        // project table and view; the policies decide which rows it sees.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                DO $$
+                DECLARE
+                    relation record;
+                BEGIN
+                    FOR relation IN
+                        SELECT 'project_' || replace(project_id::text, '-', '') || '_' || table_name AS name,
+                               false AS is_view, false AS is_materialized
+                        FROM project_tables
+                        UNION ALL
+                        SELECT 'project_' || replace(project_id::text, '-', '') || '_' || view_name,
+                               true, is_materialized
+                        FROM project_views
+                    LOOP
+                        CONTINUE WHEN to_regclass(quote_ident(relation.name)) IS NULL;
+
+                        IF NOT relation.is_view THEN
+                            EXECUTE format('GRANT SELECT, INSERT, UPDATE, DELETE ON %I TO ferrisbase_authenticated', relation.name);
+                        ELSE
+                            EXECUTE format('GRANT SELECT ON %I TO ferrisbase_authenticated', relation.name);
+                            IF NOT relation.is_materialized THEN
+                                EXECUTE format('ALTER VIEW %I SET (security_invoker = true)', relation.name);
+                            END IF;
+                        END IF;
+                    END LOOP;
+                END
+                $$;
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                DO $$
+                DECLARE
+                    relation_name text;
+                BEGIN
+                    FOR relation_name IN
+                        SELECT 'project_' || replace(project_id::text, '-', '') || '_' || view_name
+                        FROM project_views
+                        WHERE NOT is_materialized
+                    LOOP
+                        CONTINUE WHEN to_regclass(quote_ident(relation_name)) IS NULL;
+                        EXECUTE format('ALTER VIEW %I RESET (security_invoker)', relation_name);
+                    END LOOP;
+                END
+                $$;
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
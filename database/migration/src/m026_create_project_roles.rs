@@ -0,0 +1,80 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Each project gets a role that owns its tables, views and functions. SQL editor
+        // scripts run as that role, so they cannot reach the platform's own tables or
+        // other projects. The connection's role is a member so it can still manage them.
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                DO $$
+                DECLARE
+                    project record;
+                    object record;
+                    role_name text;
+                BEGIN
+                    FOR project IN SELECT id FROM projects LOOP
+                        role_name := 'ferrisbase_project_' || replace(project.id::text, '-', '');
+
+                        IF NOT EXISTS (SELECT 1 FROM pg_roles WHERE rolname = role_name) THEN
+                            EXECUTE format('CREATE ROLE %I NOLOGIN', role_name);
+                        END IF;
+                        EXECUTE format('GRANT %I TO CURRENT_USER', role_name);
+
+                        FOR object IN
+                            SELECT format('ALTER %s %I OWNER TO %I',
+                                          CASE c.relkind WHEN 'v' THEN 'VIEW' WHEN 'm' THEN 'MATERIALIZED VIEW' ELSE 'TABLE' END,
+                                          c.relname, role_name) AS statement
+                            FROM pg_class c
+                            WHERE c.relnamespace = current_schema()::regnamespace
+                              AND c.relkind IN ('r', 'p', 'v', 'm')
+                              AND starts_with(c.relname, 'project_' || replace(project.id::text, '-', '') || '_')
+                            UNION ALL
+                            SELECT format('ALTER FUNCTION %s OWNER TO %I', p.oid::regprocedure, role_name)
+                            FROM pg_proc p
+                            WHERE p.pronamespace = current_schema()::regnamespace
+                              AND starts_with(p.proname, 'project_' || replace(project.id::text, '-', '') || '_')
+                        LOOP
+                            EXECUTE object.statement;
+                        END LOOP;
+                    END LOOP;
+                END
+                $$;
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                DO $$
+                DECLARE
+                    role_name text;
+                BEGIN
+                    FOR role_name IN
+                        SELECT rolname FROM pg_roles WHERE starts_with(rolname, 'ferrisbase_project_')
+                    LOOP
+                        EXECUTE format('REASSIGN OWNED BY %I TO CURRENT_USER', role_name);
+                        EXECUTE format('DROP OWNED BY %I', role_name);
+                        EXECUTE format('DROP ROLE %I', role_name);
+                    END LOOP;
+                END
+                $$;
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,42 @@
+use sea_orm_migration::prelude::*;
+
+#[derive(DeriveMigrationName)]
+pub struct Migration;
+
+#[async_trait::async_trait]
+impl MigrationTrait for Migration {
+    async fn up(&self, manager: &SchemaManager) -> Result<(), DbErr> {
+        // Owners demoted or removed before projects.owner_id followed membership changes
+        // left it pointing at them; point it at the longest-standing remaining owner
+        manager
+            .get_connection()
+            .execute_unprepared(
+                r#"
+                UPDATE projects
+                SET owner_id = (
+                    SELECT m.user_id
+                    FROM project_members m
+                    WHERE m.project_id = projects.id AND m.role = 'owner'
+                    ORDER BY m.created_at
+                    LIMIT 1
+                )
+                WHERE NOT EXISTS (
+                    SELECT 1 FROM project_members m
+                    WHERE m.project_id = projects.id AND m.user_id = projects.owner_id AND m.role = 'owner'
+                )
+                AND EXISTS (
+                    SELECT 1 FROM project_members m
+                    WHERE m.project_id = projects.id AND m.role = 'owner'
+                )
+                "#,
+            )
+            .await?;
+
+        Ok(())
+    }
+
+    async fn down(&self, _manager: &SchemaManager) -> Result<(), DbErr> {
+        // The previous owners are not recorded anywhere, and the new ones are correct
+        Ok(())
+    }
+}
@@ -2,9 +2,28 @@
 
 pub mod prelude;
 
+pub mod audit_logs;
 pub mod contacts;
+pub mod email_verification_tokens;
+pub mod identities;
+pub mod login_throttles;
+pub mod mfa_challenges;
+pub mod mfa_recovery_codes;
+pub mod oidc_login_states;
+pub mod password_reset_tokens;
+pub mod project_api_keys;
 pub mod project_columns;
+pub mod project_functions;
+pub mod project_invitations;
+pub mod project_members;
+pub mod project_policies;
 pub mod project_tables;
+pub mod project_views;
 pub mod projects;
+pub mod query_history;
+pub mod refresh_tokens;
+pub mod revoked_tokens;
+pub mod saved_queries;
 pub mod sea_orm_active_enums;
+pub mod totp_factors;
 pub mod users;
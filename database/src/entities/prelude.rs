@@ -1,7 +1,26 @@
 //! `SeaORM` Entity, @generated by sea-orm-codegen 1.1.19
 
+pub use super::audit_logs::Entity as AuditLogs;
 pub use super::contacts::Entity as Contacts;
+pub use super::email_verification_tokens::Entity as EmailVerificationTokens;
+pub use super::identities::Entity as Identities;
+pub use super::login_throttles::Entity as LoginThrottles;
+pub use super::mfa_challenges::Entity as MfaChallenges;
+pub use super::mfa_recovery_codes::Entity as MfaRecoveryCodes;
+pub use super::oidc_login_states::Entity as OidcLoginStates;
+pub use super::password_reset_tokens::Entity as PasswordResetTokens;
+pub use super::project_api_keys::Entity as ProjectApiKeys;
 pub use super::project_columns::Entity as ProjectColumns;
+pub use super::project_functions::Entity as ProjectFunctions;
+pub use super::project_invitations::Entity as ProjectInvitations;
+pub use super::project_members::Entity as ProjectMembers;
+pub use super::project_policies::Entity as ProjectPolicies;
 pub use super::project_tables::Entity as ProjectTables;
+pub use super::project_views::Entity as ProjectViews;
 pub use super::projects::Entity as Projects;
+pub use super::query_history::Entity as QueryHistory;
+pub use super::refresh_tokens::Entity as RefreshTokens;
+pub use super::revoked_tokens::Entity as RevokedTokens;
+pub use super::saved_queries::Entity as SavedQueries;
+pub use super::totp_factors::Entity as TotpFactors;
 pub use super::users::Entity as Users;
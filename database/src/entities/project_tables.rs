@@ -21,6 +21,8 @@ pub struct Model {
 pub enum Relation {
     #[sea_orm(has_many = "super::project_columns::Entity")]
     ProjectColumns,
+    #[sea_orm(has_many = "super::project_policies::Entity")]
+    ProjectPolicies,
     #[sea_orm(
         belongs_to = "super::projects::Entity",
         from = "Column::ProjectId",
@@ -37,6 +39,12 @@ impl Related<super::project_columns::Entity> for Entity {
     }
 }
 
+impl Related<super::project_policies::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProjectPolicies.def()
+    }
+}
+
 impl Related<super::projects::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Projects.def()
@@ -14,14 +14,29 @@ pub struct Model {
     pub slug: String,
     pub owner_id: Uuid,
     pub database_status: Option<String>,
+    pub statement_timeout_ms: i32,
     pub created_at: Option<DateTime>,
     pub updated_at: Option<DateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::project_api_keys::Entity")]
+    ProjectApiKeys,
+    #[sea_orm(has_many = "super::project_functions::Entity")]
+    ProjectFunctions,
+    #[sea_orm(has_many = "super::project_invitations::Entity")]
+    ProjectInvitations,
+    #[sea_orm(has_many = "super::project_members::Entity")]
+    ProjectMembers,
     #[sea_orm(has_many = "super::project_tables::Entity")]
     ProjectTables,
+    #[sea_orm(has_many = "super::project_views::Entity")]
+    ProjectViews,
+    #[sea_orm(has_many = "super::query_history::Entity")]
+    QueryHistory,
+    #[sea_orm(has_many = "super::saved_queries::Entity")]
+    SavedQueries,
     #[sea_orm(
         belongs_to = "super::users::Entity",
         from = "Column::OwnerId",
@@ -32,12 +47,54 @@ pub enum Relation {
     Users,
 }
 
+impl Related<super::project_api_keys::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProjectApiKeys.def()
+    }
+}
+
+impl Related<super::project_functions::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProjectFunctions.def()
+    }
+}
+
+impl Related<super::project_invitations::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProjectInvitations.def()
+    }
+}
+
+impl Related<super::project_members::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProjectMembers.def()
+    }
+}
+
 impl Related<super::project_tables::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::ProjectTables.def()
     }
 }
 
+impl Related<super::project_views::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProjectViews.def()
+    }
+}
+
+impl Related<super::query_history::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::QueryHistory.def()
+    }
+}
+
+impl Related<super::saved_queries::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::SavedQueries.def()
+    }
+}
+
 impl Related<super::users::Entity> for Entity {
     fn to() -> RelationDef {
         Relation::Users.def()
@@ -16,12 +16,85 @@ pub struct Model {
     pub is_active: Option<bool>,
     pub created_at: Option<DateTime>,
     pub updated_at: Option<DateTime>,
+    pub tokens_revoked_at: Option<DateTime>,
 }
 
 #[derive(Copy, Clone, Debug, EnumIter, DeriveRelation)]
 pub enum Relation {
+    #[sea_orm(has_many = "super::email_verification_tokens::Entity")]
+    EmailVerificationTokens,
+    #[sea_orm(has_many = "super::identities::Entity")]
+    Identities,
+    #[sea_orm(has_many = "super::mfa_challenges::Entity")]
+    MfaChallenges,
+    #[sea_orm(has_many = "super::mfa_recovery_codes::Entity")]
+    MfaRecoveryCodes,
+    #[sea_orm(has_many = "super::password_reset_tokens::Entity")]
+    PasswordResetTokens,
+    #[sea_orm(has_many = "super::project_api_keys::Entity")]
+    ProjectApiKeys,
+    #[sea_orm(has_many = "super::project_invitations::Entity")]
+    ProjectInvitations,
+    #[sea_orm(has_many = "super::project_members::Entity")]
+    ProjectMembers,
     #[sea_orm(has_many = "super::projects::Entity")]
     Projects,
+    #[sea_orm(has_many = "super::query_history::Entity")]
+    QueryHistory,
+    #[sea_orm(has_many = "super::refresh_tokens::Entity")]
+    RefreshTokens,
+    #[sea_orm(has_many = "super::revoked_tokens::Entity")]
+    RevokedTokens,
+    #[sea_orm(has_one = "super::totp_factors::Entity")]
+    TotpFactors,
+}
+
+impl Related<super::email_verification_tokens::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::EmailVerificationTokens.def()
+    }
+}
+
+impl Related<super::identities::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::Identities.def()
+    }
+}
+
+impl Related<super::mfa_challenges::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MfaChallenges.def()
+    }
+}
+
+impl Related<super::mfa_recovery_codes::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::MfaRecoveryCodes.def()
+    }
+}
+
+impl Related<super::password_reset_tokens::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::PasswordResetTokens.def()
+    }
+}
+
+impl Related<super::project_api_keys::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProjectApiKeys.def()
+    }
+}
+
+impl Related<super::project_invitations::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProjectInvitations.def()
+    }
+}
+
+impl Related<super::project_members::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::ProjectMembers.def()
+    }
 }
 
 impl Related<super::projects::Entity> for Entity {
@@ -30,4 +103,28 @@ impl Related<super::projects::Entity> for Entity {
     }
 }
 
+impl Related<super::query_history::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::QueryHistory.def()
+    }
+}
+
+impl Related<super::refresh_tokens::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RefreshTokens.def()
+    }
+}
+
+impl Related<super::revoked_tokens::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::RevokedTokens.def()
+    }
+}
+
+impl Related<super::totp_factors::Entity> for Entity {
+    fn to() -> RelationDef {
+        Relation::TotpFactors.def()
+    }
+}
+
 impl ActiveModelBehavior for ActiveModel {}
@@ -7,6 +7,17 @@ pub struct User {
     pub email: String,
     pub name: Option<String>,
     pub role: String,
+    /// Users stored before this field existed are treated as unverified until the next sign-in
+    #[serde(default)]
+    pub email_verified: bool,
+}
+
+/// An admin's own session, put aside while they act as another user
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct AdminSession {
+    pub user: User,
+    pub token: String,
+    pub refresh_token: Option<String>,
 }
 
 /// Authentication state 
@@ -14,6 +25,12 @@ pub struct User {
 pub struct AuthState {
     pub user: Option<User>,
     pub token: Option<String>,
+    /// Single-use token that renews `token` before it expires
+    pub refresh_token: Option<String>,
+    /// Seconds `token` was valid for when issued; unknown after a reload
+    pub expires_in: Option<u64>,
+    /// Set while an admin impersonates `user`
+    pub admin_session: Option<AdminSession>,
 }
 
 impl AuthState {
@@ -21,6 +38,9 @@ impl AuthState {
         Self {
             user: None,
             token: None,
+            refresh_token: None,
+            expires_in: None,
+            admin_session: None,
         }
     }
 
@@ -42,15 +62,103 @@ impl AuthState {
         self.token = Some(token);
     }
 
+    /// Store the refresh token issued with the current access token
+    pub fn set_refresh_token(&mut self, refresh_token: String, expires_in: u64) {
+        let _ = web_sys::window()
+            .and_then(|w| w.local_storage().ok().flatten())
+            .map(|storage| {
+                let _ = storage.set_item("refresh_token", &refresh_token);
+            });
+        self.refresh_token = Some(refresh_token);
+        self.expires_in = Some(expires_in);
+    }
+
+    /// Replace both tokens after a refresh, keeping the signed-in user
+    pub fn refreshed(&mut self, token: String, refresh_token: String, expires_in: u64) {
+        let _ = web_sys::window()
+            .and_then(|w| w.local_storage().ok().flatten())
+            .map(|storage| {
+                let _ = storage.set_item("token", &token);
+            });
+        self.token = Some(token);
+        self.set_refresh_token(refresh_token, expires_in);
+    }
+
+    /// Record that the signed-in user verified their email address
+    pub fn set_email_verified(&mut self) {
+        let Some(user) = self.user.as_mut() else {
+            return;
+        };
+        user.email_verified = true;
+        if let Ok(user_json) = serde_json::to_string(user) {
+            let _ = web_sys::window()
+                .and_then(|w| w.local_storage().ok().flatten())
+                .map(|storage| {
+                    let _ = storage.set_item("user", &user_json);
+                });
+        }
+    }
+
+    /// Act as another user with an impersonation token, keeping the admin's session to return to.
+    ///
+    /// Impersonation tokens cannot be refreshed, so no refresh token is kept meanwhile.
+    pub fn start_impersonation(&mut self, user: User, token: String) {
+        let (Some(admin), Some(admin_token)) = (self.user.clone(), self.token.clone()) else {
+            return;
+        };
+        let admin_session = AdminSession {
+            user: admin,
+            token: admin_token,
+            refresh_token: self.refresh_token.take(),
+        };
+
+        if let Ok(session_json) = serde_json::to_string(&admin_session) {
+            let _ = web_sys::window()
+                .and_then(|w| w.local_storage().ok().flatten())
+                .map(|storage| {
+                    let _ = storage.set_item("admin_session", &session_json);
+                    let _ = storage.remove_item("refresh_token");
+                });
+        }
+        self.admin_session = Some(admin_session);
+        self.expires_in = None;
+        self.login(user, token);
+    }
+
+    /// Go back to the admin's own session after impersonating a user
+    pub fn stop_impersonation(&mut self) {
+        let Some(session) = self.admin_session.take() else {
+            return;
+        };
+
+        let _ = web_sys::window()
+            .and_then(|w| w.local_storage().ok().flatten())
+            .map(|storage| {
+                let _ = storage.remove_item("admin_session");
+                if let Some(refresh_token) = &session.refresh_token {
+                    let _ = storage.set_item("refresh_token", refresh_token);
+                }
+            });
+        self.refresh_token = session.refresh_token;
+        // Renew the admin's token right away, as it may have expired meanwhile
+        self.expires_in = None;
+        self.login(session.user, session.token);
+    }
+
     pub fn logout(&mut self) {
         self.user = None;
         self.token = None;
+        self.refresh_token = None;
+        self.expires_in = None;
+        self.admin_session = None;
         // Clear localStorage
         let _ = web_sys::window()
             .and_then(|w| w.local_storage().ok().flatten())
             .map(|storage| {
                 let _ = storage.remove_item("user");
                 let _ = storage.remove_item("token");
+                let _ = storage.remove_item("refresh_token");
+                let _ = storage.remove_item("admin_session");
             });
     }
 
@@ -63,15 +171,70 @@ impl AuthState {
                 .flatten()
                 .and_then(|json| serde_json::from_str(&json).ok());
             let token = storage.get_item("token").ok().flatten();
+            let refresh_token = storage.get_item("refresh_token").ok().flatten();
+            let admin_session = storage
+                .get_item("admin_session")
+                .ok()
+                .flatten()
+                .and_then(|json| serde_json::from_str(&json).ok());
             
             if user.is_some() && token.is_some() {
-                return Self { user, token };
+                return Self { user, token, refresh_token, expires_in: None, admin_session };
             } 
         }
         Self::new()
     }
 }
 
+/// The refresh token last stored by any tab, since tabs share one session
+pub fn stored_refresh_token() -> Option<String> {
+    web_sys::window()
+        .and_then(|w| w.local_storage().ok().flatten())
+        .and_then(|storage| storage.get_item("refresh_token").ok().flatten())
+}
+
+/// Remember an invitation link opened while signed out, to accept it after signing in
+pub fn set_pending_invitation(token: &str) {
+    let _ = web_sys::window()
+        .and_then(|w| w.local_storage().ok().flatten())
+        .map(|storage| {
+            let _ = storage.set_item("pending_invitation", token);
+        });
+}
+
+/// The invitation remembered by `set_pending_invitation`, if any
+pub fn pending_invitation() -> Option<String> {
+    web_sys::window()
+        .and_then(|w| w.local_storage().ok().flatten())
+        .and_then(|storage| storage.get_item("pending_invitation").ok().flatten())
+}
+
+/// Forget the remembered invitation once it has been answered
+pub fn clear_pending_invitation() {
+    let _ = web_sys::window()
+        .and_then(|w| w.local_storage().ok().flatten())
+        .map(|storage| {
+            let _ = storage.remove_item("pending_invitation");
+        });
+}
+
+/// Remember the `state` of an OpenID Connect sign-in started in this tab
+pub fn set_oidc_state(state: &str) {
+    let _ = web_sys::window()
+        .and_then(|w| w.session_storage().ok().flatten())
+        .map(|storage| {
+            let _ = storage.set_item("oidc_state", state);
+        });
+}
+
+/// The `state` remembered by `set_oidc_state`, forgotten as it is read so it is only used once
+pub fn take_oidc_state() -> Option<String> {
+    let storage = web_sys::window().and_then(|w| w.session_storage().ok().flatten())?;
+    let state = storage.get_item("oidc_state").ok().flatten();
+    let _ = storage.remove_item("oidc_state");
+    state
+}
+
 impl Default for AuthState {
     fn default() -> Self {
         Self::new()
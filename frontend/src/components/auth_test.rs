@@ -25,6 +25,7 @@ pub fn AuthTestButtons() -> Element {
                         email: "demo@ferrisbase.com".to_string(),
                         name: Some("Demo User".to_string()),
                         role: "user".to_string(),
+                        email_verified: true,
                     };
                     auth_state.write().login(test_user, "fake-jwt-token-12345".to_string());
                 },
@@ -22,9 +22,45 @@ pub fn SignedInLayout() -> Element {
         };
     }
 
+    let impersonated_by = auth_state
+        .read()
+        .admin_session
+        .as_ref()
+        .map(|session| session.user.email.clone());
+    let impersonated_email = auth_state.read().user.as_ref().map(|user| user.email.clone()).unwrap_or_default();
+
+    let email_verified = auth_state
+        .read()
+        .user
+        .as_ref()
+        .is_some_and(|user| user.email_verified);
+
     rsx! {
         div { class: "flex flex-col min-h-screen",
             SignedInNavbar {}
+            if let Some(admin_email) = impersonated_by {
+                div { class: "bg-purple-50 border-b border-purple-200 px-4 sm:px-8 py-2 text-sm text-purple-800",
+                    "You ({admin_email}) are signed in as {impersonated_email}. "
+                    button {
+                        class: "font-medium underline",
+                        onclick: move |_| {
+                            crate::hooks::stop_impersonating(auth_state);
+                            navigator.push(Route::AdminDashboard {});
+                        },
+                        "Stop impersonating"
+                    }
+                }
+            }
+            if !email_verified {
+                div { class: "bg-yellow-50 border-b border-yellow-200 px-4 sm:px-8 py-2 text-sm text-yellow-800",
+                    "Please verify your email address using the link we sent you. "
+                    Link {
+                        to: Route::UserSettings {},
+                        class: "font-medium underline",
+                        "Resend the email"
+                    }
+                }
+            }
             main { class: "flex-1 bg-CustomBackground font-display text-MyText",
                 div { class: "px-4 sm:px-8 py-8", Outlet::<Route> {} }
             }
@@ -11,10 +11,11 @@ pub fn SignedInNavbar(children: Element) -> Element {
     let route_clone = route.clone();
     let mut is_mobile_menu_open = use_signal(|| false);
     let mut show_user_menu = use_signal(|| false);
-    let mut auth_state = use_context::<Signal<AuthState>>();
+    let auth_state = use_context::<Signal<AuthState>>();
     
     let is_authenticated = auth_state.read().is_authenticated();
     let user = auth_state.read().user.clone();
+    let is_admin = user.as_ref().is_some_and(|u| u.role == "admin");
 
     // Generate breadcrumb items based on current route
     let breadcrumbs = use_memo(move || {
@@ -31,6 +32,9 @@ pub fn SignedInNavbar(children: Element) -> Element {
             Route::UserSettings {} => {
                 items.push(("Settings".to_string(), None));
             }
+            Route::AdminDashboard {} => {
+                items.push(("Admin".to_string(), None));
+            }
             Route::ProjectById { ref id } => {
                 items.push(("Projects".to_string(), Some(Route::Projects {})));
                 items.push((id.clone(), None));
@@ -237,11 +241,19 @@ pub fn SignedInNavbar(children: Element) -> Element {
                                                 class: "block px-4 py-2 text-sm text-gray-700 hover:bg-gray-100",
                                                 "Settings"
                                             }
+                                            if is_admin {
+                                                Link {
+                                                    to: Route::AdminDashboard {},
+                                                    onclick: move |_| show_user_menu.set(false),
+                                                    class: "block px-4 py-2 text-sm text-gray-700 hover:bg-gray-100",
+                                                    "Admin"
+                                                }
+                                            }
                                             div { class: "border-t border-gray-200 mt-2 pt-2",
                                                 button {
                                                     class: "w-full text-left px-4 py-2 text-sm text-red-600 hover:bg-red-50",
                                                     onclick: move |_| {
-                                                        auth_state.write().logout();
+                                                        crate::hooks::sign_out(auth_state, false);
                                                         show_user_menu.set(false);
                                                         navigator().push(Route::Home {});
                                                     },
@@ -320,6 +332,14 @@ pub fn SignedInNavbar(children: Element) -> Element {
                                 "Settings"
                             }
                         }
+                        if is_admin {
+                            Link {
+                                to: Route::AdminDashboard {},
+                                class: if matches!(route, Route::AdminDashboard {}) { "block px-3 py-2 text-sm font-semibold text-blue-600 bg-blue-50 rounded-lg" } else { "block px-3 py-2 text-sm font-medium text-gray-700 hover:bg-gray-100 rounded-lg" },
+                                onclick: move |_| is_mobile_menu_open.set(false),
+                                "Admin"
+                            }
+                        }
                     }
                     // Mobile auth section
                     div { class: "px-4 py-3 border-t border-gray-200",
@@ -346,7 +366,7 @@ pub fn SignedInNavbar(children: Element) -> Element {
                                 button {
                                     class: "w-full text-left px-3 py-2 text-sm font-medium text-red-600 hover:bg-red-50 rounded-lg",
                                     onclick: move |_| {
-                                        auth_state.write().logout();
+                                        crate::hooks::sign_out(auth_state, false);
                                         is_mobile_menu_open.set(false);
                                         navigator().push(Route::Home {});
                                     },
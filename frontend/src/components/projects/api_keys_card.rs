@@ -0,0 +1,195 @@
+use dioxus::prelude::*;
+use reqwest::Client;
+use shared::models::{ApiKeyKind, ApiKeyResponse, CreateApiKeyRequest, CreatedApiKeyResponse};
+use crate::config::endpoints;
+use crate::hooks::use_api_keys;
+use crate::views::auth::reset_password::error_message;
+use crate::AuthState;
+
+/// Lists a project's API keys and lets the owner create and revoke them
+#[component]
+pub fn ApiKeysCard(slug: String) -> Element {
+    let auth_state = use_context::<Signal<AuthState>>();
+    let mut refresh = use_signal(|| 0u32);
+    let keys = use_api_keys(slug.clone(), refresh);
+
+    let mut name = use_signal(String::new);
+    let mut kind = use_signal(|| ApiKeyKind::Anon);
+    let mut created_key = use_signal(|| None::<String>);
+    let mut error = use_signal(|| None::<String>);
+
+    let create_slug = slug.clone();
+    let create_key = move |evt: FormEvent| {
+        evt.prevent_default();
+        let Some(token) = auth_state.read().token.clone() else {
+            error.set(Some("Not authenticated".to_string()));
+            return;
+        };
+
+        let request = CreateApiKeyRequest { name: name(), kind: kind() };
+        let url = endpoints::api_keys(&create_slug);
+        error.set(None);
+        created_key.set(None);
+
+        spawn(async move {
+            let response = Client::new()
+                .post(url)
+                .header("Authorization", format!("Bearer {}", token))
+                .json(&request)
+                .send()
+                .await;
+
+            match response {
+                Ok(response) if response.status().is_success() => {
+                    match response.json::<CreatedApiKeyResponse>().await {
+                        Ok(created) => {
+                            created_key.set(Some(created.key));
+                            name.set(String::new());
+                            refresh += 1;
+                        }
+                        Err(e) => error.set(Some(format!("Failed to parse response: {}", e))),
+                    }
+                }
+                Ok(response) => error.set(Some(error_message(response).await)),
+                Err(e) => error.set(Some(format!("Network error: {}", e))),
+            }
+        });
+    };
+
+    let revoke_key = move |(slug, api_key_id): (String, String)| {
+        let Some(token) = auth_state.read().token.clone() else {
+            return;
+        };
+
+        spawn(async move {
+            let response = Client::new()
+                .delete(endpoints::api_key(&slug, &api_key_id))
+                .header("Authorization", format!("Bearer {}", token))
+                .send()
+                .await;
+
+            match response {
+                Ok(response) if response.status().is_success() => refresh += 1,
+                Ok(response) => error.set(Some(error_message(response).await)),
+                Err(e) => error.set(Some(format!("Network error: {}", e))),
+            }
+        });
+    };
+
+    rsx! {
+        div { class: "bg-white rounded-lg shadow-md border border-gray-200 p-6",
+            h2 { class: "text-xl font-semibold text-gray-900 mb-2", "API Keys" }
+            p { class: "text-sm text-gray-600 mb-4",
+                "Send a key in the "
+                code { "apikey" }
+                " header to call the data API without signing in. Anon keys are safe to ship in apps and only reach tables with row-level security policies; service keys bypass policies, can run SQL, and must stay on your servers."
+            }
+            form { class: "flex flex-col sm:flex-row gap-2 mb-4", onsubmit: create_key,
+                input {
+                    r#type: "text",
+                    required: true,
+                    placeholder: "Key name, e.g. Web app",
+                    value: "{name}",
+                    oninput: move |evt| name.set(evt.value()),
+                    class: "flex-1 px-4 py-2 border border-gray-300 rounded-md focus:ring-2 focus:ring-blue-500 focus:border-transparent outline-none transition",
+                }
+                select {
+                    class: "px-4 py-2 border border-gray-300 rounded-md bg-white",
+                    onchange: move |evt| {
+                        kind.set(if evt.value() == "service" { ApiKeyKind::Service } else { ApiKeyKind::Anon })
+                    },
+                    option { value: "anon", "Anon (public)" }
+                    option { value: "service", "Service (secret)" }
+                }
+                button {
+                    r#type: "submit",
+                    class: "px-4 py-2 bg-blue-600 hover:bg-blue-700 text-white font-medium rounded-md transition",
+                    "+ Generate New Key"
+                }
+            }
+            if let Some(key) = created_key() {
+                div { class: "p-4 mb-4 bg-green-50 border border-green-200 rounded-lg",
+                    p { class: "text-sm font-medium text-gray-900 mb-2",
+                        "Copy your new key now. It will not be shown again."
+                    }
+                    code { class: "block px-3 py-2 bg-gray-900 text-green-400 rounded font-mono text-sm break-all",
+                        "{key}"
+                    }
+                }
+            }
+            if let Some(message) = error() {
+                p { class: "text-sm text-red-600 mb-4", "{message}" }
+            }
+            div { class: "space-y-3",
+                match &*keys.read() {
+                    None => rsx! {
+                        p { class: "text-sm text-gray-500", "Loading API keys..." }
+                    },
+                    Some(Err(message)) => rsx! {
+                        p { class: "text-sm text-red-600", "{message}" }
+                    },
+                    Some(Ok(keys)) if keys.is_empty() => rsx! {
+                        p { class: "text-sm text-gray-500", "No API keys yet" }
+                    },
+                    Some(Ok(keys)) => rsx! {
+                        for api_key in keys.iter().cloned() {
+                            ApiKeyRow {
+                                key: "{api_key.id}",
+                                api_key: api_key.clone(),
+                                on_revoke: {
+                                    let slug = slug.clone();
+                                    move |id: String| revoke_key((slug.clone(), id))
+                                },
+                            }
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ApiKeyRow(api_key: ApiKeyResponse, on_revoke: EventHandler<String>) -> Element {
+    let kind_label = match api_key.kind {
+        ApiKeyKind::Anon => "Anon",
+        ApiKeyKind::Service => "Service",
+    };
+    let last_used = api_key.last_used_at.clone().unwrap_or_else(|| "never".to_string());
+    let revoked = api_key.revoked_at.is_some();
+    let id = api_key.id.clone();
+
+    rsx! {
+        div { class: "p-4 border border-gray-200 rounded-lg hover:border-gray-300 transition",
+            div { class: "flex items-start justify-between mb-2",
+                div {
+                    p { class: "font-medium text-gray-900", "{api_key.name}" }
+                    p { class: "text-xs text-gray-500 mt-1",
+                        "{kind_label} key • Created {api_key.created_at} • Last used {last_used}"
+                    }
+                }
+                if revoked {
+                    span { class: "px-2 py-1 bg-gray-100 text-gray-600 text-xs font-semibold rounded",
+                        "Revoked"
+                    }
+                } else {
+                    span { class: "px-2 py-1 bg-green-100 text-green-800 text-xs font-semibold rounded",
+                        "Active"
+                    }
+                }
+            }
+            div { class: "flex items-center gap-2",
+                code { class: "flex-1 px-3 py-2 bg-gray-900 text-green-400 rounded font-mono text-sm",
+                    "{api_key.key_prefix}..."
+                }
+                if !revoked {
+                    button {
+                        class: "px-3 py-2 bg-red-100 hover:bg-red-200 text-red-700 rounded text-sm font-medium",
+                        onclick: move |_| on_revoke.call(id.clone()),
+                        "Revoke"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,166 @@
+use dioxus::prelude::*;
+use reqwest::Client;
+use shared::models::{CreateInvitationRequest, InvitationResponse, ProjectRole};
+use crate::components::projects::members_card::{role_label, ROLES};
+use crate::config::endpoints;
+use crate::hooks::use_invitations;
+use crate::views::auth::reset_password::error_message;
+use crate::AuthState;
+
+/// Invites people to a project by email and lists the invitations still waiting for an answer
+#[component]
+pub fn InvitationsCard(slug: String) -> Element {
+    let auth_state = use_context::<Signal<AuthState>>();
+    let mut refresh = use_signal(|| 0u32);
+    let invitations = use_invitations(slug.clone(), refresh);
+
+    let mut email = use_signal(String::new);
+    let mut role = use_signal(|| ProjectRole::Developer);
+    let mut sent_to = use_signal(|| None::<String>);
+    let mut error = use_signal(|| None::<String>);
+
+    let invite_slug = slug.clone();
+    let send_invitation = move |evt: FormEvent| {
+        evt.prevent_default();
+        let Some(token) = auth_state.read().token.clone() else {
+            error.set(Some("Not authenticated".to_string()));
+            return;
+        };
+
+        let request = CreateInvitationRequest { email: email(), role: role() };
+        let url = endpoints::invitations(&invite_slug);
+        error.set(None);
+        sent_to.set(None);
+
+        spawn(async move {
+            let response = Client::new()
+                .post(url)
+                .header("Authorization", format!("Bearer {}", token))
+                .json(&request)
+                .send()
+                .await;
+
+            match response {
+                Ok(response) if response.status().is_success() => {
+                    match response.json::<InvitationResponse>().await {
+                        Ok(invitation) => {
+                            sent_to.set(Some(invitation.email));
+                            email.set(String::new());
+                            refresh += 1;
+                        }
+                        Err(e) => error.set(Some(format!("Failed to parse response: {}", e))),
+                    }
+                }
+                Ok(response) => error.set(Some(error_message(response).await)),
+                Err(e) => error.set(Some(format!("Network error: {}", e))),
+            }
+        });
+    };
+
+    let mut revoke_invitation = move |(slug, invitation_id): (String, String)| {
+        let Some(token) = auth_state.read().token.clone() else {
+            return;
+        };
+        error.set(None);
+
+        spawn(async move {
+            let response = Client::new()
+                .delete(endpoints::invitation(&slug, &invitation_id))
+                .header("Authorization", format!("Bearer {}", token))
+                .send()
+                .await;
+
+            match response {
+                Ok(response) if response.status().is_success() => refresh += 1,
+                Ok(response) => error.set(Some(error_message(response).await)),
+                Err(e) => error.set(Some(format!("Network error: {}", e))),
+            }
+        });
+    };
+
+    rsx! {
+        div { class: "bg-white rounded-lg shadow-md border border-gray-200 p-6",
+            h2 { class: "text-xl font-semibold text-gray-900 mb-2", "Invitations" }
+            p { class: "text-sm text-gray-600 mb-4",
+                "Invite people by email. They can accept with an existing account or create one from the link."
+            }
+            form { class: "flex flex-col sm:flex-row gap-2 mb-4", onsubmit: send_invitation,
+                input {
+                    r#type: "email",
+                    required: true,
+                    placeholder: "teammate@example.com",
+                    value: "{email}",
+                    oninput: move |evt| email.set(evt.value()),
+                    class: "flex-1 px-4 py-2 border border-gray-300 rounded-md focus:ring-2 focus:ring-blue-500 focus:border-transparent outline-none transition",
+                }
+                select {
+                    class: "px-4 py-2 border border-gray-300 rounded-md bg-white",
+                    onchange: move |evt| {
+                        if let Some(selected) = ProjectRole::parse(&evt.value()) {
+                            role.set(selected);
+                        }
+                    },
+                    for option_role in ROLES {
+                        option {
+                            value: option_role.as_str(),
+                            selected: option_role == role(),
+                            {role_label(option_role)}
+                        }
+                    }
+                }
+                button {
+                    r#type: "submit",
+                    class: "px-4 py-2 bg-blue-600 hover:bg-blue-700 text-white font-medium rounded-md transition",
+                    "+ Invite Member"
+                }
+            }
+            if let Some(address) = sent_to() {
+                p { class: "text-sm text-green-700 mb-4", "Invitation sent to {address}" }
+            }
+            if let Some(message) = error() {
+                p { class: "text-sm text-red-600 mb-4", "{message}" }
+            }
+            div { class: "space-y-3",
+                match &*invitations.read() {
+                    None => rsx! {
+                        p { class: "text-sm text-gray-500", "Loading invitations..." }
+                    },
+                    Some(Err(message)) => rsx! {
+                        p { class: "text-sm text-red-600", "{message}" }
+                    },
+                    Some(Ok(invitations)) if invitations.is_empty() => rsx! {
+                        p { class: "text-sm text-gray-500", "No pending invitations" }
+                    },
+                    Some(Ok(invitations)) => rsx! {
+                        for invitation in invitations.iter().cloned() {
+                            div {
+                                key: "{invitation.id}",
+                                class: "flex items-center justify-between p-4 border border-gray-200 rounded-lg",
+                                div {
+                                    p { class: "font-medium text-gray-900", "{invitation.email}" }
+                                    p { class: "text-xs text-gray-500 mt-1",
+                                        {format!(
+                                            "{} • Invited by {} • Expires {}",
+                                            role_label(invitation.role),
+                                            invitation.invited_by.clone().unwrap_or_else(|| "a former member".to_string()),
+                                            invitation.expires_at,
+                                        )}
+                                    }
+                                }
+                                button {
+                                    class: "px-3 py-1 text-red-600 hover:bg-red-50 rounded text-sm font-medium",
+                                    onclick: {
+                                        let slug = slug.clone();
+                                        let id = invitation.id.clone();
+                                        move |_| revoke_invitation((slug.clone(), id.clone()))
+                                    },
+                                    "Revoke"
+                                }
+                            }
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
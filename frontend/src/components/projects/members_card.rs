@@ -0,0 +1,181 @@
+use dioxus::prelude::*;
+use reqwest::Client;
+use shared::models::{MemberResponse, ProjectRole, UpdateMemberRequest};
+use crate::config::endpoints;
+use crate::hooks::use_members;
+use crate::views::auth::reset_password::error_message;
+use crate::AuthState;
+
+pub(crate) const ROLES: [ProjectRole; 4] = [
+    ProjectRole::Owner,
+    ProjectRole::Admin,
+    ProjectRole::Developer,
+    ProjectRole::Viewer,
+];
+
+/// Lists a project's members and lets owners and admins change their roles and remove them
+#[component]
+pub fn MembersCard(slug: String) -> Element {
+    let auth_state = use_context::<Signal<AuthState>>();
+    let mut refresh = use_signal(|| 0u32);
+    let members = use_members(slug.clone(), refresh);
+
+    let mut error = use_signal(|| None::<String>);
+
+    let current_user_id = auth_state.read().user.as_ref().map(|user| user.id.clone()).unwrap_or_default();
+
+    let mut change_role = move |(slug, user_id, role): (String, String, ProjectRole)| {
+        let Some(token) = auth_state.read().token.clone() else {
+            return;
+        };
+        error.set(None);
+
+        spawn(async move {
+            let response = Client::new()
+                .put(endpoints::member(&slug, &user_id))
+                .header("Authorization", format!("Bearer {}", token))
+                .json(&UpdateMemberRequest { role })
+                .send()
+                .await;
+
+            match response {
+                Ok(response) if response.status().is_success() => {}
+                Ok(response) => error.set(Some(error_message(response).await)),
+                Err(e) => error.set(Some(format!("Network error: {}", e))),
+            }
+            // Reload either way so a rejected change snaps back to the stored role
+            refresh += 1;
+        });
+    };
+
+    let mut remove_member = move |(slug, user_id): (String, String)| {
+        let Some(token) = auth_state.read().token.clone() else {
+            return;
+        };
+        error.set(None);
+
+        spawn(async move {
+            let response = Client::new()
+                .delete(endpoints::member(&slug, &user_id))
+                .header("Authorization", format!("Bearer {}", token))
+                .send()
+                .await;
+
+            match response {
+                Ok(response) if response.status().is_success() => refresh += 1,
+                Ok(response) => error.set(Some(error_message(response).await)),
+                Err(e) => error.set(Some(format!("Network error: {}", e))),
+            }
+        });
+    };
+
+    rsx! {
+        div { class: "bg-white rounded-lg shadow-md border border-gray-200 p-6",
+            h2 { class: "text-xl font-semibold text-gray-900 mb-2", "Team Members" }
+            p { class: "text-sm text-gray-600 mb-4",
+                "Only owners can change or remove owners, and a project always keeps at least one owner."
+            }
+            if let Some(message) = error() {
+                p { class: "text-sm text-red-600 mb-4", "{message}" }
+            }
+            div { class: "space-y-3",
+                match &*members.read() {
+                    None => rsx! {
+                        p { class: "text-sm text-gray-500", "Loading members..." }
+                    },
+                    Some(Err(message)) => rsx! {
+                        p { class: "text-sm text-red-600", "{message}" }
+                    },
+                    Some(Ok(members)) => rsx! {
+                        for member in members.iter().cloned() {
+                            MemberRow {
+                                key: "{member.user_id}",
+                                is_self: member.user_id == current_user_id,
+                                member: member.clone(),
+                                on_change_role: {
+                                    let slug = slug.clone();
+                                    move |(user_id, role): (String, ProjectRole)| change_role((slug.clone(), user_id, role))
+                                },
+                                on_remove: {
+                                    let slug = slug.clone();
+                                    move |user_id: String| remove_member((slug.clone(), user_id))
+                                },
+                            }
+                        }
+                    },
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn MemberRow(
+    member: MemberResponse,
+    is_self: bool,
+    on_change_role: EventHandler<(String, ProjectRole)>,
+    on_remove: EventHandler<String>,
+) -> Element {
+    let display_name = member.name.clone().unwrap_or_else(|| member.email.clone());
+    let initials: String = display_name
+        .split(|c: char| c.is_whitespace() || c == '@' || c == '.')
+        .filter_map(|part| part.chars().next())
+        .take(2)
+        .collect::<String>()
+        .to_uppercase();
+    let badge_class = match member.role {
+        ProjectRole::Owner => "bg-purple-100 text-purple-600",
+        ProjectRole::Admin => "bg-blue-100 text-blue-600",
+        ProjectRole::Developer => "bg-green-100 text-green-600",
+        ProjectRole::Viewer => "bg-orange-100 text-orange-600",
+    };
+    let role_user_id = member.user_id.clone();
+    let remove_user_id = member.user_id.clone();
+
+    rsx! {
+        div { class: "flex items-center justify-between p-4 border border-gray-200 rounded-lg",
+            div { class: "flex items-center gap-3",
+                div { class: "h-10 w-10 {badge_class} rounded-full flex items-center justify-center font-semibold",
+                    "{initials}"
+                }
+                div {
+                    p { class: "font-medium text-gray-900",
+                        if is_self { "{display_name} (You)" } else { "{display_name}" }
+                    }
+                    p { class: "text-sm text-gray-500", "{member.email}" }
+                }
+            }
+            div { class: "flex items-center gap-2",
+                select {
+                    class: "px-3 py-1 border border-gray-300 rounded text-sm",
+                    onchange: move |evt| {
+                        if let Some(selected) = ProjectRole::parse(&evt.value()) {
+                            on_change_role.call((role_user_id.clone(), selected));
+                        }
+                    },
+                    for option_role in ROLES {
+                        option {
+                            value: option_role.as_str(),
+                            selected: option_role == member.role,
+                            {role_label(option_role)}
+                        }
+                    }
+                }
+                button {
+                    class: "px-3 py-1 text-red-600 hover:bg-red-50 rounded text-sm font-medium",
+                    onclick: move |_| on_remove.call(remove_user_id.clone()),
+                    if is_self { "Leave" } else { "Remove" }
+                }
+            }
+        }
+    }
+}
+
+pub(crate) fn role_label(role: ProjectRole) -> &'static str {
+    match role {
+        ProjectRole::Owner => "Owner",
+        ProjectRole::Admin => "Admin",
+        ProjectRole::Developer => "Developer",
+        ProjectRole::Viewer => "Viewer",
+    }
+}
@@ -8,4 +8,13 @@ pub mod project_left_sidebar;
 pub use project_left_sidebar::ProjectLeftSidebar;
 
 pub mod statistic_card;
-pub use statistic_card::StatisticCard;
\ No newline at end of file
+pub use statistic_card::StatisticCard;
+
+pub mod api_keys_card;
+pub use api_keys_card::ApiKeysCard;
+
+pub mod members_card;
+pub use members_card::MembersCard;
+
+pub mod invitations_card;
+pub use invitations_card::InvitationsCard;
@@ -14,10 +14,74 @@ pub mod endpoints {
         format!("{}/api/auth/register", API_BASE_URL)
     }
 
+    pub fn refresh() -> String {
+        format!("{}/api/auth/refresh", API_BASE_URL)
+    }
+
+    pub fn password_reset() -> String {
+        format!("{}/api/auth/password-reset", API_BASE_URL)
+    }
+
+    pub fn confirm_password_reset() -> String {
+        format!("{}/api/auth/password-reset/confirm", API_BASE_URL)
+    }
+
+    pub fn verify_email() -> String {
+        format!("{}/api/auth/verify-email", API_BASE_URL)
+    }
+
+    pub fn resend_verification_email() -> String {
+        format!("{}/api/auth/verify-email/resend", API_BASE_URL)
+    }
+
+    pub fn oidc_providers() -> String {
+        format!("{}/api/auth/oidc/providers", API_BASE_URL)
+    }
+
+    pub fn oidc_authorize(provider: &str) -> String {
+        format!("{}/api/auth/oidc/{}/authorize", API_BASE_URL, provider)
+    }
+
+    pub fn oidc_callback() -> String {
+        format!("{}/api/auth/oidc/callback", API_BASE_URL)
+    }
+
+    pub fn identities() -> String {
+        format!("{}/api/auth/identities", API_BASE_URL)
+    }
+
+    pub fn mfa_verify() -> String {
+        format!("{}/api/auth/mfa/verify", API_BASE_URL)
+    }
+
+    pub fn mfa() -> String {
+        format!("{}/api/auth/mfa", API_BASE_URL)
+    }
+
+    pub fn mfa_totp_enroll() -> String {
+        format!("{}/api/auth/mfa/totp/enroll", API_BASE_URL)
+    }
+
+    pub fn mfa_totp_confirm() -> String {
+        format!("{}/api/auth/mfa/totp/confirm", API_BASE_URL)
+    }
+
+    pub fn mfa_recovery_codes() -> String {
+        format!("{}/api/auth/mfa/recovery-codes", API_BASE_URL)
+    }
+
     pub fn logout() -> String {
         format!("{}/api/auth/logout", API_BASE_URL)
     }
 
+    pub fn logout_all() -> String {
+        format!("{}/api/auth/logout-all", API_BASE_URL)
+    }
+
+    pub fn change_password() -> String {
+        format!("{}/api/auth/password", API_BASE_URL)
+    }
+
     pub fn me() -> String {
         format!("{}/api/auth/me", API_BASE_URL)
     }
@@ -31,11 +95,88 @@ pub mod endpoints {
         format!("{}/api/projects/{}", API_BASE_URL, slug)
     }
 
+    pub fn api_keys(project_slug: &str) -> String {
+        format!("{}/api/projects/{}/api-keys", API_BASE_URL, project_slug)
+    }
+
+    pub fn api_key(project_slug: &str, api_key_id: &str) -> String {
+        format!("{}/api/projects/{}/api-keys/{}", API_BASE_URL, project_slug, api_key_id)
+    }
+
+    pub fn members(project_slug: &str) -> String {
+        format!("{}/api/projects/{}/members", API_BASE_URL, project_slug)
+    }
+
+    pub fn member(project_slug: &str, user_id: &str) -> String {
+        format!("{}/api/projects/{}/members/{}", API_BASE_URL, project_slug, user_id)
+    }
+
+    pub fn invitations(project_slug: &str) -> String {
+        format!("{}/api/projects/{}/invitations", API_BASE_URL, project_slug)
+    }
+
+    pub fn invitation(project_slug: &str, invitation_id: &str) -> String {
+        format!("{}/api/projects/{}/invitations/{}", API_BASE_URL, project_slug, invitation_id)
+    }
+
+    pub fn invitation_lookup() -> String {
+        format!("{}/api/invitations/lookup", API_BASE_URL)
+    }
+
+    pub fn accept_invitation() -> String {
+        format!("{}/api/invitations/accept", API_BASE_URL)
+    }
+
+    pub fn decline_invitation() -> String {
+        format!("{}/api/invitations/decline", API_BASE_URL)
+    }
+
+    // Admin endpoints
+    pub fn admin_users() -> String {
+        format!("{}/api/admin/users", API_BASE_URL)
+    }
+
+    pub fn admin_user_action(user_id: &str, action: &str) -> String {
+        format!("{}/api/admin/users/{}/{}", API_BASE_URL, user_id, action)
+    }
+
+    pub fn admin_projects() -> String {
+        format!("{}/api/admin/projects", API_BASE_URL)
+    }
+
+    pub fn admin_project(project_slug: &str) -> String {
+        format!("{}/api/admin/projects/{}", API_BASE_URL, project_slug)
+    }
+
     // SQL execution endpoint
     pub fn execute_sql(project_slug: &str) -> String {
         format!("{}/api/sql/{}", API_BASE_URL, project_slug)
     }
 
+    pub fn cancel_sql(project_slug: &str, query_id: &str) -> String {
+        format!("{}/api/sql/{}/cancel/{}", API_BASE_URL, project_slug, query_id)
+    }
+
+    pub fn sql_completions(project_slug: &str) -> String {
+        format!("{}/api/sql/{}/completions", API_BASE_URL, project_slug)
+    }
+
+    pub fn sql_history(project_slug: &str) -> String {
+        format!("{}/api/sql/{}/history", API_BASE_URL, project_slug)
+    }
+
+    pub fn saved_queries(project_slug: &str) -> String {
+        format!("{}/api/sql/{}/saved", API_BASE_URL, project_slug)
+    }
+
+    pub fn saved_query(project_slug: &str, saved_query_id: &str) -> String {
+        format!("{}/api/sql/{}/saved/{}", API_BASE_URL, project_slug, saved_query_id)
+    }
+
+    pub fn execute_saved_query(project_slug: &str, saved_query_id: &str) -> String {
+        format!("{}/api/sql/{}/saved/{}/execute", API_BASE_URL, project_slug, saved_query_id)
+    }
+
     // Dynamic data endpoints
     pub fn table_data(project_slug: &str, table_name: &str) -> String {
         format!("{}/api/data/{}/{}", API_BASE_URL, project_slug, table_name)
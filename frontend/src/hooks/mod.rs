@@ -2,8 +2,16 @@ pub mod use_auth;
 pub mod use_projects;
 pub mod use_tables;
 pub mod use_sql;
+pub mod use_api_keys;
+pub mod use_members;
+pub mod use_invitations;
+pub mod use_admin;
 
 pub use use_auth::*;
 pub use use_projects::*;
 pub use use_tables::*;
 pub use use_sql::*;
+pub use use_api_keys::*;
+pub use use_members::*;
+pub use use_invitations::*;
+pub use use_admin::*;
@@ -0,0 +1,75 @@
+use dioxus::prelude::*;
+use shared::models::{AdminProjectResponse, AdminUserListResponse};
+use crate::config::endpoints;
+
+/// Hook to search every user on the platform; changing `search` or `refresh` reloads them
+pub fn use_admin_users(
+    search: Signal<String>,
+    refresh: Signal<u32>,
+) -> Resource<Result<AdminUserListResponse, String>> {
+    let auth_state = use_context::<Signal<crate::AuthState>>();
+
+    use_resource(move || {
+        let search = search();
+        let _ = refresh();
+
+        async move {
+            let token = auth_state.read().token.clone().ok_or("Not authenticated")?;
+
+            let response = reqwest::Client::new()
+                .get(endpoints::admin_users())
+                .query(&[("search", search)])
+                .header("Authorization", format!("Bearer {}", token))
+                .send()
+                .await
+                .map_err(|e| format!("Network error: {}", e))?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(format!("HTTP {}: {}", status, error_text));
+            }
+
+            response
+                .json::<AdminUserListResponse>()
+                .await
+                .map_err(|e| format!("Failed to parse response: {}", e))
+        }
+    })
+}
+
+/// Hook to list every project on the platform; changing `search` or `refresh` reloads them
+pub fn use_admin_projects(
+    search: Signal<String>,
+    refresh: Signal<u32>,
+) -> Resource<Result<Vec<AdminProjectResponse>, String>> {
+    let auth_state = use_context::<Signal<crate::AuthState>>();
+
+    use_resource(move || {
+        let search = search();
+        let _ = refresh();
+
+        async move {
+            let token = auth_state.read().token.clone().ok_or("Not authenticated")?;
+
+            let response = reqwest::Client::new()
+                .get(endpoints::admin_projects())
+                .query(&[("search", search)])
+                .header("Authorization", format!("Bearer {}", token))
+                .send()
+                .await
+                .map_err(|e| format!("Network error: {}", e))?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(format!("HTTP {}: {}", status, error_text));
+            }
+
+            response
+                .json::<Vec<AdminProjectResponse>>()
+                .await
+                .map_err(|e| format!("Failed to parse response: {}", e))
+        }
+    })
+}
@@ -1,11 +1,19 @@
 use dioxus::prelude::*;
-use shared::models::{AuthResponse, UserResponse};
+use dioxus::dioxus_core::spawn_forever;
+use gloo_timers::future::sleep;
+use shared::models::{AuthResponse, LogoutRequest, RefreshTokenRequest, UserResponse};
 use reqwest::Client;
 use serde_json::json;
 use std::future::Future;
 use std::pin::Pin;
+use std::time::Duration;
 use crate::config::endpoints;
 
+/// Refresh the access token this long before it expires
+const REFRESH_MARGIN_SECS: u64 = 60;
+/// How often to check for a new session while signed out, and to retry after a network error
+const IDLE_POLL_SECS: u64 = 30;
+
 #[derive(Clone, Copy)]
 #[allow(dead_code)]
 pub struct AuthContext { 
@@ -45,8 +53,137 @@ async fn make_authenticated_post_request(token: &str, url: &str, body: serde_jso
         .await
 }
 
+enum RefreshError {
+    /// The server refused the refresh token, so the session is over
+    Rejected(String),
+    /// The server could not be reached; the session may still be valid
+    Unavailable(String),
+}
+
+async fn refresh_session(refresh_token: String) -> Result<AuthResponse, RefreshError> {
+    let response = Client::new()
+        .post(endpoints::refresh())
+        .json(&RefreshTokenRequest { refresh_token })
+        .send()
+        .await
+        .map_err(|e| RefreshError::Unavailable(e.to_string()))?;
+
+    let status = response.status();
+    if status.is_success() {
+        response
+            .json::<AuthResponse>()
+            .await
+            .map_err(|e| RefreshError::Unavailable(e.to_string()))
+    } else if status.as_u16() == 401 {
+        Err(RefreshError::Rejected(response.text().await.unwrap_or_default()))
+    } else {
+        Err(RefreshError::Unavailable(format!("HTTP {}", status)))
+    }
+}
+
+/// Clear the local session and revoke it on the server, or revoke every session of the user when `everywhere` is set
+pub fn sign_out(mut auth_state: Signal<crate::AuthState>, everywhere: bool) {
+    let (token, refresh_token) = {
+        let state = auth_state.read();
+        (state.token.clone(), state.refresh_token.clone())
+    };
+    auth_state.write().logout();
+
+    let Some(token) = token else {
+        return;
+    };
+
+    // The caller usually navigates away, so the request must outlive its component
+    spawn_forever(async move {
+        let url = if everywhere { endpoints::logout_all() } else { endpoints::logout() };
+        let response = Client::new()
+            .post(url)
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&LogoutRequest { refresh_token })
+            .send()
+            .await;
+
+        match response {
+            Ok(response) if response.status().is_success() => log::info!("✅ Session revoked on the server"),
+            Ok(response) => log::warn!("⚠️ Logout request failed with status: {}", response.status()),
+            Err(e) => log::warn!("🌐 Network error during logout: {}", e),
+        }
+    });
+}
+
+/// Revoke the impersonation token and switch back to the admin's own session
+pub fn stop_impersonating(mut auth_state: Signal<crate::AuthState>) {
+    let token = auth_state.read().token.clone();
+    auth_state.write().stop_impersonation();
+
+    let Some(token) = token else {
+        return;
+    };
+
+    spawn_forever(async move {
+        let response = Client::new()
+            .post(endpoints::logout())
+            .header("Authorization", format!("Bearer {}", token))
+            .json(&LogoutRequest { refresh_token: None })
+            .send()
+            .await;
+
+        if let Err(e) = response {
+            log::warn!("🌐 Network error while ending impersonation: {}", e);
+        }
+    });
+}
+
 #[component]
 pub fn AuthProvider(children: Element) -> Element {
+    let mut auth_state = use_context::<Signal<crate::AuthState>>();
+
+    // Renew the access token shortly before it expires, for as long as the refresh token is valid
+    use_future(move || async move {
+        loop {
+            let (refresh_token, expires_in) = {
+                let state = auth_state.read();
+                (state.refresh_token.clone(), state.expires_in)
+            };
+
+            let Some(refresh_token) = refresh_token else {
+                sleep(Duration::from_secs(IDLE_POLL_SECS)).await;
+                continue;
+            };
+
+            // After a reload the stored access token's remaining lifetime is unknown, so refresh right away
+            if let Some(expires_in) = expires_in {
+                sleep(Duration::from_secs(expires_in.saturating_sub(REFRESH_MARGIN_SECS).max(1))).await;
+
+                // Signed out, or signed in again, while waiting
+                if auth_state.read().refresh_token.as_ref() != Some(&refresh_token) {
+                    continue;
+                }
+            }
+
+            // Another tab may already have rotated the shared refresh token
+            let current = crate::auth::stored_refresh_token().unwrap_or(refresh_token.clone());
+
+            match refresh_session(current).await {
+                Ok(response) => {
+                    log::debug!("🔄 Access token refreshed");
+                    auth_state.write().refreshed(response.token, response.refresh_token, response.expires_in);
+                }
+                Err(RefreshError::Rejected(e)) => {
+                    log::warn!("🔒 Session ended, refresh token rejected: {}", e);
+                    auth_state.write().logout();
+                }
+                Err(RefreshError::Unavailable(e)) => {
+                    log::warn!("🌐 Could not refresh access token, retrying: {}", e);
+                    sleep(Duration::from_secs(IDLE_POLL_SECS)).await;
+                    if auth_state.read().refresh_token.as_ref() == Some(&refresh_token) {
+                        auth_state.write().expires_in = None;
+                    }
+                }
+            }
+        }
+    });
+
     let is_authenticated = use_signal(|| false);
     let user = use_signal(|| None::<UserResponse>);
     let token = use_signal(|| None::<String>);
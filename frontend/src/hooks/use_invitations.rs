@@ -0,0 +1,38 @@
+use dioxus::prelude::*;
+use shared::models::InvitationResponse;
+use crate::config::endpoints;
+
+/// Hook to list the project's pending invitations; changing `refresh` reloads them
+pub fn use_invitations(
+    project_slug: String,
+    refresh: Signal<u32>,
+) -> Resource<Result<Vec<InvitationResponse>, String>> {
+    let auth_state = use_context::<Signal<crate::AuthState>>();
+
+    use_resource(move || {
+        let project_slug = project_slug.clone();
+        let _ = refresh();
+
+        async move {
+            let token = auth_state.read().token.clone().ok_or("Not authenticated")?;
+
+            let response = reqwest::Client::new()
+                .get(endpoints::invitations(&project_slug))
+                .header("Authorization", format!("Bearer {}", token))
+                .send()
+                .await
+                .map_err(|e| format!("Network error: {}", e))?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(format!("HTTP {}: {}", status, error_text));
+            }
+
+            response
+                .json::<Vec<InvitationResponse>>()
+                .await
+                .map_err(|e| format!("Failed to parse response: {}", e))
+        }
+    })
+}
@@ -0,0 +1,38 @@
+use dioxus::prelude::*;
+use shared::models::MemberResponse;
+use crate::config::endpoints;
+
+/// Hook to list the project's members and their roles; changing `refresh` reloads them
+pub fn use_members(
+    project_slug: String,
+    refresh: Signal<u32>,
+) -> Resource<Result<Vec<MemberResponse>, String>> {
+    let auth_state = use_context::<Signal<crate::AuthState>>();
+
+    use_resource(move || {
+        let project_slug = project_slug.clone();
+        let _ = refresh();
+
+        async move {
+            let token = auth_state.read().token.clone().ok_or("Not authenticated")?;
+
+            let response = reqwest::Client::new()
+                .get(endpoints::members(&project_slug))
+                .header("Authorization", format!("Bearer {}", token))
+                .send()
+                .await
+                .map_err(|e| format!("Network error: {}", e))?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(format!("HTTP {}: {}", status, error_text));
+            }
+
+            response
+                .json::<Vec<MemberResponse>>()
+                .await
+                .map_err(|e| format!("Failed to parse response: {}", e))
+        }
+    })
+}
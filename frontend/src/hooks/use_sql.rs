@@ -1,20 +1,25 @@
 use dioxus::prelude::*;
 use futures::stream::StreamExt;
-use shared::models::{ExecuteSqlRequest, ExecuteSqlResponse};
+use shared::models::{
+    CreateSavedQueryRequest, ExecuteSavedQueryRequest, ExecuteSqlRequest, ExecuteSqlResponse, QueryHistoryEntry,
+    SavedQueryResponse, SqlCompletions,
+};
 use crate::config::endpoints;
 
+/// Where a coroutine action reports its outcome: `None` while it runs
+type ResultSignal<T> = Signal<Option<Result<T, String>>>;
+
 /// Hook to execute SQL queries against a project
 pub fn use_execute_sql(
     project_slug: String,
-) -> Coroutine<(String, Signal<Option<Result<ExecuteSqlResponse, String>>>)> {
+) -> Coroutine<(ExecuteSqlRequest, ResultSignal<ExecuteSqlResponse>)> {
     let auth_state = use_context::<Signal<crate::AuthState>>();
     
-    use_coroutine(move |mut rx: UnboundedReceiver<(String, Signal<Option<Result<ExecuteSqlResponse, String>>>)>| {
+    use_coroutine(move |mut rx: UnboundedReceiver<(ExecuteSqlRequest, ResultSignal<ExecuteSqlResponse>)>| {
         let project_slug = project_slug.clone();
-        let auth_state = auth_state.clone();
         
         async move {
-            while let Some((query, mut result_signal)) = rx.next().await {
+            while let Some((request, mut result_signal)) = rx.next().await {
                 // Set loading state
                 result_signal.set(None);
                 
@@ -27,7 +32,6 @@ pub fn use_execute_sql(
                 };
                 
                 let url = endpoints::execute_sql(&project_slug);
-                let request = ExecuteSqlRequest { query };
                 
                 let response = reqwest::Client::new()
                     .post(&url)
@@ -57,3 +61,251 @@ pub fn use_execute_sql(
         }
     })
 }
+
+/// Hook to cancel a running SQL query by its query ID
+pub fn use_cancel_sql(project_slug: String) -> Coroutine<String> {
+    let auth_state = use_context::<Signal<crate::AuthState>>();
+
+    use_coroutine(move |mut rx: UnboundedReceiver<String>| {
+        let project_slug = project_slug.clone();
+
+        async move {
+            while let Some(query_id) = rx.next().await {
+                let Some(token) = auth_state.read().token.clone() else {
+                    continue;
+                };
+
+                let url = endpoints::cancel_sql(&project_slug, &query_id);
+
+                // The running query reports the cancellation through its own response
+                if let Err(e) = reqwest::Client::new()
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .send()
+                    .await
+                {
+                    log::error!("Failed to cancel query {}: {}", query_id, e);
+                }
+            }
+        }
+    })
+}
+
+/// Hook to run a saved query by ID with parameter values
+pub fn use_execute_saved_query(
+    project_slug: String,
+) -> Coroutine<(String, ExecuteSavedQueryRequest, ResultSignal<ExecuteSqlResponse>)> {
+    let auth_state = use_context::<Signal<crate::AuthState>>();
+
+    use_coroutine(move |mut rx: UnboundedReceiver<(String, ExecuteSavedQueryRequest, ResultSignal<ExecuteSqlResponse>)>| {
+        let project_slug = project_slug.clone();
+
+        async move {
+            while let Some((saved_query_id, request, mut result_signal)) = rx.next().await {
+                result_signal.set(None);
+
+                let Some(token) = auth_state.read().token.clone() else {
+                    result_signal.set(Some(Err("Not authenticated".to_string())));
+                    continue;
+                };
+
+                let url = endpoints::execute_saved_query(&project_slug, &saved_query_id);
+
+                let response = reqwest::Client::new()
+                    .post(&url)
+                    .header("Authorization", format!("Bearer {}", token))
+                    .json(&request)
+                    .send()
+                    .await;
+
+                let result = match response {
+                    Ok(resp) if resp.status().is_success() => resp
+                        .json::<ExecuteSqlResponse>()
+                        .await
+                        .map_err(|e| format!("Failed to parse response: {}", e)),
+                    Ok(resp) => {
+                        let status = resp.status();
+                        let error_text = resp.text().await.unwrap_or_default();
+                        Err(format!("HTTP {}: {}", status, error_text))
+                    }
+                    Err(e) => Err(format!("Network error: {}", e)),
+                };
+                result_signal.set(Some(result));
+            }
+        }
+    })
+}
+
+/// Hook to list the current user's query history; changing `refresh` reloads it
+pub fn use_sql_history(
+    project_slug: String,
+    refresh: Signal<u32>,
+) -> Resource<Result<Vec<QueryHistoryEntry>, String>> {
+    let auth_state = use_context::<Signal<crate::AuthState>>();
+
+    use_resource(move || {
+        let project_slug = project_slug.clone();
+        let _ = refresh();
+
+        async move {
+            let token = auth_state.read().token.clone().ok_or("Not authenticated")?;
+
+            let response = reqwest::Client::new()
+                .get(format!("{}?limit=50", endpoints::sql_history(&project_slug)))
+                .header("Authorization", format!("Bearer {}", token))
+                .send()
+                .await
+                .map_err(|e| format!("Network error: {}", e))?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(format!("HTTP {}: {}", status, error_text));
+            }
+
+            response
+                .json::<Vec<QueryHistoryEntry>>()
+                .await
+                .map_err(|e| format!("Failed to parse response: {}", e))
+        }
+    })
+}
+
+/// Hook to list the project's saved queries; changing `refresh` reloads them
+pub fn use_saved_queries(
+    project_slug: String,
+    refresh: Signal<u32>,
+) -> Resource<Result<Vec<SavedQueryResponse>, String>> {
+    let auth_state = use_context::<Signal<crate::AuthState>>();
+
+    use_resource(move || {
+        let project_slug = project_slug.clone();
+        let _ = refresh();
+
+        async move {
+            let token = auth_state.read().token.clone().ok_or("Not authenticated")?;
+
+            let response = reqwest::Client::new()
+                .get(endpoints::saved_queries(&project_slug))
+                .header("Authorization", format!("Bearer {}", token))
+                .send()
+                .await
+                .map_err(|e| format!("Network error: {}", e))?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(format!("HTTP {}: {}", status, error_text));
+            }
+
+            response
+                .json::<Vec<SavedQueryResponse>>()
+                .await
+                .map_err(|e| format!("Failed to parse response: {}", e))
+        }
+    })
+}
+
+/// Action to save a query; bumps `refresh` once saved so lists reload
+pub fn use_save_query(
+    project_slug: String,
+    mut refresh: Signal<u32>,
+) -> Coroutine<(CreateSavedQueryRequest, ResultSignal<SavedQueryResponse>)> {
+    let auth_state = use_context::<Signal<crate::AuthState>>();
+
+    use_coroutine(move |mut rx: UnboundedReceiver<(CreateSavedQueryRequest, ResultSignal<SavedQueryResponse>)>| {
+        let project_slug = project_slug.clone();
+
+        async move {
+            while let Some((request, mut result_signal)) = rx.next().await {
+                let Some(token) = auth_state.read().token.clone() else {
+                    result_signal.set(Some(Err("Not authenticated".to_string())));
+                    continue;
+                };
+
+                let response = reqwest::Client::new()
+                    .post(endpoints::saved_queries(&project_slug))
+                    .header("Authorization", format!("Bearer {}", token))
+                    .json(&request)
+                    .send()
+                    .await;
+
+                let result = match response {
+                    Ok(resp) if resp.status().is_success() => resp
+                        .json::<SavedQueryResponse>()
+                        .await
+                        .map_err(|e| format!("Failed to parse response: {}", e)),
+                    Ok(resp) => {
+                        let status = resp.status();
+                        let error_text = resp.text().await.unwrap_or_default();
+                        Err(format!("HTTP {}: {}", status, error_text))
+                    }
+                    Err(e) => Err(format!("Network error: {}", e)),
+                };
+
+                if result.is_ok() {
+                    refresh += 1;
+                }
+                result_signal.set(Some(result));
+            }
+        }
+    })
+}
+
+/// Action to delete a saved query by ID; bumps `refresh` once deleted
+pub fn use_delete_saved_query(project_slug: String, mut refresh: Signal<u32>) -> Coroutine<String> {
+    let auth_state = use_context::<Signal<crate::AuthState>>();
+
+    use_coroutine(move |mut rx: UnboundedReceiver<String>| {
+        let project_slug = project_slug.clone();
+
+        async move {
+            while let Some(saved_query_id) = rx.next().await {
+                let Some(token) = auth_state.read().token.clone() else {
+                    continue;
+                };
+
+                match reqwest::Client::new()
+                    .delete(endpoints::saved_query(&project_slug, &saved_query_id))
+                    .header("Authorization", format!("Bearer {}", token))
+                    .send()
+                    .await
+                {
+                    Ok(_) => refresh += 1,
+                    Err(e) => log::error!("Failed to delete saved query {}: {}", saved_query_id, e),
+                }
+            }
+        }
+    })
+}
+
+/// Hook to load the project's schema completions for the SQL editor
+pub fn use_sql_completions(project_slug: String) -> Resource<Result<SqlCompletions, String>> {
+    let auth_state = use_context::<Signal<crate::AuthState>>();
+
+    use_resource(move || {
+        let project_slug = project_slug.clone();
+
+        async move {
+            let token = auth_state.read().token.clone().ok_or("Not authenticated")?;
+
+            let response = reqwest::Client::new()
+                .get(endpoints::sql_completions(&project_slug))
+                .header("Authorization", format!("Bearer {}", token))
+                .send()
+                .await
+                .map_err(|e| format!("Network error: {}", e))?;
+
+            if !response.status().is_success() {
+                let status = response.status();
+                let error_text = response.text().await.unwrap_or_default();
+                return Err(format!("HTTP {}: {}", status, error_text));
+            }
+
+            response
+                .json::<SqlCompletions>()
+                .await
+                .map_err(|e| format!("Failed to parse response: {}", e))
+        }
+    })
+}
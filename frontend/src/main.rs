@@ -8,7 +8,9 @@ mod hooks;
 mod config;
 pub mod routes;
 pub use routes::Route;
-pub use auth::{AuthState, User};
+pub use auth::{
+    clear_pending_invitation, pending_invitation, set_oidc_state, set_pending_invitation, take_oidc_state, AuthState, User,
+};
 
 const FAVICON: Asset = asset!("/assets/favicon.ico");
 const TAILWIND_CSS: Asset = asset!("/assets/tailwind.css");
@@ -24,6 +26,6 @@ fn App() -> Element {
     rsx! {
         document::Link { rel: "icon", href: FAVICON }
         document::Link { rel: "stylesheet", href: TAILWIND_CSS }
-        Router::<Route> {}
+        hooks::AuthProvider { Router::<Route> {} }
     }
 }
@@ -1,10 +1,10 @@
 use dioxus::prelude::*;
 
 use crate::{components::layout::{UnprotectedLayout, ProjectLayout, SignedInLayout}, 
-    views::{FAQ, Home, PrivacyPolicy, TermsOfService, NotFound, UserSettings}}; 
+    views::{FAQ, Home, PrivacyPolicy, TermsOfService, NotFound, UserSettings, AdminDashboard}}; 
 use crate::views::projects::{ProjectById, Projects, TableEditor, SQLEditor, 
     CreateNewProject, ProjectSettings, APIDocs, Logs, Reports, Authentication, Database};
-use crate::views::auth::{LoginForm, ResetPasswordForm, RegisterForm};
+use crate::views::auth::{AcceptInvitation, LoginForm, OidcCallback, ResetPasswordForm, RegisterForm, VerifyEmail};
 
 #[derive(Debug, Clone, Routable, PartialEq)]
 #[rustfmt::skip]
@@ -14,10 +14,16 @@ pub enum Route {
         Home {},
         #[route("/login")]
         LoginForm {},
-        #[route("/reset-password")]
-        ResetPasswordForm {},
+        #[route("/reset-password?:token")]
+        ResetPasswordForm { token: String },
         #[route("/register")]
         RegisterForm {},
+        #[route("/verify-email?:token")]
+        VerifyEmail { token: String },
+        #[route("/invitations?:token")]
+        AcceptInvitation { token: String },
+        #[route("/auth/callback?:code&:state")]
+        OidcCallback { code: String, state: String },
         #[route("/faq")]
         FAQ {},
         #[route("/privacy-policy")]
@@ -36,6 +42,8 @@ pub enum Route {
         CreateNewProject {},
         #[route("/settings")]
         UserSettings {},
+        #[route("/admin")]
+        AdminDashboard {},
 
 
 // Project Specific Routes
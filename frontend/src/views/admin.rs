@@ -0,0 +1,406 @@
+use dioxus::prelude::*;
+use reqwest::Client;
+use shared::models::{AdminProjectResponse, AdminUserResponse, ImpersonationResponse, UpdateUserRoleRequest};
+use crate::config::endpoints;
+use crate::hooks::{use_admin_projects, use_admin_users};
+use crate::views::auth::reset_password::error_message;
+use crate::{AuthState, Route, User};
+
+#[derive(Clone, Copy, PartialEq)]
+enum AdminTab {
+    Users,
+    Projects,
+}
+
+/// Platform administration: manage user accounts and every project, for admins only
+#[component]
+pub fn AdminDashboard() -> Element {
+    let auth_state = use_context::<Signal<AuthState>>();
+    let mut tab = use_signal(|| AdminTab::Users);
+
+    let is_admin = auth_state.read().user.as_ref().is_some_and(|user| user.role == "admin");
+    if !is_admin {
+        return rsx! {
+            div { class: "max-w-3xl mx-auto bg-white rounded-lg shadow-md border border-gray-200 p-6",
+                h1 { class: "text-xl font-semibold text-gray-900 mb-2", "Admins only" }
+                p { class: "text-sm text-gray-600", "You need the admin role to open this page." }
+            }
+        };
+    }
+
+    let tab_class = move |this: AdminTab| {
+        if tab() == this {
+            "px-4 py-2 text-sm font-semibold text-blue-600 border-b-2 border-blue-600"
+        } else {
+            "px-4 py-2 text-sm font-medium text-gray-600 hover:text-gray-900"
+        }
+    };
+
+    rsx! {
+        div { class: "max-w-6xl mx-auto space-y-6",
+            div {
+                h1 { class: "text-3xl font-bold text-gray-900", "Admin" }
+                p { class: "text-gray-600 mt-1", "Manage accounts and projects across the platform" }
+            }
+            div { class: "flex gap-2 border-b border-gray-200",
+                button { class: tab_class(AdminTab::Users), onclick: move |_| tab.set(AdminTab::Users), "Users" }
+                button { class: tab_class(AdminTab::Projects), onclick: move |_| tab.set(AdminTab::Projects), "Projects" }
+            }
+            match tab() {
+                AdminTab::Users => rsx! { UsersPanel {} },
+                AdminTab::Projects => rsx! { ProjectsPanel {} },
+            }
+        }
+    }
+}
+
+#[component]
+fn UsersPanel() -> Element {
+    let mut auth_state = use_context::<Signal<AuthState>>();
+    let navigator = use_navigator();
+    let mut search = use_signal(String::new);
+    let mut refresh = use_signal(|| 0u32);
+    let users = use_admin_users(search, refresh);
+    let mut error = use_signal(|| None::<String>);
+
+    let current_user_id = auth_state.read().user.as_ref().map(|user| user.id.clone()).unwrap_or_default();
+
+    let mut run_action = move |request: reqwest::RequestBuilder| {
+        let Some(token) = auth_state.read().token.clone() else {
+            return;
+        };
+        error.set(None);
+
+        spawn(async move {
+            let response = request
+                .header("Authorization", format!("Bearer {}", token))
+                .send()
+                .await;
+
+            match response {
+                Ok(response) if response.status().is_success() => {}
+                Ok(response) => error.set(Some(error_message(response).await)),
+                Err(e) => error.set(Some(format!("Network error: {}", e))),
+            }
+            // Reload either way so a rejected change snaps back
+            refresh += 1;
+        });
+    };
+
+    let impersonate = move |user_id: String| {
+        let Some(token) = auth_state.read().token.clone() else {
+            return;
+        };
+        error.set(None);
+
+        spawn(async move {
+            let response = Client::new()
+                .post(endpoints::admin_user_action(&user_id, "impersonate"))
+                .header("Authorization", format!("Bearer {}", token))
+                .send()
+                .await;
+
+            match response {
+                Ok(response) if response.status().is_success() => {
+                    match response.json::<ImpersonationResponse>().await {
+                        Ok(impersonation) => {
+                            let user = User {
+                                id: impersonation.user.id,
+                                email: impersonation.user.email,
+                                name: impersonation.user.name,
+                                role: impersonation.user.role,
+                                email_verified: impersonation.user.email_verified,
+                            };
+                            auth_state.write().start_impersonation(user, impersonation.token);
+                            navigator.push(Route::Projects {});
+                        }
+                        Err(e) => error.set(Some(format!("Failed to parse response: {}", e))),
+                    }
+                }
+                Ok(response) => error.set(Some(error_message(response).await)),
+                Err(e) => error.set(Some(format!("Network error: {}", e))),
+            }
+        });
+    };
+
+    rsx! {
+        div { class: "bg-white rounded-lg shadow-md border border-gray-200 p-6",
+            input {
+                r#type: "search",
+                placeholder: "Search by email or name",
+                value: "{search}",
+                oninput: move |evt| search.set(evt.value()),
+                class: "w-full mb-4 px-4 py-2 border border-gray-300 rounded-md focus:ring-2 focus:ring-blue-500 focus:border-transparent outline-none transition",
+            }
+            if let Some(message) = error() {
+                p { class: "text-sm text-red-600 mb-4", "{message}" }
+            }
+            match &*users.read() {
+                None => rsx! {
+                    p { class: "text-sm text-gray-500", "Loading users..." }
+                },
+                Some(Err(message)) => rsx! {
+                    p { class: "text-sm text-red-600", "{message}" }
+                },
+                Some(Ok(page)) => rsx! {
+                    p { class: "text-xs text-gray-500 mb-2",
+                        {format!("Showing {} of {} users", page.users.len(), page.total)}
+                    }
+                    div { class: "overflow-x-auto",
+                        table { class: "w-full text-sm text-left",
+                            thead { class: "text-xs text-gray-500 uppercase border-b border-gray-200",
+                                tr {
+                                    th { class: "py-2 pr-4", "User" }
+                                    th { class: "py-2 pr-4", "Role" }
+                                    th { class: "py-2 pr-4", "Status" }
+                                    th { class: "py-2 pr-4", "Projects" }
+                                    th { class: "py-2 pr-4", "Joined" }
+                                    th { class: "py-2", "" }
+                                }
+                            }
+                            tbody {
+                                for user in page.users.iter().cloned() {
+                                    UserRow {
+                                        key: "{user.id}",
+                                        is_self: user.id == current_user_id,
+                                        user: user.clone(),
+                                        on_role: move |(user_id, role): (String, String)| {
+                                            run_action(
+                                                Client::new()
+                                                    .put(endpoints::admin_user_action(&user_id, "role"))
+                                                    .json(&UpdateUserRoleRequest { role }),
+                                            )
+                                        },
+                                        on_set_active: move |(user_id, active): (String, bool)| {
+                                            let action = if active { "reactivate" } else { "deactivate" };
+                                            run_action(Client::new().post(endpoints::admin_user_action(&user_id, action)))
+                                        },
+                                        on_impersonate: impersonate,
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+        }
+    }
+}
+
+#[component]
+fn UserRow(
+    user: AdminUserResponse,
+    is_self: bool,
+    on_role: EventHandler<(String, String)>,
+    on_set_active: EventHandler<(String, bool)>,
+    on_impersonate: EventHandler<String>,
+) -> Element {
+    let role_user_id = user.id.clone();
+    let active_user_id = user.id.clone();
+    let impersonate_user_id = user.id.clone();
+    let can_impersonate = !is_self && user.is_active && user.role != "admin";
+
+    rsx! {
+        tr { class: "border-b border-gray-100",
+            td { class: "py-3 pr-4",
+                p { class: "font-medium text-gray-900",
+                    if is_self { "{user.email} (You)" } else { "{user.email}" }
+                }
+                if let Some(name) = &user.name {
+                    p { class: "text-xs text-gray-500", "{name}" }
+                }
+            }
+            td { class: "py-3 pr-4",
+                select {
+                    class: "px-2 py-1 border border-gray-300 rounded text-sm disabled:opacity-50",
+                    disabled: is_self,
+                    onchange: move |evt| on_role.call((role_user_id.clone(), evt.value())),
+                    option { value: "user", selected: user.role == "user", "User" }
+                    option { value: "admin", selected: user.role == "admin", "Admin" }
+                }
+            }
+            td { class: "py-3 pr-4",
+                if user.is_active {
+                    span { class: "px-2 py-1 text-xs rounded-full bg-green-100 text-green-700", "Active" }
+                } else {
+                    span { class: "px-2 py-1 text-xs rounded-full bg-gray-200 text-gray-700", "Deactivated" }
+                }
+                if !user.email_verified {
+                    span { class: "ml-2 px-2 py-1 text-xs rounded-full bg-yellow-100 text-yellow-700", "Unverified" }
+                }
+            }
+            td { class: "py-3 pr-4 text-gray-700", "{user.project_count}" }
+            td { class: "py-3 pr-4 text-gray-500", "{user.created_at}" }
+            td { class: "py-3 text-right whitespace-nowrap",
+                if can_impersonate {
+                    button {
+                        class: "px-3 py-1 text-blue-600 hover:bg-blue-50 rounded text-sm font-medium",
+                        onclick: move |_| on_impersonate.call(impersonate_user_id.clone()),
+                        "Impersonate"
+                    }
+                }
+                if !is_self {
+                    button {
+                        class: if user.is_active {
+                            "px-3 py-1 text-red-600 hover:bg-red-50 rounded text-sm font-medium"
+                        } else {
+                            "px-3 py-1 text-green-600 hover:bg-green-50 rounded text-sm font-medium"
+                        },
+                        onclick: {
+                            let activate = !user.is_active;
+                            move |_| on_set_active.call((active_user_id.clone(), activate))
+                        },
+                        if user.is_active { "Deactivate" } else { "Reactivate" }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[component]
+fn ProjectsPanel() -> Element {
+    let auth_state = use_context::<Signal<AuthState>>();
+    let mut search = use_signal(String::new);
+    let mut refresh = use_signal(|| 0u32);
+    let projects = use_admin_projects(search, refresh);
+    let mut error = use_signal(|| None::<String>);
+    let mut pending_delete = use_signal(|| None::<AdminProjectResponse>);
+
+    let delete_project = move |_| {
+        let Some(project) = pending_delete() else {
+            return;
+        };
+        let Some(token) = auth_state.read().token.clone() else {
+            return;
+        };
+        pending_delete.set(None);
+        error.set(None);
+
+        spawn(async move {
+            let response = Client::new()
+                .delete(endpoints::admin_project(&project.slug))
+                .header("Authorization", format!("Bearer {}", token))
+                .send()
+                .await;
+
+            match response {
+                Ok(response) if response.status().is_success() => refresh += 1,
+                Ok(response) => error.set(Some(error_message(response).await)),
+                Err(e) => error.set(Some(format!("Network error: {}", e))),
+            }
+        });
+    };
+
+    rsx! {
+        div { class: "bg-white rounded-lg shadow-md border border-gray-200 p-6",
+            input {
+                r#type: "search",
+                placeholder: "Search by name, slug or owner email",
+                value: "{search}",
+                oninput: move |evt| search.set(evt.value()),
+                class: "w-full mb-4 px-4 py-2 border border-gray-300 rounded-md focus:ring-2 focus:ring-blue-500 focus:border-transparent outline-none transition",
+            }
+            if let Some(message) = error() {
+                p { class: "text-sm text-red-600 mb-4", "{message}" }
+            }
+            match &*projects.read() {
+                None => rsx! {
+                    p { class: "text-sm text-gray-500", "Loading projects..." }
+                },
+                Some(Err(message)) => rsx! {
+                    p { class: "text-sm text-red-600", "{message}" }
+                },
+                Some(Ok(projects)) if projects.is_empty() => rsx! {
+                    p { class: "text-sm text-gray-500", "No projects found" }
+                },
+                Some(Ok(projects)) => rsx! {
+                    div { class: "overflow-x-auto",
+                        table { class: "w-full text-sm text-left",
+                            thead { class: "text-xs text-gray-500 uppercase border-b border-gray-200",
+                                tr {
+                                    th { class: "py-2 pr-4", "Project" }
+                                    th { class: "py-2 pr-4", "Owner" }
+                                    th { class: "py-2 pr-4", "Members" }
+                                    th { class: "py-2 pr-4", "Tables" }
+                                    th { class: "py-2 pr-4", "Storage" }
+                                    th { class: "py-2 pr-4", "Created" }
+                                    th { class: "py-2", "" }
+                                }
+                            }
+                            tbody {
+                                for project in projects.iter().cloned() {
+                                    tr { key: "{project.id}", class: "border-b border-gray-100",
+                                        td { class: "py-3 pr-4",
+                                            p { class: "font-medium text-gray-900", "{project.name}" }
+                                            p { class: "text-xs text-gray-500 font-mono", "{project.slug}" }
+                                        }
+                                        td { class: "py-3 pr-4 text-gray-700",
+                                            {project.owner_email.clone().unwrap_or_else(|| project.owner_id.clone())}
+                                        }
+                                        td { class: "py-3 pr-4 text-gray-700", "{project.member_count}" }
+                                        td { class: "py-3 pr-4 text-gray-700", "{project.table_count}" }
+                                        td { class: "py-3 pr-4 text-gray-700", {format_bytes(project.storage_bytes)} }
+                                        td { class: "py-3 pr-4 text-gray-500", "{project.created_at}" }
+                                        td { class: "py-3 text-right",
+                                            button {
+                                                class: "px-3 py-1 text-red-600 hover:bg-red-50 rounded text-sm font-medium",
+                                                onclick: {
+                                                    let project = project.clone();
+                                                    move |_| pending_delete.set(Some(project.clone()))
+                                                },
+                                                "Delete"
+                                            }
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                },
+            }
+        }
+        if let Some(project) = pending_delete() {
+            div {
+                class: "fixed inset-0 bg-black bg-opacity-50 flex items-center justify-center z-50 p-4",
+                onclick: move |_| pending_delete.set(None),
+                div {
+                    class: "bg-white rounded-lg shadow-xl max-w-md w-full p-6",
+                    onclick: move |e| e.stop_propagation(),
+                    h3 { class: "text-lg font-bold text-gray-900 mb-4", "Delete {project.name}?" }
+                    p { class: "text-gray-600 mb-6",
+                        "This deletes the project for every member. It cannot be undone."
+                    }
+                    div { class: "flex justify-end gap-3",
+                        button {
+                            class: "px-4 py-2 border border-gray-300 text-gray-700 font-medium rounded-md hover:bg-gray-50 transition",
+                            onclick: move |_| pending_delete.set(None),
+                            "Cancel"
+                        }
+                        button {
+                            class: "px-4 py-2 bg-red-600 hover:bg-red-700 text-white font-medium rounded-md transition",
+                            onclick: delete_project,
+                            "Delete"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn format_bytes(bytes: i64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut size = bytes as f64;
+    let mut unit = 0;
+    while size >= 1024.0 && unit < UNITS.len() - 1 {
+        size /= 1024.0;
+        unit += 1;
+    }
+
+    if unit == 0 {
+        format!("{} {}", bytes, UNITS[0])
+    } else {
+        format!("{:.1} {}", size, UNITS[unit])
+    }
+}
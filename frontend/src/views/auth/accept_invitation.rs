@@ -0,0 +1,206 @@
+use dioxus::prelude::*;
+use reqwest::Client;
+use shared::models::{InvitationDetailsResponse, InvitationTokenRequest, ProjectResponse};
+use crate::components::ErrorNotification;
+use crate::config::endpoints;
+use crate::views::auth::reset_password::error_message;
+use crate::{clear_pending_invitation, set_pending_invitation, AuthState, Route};
+const LOGO: Asset = asset!("/assets/Original_Ferris.svg");
+
+/// Shows a project invitation opened from its email link (`?token=...`) and lets the
+/// invitee accept or decline it; signed-out visitors are sent through sign-in or sign-up first
+#[component]
+pub fn AcceptInvitation(token: String) -> Element {
+    let mut auth_state = use_context::<Signal<AuthState>>();
+    let navigator = use_navigator();
+    let mut declined = use_signal(|| false);
+    let mut is_loading = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+
+    let lookup_token = token.clone();
+    let invitation = use_resource(move || {
+        let token = lookup_token.clone();
+        async move {
+            if token.is_empty() {
+                return Err("Invitation link is missing its token".to_string());
+            }
+
+            let response = Client::new()
+                .post(endpoints::invitation_lookup())
+                .json(&InvitationTokenRequest { token })
+                .send()
+                .await;
+
+            match response {
+                Ok(response) if response.status().is_success() => response
+                    .json::<InvitationDetailsResponse>()
+                    .await
+                    .map_err(|e| format!("Failed to parse response: {}", e)),
+                Ok(response) => {
+                    // A used or expired link should not be reopened after every sign-in
+                    clear_pending_invitation();
+                    Err(error_message(response).await)
+                }
+                Err(e) => Err(format!("Network error: {}", e)),
+            }
+        }
+    });
+
+    let signed_in = auth_state.read().is_authenticated();
+    let signed_in_email = auth_state.read().user.as_ref().map(|user| user.email.clone());
+
+    // Come back here after signing in or creating an account
+    let pending_token = token.clone();
+    use_effect(move || {
+        if !auth_state.read().is_authenticated() && !pending_token.is_empty() {
+            set_pending_invitation(&pending_token);
+        }
+    });
+
+    let accept_token = token.clone();
+    let accept = move |_| {
+        let Some(auth_token) = auth_state.read().token.clone() else {
+            return;
+        };
+        let request = InvitationTokenRequest { token: accept_token.clone() };
+        error.set(None);
+        is_loading.set(true);
+
+        spawn(async move {
+            let response = Client::new()
+                .post(endpoints::accept_invitation())
+                .header("Authorization", format!("Bearer {}", auth_token))
+                .json(&request)
+                .send()
+                .await;
+
+            is_loading.set(false);
+            match response {
+                Ok(response) if response.status().is_success() => {
+                    match response.json::<ProjectResponse>().await {
+                        Ok(project) => {
+                            clear_pending_invitation();
+                            // The emailed link proves the address
+                            auth_state.write().set_email_verified();
+                            navigator.push(Route::ProjectById { id: project.slug });
+                        }
+                        Err(e) => error.set(Some(format!("Failed to parse response: {}", e))),
+                    }
+                }
+                Ok(response) => error.set(Some(error_message(response).await)),
+                Err(e) => error.set(Some(format!("Network error: {}", e))),
+            }
+        });
+    };
+
+    let decline_token = token.clone();
+    let decline = move |_| {
+        let request = InvitationTokenRequest { token: decline_token.clone() };
+        error.set(None);
+        is_loading.set(true);
+
+        spawn(async move {
+            let response = Client::new()
+                .post(endpoints::decline_invitation())
+                .json(&request)
+                .send()
+                .await;
+
+            is_loading.set(false);
+            match response {
+                Ok(response) if response.status().is_success() => {
+                    clear_pending_invitation();
+                    declined.set(true);
+                }
+                Ok(response) => error.set(Some(error_message(response).await)),
+                Err(e) => error.set(Some(format!("Network error: {}", e))),
+            }
+        });
+    };
+
+    rsx! {
+        div { class: " bg-gray-900 min-h-screen flex items-center justify-center px-4 py-8",
+            div { class: "w-full max-w-md",
+                div { class: "bg-white rounded-xl shadow-2xl p-8",
+                    div { class: "text-center pt-4 mb-8",
+                        a {
+                            href: "#",
+                            class: "flex items-center justify-center mb-6 text-2xl font-semibold text-gray-900",
+                            img {
+                                class: "w-8 h-8 mr-2",
+                                src: LOGO,
+                                alt: "logo",
+                            }
+                            "Ferrisbase"
+                        }
+                    }
+                    div { class: "space-y-6 px-4 text-center",
+                        match &*invitation.read() {
+                            None => rsx! {
+                                p { class: "text-sm text-gray-600", "Loading invitation..." }
+                            },
+                            Some(Err(message)) => rsx! {
+                                h1 { class: "text-xl font-bold text-gray-900", "Invitation unavailable" }
+                                ErrorNotification { message: message.clone(), class: None }
+                                p { class: "text-sm text-gray-600",
+                                    "Ask a project owner or admin to send you a new invitation."
+                                }
+                            },
+                            Some(Ok(_)) if declined() => rsx! {
+                                h1 { class: "text-xl font-bold text-gray-900", "Invitation declined" }
+                                p { class: "text-sm text-gray-600", "You will not be added to the project." }
+                            },
+                            Some(Ok(details)) => rsx! {
+                                h1 { class: "text-xl font-bold text-gray-900", "Join {details.project_name}" }
+                                p { class: "text-sm text-gray-600",
+                                    {format!(
+                                        "{} invited {} to join as {}.",
+                                        details.invited_by.clone().unwrap_or_else(|| "A project member".to_string()),
+                                        details.email,
+                                        details.role.as_str(),
+                                    )}
+                                }
+                                if let Some(message) = error() {
+                                    ErrorNotification { message, class: None }
+                                }
+                                if signed_in {
+                                    if signed_in_email.as_ref().is_some_and(|email| !email.eq_ignore_ascii_case(&details.email)) {
+                                        p { class: "text-sm text-yellow-700",
+                                            "You are signed in with a different email address. Sign in as {details.email} to accept."
+                                        }
+                                    }
+                                    button {
+                                        class: "block w-full text-white bg-blue-600 hover:bg-blue-700 font-medium rounded-lg text-sm px-5 py-2.5 text-center disabled:opacity-50",
+                                        disabled: is_loading(),
+                                        onclick: accept,
+                                        "Accept invitation"
+                                    }
+                                } else {
+                                    p { class: "text-sm text-gray-600",
+                                        "Sign in or create an account with {details.email} to accept."
+                                    }
+                                    Link {
+                                        to: Route::RegisterForm {},
+                                        class: "block w-full text-white bg-blue-600 hover:bg-blue-700 font-medium rounded-lg text-sm px-5 py-2.5 text-center",
+                                        "Create an account"
+                                    }
+                                    Link {
+                                        to: Route::LoginForm {},
+                                        class: "block w-full text-blue-600 border border-blue-600 hover:bg-blue-50 font-medium rounded-lg text-sm px-5 py-2.5 text-center",
+                                        "Sign in"
+                                    }
+                                }
+                                button {
+                                    class: "w-full text-sm text-gray-600 hover:text-gray-900 disabled:opacity-50",
+                                    disabled: is_loading(),
+                                    onclick: decline,
+                                    "Decline"
+                                }
+                            },
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,7 +1,8 @@
 use dioxus::prelude::*;
-use crate::{Route, AuthState, User};
+use crate::{Route, AuthState};
 use crate::components::ErrorNotification;
-use shared::models::{AuthResponse, LoginRequest};
+use crate::views::auth::{signed_in_route, start_session, MfaChallengeForm, OidcProviderButtons};
+use shared::models::{LoginRequest, LoginResponse};
 use reqwest::Client;
 use crate::config::endpoints;
  
@@ -13,13 +14,14 @@ pub fn LoginForm() -> Element {
     let mut password = use_signal(|| String::new());
     let mut is_loading = use_signal(|| false);
     let mut error_message = use_signal(|| None::<String>);
-    let mut auth_state = use_context::<Signal<AuthState>>();
+    let mut mfa_token = use_signal(|| None::<String>);
+    let auth_state = use_context::<Signal<AuthState>>();
     let navigator = use_navigator();
 
     // Check if already authenticated
     use_effect(move || {
         if auth_state.read().is_authenticated() {
-            navigator.push(Route::Projects {});
+            navigator.push(signed_in_route());
         }
     });
 
@@ -67,20 +69,15 @@ pub fn LoginForm() -> Element {
             {
                 Ok(response) => {
                     if response.status().is_success() {
-                        match response.json::<AuthResponse>().await {
-                            Ok(auth_response) => {
-                                // Convert UserResponse to User
-                                let user = User {
-                                    id: auth_response.user.id,
-                                    email: auth_response.user.email,
-                                    name: auth_response.user.name,
-                                    role: auth_response.user.role,
-                                };
-                                
-                                // Update auth state
-                                auth_state.write().login(user, auth_response.token);
+                        match response.json::<LoginResponse>().await {
+                            Ok(LoginResponse::Authenticated(auth_response)) => {
+                                start_session(auth_state, auth_response);
                                 is_loading.set(false);
-                                navigator.push(Route::Projects {});
+                                navigator.push(signed_in_route());
+                            }
+                            Ok(LoginResponse::MfaRequired(challenge)) => {
+                                is_loading.set(false);
+                                mfa_token.set(Some(challenge.mfa_token));
                             }
                             Err(e) => {
                                 is_loading.set(false);
@@ -89,10 +86,19 @@ pub fn LoginForm() -> Element {
                         }
                     } else {
                         let status_code = response.status().as_u16();
+                        let retry_after = response
+                            .headers()
+                            .get("retry-after")
+                            .and_then(|value| value.to_str().ok())
+                            .and_then(|value| value.parse::<u64>().ok());
                         let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
                         is_loading.set(false);
-                        error_message.set(Some(match status_code {
-                            401 => "Invalid email or password".to_string(),
+                        error_message.set(Some(match (status_code, retry_after) {
+                            (401, _) => "Invalid email or password".to_string(),
+                            (429, Some(seconds)) => format!(
+                                "Too many failed sign-in attempts. Try again in {} seconds.",
+                                seconds
+                            ),
                             _ => error_text,
                         }));
                     }
@@ -122,85 +128,90 @@ pub fn LoginForm() -> Element {
                         }
                     }
                     div { class: "space-y-6 px-4",
-                        h1 { class: "text-xl font-bold leading-tight tracking-tight text-gray-900 md:text-2xl mb-6",
-                            "Sign in to your account"
-                        }
-                        form {
-                            class: "space-y-4 md:space-y-6",
-                            onsubmit: handle_submit,
-                            div {
-                                label {
-                                    r#for: "email",
-                                    class: "block mb-2 text-sm font-medium text-gray-900",
-                                    "Email or Username"
-                                }
-                                input {
-                                    r#type: "text",
-                                    id: "email",
-                                    class: "bg-gray-50 border border-gray-300 text-gray-900 rounded-lg focus:ring-blue-600 focus:border-blue-600 block w-full p-2.5",
-                                    placeholder: "Enter your email or username",
-                                    required: true,
-                                    value: "{email}",
-                                    oninput: move |e| email.set(e.value()),
-                                }
+                        if let Some(token) = mfa_token() {
+                            MfaChallengeForm { mfa_token: token }
+                        } else {
+                            h1 { class: "text-xl font-bold leading-tight tracking-tight text-gray-900 md:text-2xl mb-6",
+                                "Sign in to your account"
                             }
-                            div {
-                                label {
-                                    r#for: "password",
-                                    class: "block mb-2 text-sm font-medium text-gray-900",
-                                    "Password"
+                            form {
+                                class: "space-y-4 md:space-y-6",
+                                onsubmit: handle_submit,
+                                div {
+                                    label {
+                                        r#for: "email",
+                                        class: "block mb-2 text-sm font-medium text-gray-900",
+                                        "Email or Username"
+                                    }
+                                    input {
+                                        r#type: "text",
+                                        id: "email",
+                                        class: "bg-gray-50 border border-gray-300 text-gray-900 rounded-lg focus:ring-blue-600 focus:border-blue-600 block w-full p-2.5",
+                                        placeholder: "Enter your email or username",
+                                        required: true,
+                                        value: "{email}",
+                                        oninput: move |e| email.set(e.value()),
+                                    }
                                 }
-                                input {
-                                    r#type: "password",
-                                    id: "password",
-                                    placeholder: "••••••••",
-                                    class: "bg-gray-50 border border-gray-300 text-gray-900 rounded-lg focus:ring-blue-600 focus:border-blue-600 block w-full p-2.5",
-                                    required: true,
-                                    value: "{password}",
-                                    oninput: move |e| password.set(e.value()),
+                                div {
+                                    label {
+                                        r#for: "password",
+                                        class: "block mb-2 text-sm font-medium text-gray-900",
+                                        "Password"
+                                    }
+                                    input {
+                                        r#type: "password",
+                                        id: "password",
+                                        placeholder: "••••••••",
+                                        class: "bg-gray-50 border border-gray-300 text-gray-900 rounded-lg focus:ring-blue-600 focus:border-blue-600 block w-full p-2.5",
+                                        required: true,
+                                        value: "{password}",
+                                        oninput: move |e| password.set(e.value()),
+                                    }
                                 }
-                            }
-                            div { class: "flex items-center justify-between",
-                                div { class: "flex items-start",
-                                    div { class: "flex items-center h-5",
-                                        input {
-                                            id: "remember",
-                                            aria_describedby: "remember",
-                                            r#type: "checkbox",
-                                            class: "w-4 h-4 border border-gray-300 rounded bg-gray-50 focus:ring-3 focus:ring-blue-300 dark:bg-gray-700 dark:border-gray-600 dark:focus:ring-blue-600 dark:ring-offset-gray-800",
-                                            required: true,
+                                div { class: "flex items-center justify-between",
+                                    div { class: "flex items-start",
+                                        div { class: "flex items-center h-5",
+                                            input {
+                                                id: "remember",
+                                                aria_describedby: "remember",
+                                                r#type: "checkbox",
+                                                class: "w-4 h-4 border border-gray-300 rounded bg-gray-50 focus:ring-3 focus:ring-blue-300 dark:bg-gray-700 dark:border-gray-600 dark:focus:ring-blue-600 dark:ring-offset-gray-800",
+                                                required: true,
+                                            }
                                         }
-                                    }
-                                    div { class: "ml-3 text-sm",
-                                        label {
-                                            r#for: "remember",
-                                            class: "text-gray-500",
-                                            "Remember me"
+                                        div { class: "ml-3 text-sm",
+                                            label {
+                                                r#for: "remember",
+                                                class: "text-gray-500",
+                                                "Remember me"
+                                            }
                                         }
                                     }
+                                    Link {
+                                        to: Route::ResetPasswordForm { token: String::new() },
+                                        class: "text-sm font-medium text-blue-600 hover:underline",
+                                        "Forgot password?"
+                                    }
                                 }
-                                Link {
-                                    to: Route::ResetPasswordForm {},
-                                    class: "text-sm font-medium text-blue-600 hover:underline",
-                                    "Forgot password?"
-                                }
-                            }
-                            button {
-                                r#type: "submit",
-                                class: "w-full mb-4 cursor-pointer text-white bg-blue-600 hover:bg-blue-700 focus:ring-4 focus:outline-none focus:ring-blue-300 font-medium rounded-lg text-sm px-5 py-2.5 text-center disabled:opacity-75",
-                                disabled: *is_loading.read(),
-                                if *is_loading.read() {
-                                    "Signing in..."
-                                } else {
-                                    "Sign in"
+                                button {
+                                    r#type: "submit",
+                                    class: "w-full mb-4 cursor-pointer text-white bg-blue-600 hover:bg-blue-700 focus:ring-4 focus:outline-none focus:ring-blue-300 font-medium rounded-lg text-sm px-5 py-2.5 text-center disabled:opacity-75",
+                                    disabled: *is_loading.read(),
+                                    if *is_loading.read() {
+                                        "Signing in..."
+                                    } else {
+                                        "Sign in"
+                                    }
                                 }
-                            }
-                            if let Some(msg) = &*error_message.read() {
-                                ErrorNotification {
-                                    message: msg.clone(),
-                                    class: Some("mb-0".to_string()),
+                                if let Some(msg) = &*error_message.read() {
+                                    ErrorNotification {
+                                        message: msg.clone(),
+                                        class: Some("mb-0".to_string()),
+                                    }
                                 }
                             }
+                            OidcProviderButtons {}
                         }
                     }
                 }
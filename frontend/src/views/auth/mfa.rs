@@ -0,0 +1,102 @@
+use dioxus::prelude::*;
+use reqwest::Client;
+use shared::models::{AuthResponse, MfaVerifyRequest};
+use crate::components::ErrorNotification;
+use crate::config::endpoints;
+use crate::views::auth::reset_password::error_message;
+use crate::views::auth::{signed_in_route, start_session};
+use crate::AuthState;
+
+/// Second step of signing in for users with multi-factor authentication enabled
+#[component]
+pub fn MfaChallengeForm(mfa_token: String) -> Element {
+    let mut code = use_signal(String::new);
+    let mut is_loading = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+    let auth_state = use_context::<Signal<AuthState>>();
+    let navigator = use_navigator();
+
+    let handle_submit = move |evt: FormEvent| {
+        evt.prevent_default();
+
+        let request = MfaVerifyRequest {
+            mfa_token: mfa_token.clone(),
+            code: code.read().trim().to_string(),
+        };
+        if request.code.is_empty() {
+            error.set(Some("Enter the code from your authenticator app".to_string()));
+            return;
+        }
+
+        error.set(None);
+        is_loading.set(true);
+
+        spawn(async move {
+            let response = Client::new()
+                .post(endpoints::mfa_verify())
+                .json(&request)
+                .send()
+                .await;
+
+            let result = match response {
+                Ok(response) if response.status().is_success() => response
+                    .json::<AuthResponse>()
+                    .await
+                    .map_err(|e| format!("Failed to parse response: {}", e)),
+                Ok(response) => Err(error_message(response).await),
+                Err(e) => Err(format!("Network error: {}", e)),
+            };
+
+            is_loading.set(false);
+            match result {
+                Ok(auth_response) => {
+                    start_session(auth_state, auth_response);
+                    navigator.push(signed_in_route());
+                }
+                Err(message) => error.set(Some(message)),
+            }
+        });
+    };
+
+    rsx! {
+        h1 { class: "text-xl font-bold leading-tight tracking-tight text-gray-900 md:text-2xl mb-2",
+            "Two-factor authentication"
+        }
+        p { class: "text-sm text-gray-600",
+            "Enter the 6-digit code from your authenticator app, or one of your recovery codes."
+        }
+        form {
+            class: "space-y-4 md:space-y-6",
+            onsubmit: handle_submit,
+            div {
+                label {
+                    r#for: "mfa-code",
+                    class: "block mb-2 text-sm font-medium text-gray-900",
+                    "Code"
+                }
+                input {
+                    r#type: "text",
+                    id: "mfa-code",
+                    autocomplete: "one-time-code",
+                    class: "bg-gray-50 border border-gray-300 text-gray-900 rounded-lg focus:ring-blue-600 focus:border-blue-600 block w-full p-2.5 tracking-widest",
+                    placeholder: "123456",
+                    required: true,
+                    value: "{code}",
+                    oninput: move |e| code.set(e.value()),
+                }
+            }
+            button {
+                r#type: "submit",
+                class: "w-full mb-4 cursor-pointer text-white bg-blue-600 hover:bg-blue-700 focus:ring-4 focus:outline-none focus:ring-blue-300 font-medium rounded-lg text-sm px-5 py-2.5 text-center disabled:opacity-75",
+                disabled: *is_loading.read(),
+                if *is_loading.read() { "Verifying..." } else { "Verify" }
+            }
+            if let Some(msg) = &*error.read() {
+                ErrorNotification {
+                    message: msg.clone(),
+                    class: Some("mb-0".to_string()),
+                }
+            }
+        }
+    }
+}
@@ -1,3 +1,7 @@
+use dioxus::prelude::*;
+use shared::models::AuthResponse;
+use crate::{pending_invitation, AuthState, Route, User};
+
 pub mod login;
 pub use login::LoginForm;
 
@@ -5,4 +9,37 @@ pub mod reset_password;
 pub use reset_password::ResetPasswordForm;
 
 pub mod register;
-pub use register::RegisterForm;
\ No newline at end of file
+pub use register::RegisterForm;
+
+pub mod verify_email;
+pub use verify_email::VerifyEmail;
+pub mod accept_invitation;
+pub use accept_invitation::AcceptInvitation;
+pub mod oidc;
+pub use oidc::{OidcCallback, OidcProviderButtons};
+pub mod mfa;
+pub use mfa::MfaChallengeForm;
+
+/// Where to go after signing in: back to an invitation opened while signed out, or the projects
+pub(crate) fn signed_in_route() -> Route {
+    match pending_invitation() {
+        Some(token) => Route::AcceptInvitation { token },
+        None => Route::Projects {},
+    }
+}
+
+/// Store the session from a completed sign-in
+pub(crate) fn start_session(mut auth_state: Signal<AuthState>, auth_response: AuthResponse) {
+    let user = User {
+        id: auth_response.user.id,
+        email: auth_response.user.email,
+        name: auth_response.user.name,
+        role: auth_response.user.role,
+        email_verified: auth_response.user.email_verified,
+    };
+
+    auth_state.with_mut(|auth| {
+        auth.login(user, auth_response.token);
+        auth.set_refresh_token(auth_response.refresh_token, auth_response.expires_in);
+    });
+}
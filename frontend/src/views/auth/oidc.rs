@@ -0,0 +1,189 @@
+use dioxus::prelude::*;
+use reqwest::Client;
+use shared::models::{LoginResponse, OidcAuthorizeResponse, OidcCallbackRequest, OidcProviderResponse};
+use crate::components::ErrorNotification;
+use crate::config::endpoints;
+use crate::views::auth::reset_password::error_message;
+use crate::views::auth::{signed_in_route, start_session, MfaChallengeForm};
+use crate::{set_oidc_state, take_oidc_state, AuthState, Route};
+const LOGO: Asset = asset!("/assets/Original_Ferris.svg");
+
+/// "Continue with ..." buttons for every configured OpenID Connect provider; renders nothing if there are none
+#[component]
+pub fn OidcProviderButtons() -> Element {
+    let mut redirecting = use_signal(|| None::<String>);
+    let mut error = use_signal(|| None::<String>);
+
+    let providers = use_resource(|| async move {
+        Client::new()
+            .get(endpoints::oidc_providers())
+            .send()
+            .await
+            .ok()?
+            .json::<Vec<OidcProviderResponse>>()
+            .await
+            .ok()
+    });
+
+    let mut start_login = move |provider: String| {
+        error.set(None);
+        redirecting.set(Some(provider.clone()));
+
+        spawn(async move {
+            let response = Client::new()
+                .post(endpoints::oidc_authorize(&provider))
+                .send()
+                .await;
+
+            let result = match response {
+                Ok(response) if response.status().is_success() => response
+                    .json::<OidcAuthorizeResponse>()
+                    .await
+                    .map_err(|e| format!("Failed to parse response: {}", e)),
+                Ok(response) => Err(error_message(response).await),
+                Err(e) => Err(format!("Network error: {}", e)),
+            };
+
+            match result {
+                Ok(authorize) => {
+                    set_oidc_state(&authorize.state);
+                    let navigated = web_sys::window()
+                        .map(|window| window.location().set_href(&authorize.authorization_url).is_ok())
+                        .unwrap_or(false);
+                    if !navigated {
+                        redirecting.set(None);
+                        error.set(Some("Could not open the sign-in page".to_string()));
+                    }
+                }
+                Err(message) => {
+                    redirecting.set(None);
+                    error.set(Some(message));
+                }
+            }
+        });
+    };
+
+    let providers = match &*providers.read() {
+        Some(Some(providers)) if !providers.is_empty() => providers.clone(),
+        _ => return rsx! {},
+    };
+
+    rsx! {
+        div { class: "space-y-3",
+            div { class: "flex items-center gap-3",
+                div { class: "flex-1 border-t border-gray-200" }
+                span { class: "text-xs uppercase text-gray-400", "or" }
+                div { class: "flex-1 border-t border-gray-200" }
+            }
+            for provider in providers {
+                button {
+                    key: "{provider.id}",
+                    r#type: "button",
+                    class: "w-full cursor-pointer text-gray-900 bg-white border border-gray-300 hover:bg-gray-50 focus:ring-4 focus:outline-none focus:ring-gray-200 font-medium rounded-lg text-sm px-5 py-2.5 text-center disabled:opacity-75",
+                    disabled: redirecting.read().is_some(),
+                    onclick: {
+                        let id = provider.id.clone();
+                        move |_| start_login(id.clone())
+                    },
+                    if redirecting.read().as_deref() == Some(provider.id.as_str()) {
+                        "Redirecting..."
+                    } else {
+                        "Continue with {provider.name}"
+                    }
+                }
+            }
+            if let Some(msg) = &*error.read() {
+                ErrorNotification { message: msg.clone(), class: Some("mb-0".to_string()) }
+            }
+        }
+    }
+}
+
+/// Finishes an OpenID Connect sign-in when the provider redirects back (`?code=...&state=...`)
+#[component]
+pub fn OidcCallback(code: String, state: String) -> Element {
+    let auth_state = use_context::<Signal<AuthState>>();
+    let navigator = use_navigator();
+
+    // Resolves to the MFA challenge token when the user still has to enter a code
+    let sign_in = use_resource(move || {
+        let code = code.clone();
+        let state = state.clone();
+        async move {
+            if code.is_empty() || state.is_empty() {
+                return Err("The sign-in was cancelled or the link is incomplete".to_string());
+            }
+
+            // Only finish sign-ins this tab started, so a link cannot sign it in to someone else's account
+            if take_oidc_state().as_deref() != Some(state.as_str()) {
+                return Err("This sign-in was not started from this browser; please try again".to_string());
+            }
+
+            let response = Client::new()
+                .post(endpoints::oidc_callback())
+                .json(&OidcCallbackRequest { code, state })
+                .send()
+                .await;
+
+            let login_response = match response {
+                Ok(response) if response.status().is_success() => response
+                    .json::<LoginResponse>()
+                    .await
+                    .map_err(|e| format!("Failed to parse response: {}", e))?,
+                Ok(response) => return Err(error_message(response).await),
+                Err(e) => return Err(format!("Network error: {}", e)),
+            };
+
+            match login_response {
+                LoginResponse::Authenticated(auth_response) => {
+                    start_session(auth_state, auth_response);
+                    navigator.replace(signed_in_route());
+                    Ok(None)
+                }
+                LoginResponse::MfaRequired(challenge) => Ok(Some(challenge.mfa_token)),
+            }
+        }
+    });
+
+    rsx! {
+        div { class: " bg-gray-900 min-h-screen flex items-center justify-center px-4 py-8",
+            div { class: "w-full max-w-md",
+                div { class: "bg-white rounded-xl shadow-2xl p-8",
+                    div { class: "text-center pt-4 mb-8",
+                        a {
+                            href: "#",
+                            class: "flex items-center justify-center mb-6 text-2xl font-semibold text-gray-900",
+                            img {
+                                class: "w-8 h-8 mr-2",
+                                src: LOGO,
+                                alt: "logo",
+                            }
+                            "Ferrisbase"
+                        }
+                    }
+                    div { class: "space-y-6 px-4 text-center",
+                        match &*sign_in.read() {
+                            Some(Ok(Some(mfa_token))) => rsx! {
+                                div { class: "text-left space-y-6",
+                                    MfaChallengeForm { mfa_token: mfa_token.clone() }
+                                }
+                            },
+                            Some(Err(message)) => rsx! {
+                                h1 { class: "text-xl font-bold text-gray-900", "Could not sign you in" }
+                                ErrorNotification { message: message.clone(), class: None }
+                                Link {
+                                    to: Route::LoginForm {},
+                                    class: "block w-full text-white bg-blue-600 hover:bg-blue-700 font-medium rounded-lg text-sm px-5 py-2.5 text-center",
+                                    "Back to sign in"
+                                }
+                            },
+                            _ => rsx! {
+                                p { class: "text-sm text-gray-600", "Signing you in..." }
+                            },
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
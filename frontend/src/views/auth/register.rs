@@ -1,6 +1,7 @@
 use dioxus::prelude::*;
 use crate::{Route, AuthState, User};
 use crate::components::ErrorNotification;
+use crate::views::auth::{signed_in_route, OidcProviderButtons};
 use shared::models::{AuthResponse, RegisterRequest};
 use reqwest::Client;
 use crate::config::endpoints;
@@ -21,7 +22,7 @@ pub fn RegisterForm() -> Element {
     // Check if already authenticated
     use_effect(move || {
         if auth_state.read().is_authenticated() {
-            navigator.push(Route::Projects {});
+            navigator.push(signed_in_route());
         }
     });
 
@@ -92,12 +93,16 @@ pub fn RegisterForm() -> Element {
                                     email: auth_response.user.email,
                                     name: auth_response.user.name,
                                     role: auth_response.user.role,
+                                    email_verified: auth_response.user.email_verified,
                                 };
                                 
                                 // Update auth state
-                                auth_state.write().login(user, auth_response.token);
+                                auth_state.with_mut(|auth| {
+                                    auth.login(user, auth_response.token);
+                                    auth.set_refresh_token(auth_response.refresh_token, auth_response.expires_in);
+                                });
                                 is_loading.set(false);
-                                navigator.push(Route::Projects {});
+                                navigator.push(signed_in_route());
                             }
                             Err(e) => {
                                 is_loading.set(false);
@@ -258,6 +263,8 @@ pub fn RegisterForm() -> Element {
                                 }
                             }
 
+                            OidcProviderButtons {}
+
                             p { class: "text-sm font-light text-gray-500 text-center",
                                 "Already have an account? "
                                 Link {
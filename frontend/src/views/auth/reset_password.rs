@@ -1,10 +1,23 @@
 use dioxus::prelude::*;
+use reqwest::Client;
+use shared::models::{ForgotPasswordRequest, ResetPasswordRequest};
 use crate::Route;
 use crate::components::{ErrorNotification, SuccessNotification};
+use crate::config::endpoints;
 const LOGO: Asset = asset!("/assets/Original_Ferris.svg");
 
+/// Read the `error` field of a JSON error response, falling back to the raw body
+pub(crate) async fn error_message(response: reqwest::Response) -> String {
+    let body = response.text().await.unwrap_or_default();
+    serde_json::from_str::<serde_json::Value>(&body)
+        .ok()
+        .and_then(|json| json["error"].as_str().map(str::to_string))
+        .unwrap_or(body)
+}
+
+/// Requests a reset link, or sets a new password when opened from one (`?token=...`)
 #[component]
-pub fn ResetPasswordForm() -> Element {
+pub fn ResetPasswordForm(token: String) -> Element {
     let mut email = use_signal(|| String::new());
     let mut email_error = use_signal(|| String::new());
     let mut success_message = use_signal(|| String::new());
@@ -29,14 +42,20 @@ pub fn ResetPasswordForm() -> Element {
         
         is_loading.set(true);
         
-        // Simulate API call
-        let email_val = email();
+        let request = ForgotPasswordRequest { email: email() };
         spawn(async move {
-            // For demo purposes, simulate success after a brief moment
-            // In real implementation, this would be an API call
+            let response = Client::new()
+                .post(endpoints::password_reset())
+                .json(&request)
+                .send()
+                .await;
+
             is_loading.set(false);
-            success_message.set(format!("Password reset link sent to {}", email_val));
-            is_submitted.set(true);
+            match response {
+                Ok(response) if response.status().is_success() => is_submitted.set(true),
+                Ok(response) => email_error.set(error_message(response).await),
+                Err(e) => email_error.set(format!("Network error: {}", e)),
+            }
         });
     };
 
@@ -58,7 +77,9 @@ pub fn ResetPasswordForm() -> Element {
                         }
                     }
                     div { class: "space-y-6 px-4",
-                        if !is_submitted() {
+                        if !token.is_empty() {
+                            ChooseNewPassword { token: token.clone() }
+                        } else if !is_submitted() {
                             h1 { class: "text-xl font-bold leading-tight tracking-tight text-gray-900 md:text-2xl",
                                 "Reset your password"
                             }
@@ -159,8 +180,9 @@ pub fn ResetPasswordForm() -> Element {
                                     "Check your email"
                                 }
                                 p { class: "mt-2 text-sm text-gray-600",
-                                    "We've sent a password reset link to "
+                                    "If an account exists for "
                                     span { class: "font-medium text-blue-600", "{email}" }
+                                    ", we've sent it a password reset link."
                                 }
                             }
                             div { class: "mt-6",
@@ -187,4 +209,115 @@ pub fn ResetPasswordForm() -> Element {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+/// Second step of the reset: choose a new password with the token from the emailed link
+#[component]
+fn ChooseNewPassword(token: String) -> Element {
+    let mut new_password = use_signal(|| String::new());
+    let mut confirm_password = use_signal(|| String::new());
+    let mut error = use_signal(|| String::new());
+    let mut is_reset = use_signal(|| false);
+    let mut is_loading = use_signal(|| false);
+
+    let handle_submit = move |_| {
+        error.set("".to_string());
+
+        if new_password() != confirm_password() {
+            error.set("Passwords do not match".to_string());
+            return;
+        }
+
+        is_loading.set(true);
+
+        let request = ResetPasswordRequest {
+            token: token.clone(),
+            new_password: new_password(),
+        };
+        spawn(async move {
+            let response = Client::new()
+                .post(endpoints::confirm_password_reset())
+                .json(&request)
+                .send()
+                .await;
+
+            is_loading.set(false);
+            match response {
+                Ok(response) if response.status().is_success() => is_reset.set(true),
+                Ok(response) => error.set(error_message(response).await),
+                Err(e) => error.set(format!("Network error: {}", e)),
+            }
+        });
+    };
+
+    rsx! {
+        if is_reset() {
+            div { class: "text-center",
+                h2 { class: "mt-4 text-xl font-bold text-gray-900", "Password updated" }
+                p { class: "mt-2 text-sm text-gray-600",
+                    "Your password has been reset and you have been signed out everywhere."
+                }
+                div { class: "mt-6",
+                    Link {
+                        to: Route::LoginForm {},
+                        class: "block w-full text-white bg-blue-600 hover:bg-blue-700 font-medium rounded-lg text-sm px-5 py-2.5 text-center",
+                        "Sign in"
+                    }
+                }
+            }
+        } else {
+            h1 { class: "text-xl font-bold leading-tight tracking-tight text-gray-900 md:text-2xl",
+                "Choose a new password"
+            }
+            form {
+                class: "space-y-4 md:space-y-6",
+                onsubmit: handle_submit,
+                div {
+                    label {
+                        r#for: "new-password",
+                        class: "block mb-2 text-sm font-medium text-gray-900",
+                        "New password"
+                    }
+                    input {
+                        r#type: "password",
+                        id: "new-password",
+                        value: "{new_password}",
+                        oninput: move |e| new_password.set(e.value()),
+                        class: "bg-gray-50 border border-gray-300 text-gray-900 rounded-lg focus:ring-blue-600 focus:border-blue-600 block w-full p-2.5",
+                        required: true,
+                    }
+                    p { class: "mt-1 text-xs text-gray-500",
+                        "At least 8 characters, with a letter and a number or symbol"
+                    }
+                }
+                div {
+                    label {
+                        r#for: "confirm-password",
+                        class: "block mb-2 text-sm font-medium text-gray-900",
+                        "Confirm new password"
+                    }
+                    input {
+                        r#type: "password",
+                        id: "confirm-password",
+                        value: "{confirm_password}",
+                        oninput: move |e| confirm_password.set(e.value()),
+                        class: "bg-gray-50 border border-gray-300 text-gray-900 rounded-lg focus:ring-blue-600 focus:border-blue-600 block w-full p-2.5",
+                        required: true,
+                    }
+                    if !error().is_empty() {
+                        ErrorNotification {
+                            message: error(),
+                            class: Some("mt-2 mb-0".to_string()),
+                        }
+                    }
+                }
+                button {
+                    r#type: "submit",
+                    disabled: is_loading(),
+                    class: "w-full text-white bg-blue-600 hover:bg-blue-700 focus:ring-4 focus:outline-none focus:ring-blue-300 font-medium rounded-lg text-sm px-5 py-2.5 text-center disabled:opacity-75",
+                    if is_loading() { "Saving..." } else { "Set new password" }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,94 @@
+use dioxus::prelude::*;
+use reqwest::Client;
+use shared::models::VerifyEmailRequest;
+use crate::components::ErrorNotification;
+use crate::config::endpoints;
+use crate::views::auth::reset_password::error_message;
+use crate::{AuthState, Route};
+const LOGO: Asset = asset!("/assets/Original_Ferris.svg");
+
+/// Verifies the user's email address when opened from a verification link (`?token=...`)
+#[component]
+pub fn VerifyEmail(token: String) -> Element {
+    let mut auth_state = use_context::<Signal<AuthState>>();
+
+    let verification = use_resource(move || {
+        let token = token.clone();
+        async move {
+            if token.is_empty() {
+                return Err("Verification link is missing its token".to_string());
+            }
+
+            let response = Client::new()
+                .post(endpoints::verify_email())
+                .json(&VerifyEmailRequest { token })
+                .send()
+                .await;
+
+            match response {
+                Ok(response) if response.status().is_success() => {
+                    auth_state.write().set_email_verified();
+                    Ok(())
+                }
+                Ok(response) => Err(error_message(response).await),
+                Err(e) => Err(format!("Network error: {}", e)),
+            }
+        }
+    });
+
+    let signed_in = auth_state.read().is_authenticated();
+
+    rsx! {
+        div { class: " bg-gray-900 min-h-screen flex items-center justify-center px-4 py-8",
+            div { class: "w-full max-w-md",
+                div { class: "bg-white rounded-xl shadow-2xl p-8",
+                    div { class: "text-center pt-4 mb-8",
+                        a {
+                            href: "#",
+                            class: "flex items-center justify-center mb-6 text-2xl font-semibold text-gray-900",
+                            img {
+                                class: "w-8 h-8 mr-2",
+                                src: LOGO,
+                                alt: "logo",
+                            }
+                            "Ferrisbase"
+                        }
+                    }
+                    div { class: "space-y-6 px-4 text-center",
+                        match &*verification.read() {
+                            None => rsx! {
+                                p { class: "text-sm text-gray-600", "Verifying your email address..." }
+                            },
+                            Some(Ok(())) => rsx! {
+                                h1 { class: "text-xl font-bold text-gray-900", "Email verified" }
+                                p { class: "text-sm text-gray-600",
+                                    "Thanks for confirming your email address."
+                                }
+                            },
+                            Some(Err(message)) => rsx! {
+                                h1 { class: "text-xl font-bold text-gray-900", "Could not verify your email" }
+                                ErrorNotification { message: message.clone(), class: None }
+                                p { class: "text-sm text-gray-600",
+                                    "You can request a new link from your user settings."
+                                }
+                            },
+                        }
+                        if signed_in {
+                            Link {
+                                to: Route::Projects {},
+                                class: "block w-full text-white bg-blue-600 hover:bg-blue-700 font-medium rounded-lg text-sm px-5 py-2.5 text-center",
+                                "Go to your projects"
+                            }
+                        } else {
+                            Link {
+                                to: Route::LoginForm {},
+                                class: "block w-full text-white bg-blue-600 hover:bg-blue-700 font-medium rounded-lg text-sm px-5 py-2.5 text-center",
+                                "Sign in"
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
@@ -21,4 +21,7 @@ pub mod terms_of_service;
 pub use terms_of_service::TermsOfService;
 
 pub mod user_settings;
-pub use user_settings::UserSettings;
\ No newline at end of file
+pub use user_settings::UserSettings;
+
+pub mod admin;
+pub use admin::AdminDashboard;
\ No newline at end of file
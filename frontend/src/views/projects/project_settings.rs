@@ -1,4 +1,5 @@
 use dioxus::prelude::*;
+use crate::components::projects::{ApiKeysCard, InvitationsCard, MembersCard};
 
 /// The Project Settings page for managing project configuration
 #[component]
@@ -210,75 +211,7 @@ pub fn ProjectSettings(id: String) -> Element {
                         }
                     }
                     // API Keys
-                    div { class: "bg-white rounded-lg shadow-md border border-gray-200 p-6",
-                        div { class: "flex items-center justify-between mb-4",
-                            h2 { class: "text-xl font-semibold text-gray-900", "API Keys" }
-                            button { class: "px-4 py-2 bg-blue-600 hover:bg-blue-700 text-white font-medium rounded-md transition",
-                                "+ Generate New Key"
-                            }
-                        }
-                        p { class: "text-sm text-gray-600 mb-4",
-                            "API keys are used to authenticate requests to your project"
-                        }
-                        div { class: "space-y-3",
-                            // API Key 1
-                            div { class: "p-4 border border-gray-200 rounded-lg hover:border-gray-300 transition",
-                                div { class: "flex items-start justify-between mb-2",
-                                    div {
-                                        p { class: "font-medium text-gray-900", "Production API Key" }
-                                        p { class: "text-xs text-gray-500 mt-1",
-                                            "Created on Nov 15, 2025 • Last used 2 hours ago"
-                                        }
-                                    }
-                                    span { class: "px-2 py-1 bg-green-100 text-green-800 text-xs font-semibold rounded",
-                                        "Active"
-                                    }
-                                }
-                                div { class: "flex items-center gap-2",
-                                    code { class: "flex-1 px-3 py-2 bg-gray-900 text-green-400 rounded font-mono text-sm",
-                                        "frb_live_sk_a8f9d2e1b3c4..."
-                                    }
-                                    button { class: "px-3 py-2 bg-gray-200 hover:bg-gray-300 rounded text-sm font-medium",
-                                        "👁️"
-                                    }
-                                    button { class: "px-3 py-2 bg-gray-200 hover:bg-gray-300 rounded text-sm font-medium",
-                                        "📋"
-                                    }
-                                    button { class: "px-3 py-2 bg-red-100 hover:bg-red-200 text-red-700 rounded text-sm font-medium",
-                                        "🗑️"
-                                    }
-                                }
-                            }
-                            // API Key 2
-                            div { class: "p-4 border border-gray-200 rounded-lg hover:border-gray-300 transition",
-                                div { class: "flex items-start justify-between mb-2",
-                                    div {
-                                        p { class: "font-medium text-gray-900", "Development API Key" }
-                                        p { class: "text-xs text-gray-500 mt-1",
-                                            "Created on Nov 20, 2025 • Last used 1 day ago"
-                                        }
-                                    }
-                                    span { class: "px-2 py-1 bg-green-100 text-green-800 text-xs font-semibold rounded",
-                                        "Active"
-                                    }
-                                }
-                                div { class: "flex items-center gap-2",
-                                    code { class: "flex-1 px-3 py-2 bg-gray-900 text-green-400 rounded font-mono text-sm",
-                                        "frb_test_sk_c7b2e4f3a1d5..."
-                                    }
-                                    button { class: "px-3 py-2 bg-gray-200 hover:bg-gray-300 rounded text-sm font-medium",
-                                        "👁️"
-                                    }
-                                    button { class: "px-3 py-2 bg-gray-200 hover:bg-gray-300 rounded text-sm font-medium",
-                                        "📋"
-                                    }
-                                    button { class: "px-3 py-2 bg-red-100 hover:bg-red-200 text-red-700 rounded text-sm font-medium",
-                                        "🗑️"
-                                    }
-                                }
-                            }
-                        }
-                    }
+                    ApiKeysCard { slug: id.clone() }
                     // Rate Limiting
                     div { class: "bg-white rounded-lg shadow-md border border-gray-200 p-6",
                         h2 { class: "text-xl font-semibold text-gray-900 mb-4", "Rate Limiting" }
@@ -323,76 +256,8 @@ pub fn ProjectSettings(id: String) -> Element {
             // Team & Access Tab
             if active_tab() == "team" {
                 div { class: "max-w-7xl mx-auto space-y-6",
-                    // Team Members
-                    div { class: "bg-white rounded-lg shadow-md border border-gray-200 p-6",
-                        div { class: "flex items-center justify-between mb-4",
-                            h2 { class: "text-xl font-semibold text-gray-900", "Team Members" }
-                            button { class: "px-4 py-2 bg-blue-600 hover:bg-blue-700 text-white font-medium rounded-md transition",
-                                "+ Invite Member"
-                            }
-                        }
-                        div { class: "space-y-3",
-                            // Team Member 1
-                            div { class: "flex items-center justify-between p-4 border border-gray-200 rounded-lg",
-                                div { class: "flex items-center gap-3",
-                                    div { class: "h-10 w-10 bg-blue-100 rounded-full flex items-center justify-center text-blue-600 font-semibold",
-                                        "YO"
-                                    }
-                                    div {
-                                        p { class: "font-medium text-gray-900", "You (Owner)" }
-                                        p { class: "text-sm text-gray-500", "your.email@example.com" }
-                                    }
-                                }
-                                span { class: "px-3 py-1 bg-purple-100 text-purple-800 text-xs font-semibold rounded-full",
-                                    "Owner"
-                                }
-                            }
-                            // Team Member 2
-                            div { class: "flex items-center justify-between p-4 border border-gray-200 rounded-lg",
-                                div { class: "flex items-center gap-3",
-                                    div { class: "h-10 w-10 bg-green-100 rounded-full flex items-center justify-center text-green-600 font-semibold",
-                                        "JD"
-                                    }
-                                    div {
-                                        p { class: "font-medium text-gray-900", "John Developer" }
-                                        p { class: "text-sm text-gray-500", "john@example.com" }
-                                    }
-                                }
-                                div { class: "flex items-center gap-2",
-                                    select { class: "px-3 py-1 border border-gray-300 rounded text-sm",
-                                        option { "Admin" }
-                                        option { "Developer" }
-                                        option { "Viewer" }
-                                    }
-                                    button { class: "px-3 py-1 text-red-600 hover:bg-red-50 rounded text-sm font-medium",
-                                        "Remove"
-                                    }
-                                }
-                            }
-                            // Team Member 3
-                            div { class: "flex items-center justify-between p-4 border border-gray-200 rounded-lg",
-                                div { class: "flex items-center gap-3",
-                                    div { class: "h-10 w-10 bg-orange-100 rounded-full flex items-center justify-center text-orange-600 font-semibold",
-                                        "SA"
-                                    }
-                                    div {
-                                        p { class: "font-medium text-gray-900", "Sarah Analyst" }
-                                        p { class: "text-sm text-gray-500", "sarah@example.com" }
-                                    }
-                                }
-                                div { class: "flex items-center gap-2",
-                                    select { class: "px-3 py-1 border border-gray-300 rounded text-sm",
-                                        option { "Viewer" }
-                                        option { "Admin" }
-                                        option { "Developer" }
-                                    }
-                                    button { class: "px-3 py-1 text-red-600 hover:bg-red-50 rounded text-sm font-medium",
-                                        "Remove"
-                                    }
-                                }
-                            }
-                        }
-                    }
+                    MembersCard { slug: id.clone() }
+                    InvitationsCard { slug: id.clone() }
                     // Roles & Permissions
                     div { class: "bg-white rounded-lg shadow-md border border-gray-200 p-6",
                         h2 { class: "text-xl font-semibold text-gray-900 mb-4", "Roles & Permissions" }
@@ -406,7 +271,7 @@ pub fn ProjectSettings(id: String) -> Element {
                                     p { class: "font-medium text-gray-900", "Full Access" }
                                 }
                                 p { class: "text-sm text-gray-600",
-                                    "Can manage all project settings, delete project, and manage billing"
+                                    "Can do everything, including managing owners and deleting the project"
                                 }
                             }
                             // Admin Role
@@ -418,7 +283,7 @@ pub fn ProjectSettings(id: String) -> Element {
                                     p { class: "font-medium text-gray-900", "Administrative Access" }
                                 }
                                 p { class: "text-sm text-gray-600",
-                                    "Can modify settings, manage team members, and access all features"
+                                    "Can modify settings, API keys, and team members, and access all features"
                                 }
                             }
                             // Developer Role
@@ -430,7 +295,7 @@ pub fn ProjectSettings(id: String) -> Element {
                                     p { class: "font-medium text-gray-900", "Development Access" }
                                 }
                                 p { class: "text-sm text-gray-600",
-                                    "Can manage database, API settings, and view analytics"
+                                    "Can change data and the database schema and run any SQL"
                                 }
                             }
                             // Viewer Role
@@ -442,7 +307,7 @@ pub fn ProjectSettings(id: String) -> Element {
                                     p { class: "font-medium text-gray-900", "Read-Only Access" }
                                 }
                                 p { class: "text-sm text-gray-600",
-                                    "Can view project data and analytics, but cannot make changes"
+                                    "Can view project data and run read-only SQL, but cannot make changes"
                                 }
                             }
                         }
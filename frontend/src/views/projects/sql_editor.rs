@@ -1,7 +1,20 @@
 use dioxus::prelude::*;
-use crate::hooks::use_execute_sql;
-use shared::models::ExecuteSqlResponse;
-use serde_json::Value as JsonValue;
+use crate::hooks::{
+    use_cancel_sql, use_delete_saved_query, use_execute_saved_query, use_execute_sql, use_save_query,
+    use_saved_queries, use_sql_completions, use_sql_history,
+};
+use shared::models::{
+    CreateSavedQueryRequest, ExecuteSavedQueryRequest, ExecuteSqlRequest, ExecuteSqlResponse, ExplainPlan, PlanNode,
+    QueryParameter, SavedQueryResponse, SqlColumn, SqlCompletions, SqlStatementKind, SqlStatementResult,
+};
+use serde_json::{Map, Value as JsonValue};
+
+/// Side panels opened from the header buttons
+#[derive(Clone, Copy, PartialEq)]
+enum SidePanel {
+    History,
+    SavedQueries,
+}
 
 /// The SQL Editor page component that will be rendered when the current route is `[Route::SQLEditor]`
 #[component]
@@ -9,16 +22,56 @@ pub fn SQLEditor(id: String) -> Element {
     let mut query = use_signal(|| "SELECT * FROM users LIMIT 10;".to_string());
     let mut result = use_signal(|| None::<Result<ExecuteSqlResponse, String>>);
     let mut is_loading = use_signal(|| false);
+    let mut read_only = use_signal(|| false);
+    let mut transaction = use_signal(|| false);
+    let mut running_query_id = use_signal(|| None::<String>);
+    let mut panel = use_signal(|| None::<SidePanel>);
+    let mut history_refresh = use_signal(|| 0u32);
+    let saved_refresh = use_signal(|| 0u32);
+    // Saved query loaded into the editor; runs by ID with parameters while its text is unchanged
+    let mut active_saved = use_signal(|| None::<SavedQueryResponse>);
+    let mut parameter_values = use_signal(Vec::<String>::new);
+    let mut show_save_form = use_signal(|| false);
+    let mut save_name = use_signal(String::new);
+    let mut save_parameters = use_signal(Vec::<QueryParameter>::new);
+    let mut save_result = use_signal(|| None::<Result<SavedQueryResponse, String>>);
     
-    // Hook to execute SQL queries
+    // Hooks to execute and cancel SQL queries
     let execute_sql = use_execute_sql(id.clone());
+    let execute_saved_query = use_execute_saved_query(id.clone());
+    let cancel_sql = use_cancel_sql(id.clone());
+    let save_query = use_save_query(id.clone(), saved_refresh);
+    let delete_saved_query = use_delete_saved_query(id.clone(), saved_refresh);
+    let history = use_sql_history(id.clone(), history_refresh);
+    let saved_queries = use_saved_queries(id.clone(), saved_refresh);
+    let completions = use_sql_completions(id.clone());
     
     // Effect to turn off loading when result is available
     use_effect(move || {
         if result.read().is_some() {
             is_loading.set(false);
+            running_query_id.set(None);
+            *history_refresh.write() += 1;
+        }
+    });
+
+    // Effect to load a query once it has been saved
+    use_effect(move || {
+        if let Some(Ok(saved)) = save_result() {
+            parameter_values.set(vec![String::new(); saved.parameters.len()]);
+            active_saved.set(Some(saved));
+            show_save_form.set(false);
         }
     });
+
+    let runs_saved_query = active_saved().filter(|saved| saved.query == query());
+
+    // Completions for the word being typed at the end of the query
+    let suggestions = match &*completions.read() {
+        Some(Ok(completions)) => complete(completions, &query()),
+        _ => Vec::new(),
+    };
+    let first_suggestion = suggestions.first().cloned();
     
     rsx! {
         div { class: "min-h-screen bg-gray-50 p-6",
@@ -30,14 +83,120 @@ pub fn SQLEditor(id: String) -> Element {
                         p { class: "text-gray-600 mt-1", "Project: {id}" }
                     }
                     div { class: "flex gap-3",
-                        button { class: "px-4 py-2 bg-gray-200 hover:bg-gray-300 text-gray-700 font-medium rounded-md transition duration-200",
+                        button {
+                            class: "px-4 py-2 bg-gray-200 hover:bg-gray-300 text-gray-700 font-medium rounded-md transition duration-200",
+                            onclick: move |_| {
+                                let next = if panel() == Some(SidePanel::History) { None } else { Some(SidePanel::History) };
+                                panel.set(next);
+                            },
                             "History"
                         }
-                        button { class: "px-4 py-2 bg-gray-200 hover:bg-gray-300 text-gray-700 font-medium rounded-md transition duration-200",
+                        button {
+                            class: "px-4 py-2 bg-gray-200 hover:bg-gray-300 text-gray-700 font-medium rounded-md transition duration-200",
+                            onclick: move |_| {
+                                let next = if panel() == Some(SidePanel::SavedQueries) {
+                                    None
+                                } else {
+                                    Some(SidePanel::SavedQueries)
+                                };
+                                panel.set(next);
+                            },
                             "Saved Queries"
                         }
                     }
                 }
+                // History panel
+                if panel() == Some(SidePanel::History) {
+                    div { class: "bg-white rounded-lg shadow-md border border-gray-200 p-4 max-h-80 overflow-y-auto",
+                        match &*history.read() {
+                            None => rsx! { p { class: "text-sm text-gray-500", "Loading history..." } },
+                            Some(Err(error)) => rsx! { p { class: "text-sm text-red-500", "{error}" } },
+                            Some(Ok(entries)) if entries.is_empty() => rsx! {
+                                p { class: "text-sm text-gray-500", "No queries run yet." }
+                            },
+                            Some(Ok(entries)) => rsx! {
+                                ul { class: "divide-y divide-gray-100",
+                                    for entry in entries.clone() {
+                                        li {
+                                            key: "{entry.id}",
+                                            class: "py-2 cursor-pointer hover:bg-gray-50",
+                                            onclick: {
+                                                let entry_query = entry.query.clone();
+                                                move |_| {
+                                                    query.set(entry_query.clone());
+                                                    panel.set(None);
+                                                }
+                                            },
+                                            code { class: "block text-xs text-gray-800 truncate", "{entry.query}" }
+                                            div { class: "flex gap-3 text-xs text-gray-500 mt-1",
+                                                span { "{entry.executed_at}" }
+                                                span { "{entry.execution_time_ms}ms" }
+                                                if let Some(rows) = entry.row_count {
+                                                    span { "{rows} row(s)" }
+                                                }
+                                                if let Some(error) = &entry.error {
+                                                    span { class: "text-red-500 truncate", "{error}" }
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            },
+                        }
+                    }
+                }
+                // Saved queries panel
+                if panel() == Some(SidePanel::SavedQueries) {
+                    div { class: "bg-white rounded-lg shadow-md border border-gray-200 p-4 max-h-80 overflow-y-auto",
+                        match &*saved_queries.read() {
+                            None => rsx! { p { class: "text-sm text-gray-500", "Loading saved queries..." } },
+                            Some(Err(error)) => rsx! { p { class: "text-sm text-red-500", "{error}" } },
+                            Some(Ok(saved)) if saved.is_empty() => rsx! {
+                                p { class: "text-sm text-gray-500", "No saved queries yet." }
+                            },
+                            Some(Ok(saved)) => rsx! {
+                                ul { class: "divide-y divide-gray-100",
+                                    for saved_query in saved.clone() {
+                                        li { key: "{saved_query.id}", class: "py-2 flex items-center justify-between gap-3",
+                                            div { class: "min-w-0",
+                                                div { class: "text-sm font-medium text-gray-900", "{saved_query.name}" }
+                                                code { class: "block text-xs text-gray-500 truncate", "{saved_query.query}" }
+                                            }
+                                            div { class: "flex gap-2 shrink-0",
+                                                button {
+                                                    class: "px-3 py-1 text-sm bg-gray-100 hover:bg-gray-200 text-gray-700 rounded-md transition",
+                                                    onclick: {
+                                                        let saved_query = saved_query.clone();
+                                                        move |_| {
+                                                            query.set(saved_query.query.clone());
+                                                            parameter_values.set(vec![String::new(); saved_query.parameters.len()]);
+                                                            active_saved.set(Some(saved_query.clone()));
+                                                            panel.set(None);
+                                                        }
+                                                    },
+                                                    "Load"
+                                                }
+                                                button {
+                                                    class: "px-3 py-1 text-sm bg-red-50 hover:bg-red-100 text-red-700 rounded-md transition",
+                                                    onclick: {
+                                                        let saved_query_id = saved_query.id.clone();
+                                                        move |_| {
+                                                            if active_saved().is_some_and(|active| active.id == saved_query_id) {
+                                                                active_saved.set(None);
+                                                            }
+                                                            delete_saved_query.send(saved_query_id.clone());
+                                                        }
+                                                    },
+                                                    "Delete"
+                                                }
+                                            }
+                                        }
+                                    }
+                                }
+                            },
+                        }
+                    }
+                }
             }
             // Main Editor Container
             div { class: "max-w-7xl mx-auto grid grid-cols-1 gap-6",
@@ -70,6 +229,32 @@ pub fn SQLEditor(id: String) -> Element {
                         placeholder: "Enter your SQL query here...",
                         value: "{query}",
                         oninput: move |evt| query.set(evt.value()),
+                        onkeydown: move |evt| {
+                            // Tab accepts the first completion
+                            if evt.key() == Key::Tab {
+                                if let Some(suggestion) = &first_suggestion {
+                                    evt.prevent_default();
+                                    query.set(suggestion.apply(&query()));
+                                }
+                            }
+                        },
+                    }
+                    if !suggestions.is_empty() {
+                        div { class: "mt-1 flex flex-wrap gap-1",
+                            for suggestion in suggestions {
+                                button {
+                                    key: "{suggestion.kind}:{suggestion.label}",
+                                    class: "px-2 py-0.5 text-xs font-mono bg-gray-100 hover:bg-blue-100 text-gray-800 rounded",
+                                    title: "{suggestion.detail}",
+                                    onclick: {
+                                        let suggestion = suggestion.clone();
+                                        move |_| query.set(suggestion.apply(&query()))
+                                    },
+                                    span { class: "text-gray-400 mr-1", "{suggestion.kind}" }
+                                    "{suggestion.label}"
+                                }
+                            }
+                        }
                     }
                     // Action Buttons
                     div { class: "flex items-center justify-between mt-4",
@@ -78,6 +263,24 @@ pub fn SQLEditor(id: String) -> Element {
                                 "PostgreSQL"
                             }
                             span { "Lines: {query().lines().count()}" }
+                            label { class: "flex items-center gap-1 cursor-pointer",
+                                input {
+                                    r#type: "checkbox",
+                                    checked: read_only(),
+                                    onchange: move |evt| read_only.set(evt.checked()),
+                                }
+                                "Read-only"
+                            }
+                            label {
+                                class: "flex items-center gap-1 cursor-pointer",
+                                title: "Run all statements in one transaction and roll back if any fails",
+                                input {
+                                    r#type: "checkbox",
+                                    checked: transaction(),
+                                    onchange: move |evt| transaction.set(evt.checked()),
+                                }
+                                "Single transaction"
+                            }
                         }
                         div { class: "flex gap-3",
                             button {
@@ -85,7 +288,36 @@ pub fn SQLEditor(id: String) -> Element {
                                 disabled: is_loading() || query().trim().is_empty(),
                                 onclick: move |_| {
                                     is_loading.set(true);
-                                    execute_sql.send((query(), result));
+                                    let query_id = uuid::Uuid::new_v4().to_string();
+                                    running_query_id.set(Some(query_id.clone()));
+                                    if let Some(saved) = active_saved().filter(|saved| saved.query == query()) {
+                                        // Empty inputs bind NULL
+                                        let parameters: Map<String, JsonValue> = saved
+                                            .parameters
+                                            .iter()
+                                            .zip(parameter_values())
+                                            .map(|(param, value)| {
+                                                let value = if value.is_empty() { JsonValue::Null } else { JsonValue::String(value) };
+                                                (param.name.clone(), value)
+                                            })
+                                            .collect();
+                                        let request = ExecuteSavedQueryRequest {
+                                            parameters,
+                                            read_only: read_only(),
+                                            transaction: transaction(),
+                                            query_id: Some(query_id),
+                                        };
+                                        execute_saved_query.send((saved.id.clone(), request, result));
+                                    } else {
+                                        let request = ExecuteSqlRequest {
+                                            query: query(),
+                                            read_only: read_only(),
+                                            transaction: transaction(),
+                                            explain: false,
+                                            query_id: Some(query_id),
+                                        };
+                                        execute_sql.send((request, result));
+                                    }
                                 },
                                 if is_loading() {
                                     "Running..."
@@ -93,18 +325,153 @@ pub fn SQLEditor(id: String) -> Element {
                                     "▶ Run Query"
                                 }
                             }
-                            button { class: "px-4 py-2 bg-blue-600 hover:bg-blue-700 text-white font-medium rounded-md transition duration-200",
+                            button {
+                                class: "px-4 py-2 bg-gray-200 hover:bg-gray-300 text-gray-700 font-medium rounded-md transition duration-200 disabled:opacity-50 disabled:cursor-not-allowed",
+                                title: "Profile with EXPLAIN ANALYZE; changes are rolled back",
+                                disabled: is_loading() || query().trim().is_empty(),
+                                onclick: move |_| {
+                                    is_loading.set(true);
+                                    let query_id = uuid::Uuid::new_v4().to_string();
+                                    running_query_id.set(Some(query_id.clone()));
+                                    let request = ExecuteSqlRequest {
+                                        query: query(),
+                                        read_only: read_only(),
+                                        transaction: true,
+                                        explain: true,
+                                        query_id: Some(query_id),
+                                    };
+                                    execute_sql.send((request, result));
+                                },
+                                "Explain"
+                            }
+                            if let Some(query_id) = running_query_id() {
+                                button {
+                                    class: "px-4 py-2 bg-red-600 hover:bg-red-700 text-white font-medium rounded-md transition duration-200",
+                                    onclick: move |_| cancel_sql.send(query_id.clone()),
+                                    "■ Cancel"
+                                }
+                            }
+                            button {
+                                class: "px-4 py-2 bg-blue-600 hover:bg-blue-700 text-white font-medium rounded-md transition duration-200",
+                                disabled: query().trim().is_empty(),
+                                onclick: move |_| {
+                                    // Declare one parameter per placeholder, keeping names and types already given
+                                    let existing = active_saved().map(|saved| saved.parameters).unwrap_or_default();
+                                    let parameters = (0..placeholder_count(&query()))
+                                        .map(|index| {
+                                            existing.get(index).cloned().unwrap_or(QueryParameter {
+                                                name: format!("param{}", index + 1),
+                                                data_type: "text".to_string(),
+                                            })
+                                        })
+                                        .collect();
+                                    save_parameters.set(parameters);
+                                    save_result.set(None);
+                                    show_save_form.set(true);
+                                },
                                 "Save Query"
                             }
                         }
                     }
+                    // Parameter values for a loaded saved query
+                    if let Some(saved) = runs_saved_query {
+                        if !saved.parameters.is_empty() {
+                            div { class: "mt-4 grid grid-cols-1 sm:grid-cols-2 lg:grid-cols-3 gap-3",
+                                for (index, param) in saved.parameters.iter().enumerate() {
+                                    label { class: "text-sm text-gray-700",
+                                        "${index + 1} {param.name} ({param.data_type})"
+                                        input {
+                                            class: "mt-1 w-full px-2 py-1 text-sm font-mono border border-gray-300 rounded-md",
+                                            placeholder: "NULL",
+                                            value: parameter_values().get(index).cloned().unwrap_or_default(),
+                                            oninput: move |evt| {
+                                                if let Some(value) = parameter_values.write().get_mut(index) {
+                                                    *value = evt.value();
+                                                }
+                                            },
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                    // Save form
+                    if show_save_form() {
+                        div { class: "mt-4 p-4 bg-gray-50 border border-gray-200 rounded-md",
+                            div { class: "flex items-center gap-3 mb-3",
+                                input {
+                                    class: "flex-1 px-3 py-2 text-sm border border-gray-300 rounded-md",
+                                    placeholder: "Query name",
+                                    value: "{save_name}",
+                                    oninput: move |evt| save_name.set(evt.value()),
+                                }
+                                button {
+                                    class: "px-4 py-2 bg-blue-600 hover:bg-blue-700 text-white text-sm font-medium rounded-md transition",
+                                    disabled: save_name().trim().is_empty(),
+                                    onclick: move |_| {
+                                        let request = CreateSavedQueryRequest {
+                                            name: save_name(),
+                                            description: None,
+                                            query: query(),
+                                            parameters: save_parameters(),
+                                        };
+                                        save_query.send((request, save_result));
+                                    },
+                                    "Save"
+                                }
+                                button {
+                                    class: "px-4 py-2 bg-gray-200 hover:bg-gray-300 text-gray-700 text-sm rounded-md transition",
+                                    onclick: move |_| show_save_form.set(false),
+                                    "Cancel"
+                                }
+                            }
+                            for (index, param) in save_parameters().into_iter().enumerate() {
+                                div { class: "flex items-center gap-2 mb-2 text-sm",
+                                    span { class: "w-10 font-mono text-gray-500", "${index + 1}" }
+                                    input {
+                                        class: "flex-1 px-2 py-1 border border-gray-300 rounded-md",
+                                        value: "{param.name}",
+                                        oninput: move |evt| {
+                                            if let Some(param) = save_parameters.write().get_mut(index) {
+                                                param.name = evt.value();
+                                            }
+                                        },
+                                    }
+                                    input {
+                                        class: "flex-1 px-2 py-1 font-mono border border-gray-300 rounded-md",
+                                        value: "{param.data_type}",
+                                        oninput: move |evt| {
+                                            if let Some(param) = save_parameters.write().get_mut(index) {
+                                                param.data_type = evt.value();
+                                            }
+                                        },
+                                    }
+                                }
+                            }
+                            if let Some(Err(error)) = save_result() {
+                                p { class: "text-sm text-red-500", "{error}" }
+                            }
+                        }
+                    }
                 }
                 // Query Results Section
                 div { class: "bg-white rounded-lg shadow-md border border-gray-200 p-6",
                     div { class: "flex items-center justify-between mb-4",
-                        h2 { class: "text-lg font-semibold text-gray-900", "Query Results" }
+                        div { class: "flex items-center gap-2",
+                            h2 { class: "text-lg font-semibold text-gray-900", "Query Results" }
+                            if let Some(Ok(resp)) = result() {
+                                if resp.results.len() > 1 {
+                                    span { class: "px-2 py-0.5 text-xs bg-gray-100 text-gray-700 rounded",
+                                        "{resp.results.len()} statements"
+                                    }
+                                }
+                                if resp.transaction {
+                                    span { class: "px-2 py-0.5 text-xs bg-gray-100 text-gray-700 rounded", "transaction" }
+                                }
+                            }
+                        }
                         if let Some(Ok(resp)) = result() {
-                            if !resp.rows.is_empty() {
+                            if resp.results.iter().any(|result| !result.rows.is_empty()) {
                                 div { class: "flex gap-2",
                                     button { class: "px-3 py-1 text-sm bg-gray-100 hover:bg-gray-200 text-gray-700 rounded-md transition",
                                         "Export CSV"
@@ -131,30 +498,16 @@ pub fn SQLEditor(id: String) -> Element {
                                 pre { class: "text-xs bg-red-50 p-4 rounded-md max-w-full overflow-auto", "{error}" }
                             }
                         },
-                        Some(Ok(resp)) => {
-                            if resp.rows.is_empty() {
-                                let message = if let Some(affected) = resp.rows_affected {
-                                    format!("Query executed successfully. {} row(s) affected.", affected)
-                                } else {
-                                    "Query executed successfully. No rows returned.".to_string()
-                                };
-                                rsx! {
-                                    div { class: "flex flex-col items-center justify-center h-64 text-gray-500",
-                                        span { class: "text-5xl mb-4", "✓" }
-                                        p { class: "text-sm font-semibold", "{message}" }
-                                        p { class: "text-xs text-gray-400 mt-2", "Executed in {resp.execution_time_ms}ms" }
-                                    }
-                                }
-                            } else {
-                                rsx! {
-                                    div { class: "bg-gray-50 rounded-md p-4 overflow-x-auto", {render_table(&resp.rows)} }
-                                    div { class: "mt-4 flex items-center justify-between text-sm text-gray-600",
-                                        span { "{resp.rows.len()} row(s) returned" }
-                                        span { "Executed in {resp.execution_time_ms}ms" }
-                                    }
+                        Some(Ok(resp)) => rsx! {
+                            div { class: "flex flex-col gap-6",
+                                for (index, statement_result) in resp.results.iter().enumerate() {
+                                    {render_statement_result(index, resp.results.len(), statement_result)}
                                 }
                             }
-                        }
+                            div { class: "mt-4 text-right text-sm text-gray-600",
+                                "Executed in {resp.execution_time_ms}ms"
+                            }
+                        },
                     }
                 }
                 // Query Templates Section
@@ -202,12 +555,12 @@ pub fn SQLEditor(id: String) -> Element {
                             onclick: move |_| {
                                 query
                                     .set(
-                                        "CREATE TABLE table_name (\n  id UUID PRIMARY KEY DEFAULT gen_random_uuid(),\n  column1 TEXT NOT NULL,\n  column2 INTEGER,\n  created_at TIMESTAMP DEFAULT NOW(),\n  updated_at TIMESTAMP\n);"
+                                        "EXPLAIN SELECT * FROM table_name WHERE column1 = 'value1';"
                                             .to_string(),
                                     )
                             },
-                            div { class: "font-medium text-gray-900 text-sm mb-1", "Create Table" }
-                            div { class: "text-xs text-gray-600 font-mono", "CREATE TABLE ..." }
+                            div { class: "font-medium text-gray-900 text-sm mb-1", "Explain Query" }
+                            div { class: "text-xs text-gray-600 font-mono", "EXPLAIN SELECT ..." }
                         }
                         button {
                             class: "p-3 text-left bg-gray-50 hover:bg-gray-100 border border-gray-200 rounded-md transition",
@@ -228,46 +581,283 @@ pub fn SQLEditor(id: String) -> Element {
     }
 }
 
-/// Helper function to render JSON results as an HTML table
-fn render_table(rows: &[JsonValue]) -> Element {
-    if rows.is_empty() {
-        return rsx! {
-            p { class: "text-gray-500 text-sm", "No data to display" }
-        };
+/// Maximum number of completions shown under the editor
+const MAX_SUGGESTIONS: usize = 8;
+
+/// Completion for the word at the end of the query
+#[derive(Clone, PartialEq)]
+struct Suggestion {
+    kind: &'static str,
+    label: String,
+    detail: String,
+    /// Length of the typed prefix the label replaces
+    replace_len: usize,
+}
+
+impl Suggestion {
+    fn apply(&self, sql: &str) -> String {
+        format!("{}{}", &sql[..sql.len() - self.replace_len], self.label)
+    }
+}
+
+/// Suggest completions for the last word of `sql`.
+///
+/// `name.` completes the columns of that table or view, words after FROM, JOIN,
+/// INTO or UPDATE complete relations, and `::` completes type names; anything
+/// else matches columns of the relations already in the query, relations,
+/// functions and keywords.
+fn complete(completions: &SqlCompletions, sql: &str) -> Vec<Suggestion> {
+    let word_start = sql
+        .rfind(|c: char| !(c.is_alphanumeric() || c == '_' || c == '.' || c == ':'))
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let word = &sql[word_start..];
+    if word.is_empty() {
+        return Vec::new();
+    }
+
+    let previous_word = sql[..word_start]
+        .split_whitespace()
+        .last()
+        .unwrap_or_default()
+        .to_uppercase();
+
+    let mut suggestions: Vec<Suggestion> = Vec::new();
+    let mut offer = |kind: &'static str, label: &str, detail: String, prefix: &str| {
+        if label.len() > prefix.len() && label.to_lowercase().starts_with(&prefix.to_lowercase()) {
+            suggestions.push(Suggestion {
+                kind,
+                label: label.to_string(),
+                detail,
+                replace_len: prefix.len(),
+            });
+        }
+    };
+
+    if let Some((_, prefix)) = word.split_once("::") {
+        for data_type in &completions.types {
+            offer("type", data_type, String::new(), prefix);
+        }
+    } else if let Some((qualifier, prefix)) = word.rsplit_once('.') {
+        if let Some(relation) = completions.relations.iter().find(|r| r.name.eq_ignore_ascii_case(qualifier)) {
+            for column in &relation.columns {
+                offer("column", &column.name, column.data_type.clone(), prefix);
+            }
+        }
+    } else if matches!(previous_word.as_str(), "FROM" | "JOIN" | "INTO" | "UPDATE") {
+        for relation in &completions.relations {
+            offer("table", &relation.name, relation.kind.replace('_', " "), word);
+        }
+    } else {
+        let lowered = sql.to_lowercase();
+        for relation in completions.relations.iter().filter(|r| lowered.contains(&r.name.to_lowercase())) {
+            for column in &relation.columns {
+                offer("column", &column.name, format!("{}.{} {}", relation.name, column.name, column.data_type), word);
+            }
+        }
+        for relation in &completions.relations {
+            offer("table", &relation.name, relation.kind.replace('_', " "), word);
+        }
+        for function in &completions.functions {
+            offer(
+                "function",
+                &function.name,
+                format!("{}({}) → {}", function.name, function.arguments, function.return_type),
+                word,
+            );
+        }
+        for keyword in &completions.keywords {
+            offer("keyword", keyword, String::new(), word);
+        }
+    }
+
+    suggestions.dedup_by(|a, b| a.kind == b.kind && a.label == b.label);
+    suggestions.truncate(MAX_SUGGESTIONS);
+    suggestions
+}
+
+/// Highest `$n` placeholder in a script, which is how many parameters it declares
+fn placeholder_count(sql: &str) -> usize {
+    sql.split('$')
+        .skip(1)
+        .filter_map(|rest| {
+            let digits: String = rest.chars().take_while(|c| c.is_ascii_digit()).collect();
+            digits.parse::<usize>().ok()
+        })
+        .max()
+        .unwrap_or(0)
+}
+
+/// Render the result of one statement, labelled with its position when a script has several
+fn render_statement_result(index: usize, total: usize, result: &SqlStatementResult) -> Element {
+    let kind_badge = match result.statement_kind {
+        SqlStatementKind::Read => rsx! {
+            span { class: "px-2 py-0.5 text-xs bg-blue-50 text-blue-700 rounded", "read" }
+        },
+        SqlStatementKind::Write => rsx! {
+            span { class: "px-2 py-0.5 text-xs bg-amber-50 text-amber-700 rounded", "write" }
+        },
+    };
+
+    let summary = match (&result.plan, result.columns.is_empty(), result.rows_affected) {
+        (Some(_), _, _) => "Explained; changes were rolled back.".to_string(),
+        (None, false, _) => format!("{} row(s) returned", result.rows.len()),
+        (None, true, Some(affected)) => format!("Query executed successfully. {} row(s) affected.", affected),
+        (None, true, None) => "Query executed successfully. No rows returned.".to_string(),
+    };
+
+    rsx! {
+        div {
+            div { class: "flex items-center gap-2 mb-2",
+                if total > 1 {
+                    span { class: "text-sm font-semibold text-gray-700", "Statement {index + 1}" }
+                }
+                {kind_badge}
+                code { class: "text-xs text-gray-500 truncate", "{result.statement}" }
+            }
+            if let Some(plan) = &result.plan {
+                {render_plan(plan)}
+            } else if !result.columns.is_empty() {
+                div { class: "bg-gray-50 rounded-md p-4 overflow-x-auto", {render_table(&result.columns, &result.rows)} }
+            }
+            div { class: "mt-2 flex items-center justify-between text-sm text-gray-600",
+                span { "{summary}" }
+                span { "{result.execution_time_ms}ms" }
+            }
+        }
     }
+}
+
+/// Render an explain plan with its overall timings above the node tree
+fn render_plan(plan: &ExplainPlan) -> Element {
+    let timing = |value: Option<f64>| value.map(|ms| format!("{:.3}ms", ms)).unwrap_or_else(|| "-".to_string());
+    let planning = timing(plan.planning_time_ms);
+    let execution = timing(plan.execution_time_ms);
 
-    // Extract column names from the first row
-    let columns: Vec<String> = if let Some(first_row) = rows.first() {
-        if let Some(obj) = first_row.as_object() {
-            obj.keys().cloned().collect()
-        } else {
-            vec!["value".to_string()]
+    rsx! {
+        div { class: "bg-gray-50 rounded-md p-4 overflow-x-auto",
+            div { class: "flex gap-4 text-xs text-gray-600 mb-3",
+                span { "Planning: {planning}" }
+                span { "Execution: {execution}" }
+            }
+            PlanTree { node: plan.root.clone() }
         }
+    }
+}
+
+/// Collapsible plan node, highlighting sequential scans and row misestimates
+#[component]
+fn PlanTree(node: PlanNode) -> Element {
+    let mut expanded = use_signal(|| true);
+
+    let node_class = if node.is_sequential_scan() {
+        "flex flex-wrap items-center gap-2 px-2 py-1 rounded bg-amber-50 border border-amber-200"
     } else {
-        vec![]
+        "flex flex-wrap items-center gap-2 px-2 py-1 rounded bg-white border border-gray-200"
+    };
+    let toggle = match (node.children.is_empty(), expanded()) {
+        (true, _) => "•",
+        (false, true) => "▾",
+        (false, false) => "▸",
+    };
+    let actual = match (node.actual_rows, node.actual_loops, node.actual_total_time_ms) {
+        (Some(rows), Some(loops), Some(time)) => format!("actual {} rows × {} loops, {:.3}ms", rows, loops, time),
+        _ => "not executed".to_string(),
+    };
+    let estimate = format!(
+        "cost {:.2}..{:.2}, est. {} rows",
+        node.startup_cost, node.total_cost, node.plan_rows
+    );
+    let misestimate = node
+        .is_misestimated()
+        .then(|| format!("rows misestimated {:.0}×", node.estimate_error().unwrap_or_default()));
+    let buffers = match (node.shared_hit_blocks, node.shared_read_blocks) {
+        (Some(hit), Some(read)) => Some(format!("buffers hit {} read {}", hit, read)),
+        _ => None,
     };
 
+    rsx! {
+        div { class: "font-mono text-xs",
+            div {
+                class: "{node_class}",
+                onclick: move |_| expanded.toggle(),
+                span { class: "w-3 text-gray-500 cursor-pointer", "{toggle}" }
+                span { class: "font-semibold text-gray-900", "{node.node_type}" }
+                if let Some(relation) = &node.relation_name {
+                    span { class: "text-gray-700", "on {relation}" }
+                }
+                if let Some(index) = &node.index_name {
+                    span { class: "text-gray-700", "using {index}" }
+                }
+                span { class: "text-gray-500", "{estimate}" }
+                span { class: "text-gray-500", "{actual}" }
+                if let Some(buffers) = buffers {
+                    span { class: "text-gray-400", "{buffers}" }
+                }
+                if node.is_sequential_scan() {
+                    span { class: "px-1.5 py-0.5 bg-amber-100 text-amber-800 rounded", "seq scan" }
+                }
+                if let Some(misestimate) = misestimate {
+                    span { class: "px-1.5 py-0.5 bg-red-100 text-red-700 rounded", "{misestimate}" }
+                }
+            }
+            if let Some(condition) = &node.condition {
+                div { class: "ml-6 text-gray-500", "{condition}" }
+            }
+            if expanded() {
+                div { class: "ml-6 mt-1 flex flex-col gap-1 border-l border-gray-200 pl-2",
+                    for child in node.children.iter() {
+                        PlanTree { node: child.clone() }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Helper function to render JSON results as an HTML table, in the statement's column order
+fn render_table(columns: &[SqlColumn], rows: &[JsonValue]) -> Element {
     rsx! {
         div { class: "overflow-x-auto",
             table { class: "min-w-full divide-y divide-gray-200 border border-gray-300",
                 thead { class: "bg-gray-100",
                     tr {
-                        for col in &columns {
+                        for col in columns {
                             th { class: "px-4 py-2 text-left text-xs font-medium text-gray-700 uppercase tracking-wider border-r border-gray-300",
-                                "{col}"
+                                "{col.name}"
+                                span { class: "block text-[10px] font-normal normal-case text-gray-400",
+                                    "{col.data_type}"
+                                    if col.nullable == Some(false) {
+                                        " not null"
+                                    }
+                                }
                             }
                         }
                     }
                 }
                 tbody { class: "bg-white divide-y divide-gray-200",
+                    if rows.is_empty() {
+                        tr {
+                            td {
+                                class: "px-4 py-2 text-sm text-gray-500",
+                                colspan: "{columns.len()}",
+                                "No rows"
+                            }
+                        }
+                    }
                     for row in rows {
                         tr { class: "hover:bg-gray-50",
-                            for col in &columns {
-                                td { class: "px-4 py-2 text-sm text-gray-900 border-r border-gray-200 font-mono",
+                            for col in columns {
+                                td {
+                                    class: if is_numeric_type(col.type_oid) {
+                                        "px-4 py-2 text-sm text-gray-900 border-r border-gray-200 font-mono text-right"
+                                    } else {
+                                        "px-4 py-2 text-sm text-gray-900 border-r border-gray-200 font-mono"
+                                    },
                                     {
                                         if let Some(obj) = row.as_object() {
-                                            if let Some(val) = obj.get(col) {
-                                                format_json_value(val)
+                                            if let Some(val) = obj.get(&col.name) {
+                                                format_cell(val, col)
                                             } else {
                                                 rsx! {
                                                     span { class: "text-gray-400", "null" }
@@ -289,6 +879,43 @@ fn render_table(rows: &[JsonValue]) -> Element {
     }
 }
 
+/// Postgres type OIDs the result grid formats specially
+const INT2_OID: u32 = 21;
+const INT4_OID: u32 = 23;
+const INT8_OID: u32 = 20;
+const FLOAT4_OID: u32 = 700;
+const FLOAT8_OID: u32 = 701;
+const NUMERIC_OID: u32 = 1700;
+const TIMESTAMP_OID: u32 = 1114;
+const TIMESTAMPTZ_OID: u32 = 1184;
+
+fn is_numeric_type(type_oid: u32) -> bool {
+    matches!(
+        type_oid,
+        INT2_OID | INT4_OID | INT8_OID | FLOAT4_OID | FLOAT8_OID | NUMERIC_OID
+    )
+}
+
+/// Format a cell using its column's Postgres type, falling back to the JSON value
+fn format_cell(val: &JsonValue, column: &SqlColumn) -> Element {
+    match val {
+        JsonValue::Null => format_json_value(val),
+        JsonValue::Number(n) if is_numeric_type(column.type_oid) => rsx! {
+            span { class: "text-blue-600", "{n}" }
+        },
+        JsonValue::String(s) if is_numeric_type(column.type_oid) => rsx! {
+            span { class: "text-blue-600", "{s}" }
+        },
+        JsonValue::String(s) if matches!(column.type_oid, TIMESTAMP_OID | TIMESTAMPTZ_OID) => {
+            let formatted = s.replacen('T', " ", 1);
+            rsx! {
+                span { class: "text-gray-800", "{formatted}" }
+            }
+        }
+        _ => format_json_value(val),
+    }
+}
+
 /// Helper to format JSON values for display
 fn format_json_value(val: &JsonValue) -> Element {
     match val {
@@ -1,9 +1,491 @@
 use dioxus::prelude::*;
+use reqwest::Client;
+use shared::models::{
+    AuthResponse, ChangePasswordRequest, IdentityResponse, MfaCodeRequest, MfaEnabledResponse, MfaStatusResponse,
+    RecoveryCodesResponse, TotpEnrollmentResponse,
+};
+use crate::config::endpoints;
+use crate::views::auth::reset_password::error_message;
+use crate::{AuthState, Route};
 
-/// The Home page component that will be rendered when the current route is `[Route::Home]`
+/// The User Settings page for managing the signed-in account
 #[component]
 pub fn UserSettings() -> Element {
+    let mut auth_state = use_context::<Signal<AuthState>>();
+    let navigator = use_navigator();
+
+    let mut old_password = use_signal(String::new);
+    let mut new_password = use_signal(String::new);
+    let mut confirm_password = use_signal(String::new);
+    let mut is_saving = use_signal(|| false);
+    let mut password_result = use_signal(|| None::<Result<String, String>>);
+
+    let mut is_resending = use_signal(|| false);
+    let mut resend_result = use_signal(|| None::<Result<String, String>>);
+    let email_verified = auth_state
+        .read()
+        .user
+        .as_ref()
+        .is_some_and(|user| user.email_verified);
+
+    let identities = use_resource(move || async move {
+        let token = auth_state.read().token.clone().ok_or("Not authenticated")?;
+        let response = Client::new()
+            .get(endpoints::identities())
+            .header("Authorization", format!("Bearer {}", token))
+            .send()
+            .await
+            .map_err(|e| format!("Network error: {}", e))?;
+
+        if !response.status().is_success() {
+            return Err(error_message(response).await);
+        }
+
+        response
+            .json::<Vec<IdentityResponse>>()
+            .await
+            .map_err(|e| format!("Failed to parse response: {}", e))
+    });
+
+    let resend_verification = move |_| {
+        let Some(token) = auth_state.read().token.clone() else {
+            resend_result.set(Some(Err("Not authenticated".to_string())));
+            return;
+        };
+
+        is_resending.set(true);
+        resend_result.set(None);
+
+        spawn(async move {
+            let response = Client::new()
+                .post(endpoints::resend_verification_email())
+                .header("Authorization", format!("Bearer {}", token))
+                .send()
+                .await;
+
+            let result = match response {
+                Ok(response) if response.status().is_success() => {
+                    Ok("Verification email sent. Check your inbox for the link.".to_string())
+                }
+                Ok(response) => Err(error_message(response).await),
+                Err(e) => Err(format!("Network error: {}", e)),
+            };
+
+            resend_result.set(Some(result));
+            is_resending.set(false);
+        });
+    };
+
+    let change_password = move |evt: FormEvent| {
+        evt.prevent_default();
+
+        if new_password() != confirm_password() {
+            password_result.set(Some(Err("New passwords do not match".to_string())));
+            return;
+        }
+
+        let Some(token) = auth_state.read().token.clone() else {
+            password_result.set(Some(Err("Not authenticated".to_string())));
+            return;
+        };
+
+        let request = ChangePasswordRequest {
+            old_password: old_password(),
+            new_password: new_password(),
+        };
+        is_saving.set(true);
+        password_result.set(None);
+
+        spawn(async move {
+            let response = Client::new()
+                .put(endpoints::change_password())
+                .header("Authorization", format!("Bearer {}", token))
+                .json(&request)
+                .send()
+                .await;
+
+            let result = match response {
+                Ok(response) if response.status().is_success() => match response.json::<AuthResponse>().await {
+                    Ok(auth_response) => {
+                        // Every earlier token was revoked, including the one this request used
+                        auth_state.write().refreshed(
+                            auth_response.token,
+                            auth_response.refresh_token,
+                            auth_response.expires_in,
+                        );
+                        old_password.set(String::new());
+                        new_password.set(String::new());
+                        confirm_password.set(String::new());
+                        Ok("Password changed. Other sessions have been signed out.".to_string())
+                    }
+                    Err(e) => Err(format!("Failed to parse response: {}", e)),
+                },
+                Ok(response) => Err(error_message(response).await),
+                Err(e) => Err(format!("Network error: {}", e)),
+            };
+
+            password_result.set(Some(result));
+            is_saving.set(false);
+        });
+    };
+
     rsx! {
-        p { "User Settings will be added here soon." }
+        div { class: "min-h-screen bg-gray-50 p-6",
+            div { class: "max-w-3xl mx-auto space-y-6",
+                h1 { class: "text-3xl font-bold text-gray-900", "User Settings" }
+
+                // Email verification
+                if !email_verified {
+                    div { class: "bg-white rounded-lg shadow-md border border-gray-200 p-6",
+                        h2 { class: "text-xl font-semibold text-gray-900 mb-4", "Verify Your Email" }
+                        div { class: "flex items-center justify-between gap-4",
+                            p { class: "text-sm text-gray-600",
+                                "Your email address has not been verified yet. Open the link we emailed you, or request a new one."
+                            }
+                            button {
+                                disabled: is_resending(),
+                                class: "px-4 py-2 bg-blue-600 hover:bg-blue-700 disabled:opacity-50 text-white font-medium rounded-md transition whitespace-nowrap",
+                                onclick: resend_verification,
+                                if is_resending() { "Sending..." } else { "Resend email" }
+                            }
+                        }
+                        match resend_result() {
+                            Some(Ok(message)) => rsx! {
+                                p { class: "text-sm text-green-700 mt-3", "{message}" }
+                            },
+                            Some(Err(message)) => rsx! {
+                                p { class: "text-sm text-red-600 mt-3", "{message}" }
+                            },
+                            None => rsx! {},
+                        }
+                    }
+                }
+
+                // Password
+                div { class: "bg-white rounded-lg shadow-md border border-gray-200 p-6",
+                    h2 { class: "text-xl font-semibold text-gray-900 mb-4", "Change Password" }
+                    form { class: "space-y-4", onsubmit: change_password,
+                        div {
+                            label { class: "block text-sm font-medium text-gray-700 mb-2", "Current password" }
+                            input {
+                                r#type: "password",
+                                required: true,
+                                value: "{old_password}",
+                                oninput: move |evt| old_password.set(evt.value()),
+                                class: "w-full px-4 py-2 border border-gray-300 rounded-md focus:ring-2 focus:ring-blue-500 focus:border-transparent outline-none transition",
+                            }
+                        }
+                        div {
+                            label { class: "block text-sm font-medium text-gray-700 mb-2", "New password" }
+                            input {
+                                r#type: "password",
+                                required: true,
+                                value: "{new_password}",
+                                oninput: move |evt| new_password.set(evt.value()),
+                                class: "w-full px-4 py-2 border border-gray-300 rounded-md focus:ring-2 focus:ring-blue-500 focus:border-transparent outline-none transition",
+                            }
+                            p { class: "text-xs text-gray-500 mt-1",
+                                "At least 8 characters, with a letter and a number or symbol"
+                            }
+                        }
+                        div {
+                            label { class: "block text-sm font-medium text-gray-700 mb-2", "Confirm new password" }
+                            input {
+                                r#type: "password",
+                                required: true,
+                                value: "{confirm_password}",
+                                oninput: move |evt| confirm_password.set(evt.value()),
+                                class: "w-full px-4 py-2 border border-gray-300 rounded-md focus:ring-2 focus:ring-blue-500 focus:border-transparent outline-none transition",
+                            }
+                        }
+                        match password_result() {
+                            Some(Ok(message)) => rsx! {
+                                p { class: "text-sm text-green-700", "{message}" }
+                            },
+                            Some(Err(message)) => rsx! {
+                                p { class: "text-sm text-red-600", "{message}" }
+                            },
+                            None => rsx! {},
+                        }
+                        button {
+                            r#type: "submit",
+                            disabled: is_saving(),
+                            class: "px-6 py-2 bg-blue-600 hover:bg-blue-700 disabled:opacity-50 text-white font-semibold rounded-md transition",
+                            if is_saving() { "Saving..." } else { "Change password" }
+                        }
+                    }
+                }
+
+                MfaSettings {}
+
+                // Linked accounts
+                match &*identities.read() {
+                    Some(Ok(identities)) if !identities.is_empty() => rsx! {
+                        div { class: "bg-white rounded-lg shadow-md border border-gray-200 p-6",
+                            h2 { class: "text-xl font-semibold text-gray-900 mb-4", "Linked Accounts" }
+                            ul { class: "divide-y divide-gray-200",
+                                for identity in identities.iter() {
+                                    li { key: "{identity.id}", class: "py-3 flex items-center justify-between gap-4",
+                                        div {
+                                            p { class: "text-sm font-medium text-gray-900",
+                                                "{identity.provider_name.as_deref().unwrap_or(&identity.provider)}"
+                                            }
+                                            if let Some(email) = &identity.email {
+                                                p { class: "text-sm text-gray-600", "{email}" }
+                                            }
+                                        }
+                                        p { class: "text-xs text-gray-500 whitespace-nowrap",
+                                            "Linked {identity.created_at.get(..10).unwrap_or(&identity.created_at)}"
+                                        }
+                                    }
+                                }
+                            }
+                        }
+                    },
+                    _ => rsx! {},
+                }
+
+                // Sessions
+                div { class: "bg-white rounded-lg shadow-md border border-gray-200 p-6",
+                    h2 { class: "text-xl font-semibold text-gray-900 mb-4", "Sessions" }
+                    div { class: "flex items-center justify-between gap-4",
+                        p { class: "text-sm text-gray-600",
+                            "Sign out on every device and browser, including this one. Use this if a device was lost or you suspect someone else has access to your account."
+                        }
+                        button {
+                            class: "px-4 py-2 bg-red-600 hover:bg-red-700 text-white font-medium rounded-md transition whitespace-nowrap",
+                            onclick: move |_| {
+                                crate::hooks::sign_out(auth_state, true);
+                                navigator.push(Route::Home {});
+                            },
+                            "Sign out everywhere"
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Two-factor authentication: set up an authenticator app, manage recovery codes, or turn it off
+#[component]
+fn MfaSettings() -> Element {
+    let mut auth_state = use_context::<Signal<AuthState>>();
+
+    let mut refresh = use_signal(|| 0u32);
+    let mut enrollment = use_signal(|| None::<TotpEnrollmentResponse>);
+    let mut code = use_signal(String::new);
+    let mut recovery_codes = use_signal(|| None::<Vec<String>>);
+    let mut is_busy = use_signal(|| false);
+    let mut error = use_signal(|| None::<String>);
+
+    let status = use_resource(move || async move {
+        let _ = refresh();
+        let token = auth_state.read().token.clone().ok_or("Not authenticated")?;
+        send_authorized(Client::new().get(endpoints::mfa()), token)
+            .await?
+            .json::<MfaStatusResponse>()
+            .await
+            .map_err(|e| format!("Failed to parse response: {}", e))
+    });
+
+    let start_enrollment = move |_| {
+        let Some(token) = auth_state.read().token.clone() else { return };
+        is_busy.set(true);
+        error.set(None);
+        recovery_codes.set(None);
+
+        spawn(async move {
+            let result = match send_authorized(Client::new().post(endpoints::mfa_totp_enroll()), token).await {
+                Ok(response) => response
+                    .json::<TotpEnrollmentResponse>()
+                    .await
+                    .map_err(|e| format!("Failed to parse response: {}", e)),
+                Err(message) => Err(message),
+            };
+
+            match result {
+                Ok(response) => enrollment.set(Some(response)),
+                Err(message) => error.set(Some(message)),
+            }
+            is_busy.set(false);
+        });
+    };
+
+    let confirm_enrollment = move |evt: FormEvent| {
+        evt.prevent_default();
+        let Some(token) = auth_state.read().token.clone() else { return };
+        let request = MfaCodeRequest { code: code.read().trim().to_string() };
+        is_busy.set(true);
+        error.set(None);
+
+        spawn(async move {
+            let result = match send_authorized(
+                Client::new().post(endpoints::mfa_totp_confirm()).json(&request),
+                token,
+            )
+            .await
+            {
+                Ok(response) => response
+                    .json::<MfaEnabledResponse>()
+                    .await
+                    .map_err(|e| format!("Failed to parse response: {}", e)),
+                Err(message) => Err(message),
+            };
+
+            match result {
+                Ok(response) => {
+                    // The new session counts as signed in with both factors
+                    auth_state.write().refreshed(
+                        response.session.token,
+                        response.session.refresh_token,
+                        response.session.expires_in,
+                    );
+                    enrollment.set(None);
+                    code.set(String::new());
+                    recovery_codes.set(Some(response.recovery_codes));
+                    refresh += 1;
+                }
+                Err(message) => error.set(Some(message)),
+            }
+            is_busy.set(false);
+        });
+    };
+
+    let regenerate_codes = move |_| {
+        let Some(token) = auth_state.read().token.clone() else { return };
+        is_busy.set(true);
+        error.set(None);
+
+        spawn(async move {
+            let result = match send_authorized(Client::new().post(endpoints::mfa_recovery_codes()), token).await {
+                Ok(response) => response
+                    .json::<RecoveryCodesResponse>()
+                    .await
+                    .map_err(|e| format!("Failed to parse response: {}", e)),
+                Err(message) => Err(message),
+            };
+
+            match result {
+                Ok(response) => {
+                    recovery_codes.set(Some(response.recovery_codes));
+                    refresh += 1;
+                }
+                Err(message) => error.set(Some(message)),
+            }
+            is_busy.set(false);
+        });
+    };
+
+    let disable = move |_| {
+        let Some(token) = auth_state.read().token.clone() else { return };
+        is_busy.set(true);
+        error.set(None);
+
+        spawn(async move {
+            match send_authorized(Client::new().delete(endpoints::mfa()), token).await {
+                Ok(_) => {
+                    recovery_codes.set(None);
+                    refresh += 1;
+                }
+                Err(message) => error.set(Some(message)),
+            }
+            is_busy.set(false);
+        });
+    };
+
+    let enabled = matches!(&*status.read(), Some(Ok(status)) if status.enabled);
+    let remaining = match &*status.read() {
+        Some(Ok(status)) => status.recovery_codes_remaining,
+        _ => 0,
+    };
+
+    rsx! {
+        div { class: "bg-white rounded-lg shadow-md border border-gray-200 p-6 space-y-4",
+            h2 { class: "text-xl font-semibold text-gray-900", "Two-Factor Authentication" }
+
+            if enabled {
+                div { class: "flex items-center justify-between gap-4",
+                    p { class: "text-sm text-gray-600",
+                        "Two-factor authentication is on. You have {remaining} unused recovery codes."
+                    }
+                    div { class: "flex gap-2",
+                        button {
+                            disabled: is_busy(),
+                            class: "px-4 py-2 bg-gray-100 hover:bg-gray-200 disabled:opacity-50 text-gray-900 font-medium rounded-md transition whitespace-nowrap",
+                            onclick: regenerate_codes,
+                            "New recovery codes"
+                        }
+                        button {
+                            disabled: is_busy(),
+                            class: "px-4 py-2 bg-red-600 hover:bg-red-700 disabled:opacity-50 text-white font-medium rounded-md transition whitespace-nowrap",
+                            onclick: disable,
+                            "Turn off"
+                        }
+                    }
+                }
+            } else if let Some(setup) = enrollment() {
+                p { class: "text-sm text-gray-600",
+                    "Scan this QR code with your authenticator app, or enter the key by hand, then enter the code it shows."
+                }
+                div { class: "w-48 h-48", dangerous_inner_html: "{setup.qr_code_svg}" }
+                p { class: "text-xs text-gray-500 font-mono break-all", "{setup.secret}" }
+                form { class: "flex items-end gap-2", onsubmit: confirm_enrollment,
+                    input {
+                        r#type: "text",
+                        autocomplete: "one-time-code",
+                        placeholder: "123456",
+                        required: true,
+                        value: "{code}",
+                        oninput: move |evt| code.set(evt.value()),
+                        class: "w-40 px-4 py-2 border border-gray-300 rounded-md focus:ring-2 focus:ring-blue-500 focus:border-transparent outline-none transition tracking-widest",
+                    }
+                    button {
+                        r#type: "submit",
+                        disabled: is_busy(),
+                        class: "px-4 py-2 bg-blue-600 hover:bg-blue-700 disabled:opacity-50 text-white font-medium rounded-md transition",
+                        if is_busy() { "Verifying..." } else { "Turn on" }
+                    }
+                }
+            } else {
+                div { class: "flex items-center justify-between gap-4",
+                    p { class: "text-sm text-gray-600",
+                        "Protect your account with a code from an authenticator app when you sign in and before deleting projects."
+                    }
+                    button {
+                        disabled: is_busy(),
+                        class: "px-4 py-2 bg-blue-600 hover:bg-blue-700 disabled:opacity-50 text-white font-medium rounded-md transition whitespace-nowrap",
+                        onclick: start_enrollment,
+                        "Set up"
+                    }
+                }
+            }
+
+            if let Some(codes) = recovery_codes() {
+                div { class: "bg-yellow-50 border border-yellow-200 rounded-md p-4",
+                    p { class: "text-sm text-yellow-800 mb-3",
+                        "Save these recovery codes somewhere safe. Each one signs you in once if you lose your authenticator app, and they will not be shown again."
+                    }
+                    ul { class: "grid grid-cols-2 gap-1 font-mono text-sm text-gray-900",
+                        for recovery_code in codes {
+                            li { key: "{recovery_code}", "{recovery_code}" }
+                        }
+                    }
+                }
+            }
+
+            if let Some(message) = error() {
+                p { class: "text-sm text-red-600", "{message}" }
+            }
+        }
+    }
+}
+
+/// Send a request as the signed-in user, turning error responses into their message
+async fn send_authorized(request: reqwest::RequestBuilder, token: String) -> Result<reqwest::Response, String> {
+    match request.header("Authorization", format!("Bearer {}", token)).send().await {
+        Ok(response) if response.status().is_success() => Ok(response),
+        Ok(response) => Err(error_message(response).await),
+        Err(e) => Err(format!("Network error: {}", e)),
     }
 }
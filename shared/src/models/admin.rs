@@ -0,0 +1,55 @@
+use serde::{Deserialize, Serialize};
+
+use super::UserResponse;
+
+/// User account as seen by platform admins
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AdminUserResponse {
+    pub id: String,
+    pub email: String,
+    pub name: Option<String>,
+    pub role: String,
+    pub email_verified: bool,
+    pub is_active: bool,
+    /// Number of projects the user is a member of
+    pub project_count: u64,
+    pub created_at: String,
+}
+
+/// One page of users matching an admin search
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AdminUserListResponse {
+    pub users: Vec<AdminUserResponse>,
+    /// Number of matching users across all pages
+    pub total: u64,
+}
+
+/// Request to change a user's platform role (`user` or `admin`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateUserRoleRequest {
+    pub role: String,
+}
+
+/// Short-lived access token for acting as another user; it cannot be refreshed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImpersonationResponse {
+    pub token: String,
+    /// Seconds until `token` expires
+    pub expires_in: u64,
+    pub user: UserResponse,
+}
+
+/// Project with its owner and resource usage, as seen by platform admins
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AdminProjectResponse {
+    pub id: String,
+    pub name: String,
+    pub slug: String,
+    pub owner_id: String,
+    pub owner_email: Option<String>,
+    pub member_count: u64,
+    pub table_count: u64,
+    /// Disk space used by the project's tables, views and indexes
+    pub storage_bytes: i64,
+    pub created_at: String,
+}
@@ -0,0 +1,56 @@
+use serde::{Deserialize, Serialize};
+
+/// What a project API key may do
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ApiKeyKind {
+    /// Public key for browsers and apps: data API only, limited by row-level security policies
+    Anon,
+    /// Secret server-side key: data API and SQL execution, bypassing row-level security
+    Service,
+}
+
+impl ApiKeyKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiKeyKind::Anon => "anon",
+            ApiKeyKind::Service => "service",
+        }
+    }
+
+    pub fn parse(kind: &str) -> Option<Self> {
+        match kind {
+            "anon" => Some(ApiKeyKind::Anon),
+            "service" => Some(ApiKeyKind::Service),
+            _ => None,
+        }
+    }
+}
+
+/// Request to create a project API key
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub name: String,
+    pub kind: ApiKeyKind,
+}
+
+/// Project API key response; the key itself is only returned when it is created
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ApiKeyResponse {
+    pub id: String,
+    pub name: String,
+    pub kind: ApiKeyKind,
+    /// Start of the key, to tell keys apart
+    pub key_prefix: String,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+    pub revoked_at: Option<String>,
+}
+
+/// A newly created API key, including the full key to copy now
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CreatedApiKeyResponse {
+    pub key: String,
+    #[serde(flatten)]
+    pub api_key: ApiKeyResponse,
+}
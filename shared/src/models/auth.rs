@@ -15,10 +15,13 @@ pub struct LoginRequest {
     pub password: String,
 }
 
-/// Response payload containing JWT token
+/// Response payload containing a short-lived JWT access token and the refresh token that renews it
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AuthResponse {
     pub token: String,
+    pub refresh_token: String,
+    /// Seconds until `token` expires
+    pub expires_in: u64,
     pub user: UserResponse,
 }
 
@@ -41,6 +44,41 @@ pub struct Claims {
     pub role: String,     // User role
     pub exp: usize,       // Expiration time (as timestamp)
     pub iat: usize,       // Issued at (as timestamp)
+    pub jti: String,      // Token ID, used to revoke this token alone
+    /// Issue time in microseconds, to tell tokens from the same second apart on revocation
+    #[serde(default)]
+    pub iat_us: i64,
+    /// ID of the admin acting as this user, on impersonation tokens
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impersonator: Option<String>,
+    /// Authentication methods used to sign in, such as `pwd`, `oidc` and `otp`
+    #[serde(default)]
+    pub amr: Vec<String>,
+    /// Authenticator assurance level: `aal2` when a second factor was used, otherwise `aal1`
+    #[serde(default = "default_aal")]
+    pub aal: String,
+}
+
+fn default_aal() -> String {
+    "aal1".to_string()
+}
+
+/// Result of a sign-in: either a session, or a challenge for the second factor
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum LoginResponse {
+    Authenticated(AuthResponse),
+    MfaRequired(MfaChallengeResponse),
+}
+
+/// Returned instead of a session when the user has multi-factor authentication enabled
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MfaChallengeResponse {
+    pub mfa_required: bool,
+    /// Single-use token to send back with the code to `/auth/mfa/verify`
+    pub mfa_token: String,
+    /// Seconds until `mfa_token` expires
+    pub expires_in: u64,
 }
 
 /// Request to refresh an access token
@@ -49,6 +87,32 @@ pub struct RefreshTokenRequest {
     pub refresh_token: String,
 }
 
+/// Request to log out, optionally ending the refresh token's session as well
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LogoutRequest {
+    #[serde(default)]
+    pub refresh_token: Option<String>,
+}
+
+/// Request a password reset link by email
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ForgotPasswordRequest {
+    pub email: String,
+}
+
+/// Set a new password with the token from a password reset link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResetPasswordRequest {
+    pub token: String,
+    pub new_password: String,
+}
+
+/// Verify an email address with the token from a verification link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct VerifyEmailRequest {
+    pub token: String,
+}
+
 /// Request to change password
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ChangePasswordRequest {
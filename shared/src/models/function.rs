@@ -0,0 +1,57 @@
+use serde::{Deserialize, Serialize};
+
+/// Languages a project function can be written in
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FunctionLanguage {
+    Sql,
+    Plpgsql,
+}
+
+impl FunctionLanguage {
+    pub fn as_str(&self) -> &str {
+        match self {
+            FunctionLanguage::Sql => "sql",
+            FunctionLanguage::Plpgsql => "plpgsql",
+        }
+    }
+}
+
+/// Named argument of a project function
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FunctionArgument {
+    pub name: String,
+    /// PostgreSQL type name, e.g. `integer` or `text[]`
+    pub data_type: String,
+    /// SQL expression used when the caller omits the argument
+    pub default_value: Option<String>,
+}
+
+/// Request to define a function callable through `/api/rpc/{slug}/{function}`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateFunctionRequest {
+    pub function_name: String,
+    pub description: Option<String>,
+    #[serde(default)]
+    pub arguments: Vec<FunctionArgument>,
+    /// Return type, e.g. `integer`, `setof orders` or `table(id uuid, total numeric)`
+    pub return_type: String,
+    pub language: FunctionLanguage,
+    /// Function body; table names in `sql` bodies refer to the project's tables, while
+    /// `plpgsql` bodies use their physical names and can only be called with a service key
+    pub definition: String,
+}
+
+/// Function definition response
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct FunctionResponse {
+    pub id: String,
+    pub function_name: String,
+    pub description: Option<String>,
+    pub arguments: Vec<FunctionArgument>,
+    pub return_type: String,
+    pub language: String,
+    pub definition: String,
+    pub returns_set: bool,
+    pub created_at: String,
+}
@@ -0,0 +1,38 @@
+use serde::{Deserialize, Serialize};
+
+use super::ProjectRole;
+
+/// Request to invite someone to a project by email
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateInvitationRequest {
+    pub email: String,
+    pub role: ProjectRole,
+}
+
+/// Pending invitation, as listed to the project's owners and admins
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InvitationResponse {
+    pub id: String,
+    pub email: String,
+    pub role: ProjectRole,
+    /// Email address of the member who sent the invitation
+    pub invited_by: Option<String>,
+    pub expires_at: String,
+    pub created_at: String,
+}
+
+/// Request carrying the token from an invitation link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvitationTokenRequest {
+    pub token: String,
+}
+
+/// What an invitation link offers, shown before it is accepted or declined
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InvitationDetailsResponse {
+    pub project_name: String,
+    pub email: String,
+    pub role: ProjectRole,
+    pub invited_by: Option<String>,
+    pub expires_at: String,
+}
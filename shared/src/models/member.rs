@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+
+/// Role of a member in a project, from most to least privileged
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectRole {
+    /// Full access, including deleting the project
+    Owner,
+    /// Everything but deleting the project and managing owners
+    Admin,
+    /// Reads and writes data and changes the schema
+    Developer,
+    /// Reads data and runs read-only SQL
+    Viewer,
+}
+
+impl ProjectRole {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ProjectRole::Owner => "owner",
+            ProjectRole::Admin => "admin",
+            ProjectRole::Developer => "developer",
+            ProjectRole::Viewer => "viewer",
+        }
+    }
+
+    pub fn parse(role: &str) -> Option<Self> {
+        match role {
+            "owner" => Some(ProjectRole::Owner),
+            "admin" => Some(ProjectRole::Admin),
+            "developer" => Some(ProjectRole::Developer),
+            "viewer" => Some(ProjectRole::Viewer),
+            _ => None,
+        }
+    }
+}
+
+/// Request to add an existing user to a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AddMemberRequest {
+    pub email: String,
+    pub role: ProjectRole,
+}
+
+/// Request to change a member's role
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdateMemberRequest {
+    pub role: ProjectRole,
+}
+
+/// Project member response
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MemberResponse {
+    pub user_id: String,
+    pub email: String,
+    pub name: Option<String>,
+    pub role: ProjectRole,
+    pub created_at: String,
+}
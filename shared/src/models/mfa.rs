@@ -0,0 +1,49 @@
+use serde::{Deserialize, Serialize};
+
+use super::AuthResponse;
+
+/// Multi-factor authentication settings of the signed-in user
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MfaStatusResponse {
+    pub enabled: bool,
+    pub recovery_codes_remaining: u64,
+}
+
+/// Secret for a new authenticator app, to be confirmed with a code from it
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TotpEnrollmentResponse {
+    /// Base32 secret for entering by hand
+    pub secret: String,
+    /// `otpauth://` URI encoded in the QR code
+    pub provisioning_uri: String,
+    /// The provisioning URI as an SVG QR code
+    pub qr_code_svg: String,
+}
+
+/// A code from the authenticator app, or a recovery code where accepted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MfaCodeRequest {
+    pub code: String,
+}
+
+/// Finish a sign-in that returned an MFA challenge
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MfaVerifyRequest {
+    pub mfa_token: String,
+    /// Code from the authenticator app, or an unused recovery code
+    pub code: String,
+}
+
+/// Returned once TOTP is enabled; the recovery codes are never shown again
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MfaEnabledResponse {
+    pub recovery_codes: Vec<String>,
+    /// New session for the caller, signed in with both factors
+    pub session: AuthResponse,
+}
+
+/// A fresh set of recovery codes, replacing the previous ones
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RecoveryCodesResponse {
+    pub recovery_codes: Vec<String>,
+}
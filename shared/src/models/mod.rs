@@ -2,8 +2,30 @@ pub mod auth;
 pub mod project;
 pub mod table;
 pub mod sql;
+pub mod schema;
+pub mod view;
+pub mod function;
+pub mod policy;
+pub mod saved_query;
+pub mod api_key;
+pub mod member;
+pub mod invitation;
+pub mod admin;
+pub mod oidc;
+pub mod mfa;
 
 pub use auth::*;
 pub use project::*;
 pub use table::*;
 pub use sql::*;
+pub use schema::*;
+pub use view::*;
+pub use function::*;
+pub use policy::*;
+pub use saved_query::*;
+pub use api_key::*;
+pub use member::*;
+pub use invitation::*;
+pub use admin::*;
+pub use oidc::*;
+pub use mfa::*;
@@ -0,0 +1,35 @@
+use serde::{Deserialize, Serialize};
+
+/// OpenID Connect provider offered on the sign-in page
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct OidcProviderResponse {
+    pub id: String,
+    pub name: String,
+}
+
+/// Where to send the browser to sign in with a provider
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OidcAuthorizeResponse {
+    pub authorization_url: String,
+    /// The `state` in the URL, for the browser to check that the callback answers its own sign-in
+    pub state: String,
+}
+
+/// Query parameters the provider redirected back with
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OidcCallbackRequest {
+    pub code: String,
+    pub state: String,
+}
+
+/// External account linked to the signed-in user
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct IdentityResponse {
+    pub id: String,
+    pub provider: String,
+    /// Display name of the provider, if it is still configured
+    pub provider_name: Option<String>,
+    pub email: Option<String>,
+    pub created_at: String,
+    pub last_sign_in_at: Option<String>,
+}
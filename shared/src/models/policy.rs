@@ -0,0 +1,52 @@
+use serde::{Deserialize, Serialize};
+
+/// Commands a row-level security policy applies to
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum PolicyCommand {
+    All,
+    Select,
+    Insert,
+    Update,
+    Delete,
+}
+
+impl PolicyCommand {
+    pub fn to_postgres_command(&self) -> &str {
+        match self {
+            PolicyCommand::All => "ALL",
+            PolicyCommand::Select => "SELECT",
+            PolicyCommand::Insert => "INSERT",
+            PolicyCommand::Update => "UPDATE",
+            PolicyCommand::Delete => "DELETE",
+        }
+    }
+}
+
+/// Request to add a row-level security policy to a project table.
+///
+/// Expressions can read the caller's JWT claims with
+/// `current_setting('request.jwt.claims', true)::jsonb` or the `auth_uid()` helper.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePolicyRequest {
+    pub policy_name: String,
+    pub command: PolicyCommand,
+    /// Rows visible to (or affected by) the command
+    pub using_expression: Option<String>,
+    /// Rows the command may write
+    pub check_expression: Option<String>,
+}
+
+/// Row-level security policy response
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PolicyResponse {
+    pub id: String,
+    pub policy_name: String,
+    pub command: PolicyCommand,
+    pub using_expression: Option<String>,
+    pub check_expression: Option<String>,
+    pub created_at: String,
+    /// Problems noticed while creating the policy, such as views it does not reach
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub warnings: Vec<String>,
+}
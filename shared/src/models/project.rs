@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 
+use super::ProjectRole;
+
 /// Request payload for creating a new project
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateProjectRequest {
@@ -16,8 +18,12 @@ pub struct ProjectResponse {
     pub slug: String,
     pub owner_id: String,
     pub database_status: String,
+    /// Maximum run time of a SQL editor statement
+    pub statement_timeout_ms: i32,
     pub created_at: String,
     pub updated_at: Option<String>,
+    /// The requesting user's role in the project
+    pub role: ProjectRole,
 }
 
 /// Request payload for updating a project
@@ -25,4 +31,5 @@ pub struct ProjectResponse {
 pub struct UpdateProjectRequest {
     pub name: Option<String>,
     pub description: Option<String>,
+    pub statement_timeout_ms: Option<i32>,
 }
@@ -0,0 +1,59 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value as JsonValue};
+
+/// Declared type of a `$n` placeholder; `$1` is the first parameter of the list
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QueryParameter {
+    pub name: String,
+    /// PostgreSQL type name, e.g. `integer` or `timestamptz`
+    pub data_type: String,
+}
+
+/// Request to save a named SQL script in a project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateSavedQueryRequest {
+    pub name: String,
+    pub description: Option<String>,
+    pub query: String,
+    #[serde(default)]
+    pub parameters: Vec<QueryParameter>,
+}
+
+/// Saved query response
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SavedQueryResponse {
+    pub id: String,
+    pub name: String,
+    pub description: Option<String>,
+    pub query: String,
+    pub parameters: Vec<QueryParameter>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Request to run a saved query, with parameter values keyed by parameter name
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct ExecuteSavedQueryRequest {
+    #[serde(default)]
+    pub parameters: Map<String, JsonValue>,
+    #[serde(default)]
+    pub read_only: bool,
+    #[serde(default)]
+    pub transaction: bool,
+    pub query_id: Option<String>,
+}
+
+/// One SQL editor execution in a user's history
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QueryHistoryEntry {
+    pub id: String,
+    pub query: String,
+    pub saved_query_id: Option<String>,
+    pub statement_count: i32,
+    /// Rows returned plus rows affected across the script's statements
+    pub row_count: Option<i64>,
+    pub execution_time_ms: i64,
+    /// Set when the script failed
+    pub error: Option<String>,
+    pub executed_at: String,
+}
@@ -0,0 +1,65 @@
+use serde::{Deserialize, Serialize};
+
+use super::table::TableResponse;
+
+/// Request to import tables from a DDL script
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportSchemaRequest {
+    pub sql: String,
+}
+
+/// Result of a DDL import
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ImportSchemaResponse {
+    pub tables: Vec<TableResponse>,
+    /// Parts of the script that were skipped (foreign keys, checks, ...)
+    pub warnings: Vec<String>,
+}
+
+/// Kind of change in a schema migration plan
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum MigrationAction {
+    CreateTable,
+    DropTable,
+    AddColumn,
+    DropColumn,
+    AlterColumn,
+    AddConstraint,
+    DropConstraint,
+    UpdateMetadata,
+}
+
+/// A single step of a migration plan
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MigrationStep {
+    pub action: MigrationAction,
+    pub table_name: String,
+    pub description: String,
+    /// DDL executed against the target project (None for metadata-only steps)
+    pub sql: Option<String>,
+    /// True when the step can lose data
+    pub destructive: bool,
+}
+
+/// Differences between two projects expressed as a plan for the target project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchemaDiffResponse {
+    pub source_project: String,
+    pub target_project: String,
+    pub steps: Vec<MigrationStep>,
+    pub has_destructive_changes: bool,
+    /// Identifies this exact plan; pass it back when applying
+    pub fingerprint: String,
+    pub applied: bool,
+}
+
+/// Request to apply a schema diff to the target project
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplySchemaDiffRequest {
+    /// Must be set when the plan drops tables, columns or changes types
+    #[serde(default)]
+    pub allow_destructive: bool,
+    /// Fingerprint of the reviewed plan; the apply is rejected if the schemas changed since
+    pub fingerprint: Option<String>,
+}
@@ -1,16 +1,149 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value as JsonValue;
 
-/// Request to execute a SQL query
+/// Request to execute a SQL script of one or more statements
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ExecuteSqlRequest {
     pub query: String,
+    /// Run the statements in read-only transactions and reject statements that modify data
+    #[serde(default)]
+    pub read_only: bool,
+    /// Run the whole script in one transaction that is rolled back if any statement fails;
+    /// otherwise each statement is committed on its own
+    #[serde(default)]
+    pub transaction: bool,
+    /// Profile each statement with `EXPLAIN (ANALYZE, BUFFERS, FORMAT JSON)` instead of
+    /// returning its rows; the script runs in one transaction that is always rolled back
+    #[serde(default)]
+    pub explain: bool,
+    /// Client-chosen ID used to cancel the statement while it runs; generated when omitted
+    pub query_id: Option<String>,
 }
 
-/// Response from executing a SQL query
+/// Whether a statement only reads data or may modify it
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SqlStatementKind {
+    Read,
+    Write,
+}
+
+/// Descriptor of a result column, listed in the statement's column order
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct SqlColumn {
+    pub name: String,
+    /// Postgres type name as shown by `format_type`, e.g. `timestamp with time zone`
+    pub data_type: String,
+    pub type_oid: u32,
+    /// Whether the column can hold NULL; unknown for computed columns
+    pub nullable: Option<bool>,
+}
+
+/// Result set of one statement in a script
 #[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct ExecuteSqlResponse {
+pub struct SqlStatementResult {
+    pub statement: String,
+    pub statement_kind: SqlStatementKind,
+    pub columns: Vec<SqlColumn>,
     pub rows: Vec<JsonValue>,
     pub rows_affected: Option<u64>,
     pub execution_time_ms: u128,
+    /// Execution plan, when the script ran in explain mode
+    pub plan: Option<ExplainPlan>,
+}
+
+/// Execution plan of a statement run with `EXPLAIN ANALYZE`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ExplainPlan {
+    pub planning_time_ms: Option<f64>,
+    pub execution_time_ms: Option<f64>,
+    pub root: PlanNode,
+}
+
+/// Node of an execution plan with its estimated cost and measured timing
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct PlanNode {
+    pub node_type: String,
+    pub relation_name: Option<String>,
+    pub index_name: Option<String>,
+    /// Filter, index or join condition, when the node has one
+    pub condition: Option<String>,
+    pub startup_cost: f64,
+    pub total_cost: f64,
+    pub plan_rows: f64,
+    /// Rows per loop, as reported by Postgres
+    pub actual_rows: Option<f64>,
+    pub actual_loops: Option<f64>,
+    pub actual_total_time_ms: Option<f64>,
+    pub shared_hit_blocks: Option<i64>,
+    pub shared_read_blocks: Option<i64>,
+    pub children: Vec<PlanNode>,
+}
+
+impl PlanNode {
+    /// Estimates off by at least this factor count as misestimates
+    pub const MISESTIMATE_FACTOR: f64 = 10.0;
+
+    pub fn is_sequential_scan(&self) -> bool {
+        self.node_type == "Seq Scan"
+    }
+
+    /// How far the row estimate was off, as a factor of at least 1, once the node has run
+    pub fn estimate_error(&self) -> Option<f64> {
+        let actual = self.actual_rows?.max(1.0);
+        let planned = self.plan_rows.max(1.0);
+        Some(actual.max(planned) / actual.min(planned))
+    }
+
+    pub fn is_misestimated(&self) -> bool {
+        self.estimate_error().is_some_and(|factor| factor >= Self::MISESTIMATE_FACTOR)
+    }
+}
+
+/// Response from executing a SQL script, with one result per statement in script order
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ExecuteSqlResponse {
+    pub query_id: String,
+    pub results: Vec<SqlStatementResult>,
+    pub transaction: bool,
+    pub execution_time_ms: u128,
+}
+
+/// Response from cancelling a running SQL query
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CancelQueryResponse {
+    pub query_id: String,
+    pub cancelled: bool,
+}
+
+/// Schema and keyword completions for the SQL editor, using logical names
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct SqlCompletions {
+    pub relations: Vec<CompletionRelation>,
+    pub functions: Vec<CompletionFunction>,
+    pub types: Vec<String>,
+    pub keywords: Vec<String>,
+}
+
+/// Table or view of a project with its columns in order
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CompletionRelation {
+    pub name: String,
+    /// `table`, `view` or `materialized_view`
+    pub kind: String,
+    pub columns: Vec<CompletionColumn>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CompletionColumn {
+    pub name: String,
+    pub data_type: String,
+}
+
+/// Project function with its signature, e.g. `total_for(customer uuid)`
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct CompletionFunction {
+    pub name: String,
+    pub arguments: String,
+    pub return_type: String,
 }
@@ -0,0 +1,44 @@
+use serde::{Deserialize, Serialize};
+
+/// Request to create a view or materialized view over project tables
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateViewRequest {
+    pub view_name: String,
+    pub display_name: String,
+    pub description: Option<String>,
+    /// SELECT query using the project's table names
+    pub definition: String,
+    #[serde(default)]
+    pub materialized: bool,
+    /// Refresh a materialized view automatically every N seconds
+    pub refresh_interval_seconds: Option<i32>,
+}
+
+/// Request to change how often a materialized view is refreshed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ViewRefreshScheduleRequest {
+    /// None disables scheduled refreshes
+    pub refresh_interval_seconds: Option<i32>,
+}
+
+/// Column exposed by a view
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ViewColumn {
+    pub name: String,
+    pub data_type: String,
+}
+
+/// View definition response
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ViewResponse {
+    pub id: String,
+    pub view_name: String,
+    pub display_name: String,
+    pub description: Option<String>,
+    pub definition: String,
+    pub is_materialized: bool,
+    pub refresh_interval_seconds: Option<i32>,
+    pub last_refreshed_at: Option<String>,
+    pub columns: Vec<ViewColumn>,
+    pub created_at: String,
+}